@@ -0,0 +1,248 @@
+//! Read-only access to the files stored inside `.zip` archives.
+//!
+//! When a scan peeks into archives, every member of a `.zip` becomes a
+//! virtual [`FileEntry`](crate::types::FileEntry) whose path is
+//! `zip://<archive>!<member>`.  [`crate::hasher::sha256_file`] recognises
+//! such paths and hashes the member's decompressed content, so duplicates
+//! are found across archives, inside one archive, and against loose files.
+//! Virtual entries cannot be deleted or renamed.
+//!
+//! Reading archives needs the optional `archive-peek` cargo feature.
+//! Without it [`zip_members`] lists nothing, so archives are scanned as
+//! plain files only.
+
+use std::path::{Path, PathBuf};
+
+use crate::types::DigestEncoding;
+
+/// True when this build can read archives (the `archive-peek` feature).
+pub const PEEK_SUPPORTED: bool = cfg!(feature = "archive-peek");
+
+/// Prefix marking a path that points inside an archive.
+pub const VIRTUAL_PREFIX: &str = "zip://";
+
+/// One file stored in an archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveMember {
+    /// Member path inside the archive, `/`-separated.
+    pub name: String,
+    /// Uncompressed size in bytes.
+    pub size: u64,
+}
+
+/// True for files with a `.zip` extension (case-insensitive).
+pub fn is_zip(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// Virtual path of `member` inside `archive`.
+pub fn virtual_path(archive: &Path, member: &str) -> PathBuf {
+    PathBuf::from(format!(
+        "{}{}!{}",
+        VIRTUAL_PREFIX,
+        archive.display(),
+        member
+    ))
+}
+
+/// Split a virtual path back into `(archive, member)`.
+///
+/// The archive part ends at the first `.zip!`, so member names may
+/// contain `!`.  `None` for ordinary paths.
+pub fn split_virtual_path(path: &Path) -> Option<(PathBuf, String)> {
+    let rest = path.to_str()?.strip_prefix(VIRTUAL_PREFIX)?;
+    let split = rest.to_ascii_lowercase().find(".zip!")? + ".zip".len();
+    Some((PathBuf::from(&rest[..split]), rest[split + 1..].to_string()))
+}
+
+/// List the regular files stored in `archive` (directories are left out).
+#[cfg(feature = "archive-peek")]
+pub fn zip_members(archive: &Path) -> std::io::Result<Vec<ArchiveMember>> {
+    let mut zip = open_zip(archive)?;
+    let mut members = Vec::new();
+    for index in 0..zip.len() {
+        // Raw access reads the header only, without inflating the member.
+        let member = zip.by_index_raw(index).map_err(zip_error)?;
+        if member.is_file() {
+            members.push(ArchiveMember {
+                name: member.name().to_string(),
+                size: member.size(),
+            });
+        }
+    }
+    Ok(members)
+}
+
+/// Without the `archive-peek` feature archives are never opened.
+#[cfg(not(feature = "archive-peek"))]
+pub fn zip_members(_archive: &Path) -> std::io::Result<Vec<ArchiveMember>> {
+    Ok(Vec::new())
+}
+
+/// SHA-256 digest of the decompressed content of `member`, in `encoding`.
+#[cfg(feature = "archive-peek")]
+pub fn sha256_member(
+    archive: &Path,
+    member: &str,
+    encoding: DigestEncoding,
+) -> std::io::Result<String> {
+    let mut zip = open_zip(archive)?;
+    let reader = zip.by_name(member).map_err(zip_error)?;
+    crate::hasher::sha256_reader(reader, encoding)
+}
+
+/// Without the `archive-peek` feature archive members cannot be read.
+#[cfg(not(feature = "archive-peek"))]
+pub fn sha256_member(
+    _archive: &Path,
+    _member: &str,
+    _encoding: DigestEncoding,
+) -> std::io::Result<String> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "archive support is not compiled in",
+    ))
+}
+
+/// BLAKE3 digest of the decompressed content of `member`, in `encoding`.
+#[cfg(feature = "archive-peek")]
+pub fn blake3_member(
+    archive: &Path,
+    member: &str,
+    encoding: DigestEncoding,
+) -> std::io::Result<String> {
+    let mut zip = open_zip(archive)?;
+    let reader = zip.by_name(member).map_err(zip_error)?;
+    crate::hasher::blake3_reader(reader, encoding)
+}
+
+/// Without the `archive-peek` feature archive members cannot be read.
+#[cfg(not(feature = "archive-peek"))]
+pub fn blake3_member(
+    _archive: &Path,
+    _member: &str,
+    _encoding: DigestEncoding,
+) -> std::io::Result<String> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "archive support is not compiled in",
+    ))
+}
+
+#[cfg(feature = "archive-peek")]
+fn open_zip(archive: &Path) -> std::io::Result<zip::ZipArchive<std::io::BufReader<std::fs::File>>> {
+    let file = std::fs::File::open(archive)?;
+    zip::ZipArchive::new(std::io::BufReader::new(file)).map_err(zip_error)
+}
+
+#[cfg(feature = "archive-peek")]
+fn zip_error(err: zip::result::ZipError) -> std::io::Error {
+    match err {
+        zip::result::ZipError::Io(e) => e,
+        other => std::io::Error::new(std::io::ErrorKind::InvalidData, other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_virtual_path_round_trips() {
+        let path = virtual_path(Path::new("/data/Backup.ZIP"), "docs/a!b.txt");
+        assert_eq!(
+            split_virtual_path(&path),
+            Some((PathBuf::from("/data/Backup.ZIP"), "docs/a!b.txt".into()))
+        );
+        assert_eq!(path.file_name().unwrap(), "a!b.txt");
+        assert_eq!(split_virtual_path(Path::new("/data/Backup.zip")), None);
+    }
+
+    #[cfg(feature = "archive-peek")]
+    mod peek {
+        use super::super::*;
+        use crate::grouper;
+        use crate::progress::NoProgress;
+        use crate::scanner;
+        use crate::types::{
+            FileTypePresets, GroupingConfig, NameCompare, SameFolderPolicy, ScanOptions,
+            SymlinkHashPolicy,
+        };
+        use std::io::Write;
+        use tempfile::tempdir;
+
+        fn write_zip(path: &Path, members: &[(&str, &[u8])]) {
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+            for (name, content) in members {
+                zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                    .unwrap();
+                zip.write_all(content).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+
+        #[test]
+        fn test_identical_members_of_two_zips_group_together() {
+            let dir = tempdir().unwrap();
+            let a = dir.path().join("a.zip");
+            let b = dir.path().join("b.zip");
+            write_zip(&a, &[("report.pdf", b"quarterly"), ("a_only.txt", b"a")]);
+            write_zip(
+                &b,
+                &[("old/report.pdf", b"quarterly"), ("b_only.txt", b"b")],
+            );
+
+            let options = ScanOptions {
+                peek_archives: true,
+                ..Default::default()
+            };
+            let (entries, _) =
+                scanner::gather_recent_files(dir.path(), 0, None, None, &options, &NoProgress);
+            assert_eq!(entries.len(), 6);
+
+            let config = GroupingConfig {
+                use_hash: true,
+                use_size: false,
+                use_name: false,
+                use_name_family: false,
+                use_mtime: false,
+                use_created: false,
+                use_mime: false,
+                use_media_meta: false,
+                use_audio_fingerprint: false,
+                use_dimensions: false,
+                use_content_prefix: false,
+                content_prefix_bytes: 0,
+                hash_max_bytes: None,
+                fast_hash_oversized: false,
+                blake3_min_bytes: None,
+                digest_encoding: DigestEncoding::Hex,
+                symlink_hash_policy: SymlinkHashPolicy::FollowTarget,
+                same_folder_policy: SameFolderPolicy::Flag,
+                name_compare_mode: NameCompare::Full,
+                custom_file_type_presets: FileTypePresets::new(),
+                type_overrides: Vec::new(),
+                alternatives: Vec::new(),
+            };
+            let (groups, _) = grouper::find_duplicate_groups(&entries, &config, &NoProgress);
+
+            assert_eq!(groups.len(), 1);
+            let mut paths: Vec<PathBuf> = groups
+                .into_values()
+                .next()
+                .unwrap()
+                .into_iter()
+                .map(|e| e.path)
+                .collect();
+            paths.sort();
+            assert_eq!(
+                paths,
+                vec![
+                    virtual_path(&a, "report.pdf"),
+                    virtual_path(&b, "old/report.pdf")
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,425 @@
+//! Chromaprint-style acoustic fingerprints for audio files.
+//!
+//! Audio is decoded to mono, resampled to 11025 Hz and cut into overlapping
+//! frames.  Every frame yields a 32-bit sub-fingerprint whose bits record
+//! whether the energy difference between neighbouring frequency bands rose or
+//! fell since the previous frame (Haitsma–Kalker).  Two encodings of the same
+//! recording end up with a low bit error rate even though their bytes (and
+//! sizes) differ completely.
+//!
+//! Decoding needs the optional `audio-fp` cargo feature.  Without it
+//! [`fingerprint_file`] always returns `None`, so the grouper counts every
+//! file as skipped.
+
+use std::path::Path;
+
+/// One 32-bit sub-fingerprint per analysis frame.
+pub type Fingerprint = Vec<u32>;
+
+/// Highest bit error rate at which two fingerprints count as the same audio.
+pub const MATCH_THRESHOLD: f64 = 0.35;
+
+/// Frames the two fingerprints may be shifted against each other (encoder
+/// delay / padding differs between codecs).
+const MAX_OFFSET: usize = 16;
+
+/// Lowest bit error rate between `a` and `b` over small alignment offsets.
+///
+/// Only offsets that overlap at least half of the shorter fingerprint are
+/// considered.  Returns `None` when either fingerprint is empty.
+pub fn bit_error_rate(a: &[u32], b: &[u32]) -> Option<f64> {
+    let min_overlap = (a.len().min(b.len()) / 2).max(1);
+    let mut best: Option<f64> = None;
+
+    for offset in 0..=MAX_OFFSET {
+        for (x, y) in [(a, b), (b, a)] {
+            if offset >= x.len() {
+                continue;
+            }
+            let x = &x[offset..];
+            let overlap = x.len().min(y.len());
+            if overlap < min_overlap {
+                continue;
+            }
+            let errors: u32 = x.iter().zip(y).map(|(p, q)| (p ^ q).count_ones()).sum();
+            let ber = f64::from(errors) / (32.0 * overlap as f64);
+            if best.is_none_or(|b| ber < b) {
+                best = Some(ber);
+            }
+        }
+    }
+
+    best
+}
+
+/// Whether `a` and `b` are close enough to be the same recording.
+pub fn is_match(a: &[u32], b: &[u32]) -> bool {
+    bit_error_rate(a, b).is_some_and(|ber| ber <= MATCH_THRESHOLD)
+}
+
+/// Compute the fingerprint of an audio file.
+///
+/// Returns `None` for files that cannot be decoded or are too short to
+/// produce a single sub-fingerprint.
+#[cfg(feature = "audio-fp")]
+pub fn fingerprint_file(path: &Path) -> Option<Fingerprint> {
+    let (samples, rate) = decode::decode_mono(path)?;
+    let resampled = analysis::resample(&samples, rate);
+    analysis::fingerprint_samples(&resampled)
+}
+
+/// Compute the fingerprint of an audio file (unavailable in this build).
+#[cfg(not(feature = "audio-fp"))]
+pub fn fingerprint_file(_path: &Path) -> Option<Fingerprint> {
+    None
+}
+
+#[cfg(feature = "audio-fp")]
+mod decode {
+    use std::path::Path;
+
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+    use symphonia::core::errors::Error;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    /// Only the first two minutes are fingerprinted.
+    const MAX_SECONDS: usize = 120;
+
+    /// Decode the first audio track to mono samples; returns `(samples, rate)`.
+    pub fn decode_mono(path: &Path) -> Option<(Vec<f32>, u32)> {
+        let file = std::fs::File::open(path).ok()?;
+        let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+        let mut hint = Hint::new();
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            hint.with_extension(ext);
+        }
+        let probed = symphonia::default::get_probe()
+            .format(
+                &hint,
+                stream,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .ok()?;
+        let mut format = probed.format;
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)?;
+        let track_id = track.id;
+        let rate = track.codec_params.sample_rate?;
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .ok()?;
+
+        let max_samples = rate as usize * MAX_SECONDS;
+        let mut mono: Vec<f32> = Vec::new();
+
+        while mono.len() < max_samples {
+            let packet = match format.next_packet() {
+                Ok(packet) => packet,
+                Err(_) => break,
+            };
+            if packet.track_id() != track_id {
+                continue;
+            }
+            let decoded = match decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // Corrupt packets are skipped, anything else ends the stream.
+                Err(Error::DecodeError(_)) => continue,
+                Err(_) => break,
+            };
+
+            let spec = *decoded.spec();
+            let channels = spec.channels.count().max(1);
+            let mut buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+            buf.copy_interleaved_ref(decoded);
+            mono.extend(
+                buf.samples()
+                    .chunks(channels)
+                    .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+            );
+        }
+
+        mono.truncate(max_samples);
+        if mono.is_empty() {
+            return None;
+        }
+        Some((mono, rate))
+    }
+}
+
+#[cfg(feature = "audio-fp")]
+mod analysis {
+    use rustfft::num_complex::Complex;
+    use rustfft::FftPlanner;
+
+    use super::Fingerprint;
+
+    /// Analysis sample rate; everything above ~5 kHz is ignored anyway.
+    pub const SAMPLE_RATE: u32 = 11_025;
+    const FRAME_SIZE: usize = 2048;
+    const HOP_SIZE: usize = 256;
+    /// 33 bands give 32 band-difference bits per frame.
+    const BANDS: usize = 33;
+    const MIN_FREQ: f64 = 300.0;
+    const MAX_FREQ: f64 = 3000.0;
+
+    /// Resample to [`SAMPLE_RATE`], averaging over each output period so
+    /// downsampling does not alias high frequencies into the analysed bands.
+    pub fn resample(samples: &[f32], rate: u32) -> Vec<f32> {
+        if rate == SAMPLE_RATE {
+            return samples.to_vec();
+        }
+        let ratio = f64::from(rate) / f64::from(SAMPLE_RATE);
+        let out_len = (samples.len() as f64 / ratio) as usize;
+
+        (0..out_len)
+            .map(|i| {
+                let start = (i as f64 * ratio) as usize;
+                if ratio <= 1.0 {
+                    // Upsampling: linear interpolation.
+                    let pos = i as f64 * ratio;
+                    let frac = (pos - start as f64) as f32;
+                    let a = samples[start];
+                    let b = samples.get(start + 1).copied().unwrap_or(a);
+                    return a + (b - a) * frac;
+                }
+                let end = (((i + 1) as f64 * ratio) as usize).clamp(start + 1, samples.len());
+                let window = &samples[start..end];
+                window.iter().sum::<f32>() / window.len() as f32
+            })
+            .collect()
+    }
+
+    /// Fingerprint mono samples at [`SAMPLE_RATE`].
+    pub fn fingerprint_samples(samples: &[f32]) -> Option<Fingerprint> {
+        if samples.len() < FRAME_SIZE + HOP_SIZE {
+            return None;
+        }
+
+        let fft = FftPlanner::<f32>::new().plan_fft_forward(FRAME_SIZE);
+        let window: Vec<f32> = (0..FRAME_SIZE)
+            .map(|i| {
+                let phase = 2.0 * std::f32::consts::PI * i as f32 / FRAME_SIZE as f32;
+                0.5 - 0.5 * phase.cos()
+            })
+            .collect();
+        let edges = band_edges();
+
+        let mut buf = vec![Complex::new(0.0f32, 0.0); FRAME_SIZE];
+        let mut previous: Option<[f32; BANDS]> = None;
+        let mut fingerprint = Vec::new();
+
+        for start in (0..=samples.len() - FRAME_SIZE).step_by(HOP_SIZE) {
+            for (i, slot) in buf.iter_mut().enumerate() {
+                *slot = Complex::new(samples[start + i] * window[i], 0.0);
+            }
+            fft.process(&mut buf);
+
+            let mut energy = [0.0f32; BANDS];
+            for (band, e) in energy.iter_mut().enumerate() {
+                *e = buf[edges[band]..edges[band + 1]]
+                    .iter()
+                    .map(|c| c.norm_sqr())
+                    .sum();
+            }
+            if let Some(prev) = &previous {
+                fingerprint.push(sub_fingerprint(prev, &energy));
+            }
+            previous = Some(energy);
+        }
+
+        Some(fingerprint)
+    }
+
+    /// FFT bin boundaries of the log-spaced analysis bands.
+    fn band_edges() -> [usize; BANDS + 1] {
+        let mut edges = [0usize; BANDS + 1];
+        for (band, edge) in edges.iter_mut().enumerate() {
+            let freq = MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf(band as f64 / BANDS as f64);
+            *edge = (freq * FRAME_SIZE as f64 / f64::from(SAMPLE_RATE)).round() as usize;
+        }
+        for band in 1..=BANDS {
+            edges[band] = edges[band].max(edges[band - 1] + 1);
+        }
+        edges
+    }
+
+    fn sub_fingerprint(prev: &[f32; BANDS], cur: &[f32; BANDS]) -> u32 {
+        let mut bits = 0u32;
+        for band in 0..BANDS - 1 {
+            let delta = (cur[band] - cur[band + 1]) - (prev[band] - prev[band + 1]);
+            if delta > 0.0 {
+                bits |= 1 << band;
+            }
+        }
+        bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_error_rate_identical_is_zero() {
+        let fp: Vec<u32> = (0..64u32).map(|i| i.wrapping_mul(2_654_435_761)).collect();
+        assert_eq!(bit_error_rate(&fp, &fp), Some(0.0));
+        assert!(is_match(&fp, &fp));
+    }
+
+    #[test]
+    fn test_bit_error_rate_finds_shifted_alignment() {
+        let fp: Vec<u32> = (0..64u32).map(|i| i.wrapping_mul(2_654_435_761)).collect();
+        let shifted = &fp[3..];
+        assert_eq!(bit_error_rate(&fp, shifted), Some(0.0));
+        assert_eq!(bit_error_rate(shifted, &fp), Some(0.0));
+    }
+
+    #[test]
+    fn test_empty_fingerprint_never_matches() {
+        assert_eq!(bit_error_rate(&[], &[1, 2, 3]), None);
+        assert!(!is_match(&[], &[]));
+    }
+
+    #[cfg(feature = "audio-fp")]
+    mod decoding {
+        use super::super::*;
+        use crate::progress::NoProgress;
+        use crate::types::{
+            DigestEncoding, FileTypePresets, GroupingConfig, NameCompare, SameFolderPolicy,
+            ScanOptions, SymlinkHashPolicy,
+        };
+        use crate::{grouper, scanner};
+        use std::f32::consts::PI;
+        use std::fs;
+        use std::path::Path;
+        use tempfile::tempdir;
+
+        /// Write a 16-bit PCM WAV file of `seconds` length.
+        fn write_wav(path: &Path, rate: u32, channels: u16, seconds: f32, signal: fn(f32) -> f32) {
+            let frames = (rate as f32 * seconds) as u32;
+            let data_len = frames * u32::from(channels) * 2;
+            let mut bytes = Vec::with_capacity(44 + data_len as usize);
+            bytes.extend_from_slice(b"RIFF");
+            bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+            bytes.extend_from_slice(b"WAVEfmt ");
+            bytes.extend_from_slice(&16u32.to_le_bytes());
+            bytes.extend_from_slice(&1u16.to_le_bytes());
+            bytes.extend_from_slice(&channels.to_le_bytes());
+            bytes.extend_from_slice(&rate.to_le_bytes());
+            bytes.extend_from_slice(&(rate * u32::from(channels) * 2).to_le_bytes());
+            bytes.extend_from_slice(&(channels * 2).to_le_bytes());
+            bytes.extend_from_slice(&16u16.to_le_bytes());
+            bytes.extend_from_slice(b"data");
+            bytes.extend_from_slice(&data_len.to_le_bytes());
+            for n in 0..frames {
+                let value = (signal(n as f32 / rate as f32) * 0.8 * f32::from(i16::MAX)) as i16;
+                for _ in 0..channels {
+                    bytes.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            fs::write(path, bytes).unwrap();
+        }
+
+        /// Rising sweep with a wobbling second partial.
+        fn rising(t: f32) -> f32 {
+            let f = 400.0 + 600.0 * t;
+            0.6 * (2.0 * PI * f * t).sin() + 0.4 * (2.0 * PI * 1.5 * f * t + (7.0 * t).sin()).sin()
+        }
+
+        /// Falling sweep over the same range.
+        fn falling(t: f32) -> f32 {
+            let f = 1600.0 - 600.0 * t;
+            0.6 * (2.0 * PI * f * t).sin() + 0.4 * (2.0 * PI * 0.7 * f * t).sin()
+        }
+
+        #[test]
+        fn test_two_encodings_of_same_clip_match() {
+            let dir = tempdir().unwrap();
+            let hi = dir.path().join("clip_44k_stereo.wav");
+            let lo = dir.path().join("clip_22k_mono.wav");
+            let other = dir.path().join("other.wav");
+            write_wav(&hi, 44_100, 2, 2.0, rising);
+            write_wav(&lo, 22_050, 1, 2.0, rising);
+            write_wav(&other, 44_100, 2, 2.0, falling);
+
+            let fp_hi = fingerprint_file(&hi).unwrap();
+            let fp_lo = fingerprint_file(&lo).unwrap();
+            let fp_other = fingerprint_file(&other).unwrap();
+
+            assert!(is_match(&fp_hi, &fp_lo));
+            assert!(!is_match(&fp_hi, &fp_other));
+        }
+
+        #[test]
+        fn test_grouper_groups_two_encodings_by_fingerprint() {
+            let dir = tempdir().unwrap();
+            write_wav(&dir.path().join("song.wav"), 44_100, 2, 2.0, rising);
+            write_wav(&dir.path().join("song_low.wav"), 22_050, 1, 2.0, rising);
+            write_wav(&dir.path().join("other.wav"), 44_100, 2, 2.0, falling);
+            fs::write(dir.path().join("cover.txt"), b"liner notes").unwrap();
+
+            let (entries, _) = scanner::gather_recent_files(
+                dir.path(),
+                0,
+                None,
+                None,
+                &ScanOptions::default(),
+                &NoProgress,
+            );
+            let config = GroupingConfig {
+                use_hash: false,
+                use_size: false,
+                use_name: false,
+                use_name_family: false,
+                use_mtime: false,
+                use_created: false,
+                use_mime: false,
+                use_media_meta: false,
+                use_audio_fingerprint: true,
+                use_dimensions: false,
+                use_content_prefix: false,
+                content_prefix_bytes: 0,
+                hash_max_bytes: None,
+                fast_hash_oversized: false,
+                blake3_min_bytes: None,
+                digest_encoding: DigestEncoding::Hex,
+                symlink_hash_policy: SymlinkHashPolicy::FollowTarget,
+                same_folder_policy: SameFolderPolicy::Flag,
+                name_compare_mode: NameCompare::Full,
+                custom_file_type_presets: FileTypePresets::new(),
+                type_overrides: Vec::new(),
+                alternatives: Vec::new(),
+            };
+            let (groups, stats) = grouper::find_duplicate_groups(&entries, &config, &NoProgress);
+
+            assert_eq!(stats.audio_skipped, 1);
+            assert_eq!(groups.len(), 1);
+            let mut names: Vec<String> = groups
+                .values()
+                .next()
+                .unwrap()
+                .iter()
+                .map(|e| e.path.file_name().unwrap().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            assert_eq!(names, vec!["song.wav", "song_low.wav"]);
+        }
+
+        #[test]
+        fn test_non_audio_file_has_no_fingerprint() {
+            let dir = tempdir().unwrap();
+            let path = dir.path().join("notes.txt");
+            fs::write(&path, b"not audio at all").unwrap();
+            assert!(fingerprint_file(&path).is_none());
+        }
+    }
+}
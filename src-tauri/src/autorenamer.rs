@@ -1,9 +1,20 @@
-use std::collections::HashSet;
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
+use crate::file_lock;
+use crate::grouper;
+use crate::hasher;
+use crate::progress::ProgressSink;
 use crate::types::{
-    AutoRenameErrorDto, AutoRenameItemDto, AutoRenameResult, RenameComponentDef, RenameSchema,
+    AutoRenameErrorDto, AutoRenameItemDto, AutoRenameResult, CollisionPolicy, CreatedFallback,
+    DigestEncoding, FileTypePresets, HashAlgorithm, OrganizeReport, PathErrorDto, RecencyBasis,
+    RenameComponentDef, RenameSchema, SanitizeOptions,
 };
+use crate::wire_path;
 
 const IMAGE_EXTENSIONS: &[&str] = &[
     "jpg", "jpeg", "png", "gif", "bmp", "webp", "tif", "tiff", "heic", "heif", "svg",
@@ -18,8 +29,15 @@ const DOCUMENT_EXTENSIONS: &[&str] = &[
 ];
 const ARCHIVE_EXTENSIONS: &[&str] = &["zip", "rar", "7z", "tar", "gz", "bz2", "xz", "tgz"];
 
-pub fn normalize_file_type_preset(preset: &str) -> String {
-    match preset.trim().to_ascii_lowercase().as_str() {
+/// Canonical name of `preset`: a `custom` preset (matched case-insensitively,
+/// and taking precedence over a built-in of the same name), one of the
+/// built-ins, or `"all"` for anything unknown.
+pub fn normalize_file_type_preset(preset: &str, custom: &FileTypePresets) -> String {
+    let preset = preset.trim().to_ascii_lowercase();
+    if custom_extensions(&preset, custom).is_some() {
+        return preset;
+    }
+    match preset.as_str() {
         "images" => "images",
         "videos" => "videos",
         "audio" => "audio",
@@ -30,8 +48,22 @@ pub fn normalize_file_type_preset(preset: &str) -> String {
     .to_string()
 }
 
-pub fn matches_file_type_preset(path: &Path, preset: &str) -> bool {
-    let normalized = normalize_file_type_preset(preset);
+/// True when `path`'s extension belongs to `preset`; see
+/// [`normalize_file_type_preset`] for how the name is resolved.
+pub fn matches_file_type_preset(path: &Path, preset: &str, custom: &FileTypePresets) -> bool {
+    let normalized = normalize_file_type_preset(preset, custom);
+    if let Some(extensions) = custom_extensions(&normalized, custom) {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        return !extension.is_empty()
+            && extensions.iter().any(|e| {
+                e.trim()
+                    .trim_start_matches('.')
+                    .eq_ignore_ascii_case(extension)
+            });
+    }
     if normalized == "all" {
         return true;
     }
@@ -55,10 +87,109 @@ pub fn matches_file_type_preset(path: &Path, preset: &str) -> bool {
     }
 }
 
+/// Extensions of the custom preset named `preset` (case-insensitive).
+fn custom_extensions<'a>(preset: &str, custom: &'a FileTypePresets) -> Option<&'a [String]> {
+    custom
+        .iter()
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case(preset))
+        .map(|(_, extensions)| extensions.as_slice())
+}
+
+/// Reject schemas that cannot produce a single valid file name.
+///
+/// With [`RenameSchema::strict`], literal text and sidecar extensions must
+/// not contain characters file names disallow; otherwise those are
+/// replaced per [`SanitizeOptions`] when names are built.
+pub fn validate_schema(schema: &RenameSchema) -> Result<(), String> {
+    if schema.separator.chars().any(is_reserved_char) {
+        return Err(format!(
+            "Separator '{}' contains characters that are not allowed in file names",
+            schema.separator
+        ));
+    }
+    if let Some(sep) = schema
+        .sequence_separator
+        .as_deref()
+        .filter(|sep| sep.chars().any(is_reserved_char))
+    {
+        return Err(format!(
+            "Sequence separator '{}' contains characters that are not allowed in file names",
+            sep
+        ));
+    }
+    validate_strftime("Date", &schema.date_format)?;
+    validate_strftime("Time", &schema.time_format)?;
+    if schema.strict {
+        validate_strict_components(&schema.components)?;
+    }
+    Ok(())
+}
+
+/// Reject user-typed component text that sanitizing would have changed.
+fn validate_strict_components(components: &[RenameComponentDef]) -> Result<(), String> {
+    for component in components {
+        let (label, text) = match component {
+            RenameComponentDef::Literal { value } => ("Literal", value),
+            RenameComponentDef::SidecarField { extension, .. } => ("Sidecar extension", extension),
+            _ => continue,
+        };
+        if text.chars().any(is_reserved_char) {
+            return Err(format!(
+                "{} '{}' contains characters that are not allowed in file names",
+                label, text
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Reject strftime patterns chrono cannot parse, or whose output would put
+/// characters into a name that file systems do not allow.
+fn validate_strftime(label: &str, pattern: &str) -> Result<(), String> {
+    use chrono::format::{Item, StrftimeItems};
+
+    if StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error)) {
+        return Err(format!(
+            "{} format '{}' is not a valid strftime pattern",
+            label, pattern
+        ));
+    }
+    let sample = chrono::Local::now().format(pattern).to_string();
+    if sample.chars().any(is_reserved_char) {
+        return Err(format!(
+            "{} format '{}' produces characters that are not allowed in file names",
+            label, pattern
+        ));
+    }
+    Ok(())
+}
+
+/// Spell the 1-based `n` in bijective base 26: 1 → `a`, 26 → `z`,
+/// 27 → `aa`, 28 → `ab`, ...  `0` has no spelling and yields an empty string.
+fn alpha_sequence(mut n: u32, uppercase: bool) -> String {
+    let base = if uppercase { b'A' } else { b'a' };
+    let mut letters = Vec::new();
+    while n > 0 {
+        n -= 1;
+        letters.push(base + (n % 26) as u8);
+        n /= 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).expect("ASCII letters")
+}
+
 /// Build a new filename stem from the schema.
 ///
-/// `seq` is `None` for the base-name pass (Sequence component is omitted);
-/// `Some(n)` for the collision-resolution pass.
+/// `seq` is `None` for the base-name pass (Sequence and AlphaSequence
+/// components are omitted);
+/// `Some(n)` for the collision-resolution pass.  `digests` holds the file's
+/// hex digest per algorithm used by a HashFragment component; a fragment
+/// whose digest is missing is omitted.  `sidecar_fields` holds the value
+/// per `(extension, field)` of a SidecarField component; a field without
+/// one is omitted.  `folder_index` is the file's position within its
+/// folder for FolderCounter components, which are omitted when it is
+/// `None`.
+#[allow(clippy::too_many_arguments)]
 fn build_name(
     schema: &RenameSchema,
     folder_name: &str,
@@ -67,177 +198,626 @@ fn build_name(
     created_dt: Option<&chrono::DateTime<chrono::Local>>,
     modified_dt: Option<&chrono::DateTime<chrono::Local>>,
     seq: Option<u32>,
+    digests: &HashMap<HashAlgorithm, String>,
+    sidecar_fields: &HashMap<(String, String), String>,
+    folder_index: Option<u32>,
 ) -> String {
     let now = chrono::Local::now();
 
-    // Precompute date/time strings with fallback to `now`.
-    let c = created_dt.unwrap_or(&now);
+    // Precompute date/time strings with fallback to `now`; a missing creation
+    // time follows the schema's `created_fallback`.
     let m = modified_dt.unwrap_or(&now);
+    let c = created_or_fallback(created_dt, m, &now, schema.created_fallback);
 
-    let c_date = c.format("%Y%m%d").to_string();
-    let c_time = c.format("%H%M%S").to_string();
-    let m_date = m.format("%Y%m%d").to_string();
-    let m_time = m.format("%H%M%S").to_string();
+    let c_date = c.map(|c| c.format(&schema.date_format).to_string());
+    let c_time = c.map(|c| c.format(&schema.time_format).to_string());
+    let m_date = m.format(&schema.date_format).to_string();
+    let m_time = m.format(&schema.time_format).to_string();
 
-    let parts: Vec<String> = schema
+    // Each part is paired with whether it is a sequence or counter, which
+    // `join_parts` attaches with the sequence separator.
+    let parts: Vec<(String, bool)> = schema
         .components
         .iter()
-        .filter_map(|comp| match comp {
-            RenameComponentDef::FolderName => Some(sanitize_filename_component(folder_name)),
-            RenameComponentDef::DateCreated => Some(c_date.clone()),
-            RenameComponentDef::DateModified => Some(m_date.clone()),
-            RenameComponentDef::TimeCreated => Some(c_time.clone()),
-            RenameComponentDef::TimeModified => Some(m_time.clone()),
-            RenameComponentDef::OriginalStem => Some(sanitize_filename_component(original_stem)),
-            RenameComponentDef::Literal { value } => {
-                let s = sanitize_filename_component(value);
-                if s.is_empty() {
-                    None
-                } else {
-                    Some(s)
+        .filter_map(|comp| {
+            let part = match comp {
+                RenameComponentDef::FolderName => {
+                    Some(sanitize_filename_component(folder_name, &schema.sanitize))
                 }
-            }
-            RenameComponentDef::Sequence { pad_width } => {
-                // Only emit the sequence token when seq is Some.
-                seq.map(|n| format!("{:0>width$}", n, width = pad_width))
-            }
+                RenameComponentDef::DateCreated => c_date.clone(),
+                RenameComponentDef::DateModified => Some(m_date.clone()),
+                RenameComponentDef::TimeCreated => c_time.clone(),
+                RenameComponentDef::TimeModified => Some(m_time.clone()),
+                RenameComponentDef::OriginalStem => {
+                    Some(sanitize_filename_component(original_stem, &schema.sanitize))
+                }
+                RenameComponentDef::Literal { value } => {
+                    let expanded =
+                        expand_placeholders(value, c.unwrap_or(m), original_stem, extension);
+                    let s = sanitize_filename_component(&expanded, &schema.sanitize);
+                    if s.is_empty() {
+                        None
+                    } else {
+                        Some(s)
+                    }
+                }
+                RenameComponentDef::Sequence { pad_width } => {
+                    // Only emit the sequence token when seq is Some.
+                    seq.map(|n| format!("{:0>width$}", n, width = pad_width))
+                }
+                RenameComponentDef::AlphaSequence { uppercase } => {
+                    seq.map(|n| alpha_sequence(n, *uppercase))
+                }
+                RenameComponentDef::StemWords { keep, from_end } => {
+                    stem_words(original_stem, *keep, *from_end, schema)
+                }
+                RenameComponentDef::HashFragment { algo, chars } => digests
+                    .get(algo)
+                    .map(|digest| digest.chars().take(*chars).collect()),
+                RenameComponentDef::FolderCounter {
+                    start,
+                    step,
+                    pad_width,
+                } => folder_index.map(|i| {
+                    let n = start.saturating_add(i.saturating_mul(*step));
+                    format!("{:0>width$}", n, width = pad_width)
+                }),
+                RenameComponentDef::SidecarField { extension, field } => sidecar_fields
+                    .get(&(extension.clone(), field.clone()))
+                    .map(|value| sanitize_filename_component(value, &schema.sanitize)),
+            };
+            let counter = matches!(
+                comp,
+                RenameComponentDef::Sequence { .. }
+                    | RenameComponentDef::AlphaSequence { .. }
+                    | RenameComponentDef::FolderCounter { .. }
+            );
+            part.map(|s| (s, counter))
         })
-        .filter(|s| !s.is_empty())
+        .filter(|(s, _)| !s.is_empty())
         .collect();
 
     let stem = if parts.is_empty() {
-        sanitize_filename_component(original_stem)
+        sanitize_filename_component(original_stem, &schema.sanitize)
+    } else {
+        join_parts(&parts, schema)
+    };
+    let stem = if schema.sanitize.guard_reserved_names {
+        guard_reserved_device_name(&stem)
     } else {
-        parts.join(&schema.separator)
+        stem
     };
 
     format!("{}{}", stem, extension)
 }
 
-pub fn auto_rename_paths(paths: &[PathBuf], schema: &RenameSchema) -> AutoRenameResult {
+/// The creation time date components use: `created` when known, otherwise
+/// what `fallback` names (`None` to leave the components out).
+fn created_or_fallback<'a>(
+    created: Option<&'a chrono::DateTime<chrono::Local>>,
+    modified: &'a chrono::DateTime<chrono::Local>,
+    now: &'a chrono::DateTime<chrono::Local>,
+    fallback: CreatedFallback,
+) -> Option<&'a chrono::DateTime<chrono::Local>> {
+    match (created, fallback) {
+        (Some(dt), _) => Some(dt),
+        (None, CreatedFallback::Now) => Some(now),
+        (None, CreatedFallback::Modified) => Some(modified),
+        (None, CreatedFallback::SkipComponent) => None,
+    }
+}
+
+/// Value at the dotted `field` path (`meta.title`, `authors.0`) of the JSON
+/// sidecar next to `source`, i.e. `source` with its extension replaced by
+/// `extension`.  Strings are taken as-is, numbers and booleans as written;
+/// `None` when the sidecar is missing or not JSON, or the field is absent,
+/// null, an object or an array.
+fn sidecar_field(source: &Path, extension: &str, field: &str) -> Option<String> {
+    let sidecar = source.with_extension(extension.trim_start_matches('.'));
+    if sidecar == source {
+        return None;
+    }
+    let text = std::fs::read_to_string(sidecar).ok()?;
+    let root: serde_json::Value = serde_json::from_str(&text).ok()?;
+    let value = field.split('.').try_fold(&root, |value, key| match value {
+        serde_json::Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+        _ => value.get(key),
+    })?;
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Join name parts with the schema separator.  A joint next to a sequence
+/// or counter part uses `sequence_separator` instead when it is set.
+fn join_parts(parts: &[(String, bool)], schema: &RenameSchema) -> String {
+    let counter_separator = schema
+        .sequence_separator
+        .as_deref()
+        .unwrap_or(&schema.separator);
+    let mut out = String::new();
+    for (i, (part, counter)) in parts.iter().enumerate() {
+        if i > 0 {
+            out.push_str(if *counter || parts[i - 1].1 {
+                counter_separator
+            } else {
+                &schema.separator
+            });
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+/// Substitute `{YYYY}`, `{MM}`, `{DD}` (from `date`), `{ext}` (without the
+/// dot) and `{stem}` in a literal component.  Unknown placeholders and
+/// unmatched braces are kept as written.
+fn expand_placeholders(
+    value: &str,
+    date: &chrono::DateTime<chrono::Local>,
+    stem: &str,
+    extension: &str,
+) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let tail = &rest[open..];
+        let Some(close) = tail.find('}') else {
+            rest = tail;
+            break;
+        };
+        let token = &tail[1..close];
+        if token.contains('{') {
+            // `{a{YYYY}`: only the innermost braces form a token.
+            out.push('{');
+            rest = &tail[1..];
+            continue;
+        }
+        match token {
+            "YYYY" => out.push_str(&date.format("%Y").to_string()),
+            "MM" => out.push_str(&date.format("%m").to_string()),
+            "DD" => out.push_str(&date.format("%d").to_string()),
+            "ext" => out.push_str(extension.trim_start_matches('.')),
+            "stem" => out.push_str(stem),
+            _ => out.push_str(&tail[..=close]),
+        }
+        rest = &tail[close + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Per-file inputs to [`build_name`], derived from the path and metadata.
+struct NameInputs {
+    parent: PathBuf,
+    folder_name: String,
+    original_stem: String,
+    extension: String,
+    created_dt: Option<chrono::DateTime<chrono::Local>>,
+    modified_dt: Option<chrono::DateTime<chrono::Local>>,
+    /// Content digests for HashFragment components; see [`Self::load_digests`].
+    digests: HashMap<HashAlgorithm, String>,
+    /// Values of SidecarField components by `(extension, field)`; see
+    /// [`Self::load_sidecar_fields`].
+    sidecar_fields: HashMap<(String, String), String>,
+    /// Position among the files renamed in the same folder; see
+    /// [`folder_indices`].
+    folder_index: Option<u32>,
+}
+
+impl NameInputs {
+    /// `None` when `source` has no parent directory.
+    fn new(source: &Path, meta: &std::fs::Metadata) -> Option<Self> {
+        Self::with_dates(
+            source,
+            meta.created().ok().map(|t| t.into()),
+            meta.modified().ok().map(|t| t.into()),
+        )
+    }
+
+    /// Like [`Self::new`], with the dates given rather than read from disk.
+    fn with_dates(
+        source: &Path,
+        created_dt: Option<chrono::DateTime<chrono::Local>>,
+        modified_dt: Option<chrono::DateTime<chrono::Local>>,
+    ) -> Option<Self> {
+        let parent = source.parent()?.to_path_buf();
+        // Names that are not UTF-8 still contribute their readable parts.
+        let folder_name = parent
+            .file_name()
+            .map_or("folder".into(), |n| n.to_string_lossy().into_owned());
+        let original_stem = source
+            .file_stem()
+            .map_or("file".into(), |s| s.to_string_lossy().into_owned());
+        let extension = source
+            .extension()
+            .map(|e| format!(".{}", e.to_string_lossy()))
+            .unwrap_or_default();
+
+        Some(Self {
+            parent,
+            folder_name,
+            original_stem,
+            extension,
+            created_dt,
+            modified_dt,
+            digests: HashMap::new(),
+            sidecar_fields: HashMap::new(),
+            folder_index: None,
+        })
+    }
+
+    /// Hash `source` once per algorithm used by a HashFragment in `schema`,
+    /// so sequence retries never re-read the file.
+    fn load_digests(&mut self, source: &Path, schema: &RenameSchema) -> std::io::Result<()> {
+        for comp in &schema.components {
+            if let RenameComponentDef::HashFragment { algo, .. } = comp {
+                if !self.digests.contains_key(algo) {
+                    let digest = hasher::hash_file(source, *algo, DigestEncoding::Hex, None)?;
+                    self.digests.insert(*algo, digest);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the field of every SidecarField component in `schema` once, so
+    /// sequence retries never re-read the sidecar.  Missing sidecars and
+    /// fields are left out.
+    fn load_sidecar_fields(&mut self, source: &Path, schema: &RenameSchema) {
+        for comp in &schema.components {
+            if let RenameComponentDef::SidecarField { extension, field } = comp {
+                let key = (extension.clone(), field.clone());
+                if let Entry::Vacant(slot) = self.sidecar_fields.entry(key) {
+                    if let Some(value) = sidecar_field(source, extension, field) {
+                        slot.insert(value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// With `schema.infer_missing_extension`, give an extensionless
+    /// `source` the extension of its sniffed type.
+    fn infer_extension(&mut self, source: &Path, schema: &RenameSchema) {
+        if schema.infer_missing_extension && self.extension.is_empty() {
+            if let Some(extension) = grouper::detect_extension(source) {
+                self.extension = format!(".{}", extension);
+            }
+        }
+    }
+
+    /// Full target path for sequence number `seq` (`None` = base pass).
+    fn target(&self, schema: &RenameSchema, seq: Option<u32>) -> PathBuf {
+        self.parent.join(self.name(schema, seq))
+    }
+
+    /// Target file name for sequence number `seq` (`None` = base pass).
+    fn name(&self, schema: &RenameSchema, seq: Option<u32>) -> String {
+        build_name(
+            schema,
+            &self.folder_name,
+            &self.original_stem,
+            &self.extension,
+            self.created_dt.as_ref(),
+            self.modified_dt.as_ref(),
+            seq,
+            &self.digests,
+            &self.sidecar_fields,
+            self.folder_index,
+        )
+    }
+}
+
+/// Base-pass target for `source` under `schema`, ignoring collisions.
+///
+/// `None` when the file's metadata or (for hash fragments) content cannot
+/// be read, or it has no parent.
+pub fn preview_base_target(source: &Path, schema: &RenameSchema) -> Option<PathBuf> {
+    let meta = std::fs::metadata(source).ok()?;
+    let mut inputs = NameInputs::new(source, &meta)?;
+    inputs.load_digests(source, schema).ok()?;
+    inputs.load_sidecar_fields(source, schema);
+    inputs.infer_extension(source, schema);
+    Some(inputs.target(schema, None))
+}
+
+/// File name `schema` gives `source` on its own (base pass, no sequence),
+/// for a live preview while the schema is edited.
+///
+/// Dates and hash fragments come from the file when it can be read, and a
+/// folder counter shows its first value.  A path that does not exist gets
+/// the current time for both dates and no hash fragments, so the preview
+/// still renders.  Empty when `source` has no parent.
+pub fn preview_name(source: &Path, schema: &RenameSchema) -> String {
+    let inputs = match std::fs::metadata(source) {
+        Ok(meta) => NameInputs::new(source, &meta).map(|mut inputs| {
+            // Unreadable content only costs the hash fragments.
+            let _ = inputs.load_digests(source, schema);
+            inputs.load_sidecar_fields(source, schema);
+            inputs.infer_extension(source, schema);
+            inputs
+        }),
+        Err(_) => {
+            let now = chrono::Local::now();
+            NameInputs::with_dates(source, Some(now), Some(now))
+        }
+    };
+    inputs.map_or_else(String::new, |mut inputs| {
+        inputs.folder_index = Some(0);
+        inputs.name(schema, None)
+    })
+}
+
+/// Rename every path in `paths` according to `schema`.
+///
+/// Files may take each other's names (swaps, rotations): a file whose path
+/// is another's target is parked under a temporary name first.
+///
+/// Files are processed in path order whatever the order of `paths`, so the
+/// same batch always gets the same sequence numbers, and `items` lists
+/// them in that order.  With `schema.keep_input_order` they are processed
+/// in the order of `paths` instead.
+///
+/// Symbolic links follow their target unless `schema.rename_symlinks` is
+/// set; then the link itself is renamed, even when its target is missing.
+///
+/// With `schema.skip_duplicate_content`, a file whose folder already holds
+/// the same content is skipped (see [`ContentIndex`]).
+///
+/// `progress` hears `on_rename(current, total, path)` before each file is
+/// processed.
+pub fn auto_rename_paths(
+    paths: &[PathBuf],
+    schema: &RenameSchema,
+    progress: &dyn ProgressSink,
+) -> AutoRenameResult {
+    auto_rename_paths_cancellable(paths, schema, progress, &AtomicBool::new(false))
+}
+
+/// Same as [`auto_rename_paths`], stopping once `cancel` is raised.
+///
+/// Renames cannot be rolled back, so a file being renamed when the flag
+/// goes up still finishes and no further file starts; the result lists
+/// what was done and sets `cancelled`.  Files parked under a temporary
+/// name for a swap are the exception: they still reach their targets,
+/// since their original paths may already be taken.  Raised while the
+/// batch is still being planned, nothing is renamed at all.
+pub fn auto_rename_paths_cancellable(
+    paths: &[PathBuf],
+    schema: &RenameSchema,
+    progress: &dyn ProgressSink,
+    cancel: &AtomicBool,
+) -> AutoRenameResult {
+    rename_batch(paths, schema, progress, &|| cancel.load(Ordering::Relaxed))
+}
+
+/// Nearest folder holding all of `targets`: their shared parent, or the
+/// deepest common ancestor of their parents when they span folders.
+/// `None` without targets or when they share no ancestor (other drives).
+pub fn common_parent_dir(targets: &[PathBuf]) -> Option<PathBuf> {
+    let mut parents = targets.iter().map(|t| t.parent().unwrap_or(Path::new("")));
+    let mut common = parents.next()?.to_path_buf();
+    for parent in parents {
+        while !parent.starts_with(&common) {
+            if !common.pop() {
+                return None;
+            }
+        }
+    }
+    (!common.as_os_str().is_empty()).then_some(common)
+}
+
+/// [`auto_rename_paths_cancellable`], asking `is_cancelled` instead of
+/// reading a flag.
+fn rename_batch(
+    paths: &[PathBuf],
+    schema: &RenameSchema,
+    progress: &dyn ProgressSink,
+    is_cancelled: &dyn Fn() -> bool,
+) -> AutoRenameResult {
     let mut items: Vec<AutoRenameItemDto> = Vec::new();
     let mut errors: Vec<AutoRenameErrorDto> = Vec::new();
     let mut skipped_count = 0usize;
+    let mut cancelled = false;
     let mut reserved_targets: HashSet<PathBuf> = HashSet::new();
+    let folder_indices = folder_indices(paths, schema.rename_symlinks);
+
+    // --- Plan: validate every file up front so the targets below can tell
+    // which existing paths this batch is about to vacate ---
+    let mut planned: Vec<(&PathBuf, NameInputs, PathBuf)> = Vec::new();
+    let mut ordered: Vec<&PathBuf> = paths.iter().collect();
+    if !schema.keep_input_order {
+        ordered.sort();
+    }
+    let total = ordered.len();
+    for (index, source) in ordered.into_iter().enumerate() {
+        if is_cancelled() {
+            cancelled = true;
+            planned.clear();
+            break;
+        }
+        progress.on_rename(index + 1, total, source);
 
-    'files: for source in paths {
-        if !source.exists() {
+        let exists = if schema.rename_symlinks {
+            path_occupied(source)
+        } else {
+            source.exists()
+        };
+        if !exists {
             skipped_count += 1;
             continue;
         }
 
-        let meta = match std::fs::metadata(source) {
+        let meta = if schema.rename_symlinks {
+            std::fs::symlink_metadata(source)
+        } else {
+            std::fs::metadata(source)
+        };
+        let meta = match meta {
             Ok(meta) => meta,
             Err(e) => {
                 errors.push(AutoRenameErrorDto {
-                    path: source.to_string_lossy().to_string(),
+                    path: wire_path::encode(source),
                     message: format!("Could not read metadata: {}", e),
                 });
                 continue;
             }
         };
-        if !meta.is_file() {
+        if !meta.is_file() && !meta.file_type().is_symlink() {
             skipped_count += 1;
             continue;
         }
 
-        let parent = match source.parent() {
-            Some(p) => p.to_path_buf(),
+        let mut inputs = match NameInputs::new(source, &meta) {
+            Some(inputs) => inputs,
             None => {
                 skipped_count += 1;
                 continue;
             }
         };
-
-        let folder_name = parent
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("folder");
-
-        let original_stem = source
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("file");
-
-        let extension = source
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|e| format!(".{}", e))
-            .unwrap_or_default();
-
-        // Derive created / modified datetimes from metadata.
-        let created_dt: Option<chrono::DateTime<chrono::Local>> =
-            meta.created().ok().map(|t| t.into());
-        let modified_dt: Option<chrono::DateTime<chrono::Local>> =
-            meta.modified().ok().map(|t| t.into());
+        inputs.folder_index = folder_indices.get(source).copied();
+        if let Err(e) = inputs.load_digests(source, schema) {
+            errors.push(AutoRenameErrorDto {
+                path: wire_path::encode(source),
+                message: format!("Could not hash file: {}", e),
+            });
+            continue;
+        }
+        inputs.load_sidecar_fields(source, schema);
+        inputs.infer_extension(source, schema);
 
         // --- Pass 1: try the base name (no sequence number) ---
-        let base_name = build_name(
-            schema,
-            folder_name,
-            original_stem,
-            &extension,
-            created_dt.as_ref(),
-            modified_dt.as_ref(),
-            None,
-        );
-        let base_candidate = parent.join(&base_name);
+        let base_candidate = inputs.target(schema, None);
 
         // If the file already has its target name, skip it.
         if base_candidate == *source {
             skipped_count += 1;
-            continue 'files;
+            continue;
         }
+        planned.push((source, inputs, base_candidate));
+    }
+
+    if schema.skip_duplicate_content {
+        let mut index = ContentIndex::new(paths);
+        let before = planned.len();
+        planned.retain(|(source, _, _)| !index.holds_copy_of(source));
+        skipped_count += before - planned.len();
+    }
+
+    // A path held by another file in the batch counts as free: that file
+    // moves out of the way first (swaps and rotations).
+    let vacated: HashSet<&Path> = planned.iter().map(|(s, _, _)| s.as_path()).collect();
+    let batch: HashSet<&Path> = paths.iter().map(PathBuf::as_path).collect();
+    let dir_names = DirNames::default();
+    let is_free = |candidate: &PathBuf, reserved: &HashSet<PathBuf>| {
+        (!dir_names.occupied(candidate) || vacated.contains(candidate.as_path()))
+            && !reserved.contains(candidate)
+    };
 
+    let mut moves: Vec<(&PathBuf, PathBuf)> = Vec::new();
+    // Occupied targets the overwrite policy replaces.
+    let mut overwrites: HashSet<PathBuf> = HashSet::new();
+    'files: for (source, inputs, base_candidate) in &planned {
         // --- Pass 2: if base is free, use it; otherwise loop with seq ---
-        let target = if !base_candidate.exists() && !reserved_targets.contains(&base_candidate) {
-            base_candidate
+        let target = if is_free(base_candidate, &reserved_targets) {
+            base_candidate.clone()
+        } else if schema.collision_policy == CollisionPolicy::Overwrite
+            && !reserved_targets.contains(base_candidate)
+            && !batch.contains(base_candidate.as_path())
+        {
+            // Only files outside the batch are replaced; two batch files
+            // sharing a base name, or one already holding the name, still
+            // get sequence numbers.
+            overwrites.insert(base_candidate.clone());
+            base_candidate.clone()
         } else {
-            // Find the first free sequence number.
-            let mut found: Option<PathBuf> = None;
-            for seq in 1u32..=10_000 {
-                let name = build_name(
-                    schema,
-                    folder_name,
-                    original_stem,
-                    &extension,
-                    created_dt.as_ref(),
-                    modified_dt.as_ref(),
-                    Some(seq),
-                );
-                let candidate = parent.join(&name);
-                if candidate == *source {
-                    continue;
-                }
-                if !candidate.exists() && !reserved_targets.contains(&candidate) {
-                    found = Some(candidate);
-                    break;
-                }
-            }
+            // Find the first free sequence number.  The file's own name is
+            // never a candidate.
+            let found = first_free_seq(MAX_SEQ, |&seq| {
+                let candidate = inputs.target(schema, Some(seq));
+                candidate != **source && is_free(&candidate, &reserved_targets)
+            });
             match found {
-                Some(p) => p,
+                Some(seq) => inputs.target(schema, Some(seq)),
                 None => {
                     errors.push(AutoRenameErrorDto {
-                        path: source.to_string_lossy().to_string(),
-                        message: "Could not find a free target name after 10000 attempts".into(),
+                        path: wire_path::encode(source),
+                        message: format!(
+                            "Could not find a free target name up to sequence {}",
+                            MAX_SEQ
+                        ),
                     });
                     continue 'files;
                 }
             }
         };
+        reserved_targets.insert(target.clone());
+        moves.push((source, target));
+    }
 
-        match std::fs::rename(source, &target) {
+    // --- Stage: files whose path is another file's target step aside to a
+    // temporary name so nothing is clobbered ---
+    let targets: HashSet<PathBuf> = moves.iter().map(|(_, t)| t.clone()).collect();
+    let mut staged: Vec<(&PathBuf, PathBuf, Option<PathBuf>)> = Vec::new();
+    for (source, target) in moves {
+        let mut temp = None;
+        if targets.contains(source) {
+            let temp_path = temp_rename_path(source, &reserved_targets);
+            if let Err(e) = move_file(source, &temp_path) {
+                errors.push(AutoRenameErrorDto {
+                    path: wire_path::encode(source),
+                    message: format!("Rename failed: {}", file_lock::describe(&e)),
+                });
+                continue;
+            }
+            reserved_targets.insert(temp_path.clone());
+            temp = Some(temp_path);
+        }
+        staged.push((source, target, temp));
+    }
+
+    // --- Commit: move everything to its final name; a staged file that
+    // cannot get there goes back to where it started ---
+    for (source, target, temp) in staged {
+        if temp.is_none() && (cancelled || is_cancelled()) {
+            cancelled = true;
+            continue;
+        }
+        let from = temp.as_deref().unwrap_or(source);
+        // Targets were checked while planning; a vacating file that failed
+        // to move may still be sitting there, and rename would overwrite it.
+        let result = if overwrites.contains(&target) {
+            replace_file(from, &target, &reserved_targets)
+        } else if path_occupied(&target) {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                "target already exists",
+            ))
+        } else {
+            move_file(from, &target)
+        };
+        match result {
             Ok(()) => {
-                reserved_targets.insert(target.clone());
                 items.push(AutoRenameItemDto {
-                    from_path: source.to_string_lossy().to_string(),
-                    to_path: target.to_string_lossy().to_string(),
+                    from_path: wire_path::encode(source),
+                    to_path: wire_path::encode(&target),
                 });
             }
             Err(e) => {
+                let mut message = format!("Rename failed: {}", file_lock::describe(&e));
+                if let Some(temp) = &temp {
+                    if source.exists() || move_file(temp, source).is_err() {
+                        message.push_str(&format!("; file left at {}", temp.display()));
+                    }
+                }
                 errors.push(AutoRenameErrorDto {
-                    path: source.to_string_lossy().to_string(),
-                    message: format!("Rename failed: {}", e),
+                    path: wire_path::encode(source),
+                    message,
                 });
             }
         }
@@ -249,92 +829,800 @@ pub fn auto_rename_paths(paths: &[PathBuf], schema: &RenameSchema) -> AutoRename
         error_count: errors.len(),
         items,
         errors,
+        cancelled,
     }
 }
 
-fn sanitize_filename_component(input: &str) -> String {
-    let mut out = String::with_capacity(input.len());
-    for ch in input.chars() {
-        if ch.is_ascii_control()
-            || matches!(ch, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*')
-        {
-            out.push('_');
-        } else {
-            out.push(ch);
+/// Contents of the folders a batch renames into, for
+/// `RenameSchema::skip_duplicate_content`.
+///
+/// Targets stay in their source's folder, so each folder is listed once and
+/// its files are hashed only when a batch file has the same size.  Batch
+/// files are left out of the listing and join it as they are visited, so of
+/// two identical batch files only the first is renamed.
+struct ContentIndex<'a> {
+    batch: HashSet<&'a Path>,
+    /// Per folder: size and path of every regular file known to be there.
+    folders: HashMap<PathBuf, Vec<(u64, PathBuf)>>,
+    digests: HashMap<PathBuf, Option<String>>,
+}
+
+impl<'a> ContentIndex<'a> {
+    fn new(batch: &'a [PathBuf]) -> Self {
+        Self {
+            batch: batch.iter().map(PathBuf::as_path).collect(),
+            folders: HashMap::new(),
+            digests: HashMap::new(),
         }
     }
 
-    let trimmed = out.trim().trim_matches('.');
-    if trimmed.is_empty() {
-        "folder".to_string()
-    } else {
-        trimmed.to_string()
+    /// True when the folder of `source` already holds a file with the
+    /// same content; otherwise `source` is recorded as being there.  Files
+    /// that cannot be read or hashed never count as copies.
+    fn holds_copy_of(&mut self, source: &Path) -> bool {
+        let (Some(folder), Ok(meta)) = (source.parent(), std::fs::metadata(source)) else {
+            return false;
+        };
+        let size = meta.len();
+        let same_size: Vec<PathBuf> = self
+            .folder(folder)
+            .iter()
+            .filter(|(other, _)| *other == size)
+            .map(|(_, path)| path.clone())
+            .collect();
+        if !same_size.is_empty() {
+            if let Some(digest) = self.digest(source) {
+                if same_size
+                    .iter()
+                    .any(|path| self.digest(path).as_ref() == Some(&digest))
+                {
+                    return true;
+                }
+            }
+        }
+        self.folder(folder).push((size, source.to_path_buf()));
+        false
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use tempfile::tempdir;
+    fn folder(&mut self, folder: &Path) -> &mut Vec<(u64, PathBuf)> {
+        let batch = &self.batch;
+        self.folders.entry(folder.to_path_buf()).or_insert_with(|| {
+            let Ok(entries) = std::fs::read_dir(folder) else {
+                return Vec::new();
+            };
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| !batch.contains(path.as_path()))
+                .filter_map(|path| {
+                    let meta = std::fs::metadata(&path).ok()?;
+                    meta.is_file().then_some((meta.len(), path))
+                })
+                .collect()
+        })
+    }
 
-    fn folder_stem_schema() -> RenameSchema {
-        RenameSchema {
-            components: vec![
-                RenameComponentDef::FolderName,
-                RenameComponentDef::OriginalStem,
-            ],
-            separator: "_".into(),
-        }
+    fn digest(&mut self, path: &Path) -> Option<String> {
+        self.digests
+            .entry(path.to_path_buf())
+            .or_insert_with(|| hasher::sha256_file(path, DigestEncoding::Hex).ok())
+            .clone()
     }
+}
 
-    fn folder_seq_schema() -> RenameSchema {
-        RenameSchema {
-            components: vec![
-                RenameComponentDef::FolderName,
-                RenameComponentDef::Sequence { pad_width: 3 },
-            ],
-            separator: "_".into(),
+/// Position of each file among the files in `paths` sharing its parent
+/// folder, counting in path order so FolderCounter numbering does not
+/// depend on selection order.  Paths that are not files (or, with
+/// `symlinks`, symbolic links) get no entry.
+fn folder_indices(paths: &[PathBuf], symlinks: bool) -> HashMap<PathBuf, u32> {
+    let mut sorted: Vec<&PathBuf> = paths
+        .iter()
+        .filter(|p| p.is_file() || (symlinks && p.is_symlink()))
+        .collect();
+    sorted.sort();
+    let mut next_index: HashMap<PathBuf, u32> = HashMap::new();
+    sorted
+        .into_iter()
+        .filter_map(|path| {
+            let slot = next_index.entry(path.parent()?.to_path_buf()).or_insert(0);
+            let index = *slot;
+            *slot += 1;
+            Some((path.clone(), index))
+        })
+        .collect()
+}
+
+/// Folder name files are sorted into by [`organize_by_date`].
+const DATED_FOLDER_FORMAT: &str = "%Y-%m";
+
+/// Move each of `paths` into a `YYYY-MM` folder under `base_dir`, dated by
+/// `basis` the way the date components of a rename see it.
+///
+/// File names are kept; a name already taken in the dated folder gets the
+/// first free `_1`, `_2`, ... suffix before its extension.  Folders are
+/// created as needed.  Files already in their dated folder are counted as
+/// `unchanged`; failures are reported per file and do not stop the rest.
+pub fn organize_by_date(paths: &[PathBuf], base_dir: &Path, basis: RecencyBasis) -> OrganizeReport {
+    let mut report = OrganizeReport::default();
+    for source in paths {
+        match organize_one(source, base_dir, basis) {
+            Ok(Some(target)) => report.moved.push(AutoRenameItemDto {
+                from_path: wire_path::encode(source),
+                to_path: wire_path::encode(&target),
+            }),
+            Ok(None) => report.unchanged += 1,
+            Err(e) => report.errors.push(PathErrorDto {
+                path: wire_path::encode(source),
+                message: e.to_string(),
+            }),
         }
     }
+    report
+}
 
-    // --- build_name unit tests ---
+/// Move `source` into its dated folder; the new path, or `None` when it is
+/// already there.
+fn organize_one(
+    source: &Path,
+    base_dir: &Path,
+    basis: RecencyBasis,
+) -> std::io::Result<Option<PathBuf>> {
+    let meta = std::fs::metadata(source)?;
+    if !meta.is_file() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "not a regular file",
+        ));
+    }
+    let name = source
+        .file_name()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "no file name"))?;
+    let dir = base_dir.join(
+        file_date(&meta, basis)
+            .format(DATED_FOLDER_FORMAT)
+            .to_string(),
+    );
+    if source.parent() == Some(dir.as_path()) {
+        return Ok(None);
+    }
+    std::fs::create_dir_all(&dir)?;
 
-    #[test]
-    fn test_build_name_no_seq_omits_sequence() {
-        let schema = RenameSchema {
-            components: vec![
-                RenameComponentDef::FolderName,
-                RenameComponentDef::Sequence { pad_width: 3 },
-            ],
-            separator: "_".into(),
-        };
-        let result = build_name(&schema, "Photos", "img001", ".jpg", None, None, None);
-        // Without a sequence number the Sequence component is omitted,
-        // so only FolderName remains.
-        assert_eq!(result, "Photos.jpg");
+    let mut target = dir.join(name);
+    let dir_names = DirNames::default();
+    if dir_names.occupied(&target) {
+        let with_seq = |n: u32| dir.join(name_with_seq(name, n));
+        let seq =
+            first_free_seq(MAX_SEQ, |&n| !dir_names.occupied(&with_seq(n))).ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::AlreadyExists,
+                    format!(
+                        "no free name for {} in {}",
+                        Path::new(name).display(),
+                        dir.display()
+                    ),
+                )
+            })?;
+        target = with_seq(seq);
     }
+    move_file(source, &target)?;
+    Ok(Some(target))
+}
 
-    #[test]
-    fn test_build_name_with_seq() {
-        let schema = folder_seq_schema();
-        let result = build_name(&schema, "Photos", "img001", ".jpg", None, None, Some(7));
-        assert_eq!(result, "Photos_007.jpg");
+/// `name` with `_n` added before its extension, keeping the bytes of
+/// names that are not valid Unicode.
+fn name_with_seq(name: &OsStr, n: u32) -> OsString {
+    let name = Path::new(name);
+    let mut with_seq = name.file_stem().unwrap_or(name.as_os_str()).to_os_string();
+    with_seq.push(format!("_{}", n));
+    if let Some(extension) = name.extension() {
+        with_seq.push(".");
+        with_seq.push(extension);
     }
+    with_seq
+}
 
-    #[test]
-    fn test_build_name_literal() {
-        let schema = RenameSchema {
-            components: vec![
-                RenameComponentDef::Literal {
-                    value: "backup".into(),
-                },
-                RenameComponentDef::OriginalStem,
-            ],
-            separator: "-".into(),
-        };
-        let result = build_name(&schema, "folder", "report", ".pdf", None, None, None);
-        assert_eq!(result, "backup-report.pdf");
+/// Local date of a file under `basis`, as the date components of a rename
+/// would show it.
+fn file_date(meta: &std::fs::Metadata, basis: RecencyBasis) -> chrono::DateTime<chrono::Local> {
+    let now = chrono::Local::now();
+    let modified: Option<chrono::DateTime<chrono::Local>> = meta.modified().ok().map(|t| t.into());
+    let modified = modified.unwrap_or(now);
+    match basis {
+        RecencyBasis::Modified => modified,
+        RecencyBasis::Created => {
+            let created: Option<chrono::DateTime<chrono::Local>> =
+                meta.created().ok().map(|t| t.into());
+            *created_or_fallback(created.as_ref(), &modified, &now, CreatedFallback::Modified)
+                .unwrap_or(&modified)
+        }
+    }
+}
+
+/// True when anything, even a broken symbolic link, sits at `path`.
+fn path_occupied(path: &Path) -> bool {
+    path.symlink_metadata().is_ok()
+}
+
+/// Highest sequence number tried when resolving a name collision.
+const MAX_SEQ: u32 = 10_000;
+
+/// First number in `1..=max` that `is_free` accepts.
+///
+/// Every number is tried in turn rather than bisected: a binary search
+/// assumes the taken numbers form one block, and would skip gaps left by
+/// deleted files and holes between names the batch reserves.  `is_free`
+/// should therefore be cheap; see [`DirNames`] for checking candidate
+/// paths without a file system call each.
+fn first_free_seq(max: u32, is_free: impl FnMut(&u32) -> bool) -> Option<u32> {
+    (1..=max).find(is_free)
+}
+
+/// Names present in each directory, read once per directory.
+///
+/// Lets a collision search test thousands of candidate names against one
+/// `read_dir` instead of a `stat` each.  Names not listed are still checked
+/// on disk, which catches files created since the listing and names that
+/// differ only in case on case-insensitive volumes.
+struct DirNames<'a> {
+    listed: RefCell<HashMap<PathBuf, HashSet<OsString>>>,
+    list_dir: &'a dyn Fn(&Path) -> HashSet<OsString>,
+    on_disk: &'a dyn Fn(&Path) -> bool,
+}
+
+impl Default for DirNames<'_> {
+    fn default() -> Self {
+        DirNames::with(&list_dir_names, &path_occupied)
+    }
+}
+
+impl<'a> DirNames<'a> {
+    /// Cache reading directories with `list_dir` and checking unlisted
+    /// names with `on_disk`.
+    fn with(
+        list_dir: &'a dyn Fn(&Path) -> HashSet<OsString>,
+        on_disk: &'a dyn Fn(&Path) -> bool,
+    ) -> Self {
+        DirNames {
+            listed: RefCell::default(),
+            list_dir,
+            on_disk,
+        }
+    }
+
+    /// True when anything, even a broken symbolic link, sits at `path`.
+    fn occupied(&self, path: &Path) -> bool {
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            return (self.on_disk)(path);
+        };
+        let listed = self
+            .listed
+            .borrow_mut()
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| (self.list_dir)(dir))
+            .contains(name);
+        listed || (self.on_disk)(path)
+    }
+}
+
+/// Names of the entries of `dir`; empty when it cannot be read.
+fn list_dir_names(dir: &Path) -> HashSet<OsString> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.file_name()).collect())
+        .unwrap_or_default()
+}
+
+/// Unused hidden sibling of `source` to park it under during a swap.
+fn temp_rename_path(source: &Path, reserved: &HashSet<PathBuf>) -> PathBuf {
+    let name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    (0u32..)
+        .map(|n| source.with_file_name(format!(".{}.rename-tmp{}", name, n)))
+        .find(|p| !path_occupied(p) && !reserved.contains(p))
+        .expect("an unused temp name exists")
+}
+
+/// Move `source` to `target`, falling back to copy + delete when the two
+/// live on different filesystems.  The fallback copy gets the source's
+/// mtime (and mode bits on Unix) back, which a plain rename keeps for free.
+fn move_file(source: &Path, target: &Path) -> std::io::Result<()> {
+    match std::fs::rename(source, target) {
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            copy_then_remove(source, target)
+        }
+        other => other,
+    }
+}
+
+/// Replace the existing `target` with `source`.
+///
+/// `source` first moves to a temp name beside `target` (copied there when
+/// it lives on another filesystem), then the temp file is renamed over
+/// `target` in one step, so `target` is never missing or half-written.
+/// When a step fails, `target` is left as it was and `source` is put back.
+fn replace_file(source: &Path, target: &Path, reserved: &HashSet<PathBuf>) -> std::io::Result<()> {
+    replace_file_with(source, target, reserved, &|from, to| {
+        std::fs::rename(from, to)
+    })
+}
+
+/// [`replace_file`] with the final rename over `target` injected, so tests
+/// can make it fail.
+fn replace_file_with(
+    source: &Path,
+    target: &Path,
+    reserved: &HashSet<PathBuf>,
+    swap: &dyn Fn(&Path, &Path) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let temp = temp_rename_path(target, reserved);
+    move_file(source, &temp)?;
+    if let Err(e) = swap(&temp, target) {
+        if move_file(&temp, source).is_err() {
+            return Err(std::io::Error::new(
+                e.kind(),
+                format!("{}; file left at {}", e, temp.display()),
+            ));
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Copy `source` to `target` preserving mtime and permissions, then remove
+/// `source`.  A half-finished copy is cleaned up so no stray file is left.
+fn copy_then_remove(source: &Path, target: &Path) -> std::io::Result<()> {
+    let meta = std::fs::metadata(source)?;
+    let copied = std::fs::copy(source, target).and_then(|_| {
+        let mtime = filetime::FileTime::from_last_modification_time(&meta);
+        filetime::set_file_mtime(target, mtime)?;
+        // fs::copy already carries permissions over; set them explicitly so
+        // the mode survives targets that apply a umask on create.
+        std::fs::set_permissions(target, meta.permissions())
+    });
+    if let Err(e) = copied {
+        let _ = std::fs::remove_file(target);
+        return Err(e);
+    }
+    std::fs::remove_file(source)
+}
+
+/// Characters that are never allowed in a file name on Windows (and `/` on
+/// every platform).  Enforced regardless of [`SanitizeOptions`].
+fn is_reserved_char(ch: char) -> bool {
+    ch.is_ascii_control() || matches!(ch, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*')
+}
+
+/// Keep `keep` words of `stem` (split on space, `_`, `-`) from the start or
+/// end, rejoined with the schema separator.  `None` when nothing is left.
+fn stem_words(stem: &str, keep: usize, from_end: bool, schema: &RenameSchema) -> Option<String> {
+    let words: Vec<&str> = stem
+        .split([' ', '_', '-'])
+        .filter(|w| !w.is_empty())
+        .collect();
+    let kept = if from_end {
+        &words[words.len().saturating_sub(keep)..]
+    } else {
+        &words[..keep.min(words.len())]
+    };
+    if kept.is_empty() {
+        return None;
+    }
+    let joined = kept
+        .iter()
+        .map(|w| sanitize_filename_component(w, &schema.sanitize))
+        .collect::<Vec<_>>()
+        .join(&schema.separator);
+    Some(joined)
+}
+
+/// Device names Windows refuses as a file name, with or without extension.
+const RESERVED_DEVICE_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Append `_` to a stem whose base (the part before the first `.`) is a
+/// reserved Windows device name, e.g. `CON` -> `CON_`, `nul.tar` -> `nul_.tar`.
+fn guard_reserved_device_name(stem: &str) -> String {
+    let base_len = stem.find('.').unwrap_or(stem.len());
+    let base = stem[..base_len].trim_end();
+    if RESERVED_DEVICE_NAMES
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(base))
+    {
+        format!("{}_{}", &stem[..base_len], &stem[base_len..])
+    } else {
+        stem.to_string()
+    }
+}
+
+fn sanitize_filename_component(input: &str, options: &SanitizeOptions) -> String {
+    // A reserved replacement would defeat the purpose; fall back to `_`.
+    let replacement = if is_reserved_char(options.replacement) {
+        '_'
+    } else {
+        options.replacement
+    };
+
+    let mut out = String::with_capacity(input.len());
+    for ch in input.chars() {
+        let ch = if is_reserved_char(ch) {
+            replacement
+        } else {
+            ch
+        };
+        if options.collapse_repeats && ch == replacement && out.ends_with(replacement) {
+            continue;
+        }
+        out.push(ch);
+    }
+
+    let trimmed = if options.strip_trailing_dots {
+        out.trim().trim_matches('.')
+    } else {
+        out.trim()
+    };
+    if trimmed.is_empty() {
+        "folder".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::NoProgress;
+    use crate::types::{DEFAULT_DATE_FORMAT, DEFAULT_TIME_FORMAT};
+    use std::cell::{Cell, RefCell};
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn folder_stem_schema() -> RenameSchema {
+        RenameSchema {
+            components: vec![
+                RenameComponentDef::FolderName,
+                RenameComponentDef::OriginalStem,
+            ],
+            separator: "_".into(),
+            created_fallback: CreatedFallback::Now,
+            date_format: DEFAULT_DATE_FORMAT.into(),
+            time_format: DEFAULT_TIME_FORMAT.into(),
+            sanitize: SanitizeOptions::default(),
+            rename_symlinks: false,
+            skip_duplicate_content: false,
+            infer_missing_extension: false,
+            collision_policy: CollisionPolicy::Sequence,
+            strict: false,
+            keep_input_order: false,
+            sequence_separator: None,
+        }
+    }
+
+    fn folder_seq_schema() -> RenameSchema {
+        RenameSchema {
+            components: vec![
+                RenameComponentDef::FolderName,
+                RenameComponentDef::Sequence { pad_width: 3 },
+            ],
+            separator: "_".into(),
+            created_fallback: CreatedFallback::Now,
+            date_format: DEFAULT_DATE_FORMAT.into(),
+            time_format: DEFAULT_TIME_FORMAT.into(),
+            sanitize: SanitizeOptions::default(),
+            rename_symlinks: false,
+            skip_duplicate_content: false,
+            infer_missing_extension: false,
+            collision_policy: CollisionPolicy::Sequence,
+            strict: false,
+            keep_input_order: false,
+            sequence_separator: None,
+        }
+    }
+
+    #[test]
+    fn test_preview_name_reads_file_dates() {
+        let dir = tempdir().unwrap();
+        let parent = dir.path().join("photos");
+        fs::create_dir(&parent).unwrap();
+        let source = parent.join("a.jpg");
+        fs::write(&source, b"img").unwrap();
+        let mtime = filetime::FileTime::from_unix_time(1_700_000_000, 0);
+        filetime::set_file_mtime(&source, mtime).unwrap();
+
+        let mut schema = folder_stem_schema();
+        schema
+            .components
+            .insert(1, RenameComponentDef::DateModified);
+        let date = chrono::DateTime::from_timestamp(1_700_000_000, 0)
+            .unwrap()
+            .with_timezone(&chrono::Local)
+            .format(DEFAULT_DATE_FORMAT);
+        assert_eq!(
+            preview_name(&source, &schema),
+            format!("photos_{}_a.jpg", date)
+        );
+        // Previewing never renames.
+        assert!(source.exists());
+    }
+
+    #[test]
+    fn test_preview_name_for_missing_file_uses_placeholder_dates() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("trip").join("missing.png");
+        let mut schema = folder_seq_schema();
+        schema.components.push(RenameComponentDef::DateCreated);
+
+        let today = chrono::Local::now().format(DEFAULT_DATE_FORMAT).to_string();
+        let preview = preview_name(&source, &schema);
+        assert_eq!(preview, format!("trip_{}.png", today));
+    }
+
+    // --- build_name unit tests ---
+
+    #[test]
+    fn test_build_name_no_seq_omits_sequence() {
+        let schema = RenameSchema {
+            components: vec![
+                RenameComponentDef::FolderName,
+                RenameComponentDef::Sequence { pad_width: 3 },
+            ],
+            separator: "_".into(),
+            ..RenameSchema::default()
+        };
+        let result = build_name(
+            &schema,
+            "Photos",
+            "img001",
+            ".jpg",
+            None,
+            None,
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        );
+        // Without a sequence number the Sequence component is omitted,
+        // so only FolderName remains.
+        assert_eq!(result, "Photos.jpg");
+    }
+
+    #[test]
+    fn test_literal_placeholders_expand() {
+        use chrono::TimeZone;
+        let schema = RenameSchema {
+            components: vec![RenameComponentDef::Literal {
+                value: "backup_{YYYY}-{MM}_{stem}.{ext}_{foo}".into(),
+            }],
+            separator: "_".into(),
+            ..RenameSchema::default()
+        };
+        let created = chrono::Local.with_ymd_and_hms(2021, 3, 9, 8, 0, 0).unwrap();
+        let modified = chrono::Local.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap();
+        let result = build_name(
+            &schema,
+            "Photos",
+            "beach",
+            ".jpg",
+            Some(&created),
+            Some(&modified),
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        );
+        assert_eq!(result, "backup_2021-03_beach.jpg_{foo}.jpg");
+    }
+
+    #[test]
+    fn test_literal_unmatched_brace_is_kept() {
+        let now = chrono::Local::now();
+        assert_eq!(expand_placeholders("a{b", &now, "s", ".x"), "a{b");
+        assert_eq!(expand_placeholders("{stem}{", &now, "s", ".x"), "s{");
+        assert_eq!(expand_placeholders("{a{ext}", &now, "s", ".x"), "{ax");
+    }
+
+    #[test]
+    fn test_build_name_with_seq() {
+        let schema = folder_seq_schema();
+        let result = build_name(
+            &schema,
+            "Photos",
+            "img001",
+            ".jpg",
+            None,
+            None,
+            Some(7),
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        );
+        assert_eq!(result, "Photos_007.jpg");
+    }
+
+    #[test]
+    fn test_sequence_separator_attaches_sequence_only() {
+        let mut schema = folder_seq_schema();
+        schema.components = vec![
+            RenameComponentDef::Literal { value: "a".into() },
+            RenameComponentDef::Literal { value: "b".into() },
+            RenameComponentDef::Sequence { pad_width: 3 },
+        ];
+        schema.separator = "-".into();
+        schema.sequence_separator = Some("_".into());
+        let build = |seq| {
+            build_name(
+                &schema,
+                "Photos",
+                "img001",
+                ".jpg",
+                None,
+                None,
+                seq,
+                &HashMap::new(),
+                &HashMap::new(),
+                None,
+            )
+        };
+        assert_eq!(build(Some(1)), "a-b_001.jpg");
+        assert_eq!(build(None), "a-b.jpg");
+    }
+
+    /// `doc` plus the `meta.title` of `scan.pdf`'s JSON sidecar, which
+    /// holds `sidecar` when given.
+    fn sidecar_name(sidecar: Option<&str>) -> String {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("scan.pdf");
+        fs::write(&source, b"%PDF").unwrap();
+        if let Some(json) = sidecar {
+            fs::write(dir.path().join("scan.json"), json).unwrap();
+        }
+        let mut schema = folder_seq_schema();
+        schema.components = vec![
+            RenameComponentDef::Literal {
+                value: "doc".into(),
+            },
+            RenameComponentDef::SidecarField {
+                extension: "json".into(),
+                field: "meta.title".into(),
+            },
+        ];
+        preview_name(&source, &schema)
+    }
+
+    #[test]
+    fn test_sidecar_field_is_sanitized_into_name() {
+        assert_eq!(
+            sidecar_name(Some(r#"{"meta": {"title": "Tax return: 2023"}}"#)),
+            "doc_Tax return_ 2023.pdf"
+        );
+    }
+
+    #[test]
+    fn test_missing_sidecar_omits_component() {
+        assert_eq!(sidecar_name(None), "doc.pdf");
+    }
+
+    #[test]
+    fn test_missing_sidecar_field_omits_component() {
+        assert_eq!(
+            sidecar_name(Some(r#"{"meta": {"author": "me"}}"#)),
+            "doc.pdf"
+        );
+    }
+
+    #[test]
+    fn test_alpha_sequence_counts_in_bijective_base_26() {
+        assert_eq!(alpha_sequence(1, false), "a");
+        assert_eq!(alpha_sequence(26, false), "z");
+        assert_eq!(alpha_sequence(27, false), "aa");
+        assert_eq!(alpha_sequence(28, false), "ab");
+        assert_eq!(alpha_sequence(52, false), "az");
+        assert_eq!(alpha_sequence(53, false), "ba");
+        assert_eq!(alpha_sequence(702, false), "zz");
+        assert_eq!(alpha_sequence(703, false), "aaa");
+        assert_eq!(alpha_sequence(27, true), "AA");
+    }
+
+    #[test]
+    fn test_build_name_alpha_sequence_omitted_on_base_pass() {
+        let mut schema = folder_seq_schema();
+        schema.components[1] = RenameComponentDef::AlphaSequence { uppercase: true };
+        let build = |seq| {
+            build_name(
+                &schema,
+                "Photos",
+                "img001",
+                ".jpg",
+                None,
+                None,
+                seq,
+                &HashMap::new(),
+                &HashMap::new(),
+                None,
+            )
+        };
+        assert_eq!(build(None), "Photos.jpg");
+        assert_eq!(build(Some(2)), "Photos_B.jpg");
+    }
+
+    #[test]
+    fn test_build_name_literal() {
+        let schema = RenameSchema {
+            components: vec![
+                RenameComponentDef::Literal {
+                    value: "backup".into(),
+                },
+                RenameComponentDef::OriginalStem,
+            ],
+            separator: "-".into(),
+            ..RenameSchema::default()
+        };
+        let result = build_name(
+            &schema,
+            "folder",
+            "report",
+            ".pdf",
+            None,
+            None,
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        );
+        assert_eq!(result, "backup-report.pdf");
+    }
+
+    /// `DateCreated` + `OriginalStem` for a file without a creation time.
+    fn created_fallback_name(fallback: CreatedFallback) -> String {
+        use chrono::TimeZone;
+        let modified = chrono::Local.timestamp_opt(1700000000, 0).single().unwrap();
+        let schema = RenameSchema {
+            components: vec![
+                RenameComponentDef::DateCreated,
+                RenameComponentDef::OriginalStem,
+            ],
+            separator: "_".into(),
+            created_fallback: fallback,
+            ..RenameSchema::default()
+        };
+        build_name(
+            &schema,
+            "f",
+            "stem",
+            ".txt",
+            None,
+            Some(&modified),
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_created_fallback_now_uses_current_date() {
+        let today = chrono::Local::now().format("%Y%m%d").to_string();
+        assert_eq!(
+            created_fallback_name(CreatedFallback::Now),
+            format!("{today}_stem.txt")
+        );
+    }
+
+    #[test]
+    fn test_created_fallback_modified_uses_mtime() {
+        use chrono::TimeZone;
+        let modified = chrono::Local.timestamp_opt(1700000000, 0).single().unwrap();
+        let expected = format!("{}_stem.txt", modified.format("%Y%m%d"));
+        assert_eq!(created_fallback_name(CreatedFallback::Modified), expected);
+    }
+
+    #[test]
+    fn test_created_fallback_skip_omits_component() {
+        assert_eq!(
+            created_fallback_name(CreatedFallback::SkipComponent),
+            "stem.txt"
+        );
     }
 
     #[test]
@@ -344,15 +1632,203 @@ mod tests {
         let schema = RenameSchema {
             components: vec![RenameComponentDef::DateCreated],
             separator: "_".into(),
+            ..RenameSchema::default()
         };
-        let result = build_name(&schema, "f", "stem", ".txt", Some(&dt), None, None);
+        let result = build_name(
+            &schema,
+            "f",
+            "stem",
+            ".txt",
+            Some(&dt),
+            None,
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        );
         // Should contain a date-like string (8 digits).
         assert!(result.len() > 4);
         assert!(result.ends_with(".txt"));
     }
 
+    #[test]
+    fn test_build_name_stem_words_from_end() {
+        let schema = RenameSchema {
+            components: vec![RenameComponentDef::StemWords {
+                keep: 2,
+                from_end: true,
+            }],
+            separator: "-".into(),
+            ..RenameSchema::default()
+        };
+        let result = build_name(
+            &schema,
+            "f",
+            "IMG_2023_summer_beach",
+            ".jpg",
+            None,
+            None,
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        );
+        assert_eq!(result, "summer-beach.jpg");
+    }
+
+    #[test]
+    fn test_build_name_stem_words_from_start() {
+        let schema = RenameSchema {
+            components: vec![
+                RenameComponentDef::StemWords {
+                    keep: 1,
+                    from_end: false,
+                },
+                RenameComponentDef::FolderName,
+            ],
+            separator: "_".into(),
+            ..RenameSchema::default()
+        };
+        let result = build_name(
+            &schema,
+            "Trip",
+            "IMG 2023-summer",
+            ".jpg",
+            None,
+            None,
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        );
+        assert_eq!(result, "IMG_Trip.jpg");
+    }
+
+    #[test]
+    fn test_build_name_stem_words_empty_is_omitted() {
+        let schema = RenameSchema {
+            components: vec![
+                RenameComponentDef::FolderName,
+                RenameComponentDef::StemWords {
+                    keep: 0,
+                    from_end: true,
+                },
+            ],
+            separator: "_".into(),
+            ..RenameSchema::default()
+        };
+        let result = build_name(
+            &schema,
+            "Trip",
+            "a_b",
+            ".jpg",
+            None,
+            None,
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        );
+        assert_eq!(result, "Trip.jpg");
+    }
+
+    #[test]
+    fn test_common_parent_dir_of_nested_and_sibling_targets() {
+        let paths = |list: &[&str]| list.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            common_parent_dir(&paths(&["/photos/a.jpg", "/photos/b.jpg"])),
+            Some(PathBuf::from("/photos"))
+        );
+        assert_eq!(
+            common_parent_dir(&paths(&["/photos/a.jpg", "/photos/2024/03/b.jpg"])),
+            Some(PathBuf::from("/photos"))
+        );
+        assert_eq!(
+            common_parent_dir(&paths(&["/photos/2024/a.jpg", "/photos/2025/b.jpg"])),
+            Some(PathBuf::from("/photos"))
+        );
+        // Components are compared whole: "/photos/ab" is not under "/photos/a".
+        assert_eq!(
+            common_parent_dir(&paths(&["/photos/a/x.jpg", "/photos/ab/y.jpg"])),
+            Some(PathBuf::from("/photos"))
+        );
+        assert_eq!(
+            common_parent_dir(&paths(&["/a/x.jpg", "/b/y.jpg"])),
+            Some(PathBuf::from("/"))
+        );
+        assert_eq!(common_parent_dir(&paths(&["x.jpg", "/b/y.jpg"])), None);
+        assert_eq!(common_parent_dir(&[]), None);
+    }
+
     // --- rename integration tests ---
 
+    #[test]
+    fn test_name_with_seq_goes_before_the_extension() {
+        assert_eq!(name_with_seq(OsStr::new("jan.jpg"), 2), "jan_2.jpg");
+        assert_eq!(name_with_seq(OsStr::new("notes"), 1), "notes_1");
+        assert_eq!(name_with_seq(OsStr::new(".env"), 1), ".env_1");
+        assert_eq!(name_with_seq(OsStr::new("a.tar.gz"), 3), "a.tar_3.gz");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_name_with_seq_keeps_non_unicode_bytes() {
+        use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+        let name = OsStr::from_bytes(b"caf\xe9.txt");
+        assert_eq!(name_with_seq(name, 1).into_vec(), b"caf\xe9_1.txt");
+    }
+
+    #[test]
+    fn test_organize_by_date_sorts_files_into_month_folders() {
+        use chrono::TimeZone;
+
+        let src = tempdir().unwrap();
+        let base = tempdir().unwrap();
+        fs::create_dir(src.path().join("other")).unwrap();
+        let files = [
+            ("jan.jpg", (2024, 1, 15)),
+            ("other/jan.jpg", (2024, 1, 20)),
+            ("mar.jpg", (2024, 3, 10)),
+        ];
+        let mut paths = Vec::new();
+        for (name, (y, m, d)) in files {
+            let path = src.path().join(name);
+            fs::write(&path, name).unwrap();
+            let when = chrono::Local.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap();
+            filetime::set_file_mtime(
+                &path,
+                filetime::FileTime::from_unix_time(when.timestamp(), 0),
+            )
+            .unwrap();
+            paths.push(path);
+        }
+
+        let report = organize_by_date(&paths, base.path(), RecencyBasis::Modified);
+
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(report.moved.len(), 3);
+        let jan = base.path().join("2024-01");
+        assert_eq!(fs::read_to_string(jan.join("jan.jpg")).unwrap(), "jan.jpg");
+        assert_eq!(
+            fs::read_to_string(jan.join("jan_1.jpg")).unwrap(),
+            "other/jan.jpg"
+        );
+        let mar = base.path().join("2024-03").join("mar.jpg");
+        assert_eq!(fs::read_to_string(&mar).unwrap(), "mar.jpg");
+        assert!(paths.iter().all(|p| !p.exists()));
+
+        // Running again over the sorted files leaves them where they are.
+        let report = organize_by_date(
+            std::slice::from_ref(&mar),
+            base.path(),
+            RecencyBasis::Modified,
+        );
+        assert_eq!((report.moved.len(), report.unchanged), (0, 1));
+        assert!(mar.exists());
+    }
+
     #[test]
     fn test_rename_preserves_extension_and_uses_schema() {
         let dir = tempdir().unwrap();
@@ -361,18 +1837,167 @@ mod tests {
         let source = parent.join("old_name.TXT");
         fs::write(&source, b"hello").unwrap();
 
-        let schema = folder_stem_schema();
-        let result = auto_rename_paths(std::slice::from_ref(&source), &schema);
-        assert_eq!(result.renamed_count, 1);
-        assert_eq!(result.error_count, 0);
-        assert_eq!(result.skipped_count, 0);
+        let schema = folder_stem_schema();
+        let result = auto_rename_paths(std::slice::from_ref(&source), &schema, &NoProgress);
+        assert_eq!(result.renamed_count, 1);
+        assert_eq!(result.error_count, 0);
+        assert_eq!(result.skipped_count, 0);
+
+        let target = PathBuf::from(&result.items[0].to_path);
+        assert!(target.exists());
+        assert!(!source.exists());
+        assert_eq!(target.extension().and_then(|e| e.to_str()), Some("TXT"));
+        let name = target.file_name().unwrap().to_string_lossy().to_string();
+        assert!(name.starts_with("Invoices_old_name"));
+    }
+
+    fn hash_fragment_schema() -> RenameSchema {
+        RenameSchema {
+            components: vec![RenameComponentDef::HashFragment {
+                algo: HashAlgorithm::Sha256,
+                chars: 8,
+            }],
+            separator: "_".into(),
+            created_fallback: CreatedFallback::Now,
+            date_format: DEFAULT_DATE_FORMAT.into(),
+            time_format: DEFAULT_TIME_FORMAT.into(),
+            sanitize: SanitizeOptions::default(),
+            rename_symlinks: false,
+            skip_duplicate_content: false,
+            infer_missing_extension: false,
+            collision_policy: CollisionPolicy::Sequence,
+            strict: false,
+            keep_input_order: false,
+            sequence_separator: None,
+        }
+    }
+
+    #[test]
+    fn test_hash_fragment_same_for_identical_content() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.txt");
+        let b = dir.path().join("b.txt");
+        let c = dir.path().join("c.txt");
+        fs::write(&a, b"hello world").unwrap();
+        fs::write(&b, b"hello world").unwrap();
+        fs::write(&c, b"something else").unwrap();
+
+        let schema = hash_fragment_schema();
+        let name = |p: &Path| {
+            preview_base_target(p, &schema)
+                .unwrap()
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string()
+        };
+        assert_eq!(name(&a), "b94d27b9.txt");
+        assert_eq!(name(&a), name(&b));
+        assert_ne!(name(&a), name(&c));
+    }
+
+    #[test]
+    fn test_hash_fragment_collision_gets_sequence() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.txt");
+        let b = dir.path().join("b.txt");
+        fs::write(&a, b"same").unwrap();
+        fs::write(&b, b"same").unwrap();
+
+        let mut schema = hash_fragment_schema();
+        schema
+            .components
+            .push(RenameComponentDef::Sequence { pad_width: 2 });
+        let result = auto_rename_paths(&[a, b], &schema, &NoProgress);
+        assert_eq!(result.renamed_count, 2);
+        let digest =
+            hasher::sha256_file(Path::new(&result.items[0].to_path), DigestEncoding::Hex).unwrap();
+        let fragment = &digest[..8];
+        let names: Vec<String> = result
+            .items
+            .iter()
+            .map(|item| {
+                PathBuf::from(&item.to_path)
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(
+            names,
+            vec![format!("{fragment}.txt"), format!("{fragment}_01.txt")]
+        );
+    }
+
+    #[test]
+    fn test_folder_counter_restarts_in_each_folder() {
+        let dir = tempdir().unwrap();
+        let trip = dir.path().join("trip");
+        let home = dir.path().join("home");
+        fs::create_dir(&trip).unwrap();
+        fs::create_dir(&home).unwrap();
+        let paths = vec![trip.join("b.jpg"), home.join("x.jpg"), trip.join("a.jpg")];
+        for path in &paths {
+            fs::write(path, b"img").unwrap();
+        }
+        let schema = RenameSchema {
+            components: vec![
+                RenameComponentDef::FolderName,
+                RenameComponentDef::FolderCounter {
+                    start: 10,
+                    step: 5,
+                    pad_width: 3,
+                },
+            ],
+            separator: "_".into(),
+            ..RenameSchema::default()
+        };
+
+        let result = auto_rename_paths(&paths, &schema, &NoProgress);
+
+        assert_eq!(result.renamed_count, 3);
+        // Numbering follows path order within a folder, not selection order.
+        assert_eq!(fs::read(trip.join("trip_010.jpg")).unwrap(), b"img");
+        assert!(trip.join("trip_015.jpg").exists());
+        assert!(home.join("home_010.jpg").exists());
+        assert!(!trip.join("a.jpg").exists());
+    }
+
+    #[test]
+    fn test_rename_swaps_names_within_batch() {
+        use chrono::TimeZone;
+        let dir = tempdir().unwrap();
+        // Each file is named after the other's modification date, so a
+        // DateModified schema asks for a straight swap.
+        let a = dir.path().join("20200101.txt");
+        let b = dir.path().join("20210101.txt");
+        fs::write(&a, b"a").unwrap();
+        fs::write(&b, b"b").unwrap();
+        for (path, year) in [(&a, 2021), (&b, 2020)] {
+            let mtime = chrono::Local
+                .with_ymd_and_hms(year, 1, 1, 12, 0, 0)
+                .unwrap();
+            filetime::set_file_mtime(
+                path,
+                filetime::FileTime::from_unix_time(mtime.timestamp(), 0),
+            )
+            .unwrap();
+        }
+        let schema = RenameSchema {
+            components: vec![RenameComponentDef::DateModified],
+            separator: "_".into(),
+            ..RenameSchema::default()
+        };
+
+        let result = auto_rename_paths(&[a.clone(), b.clone()], &schema, &NoProgress);
 
-        let target = PathBuf::from(&result.items[0].to_path);
-        assert!(target.exists());
-        assert!(!source.exists());
-        assert_eq!(target.extension().and_then(|e| e.to_str()), Some("TXT"));
-        let name = target.file_name().unwrap().to_string_lossy().to_string();
-        assert!(name.starts_with("Invoices_old_name"));
+        assert_eq!(result.error_count, 0, "{:?}", result.errors);
+        assert_eq!(result.renamed_count, 2);
+        assert_eq!(fs::read(&a).unwrap(), b"b");
+        assert_eq!(fs::read(&b).unwrap(), b"a");
+        // No temp files are left behind.
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
     }
 
     #[test]
@@ -391,7 +2016,7 @@ mod tests {
         // Use a schema that includes a Sequence component so collision
         // resolution can generate a distinct name.
         let schema = folder_seq_schema();
-        let result = auto_rename_paths(&[source], &schema);
+        let result = auto_rename_paths(&[source], &schema, &NoProgress);
         assert_eq!(result.renamed_count, 1);
         assert_eq!(result.error_count, 0);
         let target = PathBuf::from(&result.items[0].to_path);
@@ -401,6 +2026,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_overwrite_policy_replaces_existing_target() {
+        let dir = tempdir().unwrap();
+        let parent = dir.path().join("photos");
+        fs::create_dir(&parent).unwrap();
+        let source = parent.join("a.jpg");
+        fs::write(&source, b"img").unwrap();
+        let existing = parent.join("photos.jpg");
+        fs::write(&existing, b"taken").unwrap();
+
+        let mut schema = folder_seq_schema();
+        schema.collision_policy = CollisionPolicy::Overwrite;
+        let result = auto_rename_paths(std::slice::from_ref(&source), &schema, &NoProgress);
+
+        assert_eq!(result.renamed_count, 1);
+        assert_eq!(result.items[0].to_path, existing.to_string_lossy());
+        assert_eq!(fs::read(&existing).unwrap(), b"img");
+        assert!(!source.exists());
+        assert_eq!(fs::read_dir(&parent).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_overwrite_policy_spares_files_in_the_batch() {
+        let dir = tempdir().unwrap();
+        let parent = dir.path().join("photos");
+        fs::create_dir(&parent).unwrap();
+        let source = parent.join("a.jpg");
+        fs::write(&source, b"img").unwrap();
+        // Already named per the schema, so it is skipped, not moved away.
+        let named = parent.join("photos.jpg");
+        fs::write(&named, b"kept").unwrap();
+
+        let mut schema = folder_seq_schema();
+        schema.collision_policy = CollisionPolicy::Overwrite;
+        let result = auto_rename_paths(&[source, named.clone()], &schema, &NoProgress);
+
+        assert_eq!(result.renamed_count, 1);
+        assert_eq!(result.skipped_count, 1);
+        assert_eq!(
+            result.items[0].to_path,
+            parent.join("photos_001.jpg").to_string_lossy()
+        );
+        assert_eq!(fs::read(&named).unwrap(), b"kept");
+    }
+
+    #[test]
+    fn test_failed_replace_keeps_original_target_intact() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("new.txt");
+        let target = dir.path().join("report.txt");
+        fs::write(&source, b"new content").unwrap();
+        fs::write(&target, b"original content").unwrap();
+
+        // The process "dies" after the source reached its temp name but
+        // before it was renamed over the target.
+        let crash = |from: &Path, _: &Path| {
+            assert!(from.exists());
+            assert_eq!(fs::read(&target).unwrap(), b"original content");
+            Err(std::io::Error::other("simulated crash"))
+        };
+        let err = replace_file_with(&source, &target, &HashSet::new(), &crash).unwrap_err();
+
+        assert_eq!(err.to_string(), "simulated crash");
+        assert_eq!(fs::read(&target).unwrap(), b"original content");
+        assert_eq!(fs::read(&source).unwrap(), b"new content");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
     #[test]
     fn test_rename_collision_with_seq_schema() {
         let dir = tempdir().unwrap();
@@ -417,7 +2110,7 @@ mod tests {
         fs::write(&seq1_conflict, b"taken2").unwrap();
 
         let schema = folder_seq_schema();
-        let result = auto_rename_paths(&[source], &schema);
+        let result = auto_rename_paths(&[source], &schema, &NoProgress);
         assert_eq!(result.renamed_count, 1);
         let target = PathBuf::from(&result.items[0].to_path);
         assert_eq!(
@@ -426,40 +2119,631 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_first_free_seq_finds_the_first_gap() {
+        assert_eq!(first_free_seq(MAX_SEQ, |n| ![1, 2, 4].contains(n)), Some(3));
+        assert_eq!(first_free_seq(MAX_SEQ, |&n| n > 5_000), Some(5_001));
+        assert_eq!(first_free_seq(MAX_SEQ, |&n| n > 9_999), Some(10_000));
+        assert_eq!(first_free_seq(MAX_SEQ, |_| false), None);
+    }
+
+    #[test]
+    fn test_collision_search_lists_folder_once() {
+        let folder = Path::new("photos");
+        let taken: HashSet<OsString> = (1..=5_000)
+            .map(|n| OsString::from(format!("beach_{n}.jpg")))
+            .collect();
+        let listings = Cell::new(0);
+        let stats = Cell::new(0);
+        let list_dir = |_: &Path| {
+            listings.set(listings.get() + 1);
+            taken.clone()
+        };
+        let on_disk = |_: &Path| {
+            stats.set(stats.get() + 1);
+            false
+        };
+        let dir_names = DirNames::with(&list_dir, &on_disk);
+
+        let seq = first_free_seq(MAX_SEQ, |n| {
+            !dir_names.occupied(&folder.join(format!("beach_{n}.jpg")))
+        });
+
+        assert_eq!(seq, Some(5_001));
+        assert_eq!(listings.get(), 1);
+        // Listed names need no disk check; only the free one is confirmed.
+        assert_eq!(stats.get(), 1);
+    }
+
+    #[test]
+    fn test_rename_collision_takes_first_gap_in_sequences() {
+        let dir = tempdir().unwrap();
+        let parent = dir.path().join("photos");
+        fs::create_dir(&parent).unwrap();
+        let source = parent.join("a.jpg");
+        fs::write(&source, b"img").unwrap();
+        fs::write(parent.join("photos.jpg"), b"taken").unwrap();
+        for seq in [1, 2, 4] {
+            fs::write(parent.join(format!("photos_{:03}.jpg", seq)), b"taken").unwrap();
+        }
+
+        let result = auto_rename_paths(&[source], &folder_seq_schema(), &NoProgress);
+        assert_eq!(result.renamed_count, 1);
+        assert_eq!(
+            result.items[0].to_path,
+            parent.join("photos_003.jpg").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_rename_collision_skips_block_of_taken_sequences() {
+        let dir = tempdir().unwrap();
+        let parent = dir.path().join("photos");
+        fs::create_dir(&parent).unwrap();
+        let source = parent.join("a.jpg");
+        fs::write(&source, b"img").unwrap();
+        fs::write(parent.join("photos.jpg"), b"taken").unwrap();
+        for seq in 1..=300 {
+            fs::write(parent.join(format!("photos_{:03}.jpg", seq)), b"taken").unwrap();
+        }
+
+        let result = auto_rename_paths(&[source], &folder_seq_schema(), &NoProgress);
+        assert_eq!(result.renamed_count, 1);
+        assert_eq!(
+            result.items[0].to_path,
+            parent.join("photos_301.jpg").to_string_lossy()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rename_symlinks_moves_broken_link() {
+        let dir = tempdir().unwrap();
+        let parent = dir.path().join("links");
+        fs::create_dir(&parent).unwrap();
+        let link = parent.join("dangling.txt");
+        let missing_target = dir.path().join("gone.txt");
+        std::os::unix::fs::symlink(&missing_target, &link).unwrap();
+
+        // By default a broken link counts as missing.
+        let mut schema = folder_stem_schema();
+        let result = auto_rename_paths(std::slice::from_ref(&link), &schema, &NoProgress);
+        assert_eq!(result.renamed_count, 0);
+        assert_eq!(result.skipped_count, 1);
+
+        schema.rename_symlinks = true;
+        let result = auto_rename_paths(std::slice::from_ref(&link), &schema, &NoProgress);
+        assert_eq!(result.renamed_count, 1);
+        assert_eq!(result.error_count, 0);
+        let renamed = parent.join("links_dangling.txt");
+        assert!(link.symlink_metadata().is_err());
+        assert!(renamed.symlink_metadata().unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_link(&renamed).unwrap(), missing_target);
+    }
+
+    #[test]
+    fn test_collision_sequence_ignores_input_order() {
+        let schema = folder_seq_schema();
+        let names = ["c.jpg", "a.jpg", "d.jpg", "b.jpg"];
+        let run = |order: &[usize]| {
+            let dir = tempdir().unwrap();
+            let parent = dir.path().join("photos");
+            fs::create_dir(&parent).unwrap();
+            for name in names {
+                fs::write(parent.join(name), name.as_bytes()).unwrap();
+            }
+            let paths: Vec<PathBuf> = order.iter().map(|&i| parent.join(names[i])).collect();
+            let result = auto_rename_paths(&paths, &schema, &NoProgress);
+            assert_eq!(result.renamed_count, 4);
+            result
+                .items
+                .iter()
+                .map(|item| {
+                    let name = |p: &str| Path::new(p).file_name().unwrap().to_owned();
+                    (name(&item.from_path), name(&item.to_path))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let first = run(&[0, 1, 2, 3]);
+        assert_eq!(first, run(&[3, 2, 1, 0]));
+        assert_eq!(first, run(&[1, 3, 0, 2]));
+        assert_eq!(first[0].0, "a.jpg");
+        assert_eq!(first[0].1, "photos.jpg");
+    }
+
+    #[test]
+    fn test_keep_input_order_numbers_files_as_given() {
+        let dir = tempdir().unwrap();
+        let parent = dir.path().join("photos");
+        fs::create_dir(&parent).unwrap();
+        let paths: Vec<PathBuf> = ["b.jpg", "a.jpg"]
+            .iter()
+            .map(|name| parent.join(name))
+            .collect();
+        for path in &paths {
+            fs::write(path, b"img").unwrap();
+        }
+        let mut schema = folder_seq_schema();
+        schema.keep_input_order = true;
+
+        let result = auto_rename_paths(&paths, &schema, &NoProgress);
+        assert_eq!(result.renamed_count, 2);
+        assert_eq!(result.items[0].from_path, paths[0].to_string_lossy());
+        assert_eq!(
+            result.items[0].to_path,
+            parent.join("photos.jpg").to_string_lossy()
+        );
+        assert_eq!(
+            result.items[1].to_path,
+            parent.join("photos_001.jpg").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_rename_reports_progress_per_file() {
+        let dir = tempdir().unwrap();
+        let parent = dir.path().join("docs");
+        fs::create_dir(&parent).unwrap();
+        let paths: Vec<PathBuf> = ["a.txt", "b.txt", "c.txt"]
+            .iter()
+            .map(|name| {
+                let path = parent.join(name);
+                fs::write(&path, name.as_bytes()).unwrap();
+                path
+            })
+            .collect();
+
+        #[derive(Default)]
+        struct Recorder(RefCell<Vec<(usize, usize, PathBuf)>>);
+        impl ProgressSink for Recorder {
+            fn on_rename(&self, current: usize, total: usize, path: &Path) {
+                self.0
+                    .borrow_mut()
+                    .push((current, total, path.to_path_buf()));
+            }
+        }
+        let recorder = Recorder::default();
+        let schema = folder_stem_schema();
+        let result = auto_rename_paths(&paths, &schema, &recorder);
+        assert_eq!(result.renamed_count, 3);
+        let calls = recorder.0.into_inner();
+        let counts: Vec<(usize, usize)> = calls.iter().map(|(c, t, _)| (*c, *t)).collect();
+        assert_eq!(counts, vec![(1, 3), (2, 3), (3, 3)]);
+        assert_eq!(calls[1].2, paths[1]);
+    }
+
+    #[test]
+    fn test_cancel_after_first_rename_leaves_the_rest() {
+        let dir = tempdir().unwrap();
+        let folder = dir.path().join("out");
+        fs::create_dir(&folder).unwrap();
+        let paths: Vec<PathBuf> = ["a.txt", "b.txt", "c.txt"]
+            .iter()
+            .map(|name| {
+                let path = folder.join(name);
+                fs::write(&path, name.as_bytes()).unwrap();
+                path
+            })
+            .collect();
+        let schema = folder_stem_schema();
+
+        // Raised before anything starts: nothing is renamed.
+        let cancel = AtomicBool::new(true);
+        let result = auto_rename_paths_cancellable(&paths, &schema, &NoProgress, &cancel);
+        assert!(result.cancelled);
+        assert_eq!(result.renamed_count, 0);
+        assert!(paths.iter().all(|p| p.exists()));
+
+        // Raised as soon as the first rename has landed.
+        let first_target = folder.join("out_a.txt");
+        let result = rename_batch(&paths, &schema, &NoProgress, &|| first_target.exists());
+        assert!(result.cancelled);
+        assert_eq!(result.renamed_count, 1);
+        assert_eq!(result.items[0].to_path, first_target.to_string_lossy());
+        assert_eq!(result.error_count, 0);
+        assert!(!paths[0].exists());
+        assert!(paths[1].exists() && paths[2].exists());
+        assert!(!folder.join("out_b.txt").exists());
+        assert!(!folder.join("out_c.txt").exists());
+    }
+
+    #[test]
+    fn test_infer_missing_extension_from_content() {
+        const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        let dir = tempdir().unwrap();
+        let folder = dir.path().join("out");
+        fs::create_dir(&folder).unwrap();
+        let png = folder.join("photo");
+        let text = folder.join("notes");
+        fs::write(&png, PNG_HEADER).unwrap();
+        fs::write(&text, b"plain words").unwrap();
+
+        let mut schema = folder_stem_schema();
+        let result = auto_rename_paths(std::slice::from_ref(&png), &schema, &NoProgress);
+        assert_eq!(
+            result.items[0].to_path,
+            folder.join("out_photo").to_string_lossy()
+        );
+
+        schema.infer_missing_extension = true;
+        let renamed = folder.join("out_photo");
+        assert_eq!(preview_name(&renamed, &schema), "out_out_photo.png");
+        let result = auto_rename_paths(&[renamed, text], &schema, &NoProgress);
+        let targets: Vec<&str> = result.items.iter().map(|i| i.to_path.as_str()).collect();
+        assert_eq!(
+            targets,
+            [
+                folder.join("out_notes").to_string_lossy(),
+                folder.join("out_out_photo.png").to_string_lossy(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skip_duplicate_content_leaves_copies_alone() {
+        let dir = tempdir().unwrap();
+        let folder = dir.path().join("out");
+        fs::create_dir(&folder).unwrap();
+        fs::write(folder.join("kept.txt"), b"same as kept").unwrap();
+        let copy = folder.join("a.txt");
+        fs::write(&copy, b"same as kept").unwrap();
+        let first = folder.join("b.txt");
+        let second = folder.join("c.txt");
+        fs::write(&first, b"batch twins").unwrap();
+        fs::write(&second, b"batch twins").unwrap();
+        // Same size as the twins, different content.
+        let other = folder.join("d.txt");
+        fs::write(&other, b"batch other").unwrap();
+
+        let mut schema = folder_stem_schema();
+        schema.skip_duplicate_content = true;
+        let paths = vec![copy.clone(), first, second.clone(), other];
+        let result = auto_rename_paths(&paths, &schema, &NoProgress);
+
+        assert_eq!(result.error_count, 0);
+        assert_eq!(result.skipped_count, 2);
+        let renamed: Vec<&str> = result.items.iter().map(|i| i.to_path.as_str()).collect();
+        assert_eq!(
+            renamed,
+            [
+                folder.join("out_b.txt").to_string_lossy(),
+                folder.join("out_d.txt").to_string_lossy(),
+            ]
+        );
+        assert!(copy.exists());
+        assert!(second.exists());
+    }
+
     #[test]
     fn test_missing_file_is_counted_as_skipped() {
         let dir = tempdir().unwrap();
         let missing = dir.path().join("missing.txt");
         let schema = folder_stem_schema();
-        let result = auto_rename_paths(&[missing], &schema);
+        let result = auto_rename_paths(&[missing], &schema, &NoProgress);
         assert_eq!(result.renamed_count, 0);
         assert_eq!(result.skipped_count, 1);
         assert_eq!(result.error_count, 0);
     }
 
+    #[test]
+    fn test_validate_schema_rejects_path_separator() {
+        let mut schema = folder_stem_schema();
+        assert!(validate_schema(&schema).is_ok());
+        schema.separator = "/".into();
+        assert!(validate_schema(&schema).is_err());
+    }
+
+    #[test]
+    fn test_strict_schema_rejects_reserved_literal() {
+        let mut schema = folder_stem_schema();
+        schema.components.insert(
+            0,
+            RenameComponentDef::Literal {
+                value: "10:30".into(),
+            },
+        );
+        let build = |schema: &RenameSchema| {
+            build_name(
+                schema,
+                "Photos",
+                "report",
+                ".pdf",
+                None,
+                None,
+                None,
+                &HashMap::new(),
+                &HashMap::new(),
+                None,
+            )
+        };
+
+        assert!(validate_schema(&schema).is_ok());
+        assert_eq!(build(&schema), "10_30_Photos_report.pdf");
+
+        schema.strict = true;
+        let err = validate_schema(&schema).unwrap_err();
+        assert!(err.contains("'10:30'"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_schema_rejects_bad_date_formats() {
+        let mut schema = folder_stem_schema();
+        schema.date_format = "%Y-%Q".into();
+        assert!(validate_schema(&schema).is_err());
+        schema.date_format = "%Y/%m/%d".into();
+        assert!(validate_schema(&schema).is_err());
+        schema.date_format = "%d%m%Y".into();
+        schema.time_format = "%H:%M".into();
+        assert!(validate_schema(&schema).is_err());
+    }
+
+    /// `DateCreated` + `TimeCreated` for 2024-04-15 09:30:05 local time.
+    fn formatted_name(date_format: &str, time_format: &str) -> String {
+        use chrono::TimeZone;
+        let created = chrono::Local
+            .with_ymd_and_hms(2024, 4, 15, 9, 30, 5)
+            .single()
+            .unwrap();
+        let schema = RenameSchema {
+            components: vec![
+                RenameComponentDef::DateCreated,
+                RenameComponentDef::TimeCreated,
+            ],
+            separator: "_".into(),
+            date_format: date_format.into(),
+            time_format: time_format.into(),
+            ..RenameSchema::default()
+        };
+        assert!(validate_schema(&schema).is_ok());
+        build_name(
+            &schema,
+            "f",
+            "stem",
+            ".jpg",
+            Some(&created),
+            None,
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_date_and_time_formats_follow_schema() {
+        assert_eq!(
+            formatted_name(DEFAULT_DATE_FORMAT, DEFAULT_TIME_FORMAT),
+            "20240415_093005.jpg"
+        );
+        assert_eq!(formatted_name("%d%m%Y", "%Hh%M"), "15042024_09h30.jpg");
+        assert_eq!(
+            formatted_name("%Y-%m-%d", "%H.%M.%S"),
+            "2024-04-15_09.30.05.jpg"
+        );
+    }
+
     #[test]
     fn test_sanitize_filename_component() {
-        let value = sanitize_filename_component("bad:name<>");
+        let value = sanitize_filename_component("bad:name<>", &SanitizeOptions::default());
         assert_eq!(value, "bad_name__");
     }
 
+    #[test]
+    fn test_reserved_device_name_is_made_legal() {
+        assert_eq!(guard_reserved_device_name("CON"), "CON_");
+        assert_eq!(guard_reserved_device_name("con"), "con_");
+        assert_eq!(guard_reserved_device_name("Com1"), "Com1_");
+        assert_eq!(guard_reserved_device_name("nul.tar"), "nul_.tar");
+    }
+
+    #[test]
+    fn test_normal_stem_is_untouched_by_device_guard() {
+        assert_eq!(guard_reserved_device_name("report"), "report");
+        assert_eq!(guard_reserved_device_name("CONSOLE"), "CONSOLE");
+        assert_eq!(guard_reserved_device_name("COM10"), "COM10");
+    }
+
+    #[test]
+    fn test_build_name_guards_reserved_literal() {
+        let mut schema = RenameSchema {
+            components: vec![RenameComponentDef::Literal {
+                value: "prn".into(),
+            }],
+            separator: "_".into(),
+            ..RenameSchema::default()
+        };
+        let result = build_name(
+            &schema,
+            "f",
+            "stem",
+            ".txt",
+            None,
+            None,
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        );
+        assert_eq!(result, "prn_.txt");
+
+        schema.sanitize.guard_reserved_names = false;
+        let result = build_name(
+            &schema,
+            "f",
+            "stem",
+            ".txt",
+            None,
+            None,
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        );
+        assert_eq!(result, "prn.txt");
+    }
+
+    #[test]
+    fn test_sanitize_custom_replacement_char() {
+        let options = SanitizeOptions {
+            replacement: '-',
+            ..SanitizeOptions::default()
+        };
+        assert_eq!(
+            sanitize_filename_component("bad:name<>", &options),
+            "bad-name--"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_collapse_repeats() {
+        let options = SanitizeOptions {
+            replacement: '-',
+            collapse_repeats: true,
+            ..SanitizeOptions::default()
+        };
+        assert_eq!(sanitize_filename_component("a::b", &options), "a-b");
+    }
+
+    #[test]
+    fn test_sanitize_reserved_replacement_falls_back() {
+        let options = SanitizeOptions {
+            replacement: '/',
+            ..SanitizeOptions::default()
+        };
+        assert_eq!(sanitize_filename_component("a:b", &options), "a_b");
+    }
+
+    #[test]
+    fn test_sanitize_keeps_dots_when_not_stripping() {
+        let options = SanitizeOptions {
+            strip_trailing_dots: false,
+            ..SanitizeOptions::default()
+        };
+        assert_eq!(sanitize_filename_component("name..", &options), "name..");
+        assert_eq!(
+            sanitize_filename_component("name..", &SanitizeOptions::default()),
+            "name"
+        );
+    }
+
     #[test]
     fn test_file_type_filter_is_case_insensitive() {
-        assert!(matches_file_type_preset(Path::new("photo.JPEG"), "images"));
+        let none = FileTypePresets::new();
+        assert!(matches_file_type_preset(
+            Path::new("photo.JPEG"),
+            "images",
+            &none
+        ));
         assert!(matches_file_type_preset(
             Path::new("report.PDF"),
-            "documents"
+            "documents",
+            &none
+        ));
+        assert!(!matches_file_type_preset(
+            Path::new("photo.JPEG"),
+            "audio",
+            &none
         ));
-        assert!(!matches_file_type_preset(Path::new("photo.JPEG"), "audio"));
     }
 
     #[test]
     fn test_file_type_filter_all_matches_anything() {
-        assert!(matches_file_type_preset(Path::new("whatever.bin"), "all"));
-        assert!(matches_file_type_preset(Path::new("README"), "all"));
+        let none = FileTypePresets::new();
+        assert!(matches_file_type_preset(
+            Path::new("whatever.bin"),
+            "all",
+            &none
+        ));
+        assert!(matches_file_type_preset(Path::new("README"), "all", &none));
+        assert!(matches_file_type_preset(
+            Path::new("song.mp3"),
+            "unknown-preset",
+            &none
+        ));
+    }
+
+    #[test]
+    fn test_custom_file_type_preset_matches_its_extensions() {
+        let custom = FileTypePresets::from([
+            (
+                "Raw_Photos".to_string(),
+                vec!["cr2".into(), ".NEF".into(), "arw".into()],
+            ),
+            ("audio".to_string(), vec!["mid".into()]),
+        ]);
+        assert_eq!(
+            normalize_file_type_preset(" raw_photos ", &custom),
+            "raw_photos"
+        );
+        assert!(matches_file_type_preset(
+            Path::new("IMG_1.CR2"),
+            "raw_photos",
+            &custom
+        ));
+        assert!(matches_file_type_preset(
+            Path::new("dsc.nef"),
+            "RAW_PHOTOS",
+            &custom
+        ));
+        assert!(!matches_file_type_preset(
+            Path::new("dsc.jpg"),
+            "raw_photos",
+            &custom
+        ));
+        assert!(!matches_file_type_preset(
+            Path::new("cr2"),
+            "raw_photos",
+            &custom
+        ));
+
+        // A custom preset shadows the built-in of the same name...
         assert!(matches_file_type_preset(
+            Path::new("tune.mid"),
+            "audio",
+            &custom
+        ));
+        assert!(!matches_file_type_preset(
             Path::new("song.mp3"),
-            "unknown-preset"
+            "audio",
+            &custom
+        ));
+        // ...while the other built-ins are unaffected.
+        assert_eq!(normalize_file_type_preset("Images", &custom), "images");
+        assert!(matches_file_type_preset(
+            Path::new("photo.jpg"),
+            "images",
+            &custom
         ));
+        assert_eq!(normalize_file_type_preset("nope", &custom), "all");
+    }
+
+    #[test]
+    fn test_copy_then_remove_preserves_mtime_and_mode() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("a.txt");
+        fs::write(&source, b"payload").unwrap();
+        let old = filetime::FileTime::from_unix_time(1_500_000_000, 0);
+        filetime::set_file_mtime(&source, old).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&source, fs::Permissions::from_mode(0o640)).unwrap();
+        }
+        let dest_dir = dir.path().join("moved");
+        fs::create_dir(&dest_dir).unwrap();
+        let target = dest_dir.join("b.txt");
+
+        copy_then_remove(&source, &target).unwrap();
+
+        assert!(!source.exists());
+        assert_eq!(fs::read(&target).unwrap(), b"payload");
+        let meta = fs::metadata(&target).unwrap();
+        assert_eq!(filetime::FileTime::from_last_modification_time(&meta), old);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(meta.permissions().mode() & 0o777, 0o640);
+        }
     }
 }
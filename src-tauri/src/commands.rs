@@ -1,15 +1,38 @@
-use std::path::PathBuf;
-use std::time::Instant;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use crate::archive;
 use crate::autorenamer;
+use crate::delete_history;
 use crate::deleter;
+use crate::error::CommandError;
+use crate::file_lock;
 use crate::grouper;
+use crate::hasher;
+use crate::keeper;
+use crate::last_scan;
+use crate::manifest;
+use crate::progress::{
+    NoProgress, PhaseWeights, ProgressSink, WeightedProgress, WorkPhase, MIME_SNIFF_BYTES,
+};
 use crate::scanner;
 use crate::settings::{self, AppSettings};
 use crate::types::{
-    self, AutoRenameCandidateDto, AutoRenameResult, AutoRenameScanResult, DuplicateGroup,
-    FileEntryDto, GroupingConfig, RenameSchema, ScanProgress, ScanResult,
+    self, AutoDedupeGroupReport, AutoDedupeReport, AutoRenameCandidateDto, AutoRenameResult,
+    AutoRenameScanResult, CanonicalizeReport, CompareResult, CriteriaSet, DeleteHistoryEntry,
+    DeleteMode, DeleteSummary, DigestEncoding, DuplicateGroup, FileEntry, FileEntryDto,
+    FileTypePresets, FolderPrecheck, GroupingConfig, HashAlgorithm, KeepStrategy, KnownKey,
+    ManifestExport, MtimeFilter, NameCompare, OrganizeReport, PathErrorDto, RecencyBasis,
+    RemoveEmptyDirsResult, RenameSchema, RestoreSummary, SameFolderPolicy, ScanDiff, ScanMeta,
+    ScanOptions, ScanProgress, ScanResult, ScanSummary, ScanTimeEstimate, SymlinkHashPolicy,
+    VerifyEntry,
 };
+use crate::wire_path;
 use tauri::Emitter;
 
 /// Return the default downloads folder path.
@@ -20,243 +43,1318 @@ pub fn cmd_get_default_folder() -> String {
         .to_string()
 }
 
+/// Whether this build can look inside archives; without it the
+/// `peek_archives` scan option does nothing and is not offered.
+#[tauri::command]
+pub fn cmd_can_peek_archives() -> bool {
+    archive::PEEK_SUPPORTED
+}
+
 /// Load settings from disk.
 #[tauri::command]
-pub fn cmd_get_settings() -> Result<AppSettings, String> {
+pub fn cmd_get_settings() -> Result<AppSettings, CommandError> {
     Ok(settings::load_settings())
 }
 
 /// Save settings to disk.
 #[tauri::command]
-pub fn cmd_save_settings(settings: AppSettings) -> Result<(), String> {
-    settings::save_settings(&settings)
+pub fn cmd_save_settings(settings: AppSettings) -> Result<(), CommandError> {
+    settings::save_settings(&settings).map_err(CommandError::Io)
+}
+
+/// Return the recent folders that still exist.
+///
+/// Stale entries are dropped and the pruned list is saved back.
+#[tauri::command]
+pub fn cmd_get_recent_folders() -> Result<Vec<String>, CommandError> {
+    settings::load_recent_folders_at(&settings::settings_path()).map_err(CommandError::Io)
+}
+
+/// Move `path` to the front of the recent folders and return the new list.
+#[tauri::command]
+pub fn cmd_add_recent_folder(path: String) -> Result<Vec<String>, CommandError> {
+    settings::add_recent_folder_at(&settings::settings_path(), &path).map_err(CommandError::Io)
+}
+
+/// Hide the duplicate group with `signature` (a `DuplicateGroup::signature`)
+/// from later scans.
+#[tauri::command]
+pub fn cmd_ignore_group(signature: String) -> Result<(), CommandError> {
+    settings::ignore_group_at(&settings::settings_path(), &signature).map_err(CommandError::Io)
+}
+
+/// Show every ignored group again.  Returns how many were ignored.
+#[tauri::command]
+pub fn cmd_clear_ignored() -> Result<usize, CommandError> {
+    settings::clear_ignored_at(&settings::settings_path()).map_err(CommandError::Io)
+}
+
+/// Save `theme` (one of [`settings::THEMES`]) and broadcast it to every
+/// window as `theme-changed`.
+#[tauri::command]
+pub fn cmd_set_theme(app: tauri::AppHandle, theme: String) -> Result<(), CommandError> {
+    set_theme(&settings::settings_path(), &theme)?;
+    let _ = app.emit("theme-changed", &theme);
+    Ok(())
+}
+
+/// Validate `theme` and save it in the settings at `path`; an unknown theme
+/// writes nothing.
+fn set_theme(path: &Path, theme: &str) -> Result<(), CommandError> {
+    if !settings::THEMES.contains(&theme) {
+        return Err(CommandError::InvalidSchema(format!(
+            "Unknown theme \"{}\" (expected {})",
+            theme,
+            settings::THEMES.join(", ")
+        )));
+    }
+    settings::set_theme_at(path, theme).map_err(CommandError::Io)
+}
+
+/// Group signatures the user ignored, from the saved settings.
+fn ignored_signatures() -> HashSet<String> {
+    settings::load_settings()
+        .ignored_groups
+        .into_iter()
+        .collect()
 }
 
 /// Open a folder in the system file manager.
 #[tauri::command]
-pub fn cmd_open_folder(path: String) -> Result<(), String> {
-    open::that(&path).map_err(|e| format!("Failed to open folder: {}", e))
+pub fn cmd_open_folder(path: String) -> Result<(), CommandError> {
+    open::that(&path).map_err(|e| CommandError::from_io("Failed to open folder", &e))
+}
+
+/// True when another program holds `path` open, so renaming or deleting
+/// it would fail.  Only Windows locks open files; elsewhere always false.
+#[tauri::command]
+pub fn cmd_is_file_locked(path: String) -> bool {
+    file_lock::is_locked(&wire_path::decode(&path))
+}
+
+/// Show a file in the system file manager with the file selected.
+///
+/// Linux file managers have no common "select" flag, so there the parent
+/// folder is opened instead.
+#[tauri::command]
+pub fn cmd_reveal_file(path: String) -> Result<(), CommandError> {
+    reveal_file_with(
+        &wire_path::decode(&path),
+        std::env::consts::OS,
+        &launch_reveal,
+    )
+}
+
+/// How a file is revealed on one platform.
+#[derive(Debug, PartialEq, Eq)]
+enum RevealAction {
+    /// Run `program` with `args`; the file manager selects the file.
+    Spawn {
+        program: &'static str,
+        args: Vec<OsString>,
+    },
+    /// Open the folder with its default handler.
+    OpenFolder(PathBuf),
+}
+
+/// Pick the [`RevealAction`] for `path` on operating system `os` (as in
+/// `std::env::consts::OS`).
+fn reveal_action(os: &str, path: &Path) -> RevealAction {
+    match os {
+        "windows" => {
+            // Explorer wants `/select,<path>` as a single argument.
+            let mut select = OsString::from("/select,");
+            select.push(path);
+            RevealAction::Spawn {
+                program: "explorer",
+                args: vec![select],
+            }
+        }
+        "macos" => RevealAction::Spawn {
+            program: "open",
+            args: vec!["-R".into(), path.into()],
+        },
+        _ => RevealAction::OpenFolder(path.parent().unwrap_or(path).to_path_buf()),
+    }
+}
+
+/// Run a [`RevealAction`].  Spawned programs are not waited for: Explorer
+/// exits with status 1 even when it succeeds.
+fn launch_reveal(action: &RevealAction) -> std::io::Result<()> {
+    match action {
+        RevealAction::Spawn { program, args } => std::process::Command::new(program)
+            .args(args)
+            .spawn()
+            .map(drop),
+        RevealAction::OpenFolder(folder) => open::that(folder),
+    }
+}
+
+/// Testable core of [`cmd_reveal_file`] with an injectable launcher.
+fn reveal_file_with(
+    path: &Path,
+    os: &str,
+    launch: &dyn Fn(&RevealAction) -> std::io::Result<()>,
+) -> Result<(), CommandError> {
+    if !path.exists() {
+        return Err(CommandError::FolderNotFound(format!(
+            "File does not exist: {}",
+            path.display()
+        )));
+    }
+    launch(&reveal_action(os, path)).map_err(|e| CommandError::from_io("Failed to reveal file", &e))
+}
+
+/// Turns core progress updates into `ScanProgress` events for the UI.
+struct ScanProgressEmitter<F: Fn(ScanProgress)>(F);
+
+impl<F: Fn(ScanProgress)> ProgressSink for ScanProgressEmitter<F> {
+    fn on_scan(&self, files: usize, dirs: usize) {
+        (self.0)(ScanProgress {
+            phase: "scanning".into(),
+            current: files,
+            total: 0,
+            message: format!("Found {} files in {} folders...", files, dirs),
+            dirs_scanned: Some(dirs),
+            percent: None,
+        });
+    }
+
+    fn on_hash(&self, current: usize, total: usize) {
+        (self.0)(phase_progress(WorkPhase::Hash, current, total, None));
+    }
+
+    fn on_mime(&self, checked: usize, total: usize) {
+        (self.0)(phase_progress(WorkPhase::Mime, checked, total, None));
+    }
+
+    fn on_weighted(&self, phase: WorkPhase, current: usize, total: usize, percent: f64) {
+        (self.0)(phase_progress(phase, current, total, Some(percent)));
+    }
+
+    fn on_rename(&self, current: usize, total: usize, path: &Path) {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        (self.0)(ScanProgress {
+            phase: "renaming".into(),
+            current,
+            total,
+            message: format!("Renaming {} ({} / {})...", name, current, total),
+            dirs_scanned: None,
+            percent: None,
+        });
+    }
+
+    fn on_manifest(&self, bytes: u64, total_bytes: u64, files: usize, total_files: usize) {
+        (self.0)(ScanProgress {
+            phase: "manifest".into(),
+            current: files,
+            total: total_files,
+            message: format!(
+                "Hashing for manifest {} / {}...",
+                types::human_size(bytes),
+                types::human_size(total_bytes)
+            ),
+            dirs_scanned: None,
+            percent: (total_bytes > 0).then(|| bytes as f64 / total_bytes as f64 * 100.0),
+        });
+    }
+}
+
+/// Progress message for `current` of `total` files of a filtering or
+/// hashing phase.
+fn phase_progress(
+    phase: WorkPhase,
+    current: usize,
+    total: usize,
+    percent: Option<f64>,
+) -> ScanProgress {
+    let (phase, message) = match phase {
+        WorkPhase::Mime => (
+            "filtering",
+            format!("Checking file type {} / {}...", current, total),
+        ),
+        WorkPhase::Hash => (
+            "hashing",
+            format!("Hashing file {} / {}...", current, total),
+        ),
+    };
+    ScanProgress {
+        phase: phase.into(),
+        current,
+        total,
+        message,
+        dirs_scanned: None,
+        percent,
+    }
 }
 
 /// Scan a folder for duplicate files.
 ///
 /// Runs on a background thread (async command) so the UI stays responsive
-/// during disk I/O and hashing.
+/// during disk I/O and hashing.  A `max_depth` overrides the depth implied
+/// by `include_subfolders`.  With `max_groups`, only the groups wasting the
+/// most bytes are returned and the result is marked `truncated`.
+/// `mtime_filter` keeps only files modified on the given weekdays / hours,
+/// and `same_folder_policy` decides whether same-folder copies count.
+/// `name_compare_mode` picks whether `use_name` compares whole names or
+/// only stems.
+/// With `timeout_secs`, the walk stops once the budget is spent and the
+/// partial result is marked `timed_out`; with `max_files`, it stops after
+/// that many files and the result is marked `file_limit_reached`.
+/// `mime_filter` (e.g. `["image/*"]`) keeps only files whose content sniffs
+/// as a matching type, and `skip_recently_modified_secs` leaves out files
+/// still being written.
+/// With `peek_archives`, files inside `.zip` archives are grouped too (see
+/// [`crate::archive`]); they are listed but cannot be deleted.
+/// `parallel_walk` walks the folder on several threads, and
+/// `skip_trash_dirs` leaves out trash / recycle bin folders.
+/// `use_content_prefix` keys files by their first `content_prefix_bytes`
+/// bytes.
+/// `max_files_per_group` limits the members listed per group; the rest
+/// are counted in `overflow_count`.
+/// Each of `alternatives` is a further criteria set: files matching under
+/// any one of them, or under the criteria above, are grouped together.
+/// `type_overrides` pairs file-type presets with the criteria used for
+/// files of that type instead; the first matching preset wins.
+/// `custom_file_type_presets` are the user's presets, which those
+/// file-type names may refer to.
+/// The full result is cached under its `scan_id` (see [`ScanCache`]); with
+/// `page_size`, only that many groups are returned and the rest are
+/// fetched with `cmd_get_scan_page`.
 #[allow(clippy::too_many_arguments)]
 #[tauri::command(rename_all = "snake_case")]
 pub async fn cmd_scan(
     app: tauri::AppHandle,
+    cache: tauri::State<'_, ScanCache>,
     folder: String,
     days: u32,
     use_hash: bool,
     use_size: bool,
     use_name: bool,
+    use_name_family: bool,
     use_mtime: bool,
+    use_created: bool,
     use_mime: bool,
     use_media_meta: bool,
+    use_audio_fingerprint: bool,
+    use_dimensions: bool,
+    use_content_prefix: bool,
+    content_prefix_bytes: u32,
     hash_limit_enabled: bool,
     hash_max_mb: u32,
     fast_hash_oversized: bool,
+    blake3_min_mb: Option<u32>,
     include_subfolders: bool,
+    max_depth: Option<usize>,
     name_prefix: String,
-) -> Result<ScanResult, String> {
+    skip_hidden: bool,
+    follow_symlinks: bool,
+    symlink_hash_policy: SymlinkHashPolicy,
+    mtime_filter: Option<MtimeFilter>,
+    same_folder_policy: SameFolderPolicy,
+    name_compare_mode: NameCompare,
+    timeout_secs: Option<u64>,
+    max_files: Option<usize>,
+    mime_filter: Option<Vec<String>>,
+    skip_recently_modified_secs: Option<u64>,
+    peek_archives: bool,
+    parallel_walk: bool,
+    max_files_per_group: Option<usize>,
+    max_groups: Option<usize>,
+    alternatives: Vec<CriteriaSet>,
+    custom_file_type_presets: FileTypePresets,
+    type_overrides: Vec<(String, CriteriaSet)>,
+    skip_trash_dirs: bool,
+    page_size: Option<usize>,
+) -> Result<ScanResult, CommandError> {
+    cache.clear();
+    let params = ScanParams {
+        folder,
+        days,
+        use_hash,
+        use_size,
+        use_name,
+        use_name_family,
+        use_mtime,
+        use_created,
+        use_mime,
+        use_media_meta,
+        use_audio_fingerprint,
+        use_dimensions,
+        use_content_prefix,
+        content_prefix_bytes,
+        hash_limit_enabled,
+        hash_max_mb,
+        fast_hash_oversized,
+        blake3_min_mb,
+        include_subfolders,
+        max_depth,
+        name_prefix,
+        skip_hidden,
+        follow_symlinks,
+        symlink_hash_policy,
+        mtime_filter,
+        same_folder_policy,
+        name_compare_mode,
+        timeout_secs,
+        max_files,
+        mime_filter,
+        skip_recently_modified_secs,
+        peek_archives,
+        parallel_walk,
+        max_files_per_group,
+        max_groups,
+        alternatives,
+        custom_file_type_presets,
+        type_overrides,
+        skip_trash_dirs,
+    };
     // Move CPU-heavy work to a blocking thread so we don't starve the async
     // runtime.  `spawn_blocking` returns a JoinHandle whose error we convert.
-    tokio::task::spawn_blocking(move || {
-        scan_blocking(
-            &app,
-            folder,
-            days,
-            use_hash,
-            use_size,
-            use_name,
-            use_mtime,
-            use_mime,
-            use_media_meta,
-            hash_limit_enabled,
-            hash_max_mb,
-            fast_hash_oversized,
-            include_subfolders,
-            name_prefix,
-        )
+    let mut result = tokio::task::spawn_blocking(move || {
+        let progress = ScanProgressEmitter(|progress| {
+            let _ = app.emit("scan-progress", progress);
+        });
+        scan_blocking(&progress, &HashMap::new(), &ignored_signatures(), params)
+            .inspect(remember_scan)
     })
     .await
-    .map_err(|e| format!("Scan task panicked: {}", e))?
+    .map_err(|e| CommandError::task_panicked("Scan", e))??;
+    result.scan_id = cache.store(result.clone());
+    if let Some(size) = page_size {
+        result.groups.truncate(size);
+    }
+    Ok(result)
 }
 
-/// Scan a folder for auto-renamer candidate files.
+/// Scan like `cmd_scan`, but return only the totals.
+///
+/// Grouping runs in full; no file is listed in the result, so it is cheap
+/// to build and send, for a first look before fetching the groups.  The
+/// result is not saved as the last scan.
+#[allow(clippy::too_many_arguments)]
 #[tauri::command(rename_all = "snake_case")]
-pub async fn cmd_scan_auto_rename(
+pub async fn cmd_scan_summary_only(
+    app: tauri::AppHandle,
+    cache: tauri::State<'_, ScanCache>,
     folder: String,
     days: u32,
+    use_hash: bool,
+    use_size: bool,
+    use_name: bool,
+    use_name_family: bool,
+    use_mtime: bool,
+    use_created: bool,
+    use_mime: bool,
+    use_media_meta: bool,
+    use_audio_fingerprint: bool,
+    use_dimensions: bool,
+    use_content_prefix: bool,
+    content_prefix_bytes: u32,
+    hash_limit_enabled: bool,
+    hash_max_mb: u32,
+    fast_hash_oversized: bool,
+    blake3_min_mb: Option<u32>,
     include_subfolders: bool,
+    max_depth: Option<usize>,
     name_prefix: String,
-    file_type_preset: String,
-) -> Result<AutoRenameScanResult, String> {
+    skip_hidden: bool,
+    follow_symlinks: bool,
+    symlink_hash_policy: SymlinkHashPolicy,
+    mtime_filter: Option<MtimeFilter>,
+    same_folder_policy: SameFolderPolicy,
+    name_compare_mode: NameCompare,
+    timeout_secs: Option<u64>,
+    max_files: Option<usize>,
+    mime_filter: Option<Vec<String>>,
+    skip_recently_modified_secs: Option<u64>,
+    peek_archives: bool,
+    parallel_walk: bool,
+    alternatives: Vec<CriteriaSet>,
+    custom_file_type_presets: FileTypePresets,
+    type_overrides: Vec<(String, CriteriaSet)>,
+    skip_trash_dirs: bool,
+) -> Result<ScanSummary, CommandError> {
+    cache.clear();
+    let params = ScanParams {
+        folder,
+        days,
+        use_hash,
+        use_size,
+        use_name,
+        use_name_family,
+        use_mtime,
+        use_created,
+        use_mime,
+        use_media_meta,
+        use_audio_fingerprint,
+        use_dimensions,
+        use_content_prefix,
+        content_prefix_bytes,
+        hash_limit_enabled,
+        hash_max_mb,
+        fast_hash_oversized,
+        blake3_min_mb,
+        include_subfolders,
+        max_depth,
+        name_prefix,
+        skip_hidden,
+        follow_symlinks,
+        symlink_hash_policy,
+        mtime_filter,
+        same_folder_policy,
+        name_compare_mode,
+        timeout_secs,
+        max_files,
+        mime_filter,
+        skip_recently_modified_secs,
+        peek_archives,
+        parallel_walk,
+        max_files_per_group: Some(0),
+        max_groups: None,
+        alternatives,
+        custom_file_type_presets,
+        type_overrides,
+        skip_trash_dirs,
+    };
     tokio::task::spawn_blocking(move || {
-        scan_auto_rename_blocking(
-            folder,
-            days,
-            include_subfolders,
-            name_prefix,
-            file_type_preset,
-        )
+        let progress = ScanProgressEmitter(|progress| {
+            let _ = app.emit("scan-progress", progress);
+        });
+        scan_blocking(&progress, &HashMap::new(), &ignored_signatures(), params)
+            .map(|result| summarize(&result))
     })
     .await
-    .map_err(|e| format!("Auto-rename scan task panicked: {}", e))?
+    .map_err(|e| CommandError::task_panicked("Scan", e))?
 }
 
-/// The actual scan logic, called inside `spawn_blocking`.
+/// Totals of `result`, counting members hidden by `max_files_per_group`.
+fn summarize(result: &ScanResult) -> ScanSummary {
+    ScanSummary {
+        group_count: result.total_group_count,
+        duplicate_file_count: result
+            .groups
+            .iter()
+            .map(|g| g.files.len() + g.overflow_count - 1)
+            .sum(),
+        total_wasted_bytes: result.total_wasted_bytes,
+        total_files_scanned: result.total_files_scanned,
+        elapsed_seconds: result.elapsed_seconds,
+    }
+}
+
+/// Re-scan a folder, reusing grouping keys from `previous`.
+///
+/// Files whose path, size, and mtime match an entry of `previous` keep their
+/// earlier key; only new or changed files are hashed.  Takes the same scan
+/// parameters as `cmd_scan`.
 #[allow(clippy::too_many_arguments)]
-fn scan_blocking(
-    app: &tauri::AppHandle,
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_rescan(
+    app: tauri::AppHandle,
+    cache: tauri::State<'_, ScanCache>,
+    previous: ScanResult,
     folder: String,
     days: u32,
     use_hash: bool,
     use_size: bool,
     use_name: bool,
+    use_name_family: bool,
     use_mtime: bool,
+    use_created: bool,
     use_mime: bool,
     use_media_meta: bool,
+    use_audio_fingerprint: bool,
+    use_dimensions: bool,
+    use_content_prefix: bool,
+    content_prefix_bytes: u32,
     hash_limit_enabled: bool,
     hash_max_mb: u32,
     fast_hash_oversized: bool,
+    blake3_min_mb: Option<u32>,
     include_subfolders: bool,
+    max_depth: Option<usize>,
     name_prefix: String,
-) -> Result<ScanResult, String> {
-    let start = Instant::now();
-    let folder_path = PathBuf::from(&folder);
-
-    if !folder_path.exists() {
-        return Err(format!("Folder does not exist: {}", folder));
-    }
-
-    // Progress callback for the scanning phase.
-    let scan_progress = |count: usize| {
-        let _ = app.emit(
-            "scan-progress",
-            ScanProgress {
-                phase: "scanning".into(),
-                current: count,
-                total: 0,
-                message: format!("Found {} files...", count),
-            },
-        );
-    };
-
-    // Gather files.
-    let prefix = if name_prefix.is_empty() {
-        None
-    } else {
-        Some(name_prefix.as_str())
-    };
-    let (entries, scan_skip_reasons) = scanner::gather_recent_files(
-        &folder_path,
+    skip_hidden: bool,
+    follow_symlinks: bool,
+    symlink_hash_policy: SymlinkHashPolicy,
+    mtime_filter: Option<MtimeFilter>,
+    same_folder_policy: SameFolderPolicy,
+    name_compare_mode: NameCompare,
+    timeout_secs: Option<u64>,
+    max_files: Option<usize>,
+    mime_filter: Option<Vec<String>>,
+    skip_recently_modified_secs: Option<u64>,
+    peek_archives: bool,
+    parallel_walk: bool,
+    max_files_per_group: Option<usize>,
+    max_groups: Option<usize>,
+    alternatives: Vec<CriteriaSet>,
+    custom_file_type_presets: FileTypePresets,
+    type_overrides: Vec<(String, CriteriaSet)>,
+    skip_trash_dirs: bool,
+) -> Result<ScanResult, CommandError> {
+    cache.clear();
+    let params = ScanParams {
+        folder,
         days,
-        prefix,
+        use_hash,
+        use_size,
+        use_name,
+        use_name_family,
+        use_mtime,
+        use_created,
+        use_mime,
+        use_media_meta,
+        use_audio_fingerprint,
+        use_dimensions,
+        use_content_prefix,
+        content_prefix_bytes,
+        hash_limit_enabled,
+        hash_max_mb,
+        fast_hash_oversized,
+        blake3_min_mb,
         include_subfolders,
-        Some(&scan_progress),
-    );
-    let scan_skipped = scan_skip_reasons.total();
+        max_depth,
+        name_prefix,
+        skip_hidden,
+        follow_symlinks,
+        symlink_hash_policy,
+        mtime_filter,
+        same_folder_policy,
+        name_compare_mode,
+        timeout_secs,
+        max_files,
+        mime_filter,
+        skip_recently_modified_secs,
+        peek_archives,
+        parallel_walk,
+        max_files_per_group,
+        max_groups,
+        alternatives,
+        custom_file_type_presets,
+        type_overrides,
+        skip_trash_dirs,
+    };
+    tokio::task::spawn_blocking(move || {
+        let progress = ScanProgressEmitter(|progress| {
+            let _ = app.emit("scan-progress", progress);
+        });
+        let known = grouper::known_keys_from_groups(&previous.groups);
+        scan_blocking(&progress, &known, &ignored_signatures(), params).inspect(remember_scan)
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Re-scan", e))?
+}
 
-    let total_files_scanned = entries.len();
+/// The last scan result saved on disk, for showing again after a restart.
+///
+/// Files that vanished since are dropped and the result is marked
+/// `stale`.  `None` when no scan was saved.
+#[tauri::command]
+pub async fn cmd_load_last_scan() -> Result<Option<ScanResult>, CommandError> {
+    tokio::task::spawn_blocking(last_scan::load_last_scan)
+        .await
+        .map_err(|e| CommandError::task_panicked("Loading the last scan", e))
+}
 
-    // Build grouping configuration.
+/// Groups of the latest `cmd_scan`, kept in Tauri's managed state so the
+/// frontend can fetch them in pages instead of in one large payload.  Every
+/// scan command drops the cached one as it starts.
+#[derive(Default)]
+pub struct ScanCache(Mutex<Option<ScanResult>>);
+
+/// Source of `ScanResult::scan_id`; 0 is left for results never cached.
+static NEXT_SCAN_ID: AtomicU64 = AtomicU64::new(1);
+
+impl ScanCache {
+    /// Forget the cached scan.
+    fn clear(&self) {
+        *self.lock() = None;
+    }
+
+    /// Cache `result` in place of any earlier scan; returns its new id.
+    fn store(&self, mut result: ScanResult) -> u64 {
+        let id = NEXT_SCAN_ID.fetch_add(1, Ordering::Relaxed);
+        result.scan_id = id;
+        *self.lock() = Some(result);
+        id
+    }
+
+    /// Up to `limit` groups of scan `scan_id`, starting at `offset`.
+    fn page(
+        &self,
+        scan_id: u64,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<DuplicateGroup>, CommandError> {
+        self.with_scan(scan_id, |result| {
+            result
+                .groups
+                .iter()
+                .skip(offset)
+                .take(limit)
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Totals of scan `scan_id`.
+    fn meta(&self, scan_id: u64) -> Result<ScanMeta, CommandError> {
+        self.with_scan(scan_id, |result| ScanMeta {
+            scan_id,
+            group_count: result.groups.len(),
+            summary: summarize(result),
+        })
+    }
+
+    fn with_scan<T>(
+        &self,
+        scan_id: u64,
+        f: impl FnOnce(&ScanResult) -> T,
+    ) -> Result<T, CommandError> {
+        match self.lock().as_ref() {
+            Some(result) if result.scan_id == scan_id => Ok(f(result)),
+            _ => Err(CommandError::ScanExpired(format!(
+                "Scan {} is no longer available; scan again",
+                scan_id
+            ))),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<ScanResult>> {
+        // A panic while holding the lock cannot leave the Option half-written.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Up to `limit` groups of the cached scan `scan_id`, from `offset` on.
+/// Fails with `scan_expired` once another scan has started.
+#[tauri::command(rename_all = "snake_case")]
+pub fn cmd_get_scan_page(
+    cache: tauri::State<'_, ScanCache>,
+    scan_id: u64,
+    offset: usize,
+    limit: usize,
+) -> Result<Vec<DuplicateGroup>, CommandError> {
+    cache.page(scan_id, offset, limit)
+}
+
+/// Totals of the cached scan `scan_id`, including how many groups
+/// `cmd_get_scan_page` pages through.
+#[tauri::command(rename_all = "snake_case")]
+pub fn cmd_get_scan_meta(
+    cache: tauri::State<'_, ScanCache>,
+    scan_id: u64,
+) -> Result<ScanMeta, CommandError> {
+    cache.meta(scan_id)
+}
+
+/// Save a finished scan for [`cmd_load_last_scan`]; failures only cost the
+/// convenience, so they are logged rather than surfaced.
+fn remember_scan(result: &ScanResult) {
+    if let Err(e) = last_scan::save_last_scan(result) {
+        eprintln!("Could not save the last scan: {}", e);
+    }
+}
+
+/// Compare two scan results: groups that appeared, disappeared, grew or
+/// shrank (see [`grouper::diff_groups`]).
+#[tauri::command]
+pub fn cmd_diff_scans(old: ScanResult, new: ScanResult) -> ScanDiff {
+    grouper::diff_groups(&old.groups, &new.groups)
+}
+
+/// Find files in `others` that already exist in the `master` folder.
+///
+/// Walks every folder, groups the files with the given criteria, and keeps
+/// only groups holding at least one master file.  Each group's `keeper` is
+/// its master member, so deleting the rest never touches the archive.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_scan_dedupe_against_master(
+    app: tauri::AppHandle,
+    cache: tauri::State<'_, ScanCache>,
+    master: String,
+    others: Vec<String>,
+    use_hash: bool,
+    use_size: bool,
+    use_name: bool,
+    use_name_family: bool,
+    use_mtime: bool,
+    use_created: bool,
+    use_mime: bool,
+    use_media_meta: bool,
+    use_audio_fingerprint: bool,
+    use_dimensions: bool,
+    use_content_prefix: bool,
+    content_prefix_bytes: u32,
+    hash_limit_enabled: bool,
+    hash_max_mb: u32,
+    fast_hash_oversized: bool,
+    blake3_min_mb: Option<u32>,
+    include_subfolders: bool,
+    skip_hidden: bool,
+    follow_symlinks: bool,
+    symlink_hash_policy: SymlinkHashPolicy,
+    name_compare_mode: NameCompare,
+) -> Result<ScanResult, CommandError> {
+    cache.clear();
     let grouping_config = GroupingConfig {
         use_hash,
         use_size,
         use_name,
+        use_name_family,
         use_mtime,
+        use_created,
         use_mime,
         use_media_meta,
+        use_audio_fingerprint,
+        use_dimensions,
+        use_content_prefix,
+        content_prefix_bytes: content_prefix_bytes as usize,
         hash_max_bytes: if hash_limit_enabled {
             Some(u64::from(hash_max_mb) * 1024 * 1024)
         } else {
             None
         },
         fast_hash_oversized,
+        blake3_min_bytes: blake3_min_mb.map(|mb| u64::from(mb) * 1024 * 1024),
+        digest_encoding: DigestEncoding::Hex,
+        symlink_hash_policy,
+        same_folder_policy: SameFolderPolicy::Flag,
+        name_compare_mode,
+        custom_file_type_presets: FileTypePresets::new(),
+        type_overrides: Vec::new(),
+        alternatives: Vec::new(),
     };
-
-    // Progress callback for the hashing phase.
-    let hash_progress = |current: usize, total: usize| {
-        let _ = app.emit(
-            "scan-progress",
-            ScanProgress {
-                phase: "hashing".into(),
-                current,
-                total,
-                message: format!("Hashing file {} / {}...", current, total),
-            },
-        );
+    let scan_options = ScanOptions {
+        skip_hidden,
+        follow_symlinks,
+        ..ScanOptions::default()
     };
-
-    // Find duplicate groups.
-    let (raw_groups, hash_skipped) =
-        grouper::find_duplicate_groups(&entries, &grouping_config, Some(&hash_progress));
-
-    // Convert to DTOs for the frontend.
-    let groups: Vec<DuplicateGroup> = raw_groups
-        .iter()
-        .map(|(key, files)| {
-            let file_dtos: Vec<FileEntryDto> = files
-                .iter()
-                .map(|f| {
-                    let name = f
-                        .path
-                        .file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_default();
-                    let folder_str = f
-                        .path
-                        .parent()
-                        .map(|p| p.to_string_lossy().to_string())
-                        .unwrap_or_default();
-
-                    FileEntryDto {
-                        path: f.path.to_string_lossy().to_string(),
-                        name,
-                        folder: folder_str,
-                        size: f.size,
-                        size_human: types::human_size(f.size),
-                        mtime: f.mtime,
-                        mtime_formatted: format_mtime(f.mtime),
-                    }
-                })
-                .collect();
-
-            DuplicateGroup {
-                key_description: types::describe_key(key),
-                files: file_dtos,
-            }
-        })
-        .collect();
-
-    let elapsed = start.elapsed().as_secs_f64();
-
-    Ok(ScanResult {
-        groups,
-        total_files_scanned,
-        hash_skipped,
-        scan_skipped,
-        scan_skip_reasons,
-        elapsed_seconds: elapsed,
+    tokio::task::spawn_blocking(move || {
+        let progress = ScanProgressEmitter(|progress| {
+            let _ = app.emit("scan-progress", progress);
+        });
+        scan_against_master_blocking(
+            &progress,
+            &master,
+            &others,
+            &grouping_config,
+            scanner::depth_for_subfolders(include_subfolders),
+            &scan_options,
+        )
+        .inspect(remember_scan)
     })
+    .await
+    .map_err(|e| CommandError::task_panicked("Master-folder scan", e))?
 }
 
-/// The actual auto-renamer scan logic, called inside `spawn_blocking`.
-fn scan_auto_rename_blocking(
+/// Scan a folder for duplicate images with image-oriented defaults.
+///
+/// A preset over `cmd_scan`: only files whose content sniffs as `image/*`
+/// are kept, whatever their extension, and they are grouped by size,
+/// pixel dimensions and content hash.  The result is saved as the last
+/// scan.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_scan_images(
+    app: tauri::AppHandle,
+    cache: tauri::State<'_, ScanCache>,
     folder: String,
     days: u32,
     include_subfolders: bool,
-    name_prefix: String,
-    file_type_preset: String,
-) -> Result<AutoRenameScanResult, String> {
-    let start = Instant::now();
-    let folder_path = PathBuf::from(&folder);
+) -> Result<ScanResult, CommandError> {
+    cache.clear();
+    tokio::task::spawn_blocking(move || {
+        let progress = ScanProgressEmitter(|progress| {
+            let _ = app.emit("scan-progress", progress);
+        });
+        scan_images_blocking(
+            &progress,
+            &ignored_signatures(),
+            folder,
+            days,
+            include_subfolders,
+        )
+        .inspect(remember_scan)
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Image scan", e))?
+}
 
-    if !folder_path.exists() {
-        return Err(format!("Folder does not exist: {}", folder));
-    }
+/// The image preset of [`scan_blocking`], called inside `spawn_blocking`.
+fn scan_images_blocking(
+    progress: &dyn ProgressSink,
+    ignored: &HashSet<String>,
+    folder: String,
+    days: u32,
+    include_subfolders: bool,
+) -> Result<ScanResult, CommandError> {
+    scan_blocking(
+        progress,
+        &HashMap::new(),
+        ignored,
+        ScanParams {
+            folder,
+            days,
+            use_size: true,
+            use_dimensions: true,
+            include_subfolders,
+            mime_filter: Some(vec!["image/*".to_string()]),
+            ..ScanParams::default()
+        },
+    )
+}
+
+/// Scan a folder for auto-renamer candidate files.
+///
+/// With `rename_schema`, each candidate carries its base-pass
+/// `preview_target` and files already named per the schema are left out.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_scan_auto_rename(
+    folder: String,
+    days: u32,
+    include_subfolders: bool,
+    name_prefix: String,
+    file_type_preset: String,
+    custom_file_type_presets: FileTypePresets,
+    rename_schema: Option<RenameSchema>,
+) -> Result<AutoRenameScanResult, CommandError> {
+    if let Some(schema) = &rename_schema {
+        autorenamer::validate_schema(schema).map_err(CommandError::InvalidSchema)?;
+    }
+    tokio::task::spawn_blocking(move || {
+        scan_auto_rename_blocking(
+            folder,
+            days,
+            include_subfolders,
+            name_prefix,
+            file_type_preset,
+            &custom_file_type_presets,
+            rename_schema.as_ref(),
+        )
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Auto-rename scan", e))?
+}
+
+/// Options of one duplicate scan, built once from the scan command's
+/// arguments. See `cmd_scan` for what each option does.
+///
+/// `Default` is a hash-only scan of the whole tree under `folder`.
+#[derive(Debug, Clone)]
+struct ScanParams {
+    folder: String,
+    days: u32,
+    use_hash: bool,
+    use_size: bool,
+    use_name: bool,
+    use_name_family: bool,
+    use_mtime: bool,
+    use_created: bool,
+    use_mime: bool,
+    use_media_meta: bool,
+    use_audio_fingerprint: bool,
+    use_dimensions: bool,
+    use_content_prefix: bool,
+    content_prefix_bytes: u32,
+    hash_limit_enabled: bool,
+    hash_max_mb: u32,
+    fast_hash_oversized: bool,
+    blake3_min_mb: Option<u32>,
+    include_subfolders: bool,
+    max_depth: Option<usize>,
+    name_prefix: String,
+    skip_hidden: bool,
+    follow_symlinks: bool,
+    symlink_hash_policy: SymlinkHashPolicy,
+    mtime_filter: Option<MtimeFilter>,
+    same_folder_policy: SameFolderPolicy,
+    name_compare_mode: NameCompare,
+    timeout_secs: Option<u64>,
+    max_files: Option<usize>,
+    mime_filter: Option<Vec<String>>,
+    skip_recently_modified_secs: Option<u64>,
+    peek_archives: bool,
+    parallel_walk: bool,
+    max_files_per_group: Option<usize>,
+    max_groups: Option<usize>,
+    alternatives: Vec<CriteriaSet>,
+    custom_file_type_presets: FileTypePresets,
+    type_overrides: Vec<(String, CriteriaSet)>,
+    skip_trash_dirs: bool,
+}
+
+impl Default for ScanParams {
+    fn default() -> Self {
+        Self {
+            folder: String::new(),
+            days: 0,
+            use_hash: true,
+            use_size: false,
+            use_name: false,
+            use_name_family: false,
+            use_mtime: false,
+            use_created: false,
+            use_mime: false,
+            use_media_meta: false,
+            use_audio_fingerprint: false,
+            use_dimensions: false,
+            use_content_prefix: false,
+            content_prefix_bytes: 256,
+            hash_limit_enabled: false,
+            hash_max_mb: 500,
+            fast_hash_oversized: false,
+            blake3_min_mb: None,
+            include_subfolders: true,
+            max_depth: None,
+            name_prefix: String::new(),
+            skip_hidden: false,
+            follow_symlinks: false,
+            symlink_hash_policy: SymlinkHashPolicy::FollowTarget,
+            mtime_filter: None,
+            same_folder_policy: SameFolderPolicy::Flag,
+            name_compare_mode: NameCompare::Full,
+            timeout_secs: None,
+            max_files: None,
+            mime_filter: None,
+            skip_recently_modified_secs: None,
+            peek_archives: false,
+            parallel_walk: false,
+            max_files_per_group: None,
+            max_groups: None,
+            alternatives: Vec::new(),
+            custom_file_type_presets: FileTypePresets::new(),
+            type_overrides: Vec::new(),
+            skip_trash_dirs: true,
+        }
+    }
+}
+
+/// The actual scan logic, called inside `spawn_blocking`.
+fn scan_blocking(
+    progress: &dyn ProgressSink,
+    known: &HashMap<PathBuf, KnownKey>,
+    ignored: &HashSet<String>,
+    params: ScanParams,
+) -> Result<ScanResult, CommandError> {
+    let ScanParams {
+        folder,
+        days,
+        use_hash,
+        use_size,
+        use_name,
+        use_name_family,
+        use_mtime,
+        use_created,
+        use_mime,
+        use_media_meta,
+        use_audio_fingerprint,
+        use_dimensions,
+        use_content_prefix,
+        content_prefix_bytes,
+        hash_limit_enabled,
+        hash_max_mb,
+        fast_hash_oversized,
+        blake3_min_mb,
+        include_subfolders,
+        max_depth,
+        name_prefix,
+        skip_hidden,
+        follow_symlinks,
+        symlink_hash_policy,
+        mtime_filter,
+        same_folder_policy,
+        name_compare_mode,
+        timeout_secs,
+        max_files,
+        mime_filter,
+        skip_recently_modified_secs,
+        peek_archives,
+        parallel_walk,
+        max_files_per_group,
+        max_groups,
+        alternatives,
+        custom_file_type_presets,
+        type_overrides,
+        skip_trash_dirs,
+    } = params;
+    let start = Instant::now();
+    let folder_path = PathBuf::from(&folder);
+
+    if !folder_path.exists() {
+        return Err(CommandError::folder_not_found(&folder));
+    }
+
+    // Gather files.
+    let prefix = if name_prefix.is_empty() {
+        None
+    } else {
+        Some(name_prefix.as_str())
+    };
+    let scan_options = ScanOptions {
+        skip_hidden,
+        follow_symlinks,
+        mtime_filter,
+        timeout: timeout_secs.map(Duration::from_secs),
+        max_files,
+        skip_recently_modified_secs,
+        peek_archives,
+        parallel_walk,
+        skip_trash_dirs,
+        ..ScanOptions::default()
+    };
+    let (entries, scan_skip_reasons) = scanner::gather_recent_files(
+        &folder_path,
+        days,
+        prefix,
+        max_depth.or(scanner::depth_for_subfolders(include_subfolders)),
+        &scan_options,
+        progress,
+    );
+    let scan_skipped = scan_skip_reasons.total();
+    let timed_out = scan_options.cancel.load(Ordering::Relaxed);
+    let file_limit_reached = scan_options.file_limit_reached.load(Ordering::Relaxed);
+
+    let hash_max_bytes = hash_limit_enabled.then(|| u64::from(hash_max_mb) * 1024 * 1024);
+
+    // Content sniffing reads every file, so it only runs when asked for.
+    // Followed by hashing, both phases report one continuous percentage
+    // instead of two bars that each restart at zero.
+    let mime_patterns = mime_filter
+        .as_deref()
+        .filter(|patterns| !patterns.is_empty());
+    let weighted;
+    let progress: &dyn ProgressSink = match mime_patterns {
+        Some(_) if use_hash => {
+            let hash_units: u64 = entries
+                .iter()
+                .filter(|e| hash_max_bytes.is_none_or(|max| e.size <= max))
+                .map(|e| e.size)
+                .sum();
+            let mime_units = entries.len() as u64 * MIME_SNIFF_BYTES;
+            weighted = WeightedProgress::new(progress, PhaseWeights::new(mime_units, hash_units));
+            &weighted
+        }
+        _ => progress,
+    };
+    let entries = match mime_patterns {
+        Some(patterns) => scanner::filter_by_mime(entries, patterns, progress),
+        None => entries,
+    };
+
+    let total_files_scanned = entries.len();
+    let size_histogram = scanner::size_histogram(&entries);
+
+    // Build grouping configuration.
+    let mut grouping_config = GroupingConfig {
+        use_hash,
+        use_size,
+        use_name,
+        use_name_family,
+        use_mtime,
+        use_created,
+        use_mime,
+        use_media_meta,
+        use_audio_fingerprint,
+        use_dimensions,
+        use_content_prefix,
+        content_prefix_bytes: content_prefix_bytes as usize,
+        hash_max_bytes,
+        fast_hash_oversized,
+        blake3_min_bytes: blake3_min_mb.map(|mb| u64::from(mb) * 1024 * 1024),
+        digest_encoding: DigestEncoding::Hex,
+        symlink_hash_policy,
+        same_folder_policy,
+        name_compare_mode,
+        custom_file_type_presets,
+        type_overrides: Vec::new(),
+        alternatives: Vec::new(),
+    };
+    grouping_config.alternatives = alternatives
+        .iter()
+        .map(|criteria| grouping_config.with_criteria(criteria))
+        .collect();
+    grouping_config.type_overrides = type_overrides
+        .iter()
+        .map(|(preset, criteria)| (preset.clone(), grouping_config.with_criteria(criteria)))
+        .collect();
+
+    // Find duplicate groups.
+    let (mut raw_groups, grouping_stats) =
+        grouper::find_duplicate_groups_cached(&entries, &grouping_config, known, progress);
+
+    // Dismissed groups are left out before anything is counted.
+    let found_group_count = raw_groups.len();
+    raw_groups.retain(|_, files| !ignored.contains(&grouper::group_signature(files)));
+    let ignored_count = found_group_count - raw_groups.len();
+
+    // Totals cover every group, even those dropped by the cap below.
+    let total_group_count = raw_groups.len();
+    let total_wasted_bytes: u64 = raw_groups
+        .values()
+        .map(|files| grouper::wasted_bytes(files))
+        .sum();
+    let truncated = max_groups.is_some_and(|max| total_group_count > max);
+    let mut raw_groups: Vec<_> = raw_groups.into_iter().collect();
+    if let (true, Some(max)) = (truncated, max_groups) {
+        raw_groups.sort_by_cached_key(|(_, files)| Reverse(grouper::wasted_bytes(files)));
+        raw_groups.truncate(max);
+    }
+
+    let by_extension = grouper::extension_stats(raw_groups.iter().map(|(_, files)| &files[..]));
+
+    // Convert to DTOs for the frontend, listing at most `max_files_per_group`
+    // members of each group.
+    let groups: Vec<DuplicateGroup> = raw_groups
+        .iter()
+        .map(|(key, files)| {
+            let shown = max_files_per_group.map_or(files.len(), |max| max.min(files.len()));
+            DuplicateGroup {
+                key_description: types::describe_key(key),
+                confidence: types::key_confidence(key),
+                key: key.clone(),
+                files: files[..shown].iter().map(file_entry_dto).collect(),
+                keeper: None,
+                overflow_count: files.len() - shown,
+                signature: grouper::group_signature(files),
+            }
+        })
+        .collect();
+
+    let elapsed = start.elapsed().as_secs_f64();
+
+    Ok(ScanResult {
+        groups,
+        truncated,
+        timed_out,
+        file_limit_reached,
+        total_group_count,
+        total_wasted_bytes,
+        size_histogram,
+        by_extension,
+        total_files_scanned,
+        hash_skipped: grouping_stats.hash_skipped,
+        created_skipped: grouping_stats.created_skipped,
+        audio_skipped: grouping_stats.audio_skipped,
+        dimensions_skipped: grouping_stats.dimensions_skipped,
+        in_flux_skipped: grouping_stats.in_flux,
+        scan_skipped,
+        scan_skip_reasons,
+        elapsed_seconds: elapsed,
+        stale: false,
+        ignored_count,
+        scan_id: 0,
+    })
+}
+
+/// The master-folder scan logic, called inside `spawn_blocking`.
+fn scan_against_master_blocking(
+    progress: &dyn ProgressSink,
+    master: &str,
+    others: &[String],
+    grouping_config: &GroupingConfig,
+    max_depth: Option<usize>,
+    scan_options: &ScanOptions,
+) -> Result<ScanResult, CommandError> {
+    let start = Instant::now();
+    let master_path = PathBuf::from(master);
+    for folder in std::iter::once(master).chain(others.iter().map(String::as_str)) {
+        if !Path::new(folder).exists() {
+            return Err(CommandError::folder_not_found(folder));
+        }
+    }
+
+    // Walk the master first so a file reachable from several roots (an
+    // "other" nested inside the master, say) is only counted once.
+    let mut entries: Vec<FileEntry> = Vec::new();
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+    let mut scan_skip_reasons = types::ScanSkipReasons::default();
+    for folder in std::iter::once(master_path.clone()).chain(others.iter().map(PathBuf::from)) {
+        let (found, reasons) =
+            scanner::gather_recent_files(&folder, 0, None, max_depth, scan_options, progress);
+        scan_skip_reasons.permissions += reasons.permissions;
+        scan_skip_reasons.missing += reasons.missing;
+        scan_skip_reasons.transient_io += reasons.transient_io;
+        scan_skip_reasons.recently_modified += reasons.recently_modified;
+        entries.extend(found.into_iter().filter(|e| seen.insert(e.path.clone())));
+    }
+    let total_files_scanned = entries.len();
+    let size_histogram = scanner::size_histogram(&entries);
+
+    let (raw_groups, grouping_stats) =
+        grouper::find_duplicate_groups(&entries, grouping_config, progress);
+
+    // FolderPriority on the master alone picks a master member whenever the
+    // group has one; groups without one are not duplicates of the archive.
+    let strategy = KeepStrategy::FolderPriority(vec![master_path.clone()]);
+    let mut total_wasted_bytes = 0;
+    let mut kept_groups: Vec<&[FileEntry]> = Vec::new();
+    let groups: Vec<DuplicateGroup> = raw_groups
+        .iter()
+        .filter_map(|(key, files)| {
+            let keeper = &files[keeper::select_keeper(files, &strategy)?];
+            if !keeper.path.starts_with(&master_path) {
+                return None;
+            }
+            total_wasted_bytes += grouper::wasted_bytes(files);
+            kept_groups.push(files);
+            Some(DuplicateGroup {
+                key_description: types::describe_key(key),
+                confidence: types::key_confidence(key),
+                key: key.clone(),
+                files: files.iter().map(file_entry_dto).collect(),
+                keeper: Some(wire_path::encode(&keeper.path)),
+                overflow_count: 0,
+                signature: grouper::group_signature(files),
+            })
+        })
+        .collect();
+
+    Ok(ScanResult {
+        total_group_count: groups.len(),
+        groups,
+        truncated: false,
+        timed_out: false,
+        file_limit_reached: false,
+        total_wasted_bytes,
+        size_histogram,
+        by_extension: grouper::extension_stats(kept_groups),
+        total_files_scanned,
+        hash_skipped: grouping_stats.hash_skipped,
+        created_skipped: grouping_stats.created_skipped,
+        audio_skipped: grouping_stats.audio_skipped,
+        dimensions_skipped: grouping_stats.dimensions_skipped,
+        in_flux_skipped: grouping_stats.in_flux,
+        scan_skipped: scan_skip_reasons.total(),
+        scan_skip_reasons,
+        elapsed_seconds: start.elapsed().as_secs_f64(),
+        stale: false,
+        ignored_count: 0,
+        scan_id: 0,
+    })
+}
+
+/// The actual auto-renamer scan logic, called inside `spawn_blocking`.
+fn scan_auto_rename_blocking(
+    folder: String,
+    days: u32,
+    include_subfolders: bool,
+    name_prefix: String,
+    file_type_preset: String,
+    custom_file_type_presets: &FileTypePresets,
+    rename_schema: Option<&RenameSchema>,
+) -> Result<AutoRenameScanResult, CommandError> {
+    let start = Instant::now();
+    let folder_path = PathBuf::from(&folder);
+
+    if !folder_path.exists() {
+        return Err(CommandError::folder_not_found(&folder));
+    }
 
     // Gather files using shared scan controls (days/prefix/subfolders).
     let prefix = if name_prefix.is_empty() {
@@ -264,17 +1362,38 @@ fn scan_auto_rename_blocking(
     } else {
         Some(name_prefix.as_str())
     };
-    let (entries, scan_skip_reasons) =
-        scanner::gather_recent_files(&folder_path, days, prefix, include_subfolders, None);
+    let (entries, scan_skip_reasons) = scanner::gather_recent_files(
+        &folder_path,
+        days,
+        prefix,
+        scanner::depth_for_subfolders(include_subfolders),
+        &ScanOptions::default(),
+        &NoProgress,
+    );
     let scan_skipped = scan_skip_reasons.total();
 
     let total_files_scanned = entries.len();
-    let preset = autorenamer::normalize_file_type_preset(&file_type_preset);
+    let preset =
+        autorenamer::normalize_file_type_preset(&file_type_preset, custom_file_type_presets);
 
     let candidates: Vec<AutoRenameCandidateDto> = entries
         .into_iter()
-        .filter(|entry| autorenamer::matches_file_type_preset(&entry.path, &preset))
-        .map(|entry| {
+        .filter(|entry| {
+            autorenamer::matches_file_type_preset(&entry.path, &preset, custom_file_type_presets)
+        })
+        .filter_map(|entry| {
+            let preview_target = match rename_schema {
+                Some(schema) => {
+                    let target = autorenamer::preview_base_target(&entry.path, schema);
+                    if target.as_ref() == Some(&entry.path) {
+                        // Already follows the schema; renaming would not change it.
+                        return None;
+                    }
+                    target.map(|t| wire_path::encode(&t)).unwrap_or_default()
+                }
+                None => String::new(),
+            };
+
             let name = entry
                 .path
                 .file_name()
@@ -292,17 +1411,10 @@ fn scan_auto_rename_blocking(
                 .unwrap_or_default();
 
             let size = entry.size;
-            let created = std::fs::metadata(&entry.path)
-                .and_then(|m| m.created())
-                .map(|t| {
-                    t.duration_since(std::time::UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_secs_f64()
-                })
-                .unwrap_or(0.0);
+            let created = entry.created.unwrap_or(0.0);
 
-            AutoRenameCandidateDto {
-                path: entry.path.to_string_lossy().to_string(),
+            Some(AutoRenameCandidateDto {
+                path: wire_path::encode(&entry.path),
                 name,
                 folder,
                 extension,
@@ -310,7 +1422,8 @@ fn scan_auto_rename_blocking(
                 mtime: entry.mtime,
                 mtime_formatted: format_mtime(entry.mtime),
                 created,
-            }
+                preview_target,
+            })
         })
         .collect();
 
@@ -324,55 +1437,1855 @@ fn scan_auto_rename_blocking(
     })
 }
 
+/// Preview the name `rename_schema` gives the file at `path`, without
+/// renaming anything.  Works for paths that do not exist (placeholder dates).
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_preview_name(
+    path: String,
+    rename_schema: RenameSchema,
+) -> Result<String, CommandError> {
+    autorenamer::validate_schema(&rename_schema).map_err(CommandError::InvalidSchema)?;
+    tokio::task::spawn_blocking(move || {
+        autorenamer::preview_name(&wire_path::decode(&path), &rename_schema)
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Name preview", e))
+}
+
+/// Cancel flag of the running `cmd_auto_rename`, shared with
+/// `cmd_cancel_rename` through Tauri's managed state.
+#[derive(Default)]
+pub struct RenameCancel(Arc<AtomicBool>);
+
 /// Rename files with the auto-renamer schema.
+///
+/// `cmd_cancel_rename` stops the batch after the file in progress; the
+/// result then has `cancelled` set.  With `reveal_on_complete`, a
+/// "rename-complete" event carries the nearest folder holding every
+/// renamed file, so the frontend can offer to open it.
 #[tauri::command(rename_all = "snake_case")]
 pub async fn cmd_auto_rename(
+    app: tauri::AppHandle,
+    cancel: tauri::State<'_, RenameCancel>,
     paths: Vec<String>,
     rename_schema: RenameSchema,
-) -> Result<AutoRenameResult, String> {
+    reveal_on_complete: bool,
+) -> Result<AutoRenameResult, CommandError> {
+    autorenamer::validate_schema(&rename_schema).map_err(CommandError::InvalidSchema)?;
+    let cancel = Arc::clone(&cancel.0);
+    cancel.store(false, Ordering::Relaxed);
+    tokio::task::spawn_blocking(move || {
+        let path_bufs: Vec<PathBuf> = paths.iter().map(|path| wire_path::decode(path)).collect();
+        let progress = ScanProgressEmitter(|progress| {
+            let _ = app.emit("scan-progress", progress);
+        });
+        let result = autorenamer::auto_rename_paths_cancellable(
+            &path_bufs,
+            &rename_schema,
+            &progress,
+            &cancel,
+        );
+        if reveal_on_complete {
+            let targets: Vec<PathBuf> = result
+                .items
+                .iter()
+                .map(|item| wire_path::decode(&item.to_path))
+                .collect();
+            if let Some(folder) = autorenamer::common_parent_dir(&targets) {
+                let _ = app.emit("rename-complete", wire_path::encode(&folder));
+            }
+        }
+        Ok(result)
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Auto-rename", e))?
+}
+
+/// Move files into `YYYY-MM` subfolders of `base_dir` by date, keeping
+/// their names (see [`autorenamer::organize_by_date`]).
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_organize_by_date(
+    paths: Vec<String>,
+    base_dir: String,
+    basis: RecencyBasis,
+) -> Result<OrganizeReport, CommandError> {
+    let base = wire_path::decode(&base_dir);
+    if !base.is_dir() {
+        return Err(CommandError::folder_not_found(&base_dir));
+    }
     tokio::task::spawn_blocking(move || {
-        let path_bufs: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
-        Ok(autorenamer::auto_rename_paths(&path_bufs, &rename_schema))
+        let path_bufs: Vec<PathBuf> = paths.iter().map(|path| wire_path::decode(path)).collect();
+        autorenamer::organize_by_date(&path_bufs, &base, basis)
     })
     .await
-    .map_err(|e| format!("Auto-rename task panicked: {}", e))?
+    .map_err(|e| CommandError::task_panicked("Organize", e))
+}
+
+/// Ask the running `cmd_auto_rename` to stop after its current file.
+#[tauri::command]
+pub fn cmd_cancel_rename(cancel: tauri::State<'_, RenameCancel>) {
+    cancel.0.store(true, Ordering::Relaxed);
 }
 
 /// Delete files (move to trash or permanent delete).
 ///
-/// Runs on a background thread so the UI stays responsive during I/O.
-#[tauri::command]
-pub async fn cmd_delete(paths: Vec<String>) -> Result<usize, String> {
+/// Files on volumes without a trash are returned in `needs_confirmation`
+/// and left alone unless `allow_permanent` is set.  Per-file failures come
+/// back in `errors`, including files the trash keeps failing on; with
+/// `permanent_on_trash_failure` those are deleted permanently and listed
+/// in `warnings` instead.  Setting `allow_permanent` alone never does.  With `dry_run`, nothing is touched:
+/// `deleted` counts the paths that would succeed.  Runs on a background
+/// thread so the UI stays responsive during I/O.
+///
+/// With `trash_limit_bytes`, a batch bigger than that is not trashed: all
+/// paths come back in `needs_confirmation` with `trash_limit_exceeded`
+/// set, to be confirmed like files on volumes without a trash.
+///
+/// With `verify_against`, every path is first re-checked against the key
+/// of the group listing it (re-hashing for hash keys, see
+/// [`grouper::recheck_key`]); files that changed since the scan, that
+/// no group lists, or that are a group's `keeper`, are kept and reported
+/// in `spared`.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_delete(
+    paths: Vec<String>,
+    allow_permanent: bool,
+    permanent_on_trash_failure: bool,
+    dry_run: bool,
+    verify_against: Option<Vec<DuplicateGroup>>,
+    trash_limit_bytes: Option<u64>,
+) -> Result<DeleteSummary, CommandError> {
     tokio::task::spawn_blocking(move || {
-        let path_bufs: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
-        let result = deleter::delete_files(&path_bufs);
+        Ok(delete_blocking(
+            &paths,
+            allow_permanent,
+            permanent_on_trash_failure,
+            dry_run,
+            verify_against.as_deref(),
+            trash_limit_bytes,
+            &deleter::system_trash,
+            Some(&delete_history::delete_history_path()),
+        ))
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Delete", e))?
+}
 
-        if !result.errors.is_empty() {
-            let error_msgs: Vec<String> = result
-                .errors
+#[allow(clippy::too_many_arguments)]
+fn delete_blocking(
+    paths: &[String],
+    allow_permanent: bool,
+    permanent_on_trash_failure: bool,
+    dry_run: bool,
+    verify_against: Option<&[DuplicateGroup]>,
+    trash_limit: Option<u64>,
+    trash: &(dyn Fn(&Path) -> deleter::TrashOutcome + Sync),
+    history: Option<&Path>,
+) -> DeleteSummary {
+    let mut spared: Vec<PathErrorDto> = Vec::new();
+    let path_bufs: Vec<PathBuf> = match verify_against {
+        Some(groups) => {
+            let keys: HashMap<&str, &types::DuplicateKey> = groups
                 .iter()
-                .map(|(path, msg)| format!("{}: {}", path, msg))
+                .flat_map(|g| g.files.iter().map(move |f| (f.path.as_str(), &g.key)))
                 .collect();
-            return Err(format!(
-                "Deleted {} files but {} errors:\n{}",
-                result.deleted,
-                result.errors.len(),
-                error_msgs.join("\n")
-            ));
+            let keepers: HashSet<&str> =
+                groups.iter().filter_map(|g| g.keeper.as_deref()).collect();
+            paths
+                .iter()
+                .filter(|path| {
+                    let check = if keepers.contains(path.as_str()) {
+                        Err("it is the required keeper of its group".into())
+                    } else {
+                        match keys.get(path.as_str()) {
+                            Some(key) => grouper::recheck_key(&wire_path::decode(path), key),
+                            None => Err("not listed in any duplicate group".into()),
+                        }
+                    };
+                    match check {
+                        Ok(()) => true,
+                        Err(message) => {
+                            spared.push(PathErrorDto {
+                                path: path.to_string(),
+                                message: format!("Kept {}:\n{}", path, message),
+                            });
+                            false
+                        }
+                    }
+                })
+                .map(|path| wire_path::decode(path))
+                .collect()
         }
+        None => paths.iter().map(|path| wire_path::decode(path)).collect(),
+    };
+    let result = deleter::delete_files_with(
+        &path_bufs,
+        deleter::DEFAULT_DELETE_CONCURRENCY,
+        allow_permanent,
+        permanent_on_trash_failure,
+        dry_run,
+        trash_limit,
+        trash,
+    );
+    record_deletions(history, DeleteMode::Manual, &result);
+
+    let to_dtos = |pairs: Vec<(String, String)>| -> Vec<PathErrorDto> {
+        pairs
+            .into_iter()
+            .map(|(path, message)| PathErrorDto { path, message })
+            .collect()
+    };
+    DeleteSummary {
+        deleted: result.deleted,
+        needs_confirmation: result.needs_confirmation,
+        errors: to_dtos(result.errors),
+        warnings: to_dtos(result.warnings),
+        trashed: result.trashed,
+        spared,
+        trash_limit_exceeded: result.trash_limit_exceeded,
+    }
+}
+
+/// Append the files `result` removed to the delete history at `history`.
+///
+/// A failure only costs the record, so it is logged rather than surfaced.
+fn record_deletions(history: Option<&Path>, mode: DeleteMode, result: &deleter::DeleteResult) {
+    let Some(history) = history else {
+        return;
+    };
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+    let entries: Vec<DeleteHistoryEntry> = result
+        .removed
+        .iter()
+        .map(|(path, size)| DeleteHistoryEntry {
+            timestamp,
+            path: path.clone(),
+            mode,
+            freed_bytes: *size,
+            trashed: result.trashed.binary_search(path).is_ok(),
+        })
+        .collect();
+    if let Err(e) =
+        delete_history::append_history_to(&entries, history, delete_history::MAX_HISTORY_BYTES)
+    {
+        eprintln!("Could not record deleted files: {}", e);
+    }
+}
 
-        Ok(result.deleted)
+/// The newest `limit` files deleted by this app, across sessions, newest
+/// first.
+#[tauri::command]
+pub async fn cmd_get_delete_history(limit: usize) -> Result<Vec<DeleteHistoryEntry>, CommandError> {
+    tokio::task::spawn_blocking(move || {
+        delete_history::read_history_from(&delete_history::delete_history_path(), limit)
     })
     .await
-    .map_err(|e| format!("Delete task panicked: {}", e))?
+    .map_err(|e| CommandError::task_panicked("Loading the delete history", e))
 }
 
-fn format_mtime(mtime: f64) -> String {
-    chrono::DateTime::from_timestamp(mtime as i64, 0)
-        .map(|dt| {
-            dt.with_timezone(&chrono::Local)
-                .format("%Y-%m-%d %H:%M:%S")
-                .to_string()
+/// Move files trashed by `cmd_delete` back to where they were.
+///
+/// `original_paths` are the paths the files had before deletion (see
+/// `DeleteSummary::trashed`).  Fails with an `unsupported` error on
+/// platforms whose trash cannot be listed (macOS).
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_restore_from_trash(
+    original_paths: Vec<String>,
+) -> Result<RestoreSummary, CommandError> {
+    tokio::task::spawn_blocking(move || {
+        let path_bufs: Vec<PathBuf> = original_paths
+            .iter()
+            .map(|path| wire_path::decode(path))
+            .collect();
+        deleter::restore_from_trash(&path_bufs).ok_or_else(|| {
+            CommandError::Unsupported(
+                "Restoring from the trash is not supported on this platform".into(),
+            )
         })
-        .unwrap_or_default()
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Restore", e))?
+}
+
+/// Keep one file per duplicate group and delete the rest.
+///
+/// `strategy` picks the survivor of each group (see
+/// [`keeper::select_keeper`]) unless the group names its own `keeper`,
+/// which is always the one kept; the report lists, per group, exactly which
+/// path was kept and which were deleted.  Trash / confirmation behaviour
+/// matches `cmd_delete`, with `trash_limit_bytes` checked per group: a
+/// group whose copies add up to more is left alone, its paths in
+/// `needs_confirmation` and `trash_limit_exceeded` set.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_auto_dedupe(
+    groups: Vec<DuplicateGroup>,
+    strategy: KeepStrategy,
+    allow_permanent: bool,
+    trash_limit_bytes: Option<u64>,
+) -> Result<AutoDedupeReport, CommandError> {
+    tokio::task::spawn_blocking(move || {
+        auto_dedupe_blocking(
+            &groups,
+            &strategy,
+            allow_permanent,
+            trash_limit_bytes,
+            &deleter::system_trash,
+            Some(&delete_history::delete_history_path()),
+        )
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Auto-dedupe", e))
+}
+
+fn auto_dedupe_blocking(
+    groups: &[DuplicateGroup],
+    strategy: &KeepStrategy,
+    allow_permanent: bool,
+    trash_limit: Option<u64>,
+    trash: &(dyn Fn(&Path) -> deleter::TrashOutcome + Sync),
+    history: Option<&Path>,
+) -> AutoDedupeReport {
+    let groups: Vec<AutoDedupeGroupReport> = groups
+        .iter()
+        .filter_map(|group| {
+            let entries: Vec<FileEntry> = group.files.iter().map(file_entry_from_dto).collect();
+            dedupe_entries(
+                &entries,
+                group.keeper.as_deref(),
+                strategy,
+                allow_permanent,
+                trash_limit,
+                trash,
+                history,
+                DeleteMode::AutoDedupe,
+            )
+        })
+        .collect();
+    AutoDedupeReport {
+        freed_bytes: groups.iter().map(|g| g.freed_bytes).sum(),
+        groups,
+        planned: false,
+    }
+}
+
+/// Report what `cmd_auto_dedupe` would do with `groups`, without touching
+/// any file.
+///
+/// Each group lists the keeper with the reason `strategy` picks it and
+/// every other member as deleted; the report is marked `planned`.  Whether
+/// a volume has a trash is not probed, so `needs_confirmation` stays empty.
+#[tauri::command(rename_all = "snake_case")]
+pub fn cmd_dry_run_auto_dedupe(
+    groups: Vec<DuplicateGroup>,
+    strategy: KeepStrategy,
+) -> AutoDedupeReport {
+    plan_auto_dedupe(&groups, &strategy)
+}
+
+fn plan_auto_dedupe(groups: &[DuplicateGroup], strategy: &KeepStrategy) -> AutoDedupeReport {
+    let groups: Vec<AutoDedupeGroupReport> = groups
+        .iter()
+        .filter_map(|group| {
+            let entries: Vec<FileEntry> = group.files.iter().map(file_entry_from_dto).collect();
+            let (keep, keep_reason) = choose_keeper(&entries, group.keeper.as_deref(), strategy)?;
+            let doomed: Vec<&FileEntry> = entries
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| i != keep)
+                .map(|(_, e)| e)
+                .collect();
+            Some(AutoDedupeGroupReport {
+                kept: wire_path::encode(&entries[keep].path),
+                keep_reason,
+                deleted: doomed.iter().map(|e| wire_path::encode(&e.path)).collect(),
+                freed_bytes: doomed.iter().map(|e| e.size).sum(),
+                needs_confirmation: Vec::new(),
+                errors: Vec::new(),
+                trash_limit_exceeded: false,
+            })
+        })
+        .collect();
+    AutoDedupeReport {
+        freed_bytes: groups.iter().map(|g| g.freed_bytes).sum(),
+        groups,
+        planned: true,
+    }
+}
+
+/// The member of a group to keep, and why: the group's own `keeper` when
+/// it lists one (the master copy of a master-folder scan), otherwise the
+/// pick of `strategy`.
+fn choose_keeper(
+    entries: &[FileEntry],
+    group_keeper: Option<&str>,
+    strategy: &KeepStrategy,
+) -> Option<(usize, String)> {
+    let fixed = group_keeper
+        .map(wire_path::decode)
+        .and_then(|path| entries.iter().position(|e| e.path == path));
+    match fixed {
+        Some(keep) => Some((keep, "required keeper of the group".to_string())),
+        None => {
+            let keep = keeper::select_keeper(entries, strategy)?;
+            Some((keep, keeper::keep_reason(entries, keep, strategy)))
+        }
+    }
+}
+
+/// Keep the member of one duplicate group that `group_keeper` names, or
+/// else the one `strategy` picks, and delete the others, logging them to
+/// `history` as `mode`; `None` if nothing is picked.  The others are
+/// deferred untouched when they add up to more than `trash_limit`.
+#[allow(clippy::too_many_arguments)]
+fn dedupe_entries(
+    entries: &[FileEntry],
+    group_keeper: Option<&str>,
+    strategy: &KeepStrategy,
+    allow_permanent: bool,
+    trash_limit: Option<u64>,
+    trash: &(dyn Fn(&Path) -> deleter::TrashOutcome + Sync),
+    history: Option<&Path>,
+    mode: DeleteMode,
+) -> Option<AutoDedupeGroupReport> {
+    let (keep, keep_reason) = choose_keeper(entries, group_keeper, strategy)?;
+    let doomed: Vec<&FileEntry> = entries
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != keep)
+        .map(|(_, e)| e)
+        .collect();
+    let paths: Vec<PathBuf> = doomed.iter().map(|e| e.path.clone()).collect();
+    let result = deleter::delete_files_with(
+        &paths,
+        deleter::DEFAULT_DELETE_CONCURRENCY,
+        allow_permanent,
+        false,
+        false,
+        trash_limit,
+        trash,
+    );
+    record_deletions(history, mode, &result);
+
+    let left_in_place: HashSet<&str> = result
+        .errors
+        .iter()
+        .map(|(path, _)| path.as_str())
+        .chain(result.needs_confirmation.iter().map(String::as_str))
+        .collect();
+    let mut deleted = Vec::new();
+    let mut freed_bytes = 0;
+    for entry in doomed {
+        let path = wire_path::encode(&entry.path);
+        if !left_in_place.contains(path.as_str()) {
+            freed_bytes += entry.size;
+            deleted.push(path);
+        }
+    }
+
+    Some(AutoDedupeGroupReport {
+        kept: wire_path::encode(&entries[keep].path),
+        keep_reason,
+        deleted,
+        freed_bytes,
+        needs_confirmation: result.needs_confirmation.clone(),
+        errors: result
+            .errors
+            .iter()
+            .map(|(path, message)| PathErrorDto {
+                path: path.clone(),
+                message: message.clone(),
+            })
+            .collect(),
+        trash_limit_exceeded: result.trash_limit_exceeded,
+    })
+}
+
+/// Collapse byte-identical files into one file with a canonical name.
+///
+/// `strategy` picks the survivor as in `cmd_auto_dedupe` and the other
+/// paths are deleted (trash / confirmation behaviour matches `cmd_delete`,
+/// `trash_limit_bytes` included);
+/// the survivor is then renamed with `rename_schema`, so it can take a name
+/// a deleted copy held.  Nothing is touched unless every path has the same
+/// SHA-256 digest.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_canonicalize_duplicates(
+    group_paths: Vec<String>,
+    rename_schema: RenameSchema,
+    strategy: KeepStrategy,
+    allow_permanent: bool,
+    trash_limit_bytes: Option<u64>,
+) -> Result<CanonicalizeReport, CommandError> {
+    autorenamer::validate_schema(&rename_schema).map_err(CommandError::InvalidSchema)?;
+    tokio::task::spawn_blocking(move || {
+        canonicalize_blocking(
+            &group_paths,
+            &rename_schema,
+            &strategy,
+            allow_permanent,
+            trash_limit_bytes,
+            &deleter::system_trash,
+            Some(&delete_history::delete_history_path()),
+        )
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Canonicalize", e))?
+}
+
+fn canonicalize_blocking(
+    group_paths: &[String],
+    schema: &RenameSchema,
+    strategy: &KeepStrategy,
+    allow_permanent: bool,
+    trash_limit: Option<u64>,
+    trash: &(dyn Fn(&Path) -> deleter::TrashOutcome + Sync),
+    history: Option<&Path>,
+) -> Result<CanonicalizeReport, CommandError> {
+    let mut entries = Vec::with_capacity(group_paths.len());
+    let mut first_digest: Option<String> = None;
+    for path in group_paths {
+        let read_error =
+            |e: std::io::Error| CommandError::from_io(&format!("Failed to read {}", path), &e);
+        let file = wire_path::decode(path);
+        let meta = std::fs::metadata(&file).map_err(read_error)?;
+        let digest = hasher::sha256_file(&file, DigestEncoding::Hex).map_err(read_error)?;
+        if *first_digest.get_or_insert_with(|| digest.clone()) != digest {
+            return Err(CommandError::InvalidSchema(format!(
+                "{} does not have the same content as {}",
+                path, group_paths[0]
+            )));
+        }
+        let unix_seconds = |t: std::io::Result<std::time::SystemTime>| {
+            t.ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs_f64())
+        };
+        entries.push(FileEntry {
+            path: file,
+            size: meta.len(),
+            mtime: unix_seconds(meta.modified()).unwrap_or(0.0),
+            created: unix_seconds(meta.created()),
+        });
+    }
+
+    let group = dedupe_entries(
+        &entries,
+        None,
+        strategy,
+        allow_permanent,
+        trash_limit,
+        trash,
+        history,
+        DeleteMode::Canonicalize,
+    )
+    .ok_or_else(|| CommandError::InvalidSchema("No file to keep among the given paths".into()))?;
+    let renamed =
+        autorenamer::auto_rename_paths(&[wire_path::decode(&group.kept)], schema, &NoProgress);
+    Ok(CanonicalizeReport {
+        group,
+        renamed_to: renamed.items.into_iter().next().map(|item| item.to_path),
+        rename_errors: renamed.errors,
+    })
+}
+
+/// Remove empty directories left behind under `folder` (never `folder` itself).
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_remove_empty_dirs(
+    folder: String,
+    include_subfolders: bool,
+) -> Result<RemoveEmptyDirsResult, CommandError> {
+    tokio::task::spawn_blocking(move || {
+        let folder_path = PathBuf::from(&folder);
+        if !folder_path.is_dir() {
+            return Err(CommandError::folder_not_found(&folder));
+        }
+        Ok(deleter::remove_empty_dirs(&folder_path, include_subfolders))
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Empty-directory cleanup", e))?
+}
+
+/// Total size in bytes of the given paths, read fresh from disk.
+///
+/// Missing files count as 0, so the UI can show the space a selection frees.
+#[tauri::command]
+pub async fn cmd_compute_selection_size(paths: Vec<String>) -> Result<u64, CommandError> {
+    tokio::task::spawn_blocking(move || {
+        let path_bufs: Vec<PathBuf> = paths.iter().map(|path| wire_path::decode(path)).collect();
+        Ok(scanner::total_size(&path_bufs))
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Selection size", e))?
+}
+
+/// Cheap folder probe for the UI before it starts a scan; never hashes.
+#[tauri::command]
+pub async fn cmd_validate_folder(folder: String) -> Result<FolderPrecheck, CommandError> {
+    tokio::task::spawn_blocking(move || {
+        Ok(scanner::precheck_folder(
+            &PathBuf::from(&folder),
+            scanner::PRECHECK_MAX_ENTRIES,
+            scanner::PRECHECK_TIME_BUDGET,
+        ))
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Folder precheck", e))?
+}
+
+/// Rough scan duration for `folder`, from a quick sample; never hashes.
+///
+/// `hash_max_mb` mirrors the scan's hash size limit (`None` = no limit).
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_estimate_scan_time(
+    folder: String,
+    days: u32,
+    use_hash: bool,
+    hash_max_mb: Option<u32>,
+    include_subfolders: bool,
+) -> Result<ScanTimeEstimate, CommandError> {
+    tokio::task::spawn_blocking(move || {
+        let folder_path = PathBuf::from(&folder);
+        if !folder_path.is_dir() {
+            return Err(CommandError::folder_not_found(&folder));
+        }
+        Ok(scanner::estimate_scan_time(
+            &folder_path,
+            days,
+            use_hash,
+            hash_max_mb.map(|mb| u64::from(mb) * 1024 * 1024),
+            scanner::depth_for_subfolders(include_subfolders),
+            &scanner::DEFAULT_SCAN_THROUGHPUT,
+        ))
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Scan estimate", e))?
+}
+
+/// Compare two files byte-for-byte (size check first, then streamed).
+#[tauri::command]
+pub async fn cmd_compare_two_files(a: String, b: String) -> Result<CompareResult, CommandError> {
+    tokio::task::spawn_blocking(move || {
+        hasher::compare_files(&wire_path::decode(&a), &wire_path::decode(&b))
+            .map_err(|e| CommandError::from_io("Could not compare files", &e))
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Compare", e))?
+}
+
+/// Files larger than this emit "hash-progress" events from `cmd_hash_file`.
+const HASH_PROGRESS_MIN_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Hash a single file with `algo` for a spot check from the results panel;
+/// the digest is hex unless `encoding` says otherwise.
+///
+/// Large files report byte progress through "hash-progress" events.
+#[tauri::command]
+pub async fn cmd_hash_file(
+    app: tauri::AppHandle,
+    path: String,
+    algo: HashAlgorithm,
+    encoding: Option<DigestEncoding>,
+) -> Result<String, CommandError> {
+    tokio::task::spawn_blocking(move || {
+        let emit_progress = |progress: ScanProgress| {
+            let _ = app.emit("hash-progress", progress);
+        };
+        hash_file_blocking(&path, algo, encoding.unwrap_or_default(), &emit_progress)
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Hash", e))?
+}
+
+/// The actual single-file hash logic, called inside `spawn_blocking`.
+fn hash_file_blocking(
+    path: &str,
+    algo: HashAlgorithm,
+    encoding: DigestEncoding,
+    emit_progress: &dyn Fn(ScanProgress),
+) -> Result<String, CommandError> {
+    let progress = |done: u64, total: u64| {
+        if total > HASH_PROGRESS_MIN_BYTES {
+            emit_progress(ScanProgress {
+                phase: "hashing".into(),
+                current: done as usize,
+                total: total as usize,
+                message: format!(
+                    "Hashing {} / {}...",
+                    types::human_size(done),
+                    types::human_size(total)
+                ),
+                dirs_scanned: None,
+                percent: None,
+            });
+        }
+    };
+    hasher::hash_file(&wire_path::decode(path), algo, encoding, Some(&progress))
+        .map_err(|e| CommandError::from_io("Could not hash file", &e))
+}
+
+/// Cancel flag of the running `cmd_export_manifest`, shared with
+/// `cmd_cancel_manifest` through Tauri's managed state.
+#[derive(Default)]
+pub struct ManifestCancel(Arc<AtomicBool>);
+
+/// Write a `sha256sum` checksum manifest of the files in `folder` to
+/// `output_path` (see [`crate::manifest`]), with hex digests unless
+/// `encoding` says otherwise.
+///
+/// Progress arrives as `scan-progress` events in the `"manifest"` phase.
+/// `cmd_cancel_manifest` stops the export after the file in progress; the
+/// partial manifest then ends with an incomplete marker and the result has
+/// `incomplete` set.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_export_manifest(
+    app: tauri::AppHandle,
+    cancel: tauri::State<'_, ManifestCancel>,
+    folder: String,
+    include_subfolders: bool,
+    output_path: String,
+    encoding: Option<DigestEncoding>,
+) -> Result<ManifestExport, CommandError> {
+    let cancel = Arc::clone(&cancel.0);
+    cancel.store(false, Ordering::Relaxed);
+    tokio::task::spawn_blocking(move || {
+        let progress = ScanProgressEmitter(|progress| {
+            let _ = app.emit("scan-progress", progress);
+        });
+        export_manifest_blocking(
+            &folder,
+            include_subfolders,
+            Path::new(&output_path),
+            encoding.unwrap_or_default(),
+            &progress,
+            &cancel,
+        )
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Manifest export", e))?
+}
+
+/// Ask the running `cmd_export_manifest` to stop after its current file.
+#[tauri::command]
+pub fn cmd_cancel_manifest(cancel: tauri::State<'_, ManifestCancel>) {
+    cancel.0.store(true, Ordering::Relaxed);
+}
+
+/// The actual manifest export, called inside `spawn_blocking`.
+fn export_manifest_blocking(
+    folder: &str,
+    include_subfolders: bool,
+    output: &Path,
+    encoding: DigestEncoding,
+    progress: &dyn ProgressSink,
+    cancel: &AtomicBool,
+) -> Result<ManifestExport, CommandError> {
+    let folder_path = PathBuf::from(folder);
+    if !folder_path.is_dir() {
+        return Err(CommandError::folder_not_found(folder));
+    }
+    let (mut entries, _) = scanner::gather_recent_files(
+        &folder_path,
+        0,
+        None,
+        scanner::depth_for_subfolders(include_subfolders),
+        &ScanOptions::default(),
+        progress,
+    );
+    // An earlier manifest at the same spot is about to be overwritten.
+    entries.retain(|e| e.path != output);
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    manifest::export_manifest(&entries, &folder_path, output, encoding, progress, cancel)
+        .map_err(|e| CommandError::from_io("Could not write manifest", &e))
+}
+
+/// Re-hash the files listed in a `sha256sum` manifest to detect drift
+/// (see [`manifest::verify_manifest`]).
+///
+/// Progress arrives as `scan-progress` events in the `"manifest"` phase.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cmd_verify_manifest(
+    app: tauri::AppHandle,
+    manifest_path: String,
+) -> Result<Vec<VerifyEntry>, CommandError> {
+    tokio::task::spawn_blocking(move || {
+        let progress = ScanProgressEmitter(|progress| {
+            let _ = app.emit("scan-progress", progress);
+        });
+        manifest::verify_manifest(Path::new(&manifest_path), &progress)
+            .map_err(|e| CommandError::from_io("Could not read manifest", &e))
+    })
+    .await
+    .map_err(|e| CommandError::task_panicked("Manifest check", e))?
+}
+
+/// Recover the scanner's view of a file from its frontend DTO.
+fn file_entry_from_dto(dto: &FileEntryDto) -> FileEntry {
+    FileEntry {
+        path: wire_path::decode(&dto.path),
+        size: dto.size,
+        mtime: dto.mtime,
+        created: (dto.created > 0.0).then_some(dto.created),
+    }
+}
+
+/// Build the frontend DTO for a grouped file entry.
+fn file_entry_dto(f: &FileEntry) -> FileEntryDto {
+    let name = f
+        .path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let folder_str = f
+        .path
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let created = f.created.unwrap_or(0.0);
+
+    FileEntryDto {
+        path: wire_path::encode(&f.path),
+        name,
+        folder: folder_str,
+        size: f.size,
+        size_human: types::human_size(f.size),
+        mtime: f.mtime,
+        mtime_formatted: format_mtime(f.mtime),
+        created,
+        created_formatted: if created > 0.0 {
+            format_mtime(created)
+        } else {
+            String::new()
+        },
+    }
+}
+
+fn format_mtime(mtime: f64) -> String {
+    chrono::DateTime::from_timestamp(mtime as i64, 0)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RenameComponentDef;
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// Trash backend reporting that the volume has none, so with
+    /// `allow_permanent` files are deleted for good and the real trash is
+    /// never touched.
+    fn no_trash(_: &Path) -> deleter::TrashOutcome {
+        deleter::TrashOutcome::Unsupported
+    }
+
+    #[test]
+    fn test_file_entry_dto_populates_created() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        fs::write(&path, b"data").unwrap();
+        let (entries, _) = scanner::gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &ScanOptions::default(),
+            &NoProgress,
+        );
+        assert_eq!(entries.len(), 1);
+
+        let dto = file_entry_dto(&entries[0]);
+        assert_eq!(dto.name, "a.txt");
+        assert_eq!(dto.size, 4);
+
+        // Creation time is not exposed by every platform/filesystem.
+        if fs::metadata(&path).and_then(|m| m.created()).is_ok() {
+            assert!(dto.created > 0.0);
+            assert!(!dto.created_formatted.is_empty());
+        } else {
+            assert_eq!(dto.created, 0.0);
+            assert!(dto.created_formatted.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_auto_rename_scan_excludes_files_already_named_per_schema() {
+        let dir = tempdir().unwrap();
+        let docs = dir.path().join("docs");
+        fs::create_dir(&docs).unwrap();
+        fs::write(docs.join("docs.txt"), b"conforming").unwrap();
+        fs::write(docs.join("notes.txt"), b"needs rename").unwrap();
+
+        let schema = RenameSchema {
+            components: vec![RenameComponentDef::FolderName],
+            separator: "_".into(),
+            ..RenameSchema::default()
+        };
+        let result = scan_auto_rename_blocking(
+            docs.to_string_lossy().to_string(),
+            0,
+            true,
+            String::new(),
+            "all".into(),
+            &FileTypePresets::new(),
+            Some(&schema),
+        )
+        .unwrap();
+
+        assert_eq!(result.total_files_scanned, 2);
+        assert_eq!(result.candidates.len(), 1);
+        let candidate = &result.candidates[0];
+        assert_eq!(candidate.name, "notes.txt");
+        assert_eq!(
+            PathBuf::from(&candidate.preview_target),
+            docs.join("docs.txt")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_file_is_scanned_and_renamed_by_its_real_path() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = tempdir().unwrap();
+        let docs = dir.path().join("docs");
+        fs::create_dir(&docs).unwrap();
+        let original = docs.join(OsStr::from_bytes(b"caf\xe9.txt"));
+        fs::write(&original, b"latin-1 name").unwrap();
+        fs::write(docs.join("copy.txt"), b"latin-1 name").unwrap();
+
+        // Duplicate groups list the real path.
+        let groups = hash_scan(&docs, None).groups;
+        assert_eq!(groups.len(), 1);
+        let member = groups[0]
+            .files
+            .iter()
+            .find(|f| f.name == "caf\u{FFFD}.txt")
+            .unwrap();
+        assert_eq!(file_entry_from_dto(member).path, original);
+
+        let schema = RenameSchema {
+            components: vec![RenameComponentDef::FolderName],
+            separator: "_".into(),
+            ..RenameSchema::default()
+        };
+        let scan = scan_auto_rename_blocking(
+            docs.to_string_lossy().to_string(),
+            0,
+            true,
+            String::new(),
+            "all".into(),
+            &FileTypePresets::new(),
+            None,
+        )
+        .unwrap();
+        let candidate = scan
+            .candidates
+            .iter()
+            .find(|c| wire_path::decode(&c.path) == original)
+            .unwrap();
+
+        let result = autorenamer::auto_rename_paths(
+            &[wire_path::decode(&candidate.path)],
+            &schema,
+            &NoProgress,
+        );
+        assert!(result.errors.is_empty());
+        assert_eq!(wire_path::decode(&result.items[0].from_path), original);
+        assert!(!original.exists());
+        assert_eq!(fs::read(docs.join("docs.txt")).unwrap(), b"latin-1 name");
+    }
+
+    #[test]
+    fn test_auto_rename_scan_without_schema_keeps_all_candidates() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), b"a").unwrap();
+        let result = scan_auto_rename_blocking(
+            dir.path().to_string_lossy().to_string(),
+            0,
+            true,
+            String::new(),
+            "all".into(),
+            &FileTypePresets::new(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.candidates.len(), 1);
+        assert!(result.candidates[0].preview_target.is_empty());
+    }
+
+    #[test]
+    fn test_hash_file_returns_known_sha256() {
+        let dir = tempdir().unwrap();
+        let f = dir.path().join("test.txt");
+        fs::write(&f, b"hello world").unwrap();
+        let digest = hash_file_blocking(
+            &f.to_string_lossy(),
+            HashAlgorithm::Sha256,
+            DigestEncoding::Hex,
+            &|_| {},
+        )
+        .unwrap();
+        assert_eq!(
+            digest,
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+    }
+
+    #[test]
+    fn test_hash_file_missing_path_errors() {
+        let dir = tempdir().unwrap();
+        let missing = dir.path().join("missing.txt");
+        let err = hash_file_blocking(
+            &missing.to_string_lossy(),
+            HashAlgorithm::Sha256,
+            DigestEncoding::Hex,
+            &|_| {},
+        )
+        .unwrap_err();
+        assert!(err.message().starts_with("Could not hash file"));
+    }
+
+    /// [`ScanParams`] for a hash-only scan of `folder`.
+    fn hash_only(folder: &std::path::Path) -> ScanParams {
+        ScanParams {
+            folder: folder.to_string_lossy().to_string(),
+            ..ScanParams::default()
+        }
+    }
+
+    /// [`GroupingConfig`] that groups by content hash alone.
+    fn hash_only_config() -> GroupingConfig {
+        GroupingConfig {
+            use_hash: true,
+            use_size: false,
+            use_name: false,
+            use_name_family: false,
+            use_mtime: false,
+            use_created: false,
+            use_mime: false,
+            use_media_meta: false,
+            use_audio_fingerprint: false,
+            use_dimensions: false,
+            use_content_prefix: false,
+            content_prefix_bytes: 0,
+            hash_max_bytes: None,
+            fast_hash_oversized: false,
+            blake3_min_bytes: None,
+            digest_encoding: DigestEncoding::Hex,
+            symlink_hash_policy: SymlinkHashPolicy::FollowTarget,
+            same_folder_policy: SameFolderPolicy::Flag,
+            name_compare_mode: NameCompare::Full,
+            custom_file_type_presets: FileTypePresets::new(),
+            type_overrides: Vec::new(),
+            alternatives: Vec::new(),
+        }
+    }
+
+    /// Hash-only scan of `folder` with the given group cap.
+    fn hash_scan(folder: &std::path::Path, max_groups: Option<usize>) -> ScanResult {
+        hash_scan_capped(folder, max_groups, None, &HashSet::new(), Vec::new())
+    }
+
+    /// [`hash_scan`] that also caps the members listed per group, hides
+    /// the `ignored` group signatures and adds `alternatives`.
+    fn hash_scan_capped(
+        folder: &std::path::Path,
+        max_groups: Option<usize>,
+        max_files_per_group: Option<usize>,
+        ignored: &HashSet<String>,
+        alternatives: Vec<CriteriaSet>,
+    ) -> ScanResult {
+        scan_blocking(
+            &NoProgress,
+            &HashMap::new(),
+            ignored,
+            ScanParams {
+                max_files_per_group,
+                max_groups,
+                alternatives,
+                ..hash_only(folder)
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_scan_alternatives_group_by_hash_or_name() {
+        let dir = tempdir().unwrap();
+        for sub in ["a", "b"] {
+            fs::create_dir(dir.path().join(sub)).unwrap();
+        }
+        fs::write(dir.path().join("a/x.txt"), b"one").unwrap();
+        fs::write(dir.path().join("b/x.txt"), b"two").unwrap();
+        fs::write(dir.path().join("y.txt"), b"one").unwrap();
+
+        let by_hash = hash_scan(dir.path(), None);
+        assert_eq!(by_hash.groups[0].files.len(), 2);
+
+        let by_name = CriteriaSet {
+            use_name: true,
+            ..CriteriaSet::default()
+        };
+        let result = hash_scan_capped(dir.path(), None, None, &HashSet::new(), vec![by_name]);
+        assert_eq!(result.groups.len(), 1);
+        assert_eq!(result.groups[0].files.len(), 3);
+    }
+
+    #[test]
+    fn test_scan_images_judges_files_by_content() {
+        let dir = tempdir().unwrap();
+        let image = image::RgbImage::from_pixel(4, 3, image::Rgb([10, 20, 30]));
+        for name in ["photo.png", "export.dat"] {
+            image
+                .save_with_format(dir.path().join(name), image::ImageFormat::Png)
+                .unwrap();
+        }
+        fs::write(dir.path().join("notes.png"), "plain text, not an image").unwrap();
+
+        let result = scan_images_blocking(
+            &NoProgress,
+            &HashSet::new(),
+            dir.path().to_string_lossy().to_string(),
+            0,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(result.total_files_scanned, 2);
+        assert_eq!(result.groups.len(), 1);
+        let mut names: Vec<&str> = result.groups[0]
+            .files
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["export.dat", "photo.png"]);
+    }
+
+    #[test]
+    fn test_scan_images_keeps_different_pictures_apart() {
+        let dir = tempdir().unwrap();
+        // Same format, size and dimensions; only the pixels differ.
+        for (name, shade) in [("red.bmp", [255, 0, 0]), ("blue.bmp", [0, 0, 255])] {
+            image::RgbImage::from_pixel(4, 3, image::Rgb(shade))
+                .save_with_format(dir.path().join(name), image::ImageFormat::Bmp)
+                .unwrap();
+        }
+        let red = fs::metadata(dir.path().join("red.bmp")).unwrap().len();
+        assert_eq!(
+            red,
+            fs::metadata(dir.path().join("blue.bmp")).unwrap().len()
+        );
+
+        let result = scan_images_blocking(
+            &NoProgress,
+            &HashSet::new(),
+            dir.path().to_string_lossy().to_string(),
+            0,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(result.total_files_scanned, 2);
+        assert!(result.groups.is_empty());
+    }
+
+    #[test]
+    fn test_scan_max_groups_keeps_highest_waste_groups() {
+        let dir = tempdir().unwrap();
+        for (name, len) in [("small", 10), ("medium", 20), ("large", 30)] {
+            let content = vec![len as u8; len];
+            fs::write(dir.path().join(format!("{name}_a.bin")), &content).unwrap();
+            fs::write(dir.path().join(format!("{name}_b.bin")), &content).unwrap();
+        }
+
+        let result = hash_scan(dir.path(), Some(2));
+        assert!(result.truncated);
+        assert_eq!(result.total_group_count, 3);
+        assert_eq!(result.total_wasted_bytes, 60);
+        let mut sizes: Vec<u64> = result.groups.iter().map(|g| g.files[0].size).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![20, 30]);
+    }
+
+    #[test]
+    fn test_files_per_group_cap_reports_overflow() {
+        let dir = tempdir().unwrap();
+        for i in 0..5 {
+            fs::write(dir.path().join(format!("copy{i}.bin")), [7u8; 10]).unwrap();
+        }
+
+        let result = hash_scan_capped(dir.path(), None, Some(2), &HashSet::new(), Vec::new());
+        assert_eq!(result.groups.len(), 1);
+        assert_eq!(result.groups[0].files.len(), 2);
+        assert_eq!(result.groups[0].overflow_count, 3);
+        // Waste counts all five members, not just the two listed.
+        assert_eq!(result.total_wasted_bytes, 40);
+    }
+
+    #[test]
+    fn test_scan_pages_cover_every_group_once() {
+        let dir = tempdir().unwrap();
+        for i in 0..7u8 {
+            fs::write(dir.path().join(format!("{i}_a.bin")), [i; 8]).unwrap();
+            fs::write(dir.path().join(format!("{i}_b.bin")), [i; 8]).unwrap();
+        }
+        let cache = ScanCache::default();
+        let stale_id = cache.store(hash_scan(dir.path(), None));
+        let scan_id = cache.store(hash_scan(dir.path(), None));
+
+        let meta = cache.meta(scan_id).unwrap();
+        assert_eq!(meta.group_count, 7);
+        let mut signatures = Vec::new();
+        let mut offset = 0;
+        loop {
+            let page = cache.page(scan_id, offset, 3).unwrap();
+            if page.is_empty() {
+                break;
+            }
+            offset += page.len();
+            signatures.extend(page.into_iter().map(|g| g.signature));
+        }
+
+        assert_eq!(signatures.len(), 7);
+        signatures.sort();
+        signatures.dedup();
+        assert_eq!(signatures.len(), 7);
+        // The newer scan replaced the older one.
+        assert_eq!(
+            cache.page(stale_id, 0, 3).unwrap_err().code(),
+            "scan_expired"
+        );
+    }
+
+    #[test]
+    fn test_ignored_group_is_omitted_from_rescan() {
+        let dir = tempdir().unwrap();
+        for (name, byte) in [("keep", 1u8), ("dismiss", 2u8)] {
+            fs::write(dir.path().join(format!("{name}_a.bin")), [byte; 10]).unwrap();
+            fs::write(dir.path().join(format!("{name}_b.bin")), [byte; 10]).unwrap();
+        }
+        let first = hash_scan(dir.path(), None);
+        assert_eq!(first.groups.len(), 2);
+        let dismissed = first
+            .groups
+            .iter()
+            .find(|g| g.files[0].name.starts_with("dismiss"))
+            .unwrap();
+
+        let config = tempdir().unwrap();
+        let settings_path = config.path().join("settings.json");
+        settings::ignore_group_at(&settings_path, &dismissed.signature).unwrap();
+        let ignored: HashSet<String> = settings::load_settings_from(&settings_path)
+            .ignored_groups
+            .into_iter()
+            .collect();
+        let rescan = hash_scan_capped(dir.path(), None, None, &ignored, Vec::new());
+
+        assert_eq!(rescan.groups.len(), 1);
+        assert!(rescan.groups[0].files[0].name.starts_with("keep"));
+        assert_eq!(rescan.ignored_count, 1);
+        assert_eq!(rescan.total_group_count, 1);
+        assert_eq!(rescan.total_wasted_bytes, 10);
+
+        assert_eq!(settings::clear_ignored_at(&settings_path).unwrap(), 1);
+        assert!(settings::load_settings_from(&settings_path)
+            .ignored_groups
+            .is_empty());
+    }
+
+    #[test]
+    fn test_scan_breaks_duplicates_down_by_extension() {
+        let dir = tempdir().unwrap();
+        for name in ["a.jpg", "b.JPG", "c.jpg"] {
+            fs::write(dir.path().join(name), [1u8; 10]).unwrap();
+        }
+        fs::write(dir.path().join("a.png"), [2u8; 20]).unwrap();
+        fs::write(dir.path().join("b.png"), [2u8; 20]).unwrap();
+        fs::write(dir.path().join("noext"), [2u8; 20]).unwrap();
+        fs::write(dir.path().join("lonely.png"), [3u8; 5]).unwrap();
+
+        let result = hash_scan(dir.path(), None);
+        let stats = |ext: &str| result.by_extension[ext].clone();
+        assert_eq!(result.by_extension.len(), 3);
+        assert_eq!(
+            stats("jpg"),
+            types::ExtensionStats {
+                group_count: 1,
+                file_count: 3,
+                wasted_bytes: 20,
+            }
+        );
+        assert_eq!(stats("png").group_count, 1);
+        assert_eq!(stats("png").file_count, 2);
+        assert_eq!(stats(grouper::NO_EXTENSION).file_count, 1);
+        let waste: u64 = result.by_extension.values().map(|s| s.wasted_bytes).sum();
+        assert_eq!(waste, result.total_wasted_bytes);
+    }
+
+    #[test]
+    fn test_scan_summary_matches_full_scan() {
+        let dir = tempdir().unwrap();
+        for i in 0..3 {
+            fs::write(dir.path().join(format!("a{i}.bin")), [1u8; 10]).unwrap();
+        }
+        fs::write(dir.path().join("b0.bin"), [2u8; 20]).unwrap();
+        fs::write(dir.path().join("b1.bin"), [2u8; 20]).unwrap();
+        fs::write(dir.path().join("single.bin"), [3u8; 5]).unwrap();
+
+        let full = hash_scan(dir.path(), None);
+        let summary = summarize(&hash_scan_capped(
+            dir.path(),
+            None,
+            Some(0),
+            &HashSet::new(),
+            Vec::new(),
+        ));
+        assert_eq!(summary.group_count, full.groups.len());
+        let duplicates: usize = full.groups.iter().map(|g| g.files.len() - 1).sum();
+        assert_eq!(summary.duplicate_file_count, duplicates);
+        assert_eq!(summary.duplicate_file_count, 3);
+        assert_eq!(summary.total_wasted_bytes, full.total_wasted_bytes);
+        assert_eq!(summary.total_files_scanned, full.total_files_scanned);
+    }
+
+    #[test]
+    fn test_scan_without_cap_is_not_truncated() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), b"same").unwrap();
+        fs::write(dir.path().join("b.txt"), b"same").unwrap();
+
+        let result = hash_scan(dir.path(), Some(1));
+        assert!(!result.truncated);
+        assert_eq!(result.total_group_count, 1);
+        assert_eq!(result.groups.len(), 1);
+        assert_eq!(result.total_wasted_bytes, 4);
+    }
+
+    #[test]
+    fn test_verified_delete_spares_file_changed_after_scan() {
+        let dir = tempdir().unwrap();
+        for name in ["a.bin", "b.bin", "c.bin"] {
+            fs::write(dir.path().join(name), b"same bytes").unwrap();
+        }
+        let result = hash_scan(dir.path(), None);
+        assert_eq!(result.groups.len(), 1);
+
+        let changed = dir.path().join("b.bin");
+        fs::write(&changed, b"new bytes!").unwrap();
+        let stray = dir.path().join("stray.bin");
+        fs::write(&stray, b"same bytes").unwrap();
+        let doomed = dir.path().join("c.bin");
+        let paths: Vec<String> = [&changed, &doomed, &stray]
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+
+        let summary = delete_blocking(
+            &paths,
+            true,
+            false,
+            false,
+            Some(&result.groups),
+            None,
+            &no_trash,
+            None,
+        );
+        assert_eq!(summary.deleted, 1);
+        assert!(!doomed.exists());
+        assert_eq!(fs::read(&changed).unwrap(), b"new bytes!");
+        assert!(stray.exists());
+        let spared: Vec<&str> = summary.spared.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(spared, [paths[0].as_str(), paths[2].as_str()]);
+        assert!(summary.spared[0].message.contains("sha256"));
+    }
+
+    #[test]
+    fn test_delete_appends_to_history() {
+        let dir = tempdir().unwrap();
+        let doomed = dir.path().join("doomed.bin");
+        fs::write(&doomed, b"0123456789").unwrap();
+        let log = dir.path().join("history.jsonl");
+        let paths = [
+            doomed.to_string_lossy().to_string(),
+            dir.path().join("missing.bin").to_string_lossy().to_string(),
+        ];
+
+        let summary = delete_blocking(
+            &paths,
+            true,
+            false,
+            false,
+            None,
+            None,
+            &no_trash,
+            Some(&log),
+        );
+        assert_eq!(summary.deleted, 1);
+
+        let history = delete_history::read_history_from(&log, 10);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].path, paths[0]);
+        assert_eq!(history[0].mode, DeleteMode::Manual);
+        assert_eq!(history[0].freed_bytes, 10);
+        assert!(!history[0].trashed);
+        assert!(history[0].timestamp > 0.0);
+    }
+
+    #[test]
+    fn test_auto_dedupe_defers_group_over_trash_limit() {
+        let dir = tempdir().unwrap();
+        for sub in ["a", "b", "c"] {
+            fs::create_dir(dir.path().join(sub)).unwrap();
+            fs::write(dir.path().join(sub).join("copy.bin"), b"same bytes").unwrap();
+        }
+        let result = hash_scan(dir.path(), None);
+
+        let trash = |_: &Path| panic!("a group over the trash limit must not be trashed");
+        let report = auto_dedupe_blocking(
+            &result.groups,
+            &KeepStrategy::Newest,
+            false,
+            Some(15),
+            &trash,
+            None,
+        );
+
+        let group = &report.groups[0];
+        assert!(group.trash_limit_exceeded);
+        assert!(group.deleted.is_empty());
+        assert_eq!(group.freed_bytes, 0);
+        let mut deferred = group.needs_confirmation.clone();
+        deferred.push(group.kept.clone());
+        deferred.sort();
+        let mut members: Vec<String> = result.groups[0]
+            .files
+            .iter()
+            .map(|f| f.path.clone())
+            .collect();
+        members.sort();
+        assert_eq!(deferred, members);
+        for sub in ["a", "b", "c"] {
+            assert!(dir.path().join(sub).join("copy.bin").exists());
+        }
+    }
+
+    #[test]
+    fn test_auto_dedupe_reports_kept_and_deleted_paths() {
+        let dir = tempdir().unwrap();
+        for sub in ["a", "b", "c"] {
+            fs::create_dir(dir.path().join(sub)).unwrap();
+            fs::write(dir.path().join(sub).join("copy.bin"), b"same bytes").unwrap();
+        }
+        let result = hash_scan(dir.path(), None);
+        assert_eq!(result.groups.len(), 1);
+
+        let strategy = KeepStrategy::FolderPriority(vec![dir.path().join("b")]);
+        let report = auto_dedupe_blocking(&result.groups, &strategy, true, None, &no_trash, None);
+
+        assert_eq!(report.groups.len(), 1);
+        let group = &report.groups[0];
+        let entries: Vec<FileEntry> = result.groups[0]
+            .files
+            .iter()
+            .map(file_entry_from_dto)
+            .collect();
+        let expected_keep = keeper::select_keeper(&entries, &strategy).unwrap();
+        assert_eq!(group.kept, result.groups[0].files[expected_keep].path);
+        assert!(group
+            .kept
+            .contains(&format!("b{}", std::path::MAIN_SEPARATOR)));
+
+        let mut expected_deleted: Vec<String> = result.groups[0]
+            .files
+            .iter()
+            .map(|f| f.path.clone())
+            .filter(|p| *p != group.kept)
+            .collect();
+        expected_deleted.sort();
+        let mut deleted = group.deleted.clone();
+        deleted.sort();
+        assert_eq!(deleted, expected_deleted);
+        assert_eq!(group.freed_bytes, 20);
+        assert!(group.errors.is_empty());
+        assert!(PathBuf::from(&group.kept).exists());
+        assert!(deleted.iter().all(|p| !PathBuf::from(p).exists()));
+    }
+
+    #[test]
+    fn test_set_theme_persists_valid_theme_and_rejects_unknown() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+
+        let err = set_theme(&path, "sepia").unwrap_err();
+        assert_eq!(err.code(), "invalid_schema");
+        assert!(!path.exists());
+
+        set_theme(&path, "dark").unwrap();
+        assert_eq!(settings::load_settings_from(&path).theme, "dark");
+
+        let before = fs::read_to_string(&path).unwrap();
+        assert!(set_theme(&path, "Dark").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+    }
+
+    #[test]
+    fn test_dry_run_auto_dedupe_matches_real_run_and_deletes_nothing() {
+        let dir = tempdir().unwrap();
+        for sub in ["a", "b", "c"] {
+            fs::create_dir(dir.path().join(sub)).unwrap();
+            fs::write(dir.path().join(sub).join("copy.bin"), b"same bytes").unwrap();
+            fs::write(dir.path().join(sub).join("other.txt"), b"other").unwrap();
+        }
+        let result = hash_scan(dir.path(), None);
+        assert_eq!(result.groups.len(), 2);
+        let strategy = KeepStrategy::FolderPriority(vec![dir.path().join("c")]);
+
+        let plan = plan_auto_dedupe(&result.groups, &strategy);
+
+        assert!(plan.planned);
+        assert_eq!(plan.freed_bytes, 2 * 10 + 2 * 5);
+        assert!(plan
+            .groups
+            .iter()
+            .all(|g| g.keep_reason.contains("priority folder")));
+        for file in result.groups.iter().flat_map(|g| &g.files) {
+            assert!(Path::new(&file.path).exists(), "{} was touched", file.path);
+        }
+
+        let run = auto_dedupe_blocking(&result.groups, &strategy, true, None, &no_trash, None);
+        assert!(!run.planned);
+        assert_eq!(run.freed_bytes, plan.freed_bytes);
+        assert_eq!(run.groups.len(), plan.groups.len());
+        for (planned, done) in plan.groups.iter().zip(&run.groups) {
+            assert_eq!(planned.kept, done.kept);
+            assert_eq!(planned.keep_reason, done.keep_reason);
+            assert_eq!(planned.freed_bytes, done.freed_bytes);
+            let sorted = |paths: &[String]| {
+                let mut paths = paths.to_vec();
+                paths.sort();
+                paths
+            };
+            assert_eq!(sorted(&planned.deleted), sorted(&done.deleted));
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_leaves_one_canonically_named_copy() {
+        let dir = tempdir().unwrap();
+        let names = ["DSC_0001.jpg", "IMG_0001.jpg", "photo.jpg"];
+        let paths: Vec<String> = names
+            .iter()
+            .map(|name| {
+                let path = dir.path().join(name);
+                fs::write(&path, b"same pixels").unwrap();
+                path.to_string_lossy().to_string()
+            })
+            .collect();
+        let schema = RenameSchema {
+            components: vec![RenameComponentDef::Literal {
+                value: "holiday".into(),
+            }],
+            separator: "_".into(),
+            ..RenameSchema::default()
+        };
+
+        let report = canonicalize_blocking(
+            &paths,
+            &schema,
+            &KeepStrategy::Newest,
+            true,
+            None,
+            &no_trash,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(report.group.deleted.len(), 2);
+        assert_eq!(report.group.freed_bytes, 22);
+        assert!(report.rename_errors.is_empty());
+        let survivor = dir.path().join("holiday.jpg");
+        assert_eq!(
+            report.renamed_to.as_deref(),
+            Some(survivor.to_string_lossy().as_ref())
+        );
+        let left: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(left, ["holiday.jpg"]);
+        assert_eq!(fs::read(&survivor).unwrap(), b"same pixels");
+    }
+
+    #[test]
+    fn test_canonicalize_refuses_different_content() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.jpg");
+        let b = dir.path().join("b.jpg");
+        fs::write(&a, b"one").unwrap();
+        fs::write(&b, b"two").unwrap();
+        let paths = [
+            a.to_string_lossy().to_string(),
+            b.to_string_lossy().to_string(),
+        ];
+        let schema = RenameSchema {
+            components: vec![RenameComponentDef::FolderName],
+            separator: "_".into(),
+            ..RenameSchema::default()
+        };
+
+        let err = canonicalize_blocking(
+            &paths,
+            &schema,
+            &KeepStrategy::Newest,
+            false,
+            None,
+            &no_trash,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "invalid_schema");
+        assert!(a.exists() && b.exists());
+    }
+
+    #[test]
+    fn test_reveal_action_per_platform() {
+        let path = Path::new("/data/photos/beach.jpg");
+        assert_eq!(
+            reveal_action("windows", path),
+            RevealAction::Spawn {
+                program: "explorer",
+                args: vec![OsString::from("/select,/data/photos/beach.jpg")],
+            }
+        );
+        assert_eq!(
+            reveal_action("macos", path),
+            RevealAction::Spawn {
+                program: "open",
+                args: vec![OsString::from("-R"), OsString::from(path)],
+            }
+        );
+        assert_eq!(
+            reveal_action("linux", path),
+            RevealAction::OpenFolder(PathBuf::from("/data/photos"))
+        );
+    }
+
+    #[test]
+    fn test_reveal_file_launches_action_and_maps_errors() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        fs::write(&file, "x").unwrap();
+
+        let launched = std::cell::RefCell::new(Vec::new());
+        reveal_file_with(&file, "linux", &|action| {
+            launched.borrow_mut().push(format!("{action:?}"));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(
+            launched.into_inner(),
+            vec![format!("{:?}", RevealAction::OpenFolder(dir.path().into()))]
+        );
+
+        let err = reveal_file_with(&file, "macos", &|_| {
+            Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+        })
+        .unwrap_err();
+        assert_eq!(err.code(), "permission_denied");
+
+        let missing = dir.path().join("missing.txt");
+        let err = reveal_file_with(&missing, "windows", &|_| Ok(())).unwrap_err();
+        assert_eq!(err.code(), "folder_not_found");
+    }
+
+    #[test]
+    fn test_master_scan_keeps_master_copy() {
+        let dir = tempdir().unwrap();
+        let master = dir.path().join("master");
+        let other = dir.path().join("other");
+        fs::create_dir_all(&master).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        fs::write(master.join("photo.jpg"), "archived").unwrap();
+        fs::write(other.join("photo copy.jpg"), "archived").unwrap();
+        // Duplicates among the others alone are not the archive's business.
+        fs::write(other.join("a.txt"), "loose").unwrap();
+        fs::write(other.join("b.txt"), "loose").unwrap();
+        let config = hash_only_config();
+
+        let result = scan_against_master_blocking(
+            &NoProgress,
+            &master.to_string_lossy(),
+            &[other.to_string_lossy().to_string()],
+            &config,
+            None,
+            &ScanOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.groups.len(), 1);
+        let group = &result.groups[0];
+        assert_eq!(group.files.len(), 2);
+        assert_eq!(
+            group.keeper.as_deref(),
+            Some(master.join("photo.jpg").to_string_lossy().as_ref())
+        );
+        assert_eq!(result.total_files_scanned, 4);
+        assert_eq!(result.total_wasted_bytes, 8);
+    }
+
+    #[test]
+    fn test_auto_dedupe_of_master_scan_keeps_master() {
+        let dir = tempdir().unwrap();
+        let master = dir.path().join("master");
+        let other = dir.path().join("other");
+        fs::create_dir_all(&master).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        fs::write(master.join("photo.jpg"), "archived").unwrap();
+        fs::write(other.join("photo copy.jpg"), "archived").unwrap();
+        let config = hash_only_config();
+        let result = scan_against_master_blocking(
+            &NoProgress,
+            &master.to_string_lossy(),
+            &[other.to_string_lossy().to_string()],
+            &config,
+            None,
+            &ScanOptions::default(),
+        )
+        .unwrap();
+        let master_copy = master.join("photo.jpg").to_string_lossy().to_string();
+        // A strategy that, left alone, would keep the other copy.
+        let strategy = KeepStrategy::FolderPriority(vec![other.clone()]);
+
+        let plan = plan_auto_dedupe(&result.groups, &strategy);
+        assert_eq!(plan.groups[0].kept, master_copy);
+
+        let manual = delete_blocking(
+            std::slice::from_ref(&master_copy),
+            true,
+            false,
+            false,
+            Some(&result.groups),
+            None,
+            &no_trash,
+            None,
+        );
+        assert_eq!(manual.deleted, 0);
+        assert_eq!(manual.spared.len(), 1);
+
+        let run = auto_dedupe_blocking(&result.groups, &strategy, true, None, &no_trash, None);
+        assert_eq!(run.groups[0].kept, master_copy);
+        assert_eq!(
+            run.groups[0].deleted,
+            vec![other.join("photo copy.jpg").to_string_lossy().to_string()]
+        );
+        assert!(master.join("photo.jpg").exists());
+        assert!(!other.join("photo copy.jpg").exists());
+    }
+
+    #[test]
+    fn test_scan_marks_file_limit_reached() {
+        let dir = tempdir().unwrap();
+        for i in 0..5 {
+            fs::write(dir.path().join(format!("{i}.bin")), b"same bytes").unwrap();
+        }
+        let scan = |max_files| {
+            scan_blocking(
+                &NoProgress,
+                &HashMap::new(),
+                &HashSet::new(),
+                ScanParams {
+                    max_files,
+                    ..hash_only(dir.path())
+                },
+            )
+            .unwrap()
+        };
+
+        let capped = scan(Some(3));
+        assert_eq!(capped.total_files_scanned, 3);
+        assert!(capped.file_limit_reached);
+        assert_eq!(capped.groups[0].files.len(), 3);
+
+        let exact = scan(Some(5));
+        assert_eq!(exact.total_files_scanned, 5);
+        assert!(!exact.file_limit_reached);
+
+        let full = scan(None);
+        assert_eq!(full.total_files_scanned, 5);
+        assert!(!full.file_limit_reached);
+    }
+
+    #[test]
+    fn test_scan_missing_folder_returns_folder_not_found() {
+        let dir = tempdir().unwrap();
+        let missing = dir.path().join("missing");
+        let result = scan_blocking(
+            &NoProgress,
+            &HashMap::new(),
+            &HashSet::new(),
+            hash_only(&missing),
+        );
+        let err = result.unwrap_err();
+        assert_eq!(err.code(), "folder_not_found");
+        assert!(matches!(err, CommandError::FolderNotFound(_)));
+    }
+
+    /// Records the phase of every progress update, collapsing repeats.
+    #[derive(Default)]
+    struct PhaseRecorder(std::cell::RefCell<Vec<&'static str>>);
+
+    impl PhaseRecorder {
+        fn record(&self, phase: &'static str) {
+            let mut phases = self.0.borrow_mut();
+            if phases.last() != Some(&phase) {
+                phases.push(phase);
+            }
+        }
+    }
+
+    impl ProgressSink for PhaseRecorder {
+        fn on_scan(&self, _files: usize, _dirs: usize) {
+            self.record("scan");
+        }
+        fn on_hash(&self, _current: usize, _total: usize) {
+            self.record("hash");
+        }
+        fn on_mime(&self, _checked: usize, _total: usize) {
+            self.record("mime");
+        }
+    }
+
+    #[test]
+    fn test_scan_reports_phases_in_order() {
+        let dir = tempdir().unwrap();
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        fs::write(dir.path().join("a.png"), png).unwrap();
+        fs::write(dir.path().join("b.png"), png).unwrap();
+        fs::write(dir.path().join("notes.txt"), b"text").unwrap();
+
+        let recorder = PhaseRecorder::default();
+        let result = scan_blocking(
+            &recorder,
+            &HashMap::new(),
+            &HashSet::new(),
+            ScanParams {
+                mime_filter: Some(vec!["image/*".into()]),
+                ..hash_only(dir.path())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.groups.len(), 1);
+        assert_eq!(recorder.0.into_inner(), vec!["scan", "mime", "hash"]);
+    }
 }
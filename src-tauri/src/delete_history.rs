@@ -0,0 +1,136 @@
+//! Log of the files the app deleted, kept across sessions.
+//!
+//! One JSON [`DeleteHistoryEntry`] per line in the config directory, oldest
+//! first.  Once the file grows past [`MAX_HISTORY_BYTES`], the oldest
+//! entries are dropped so it shrinks to half that.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::settings;
+use crate::types::DeleteHistoryEntry;
+
+/// File name of the log inside the config directory.
+const DELETE_HISTORY_FILE: &str = ".duplicate_cleaner_delete_history.jsonl";
+
+/// Size past which the oldest entries are dropped.
+pub const MAX_HISTORY_BYTES: u64 = 1024 * 1024;
+
+/// Path to the delete history log.
+pub fn delete_history_path() -> PathBuf {
+    settings::config_dir().join(DELETE_HISTORY_FILE)
+}
+
+/// Append `entries` to the log at `path`, trimming it once it is larger
+/// than `max_bytes`.
+pub fn append_history_to(
+    entries: &[DeleteHistoryEntry],
+    path: &Path,
+    max_bytes: u64,
+) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&serde_json::to_string(entry).map_err(|e| e.to_string())?);
+        lines.push('\n');
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    file.write_all(lines.as_bytes())
+        .map_err(|e| e.to_string())?;
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+    drop(file);
+    if len > max_bytes {
+        trim_history(path, max_bytes / 2)?;
+    }
+    Ok(())
+}
+
+/// Rewrite the log at `path` with only the newest whole lines that fit in
+/// `keep_bytes`.
+fn trim_history(path: &Path, keep_bytes: u64) -> Result<(), String> {
+    let content = std::fs::read(path).map_err(|e| e.to_string())?;
+    let cut = content.len().saturating_sub(keep_bytes as usize);
+    let start = if cut == 0 || content[cut - 1] == b'\n' {
+        cut
+    } else {
+        content[cut..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(content.len(), |i| cut + i + 1)
+    };
+    std::fs::write(path, &content[start..]).map_err(|e| e.to_string())
+}
+
+/// The newest `limit` entries of the log at `path`, newest first.
+///
+/// Empty when nothing was logged; unreadable lines are skipped.
+pub fn read_history_from(path: &Path, limit: usize) -> Vec<DeleteHistoryEntry> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DeleteMode;
+    use tempfile::tempdir;
+
+    fn entry(path: &str, timestamp: f64) -> DeleteHistoryEntry {
+        DeleteHistoryEntry {
+            timestamp,
+            path: path.into(),
+            mode: DeleteMode::Manual,
+            freed_bytes: 10,
+            trashed: true,
+        }
+    }
+
+    #[test]
+    fn test_history_lists_newest_first_within_limit() {
+        let dir = tempdir().unwrap();
+        let log = dir.path().join("state").join(DELETE_HISTORY_FILE);
+        append_history_to(&[entry("a", 1.0), entry("b", 2.0)], &log, MAX_HISTORY_BYTES).unwrap();
+        append_history_to(&[entry("c", 3.0)], &log, MAX_HISTORY_BYTES).unwrap();
+
+        let paths = |entries: Vec<DeleteHistoryEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.path).collect()
+        };
+        assert_eq!(paths(read_history_from(&log, 2)), ["c", "b"]);
+        assert_eq!(paths(read_history_from(&log, 10)), ["c", "b", "a"]);
+        assert!(read_history_from(&dir.path().join("missing.jsonl"), 10).is_empty());
+    }
+
+    #[test]
+    fn test_oversized_history_drops_oldest_entries() {
+        let dir = tempdir().unwrap();
+        let log = dir.path().join(DELETE_HISTORY_FILE);
+        let line_len = serde_json::to_string(&entry("p00", 0.0)).unwrap().len() as u64 + 1;
+        for i in 0..10 {
+            let path = format!("p{:02}", i);
+            append_history_to(&[entry(&path, 0.0)], &log, line_len * 4).unwrap();
+        }
+
+        let len = std::fs::metadata(&log).unwrap().len();
+        assert!(len <= line_len * 4, "log kept {} bytes", len);
+        let kept = read_history_from(&log, 100);
+        assert!(!kept.is_empty());
+        assert_eq!(kept[0].path, "p09");
+        assert!(kept.iter().all(|e| e.path.as_str() > "p05"));
+    }
+}
@@ -1,44 +1,493 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use walkdir::WalkDir;
+
+use crate::archive;
+use crate::file_lock;
+use crate::scanner;
+use crate::types::{PathErrorDto, RemoveEmptyDirsResult, RestoreSummary};
+use crate::wire_path;
+
+/// Worker count used by [`delete_files`].
+pub const DEFAULT_DELETE_CONCURRENCY: usize = 4;
+
+/// Tries at moving one file to the trash before giving up.  Trash backends
+/// can fail briefly, e.g. while the Recycle Bin is locked by Explorer.
+const TRASH_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry of a failed trash; doubled for each next one.
+const TRASH_RETRY_DELAY: Duration = Duration::from_millis(50);
 
 /// Result of a batch delete operation.
 pub struct DeleteResult {
     pub deleted: usize,
     pub errors: Vec<(String, String)>, // (path, error_message)
+    /// Files that were deleted, but not the way the caller asked for, e.g.
+    /// permanently because the trash failed.  Same shape as `errors`.
+    pub warnings: Vec<(String, String)>,
+    /// Files left in place because their volume has no trash; deleting them
+    /// would be permanent, so the user has to confirm first.
+    pub needs_confirmation: Vec<String>,
+    /// Files that went to the trash, so [`restore_from_trash`] can bring
+    /// them back.  Sorted.
+    pub trashed: Vec<String>,
+    /// Path and size of every file actually removed, trashed or not.
+    /// Sorted by path; empty for a dry run.
+    pub removed: Vec<(String, u64)>,
+    /// The batch was bigger than the trash limit, so every path was
+    /// deferred to `needs_confirmation` untouched.
+    pub trash_limit_exceeded: bool,
+}
+
+/// What happened to one file of a batch delete.
+enum FileOutcome {
+    Deleted,
+    /// Deleted by moving it to the trash.
+    Trashed,
+    /// Deleted, with a note on how it deviated from a plain trash.
+    DeletedWithWarning(String),
+    /// Left in place pending confirmation of a permanent delete.
+    Deferred,
+}
+
+/// Outcome of one attempt to move a file to the trash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrashOutcome {
+    Trashed,
+    /// The volume has no trash (network share); nothing was touched.
+    Unsupported,
+    /// Trashing failed for another reason.
+    Failed,
+}
+
+/// Move `path` to the system Recycle Bin / Trash.
+///
+/// Network volumes are reported as [`TrashOutcome::Unsupported`] without
+/// trying: trash backends either reject them or copy the whole file back
+/// to the local disk.  Detection is best-effort (see the scanner's
+/// network-drive probe); removable drives are left to the trash backend.
+pub fn system_trash(path: &Path) -> TrashOutcome {
+    if scanner::is_network_path(path) {
+        return TrashOutcome::Unsupported;
+    }
+    match trash::delete(path) {
+        Ok(()) => TrashOutcome::Trashed,
+        Err(_) => TrashOutcome::Failed,
+    }
 }
 
 /// Delete files, preferring Recycle Bin / Trash when available.
 ///
-/// Falls back to permanent deletion (`std::fs::remove_file`) if the trash
-/// crate fails. Returns the count of deleted files and any errors.
+/// A file the trash keeps failing on (after [`TRASH_ATTEMPTS`] tries) is
+/// left in place and reported in `errors`.  Files on volumes without a
+/// trash are not deleted but listed in `needs_confirmation`.  Returns the
+/// count of deleted files and any errors.
 pub fn delete_files(paths: &[PathBuf]) -> DeleteResult {
-    let mut deleted: usize = 0;
-    let mut errors: Vec<(String, String)> = Vec::new();
-
-    for path in paths {
-        // Try trash first (cross-platform recycle bin).
-        match trash::delete(path) {
-            Ok(()) => {
-                deleted += 1;
-                continue;
-            }
-            Err(_) => {
-                // Fallback to permanent deletion.
-                match std::fs::remove_file(path) {
-                    Ok(()) => {
-                        deleted += 1;
+    delete_files_concurrent(paths, DEFAULT_DELETE_CONCURRENCY)
+}
+
+/// Same as [`delete_files`], spreading the work over at most `concurrency`
+/// worker threads.
+///
+/// Each worker pulls the next path from a shared index, so a slow trash call
+/// never stalls the whole batch.  The trash backends set up any per-thread
+/// state they need (e.g. COM on Windows) on every call, so they are safe to
+/// drive from several threads.  `errors` is sorted by path so the result does
+/// not depend on thread scheduling.
+pub fn delete_files_concurrent(paths: &[PathBuf], concurrency: usize) -> DeleteResult {
+    delete_files_with(paths, concurrency, false, false, false, None, &system_trash)
+}
+
+/// Same as [`delete_files_concurrent`] with an explicit trash backend.
+///
+/// With `allow_permanent`, files whose volume has no trash are deleted
+/// permanently instead of being deferred to `needs_confirmation`.  Files
+/// the trash keeps failing on are kept and reported in `errors`, unless
+/// `permanent_on_trash_failure` is set: then they are deleted permanently
+/// and noted in `warnings`.  The two are separate so that confirming a
+/// permanent delete never also agrees to lose files the trash rejects.
+///
+/// With `dry_run`, nothing is touched: each path is only checked (exists,
+/// readable, trash-capable volume) and the result reports what a real run
+/// would do, errors included.  Virtual `zip://` archive members are always
+/// reported as errors and their archive is left alone.
+///
+/// With `trash_limit`, a batch whose files add up to more bytes than that
+/// is not started: trashes with a size cap may silently delete such a
+/// batch for good, so every path goes to `needs_confirmation` and
+/// `trash_limit_exceeded` is set.  `allow_permanent` skips the check, as
+/// the user has then agreed to lose the files.
+pub fn delete_files_with(
+    paths: &[PathBuf],
+    concurrency: usize,
+    allow_permanent: bool,
+    permanent_on_trash_failure: bool,
+    dry_run: bool,
+    trash_limit: Option<u64>,
+    trash: &(dyn Fn(&Path) -> TrashOutcome + Sync),
+) -> DeleteResult {
+    if let (false, Some(limit)) = (allow_permanent, trash_limit) {
+        if scanner::total_size(paths) > limit {
+            let mut needs_confirmation: Vec<String> =
+                paths.iter().map(|p| wire_path::encode(p)).collect();
+            needs_confirmation.sort();
+            return DeleteResult {
+                deleted: 0,
+                errors: Vec::new(),
+                warnings: Vec::new(),
+                needs_confirmation,
+                trashed: Vec::new(),
+                removed: Vec::new(),
+                trash_limit_exceeded: true,
+            };
+        }
+    }
+
+    let workers = concurrency.clamp(1, paths.len().max(1));
+    let next = AtomicUsize::new(0);
+    let deleted = AtomicUsize::new(0);
+    let errors: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+    let warnings: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+    let deferred: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    let trashed: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    let removed: Mutex<Vec<(String, u64)>> = Mutex::new(Vec::new());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = paths.get(idx) else {
+                    break;
+                };
+                let outcome = if archive::split_virtual_path(path).is_some() {
+                    Err((
+                        wire_path::encode(path),
+                        "Files inside archives cannot be deleted".into(),
+                    ))
+                } else if dry_run {
+                    probe_one(path, allow_permanent)
+                } else {
+                    let size = scanner::safe_path_size(path);
+                    let outcome =
+                        delete_one(path, allow_permanent, permanent_on_trash_failure, trash);
+                    if let Ok(
+                        FileOutcome::Deleted
+                        | FileOutcome::Trashed
+                        | FileOutcome::DeletedWithWarning(_),
+                    ) = &outcome
+                    {
+                        removed
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .push((wire_path::encode(path), size));
+                    }
+                    outcome
+                };
+                match outcome {
+                    Ok(FileOutcome::Deleted) => {
+                        deleted.fetch_add(1, Ordering::Relaxed);
                     }
-                    Err(e) => {
-                        errors.push((
-                            path.to_string_lossy().to_string(),
-                            format!("Could not delete {}:\n{}", path.display(), e),
-                        ));
+                    Ok(FileOutcome::Trashed) => {
+                        deleted.fetch_add(1, Ordering::Relaxed);
+                        trashed
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .push(wire_path::encode(path));
+                    }
+                    Ok(FileOutcome::DeletedWithWarning(message)) => {
+                        deleted.fetch_add(1, Ordering::Relaxed);
+                        warnings
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .push((wire_path::encode(path), message));
+                    }
+                    Ok(FileOutcome::Deferred) => {
+                        deferred
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .push(wire_path::encode(path));
+                    }
+                    Err(err) => {
+                        errors.lock().unwrap_or_else(|e| e.into_inner()).push(err);
                     }
                 }
+            });
+        }
+    });
+
+    let mut errors = errors.into_inner().unwrap_or_else(|e| e.into_inner());
+    errors.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut warnings = warnings.into_inner().unwrap_or_else(|e| e.into_inner());
+    warnings.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut needs_confirmation = deferred.into_inner().unwrap_or_else(|e| e.into_inner());
+    needs_confirmation.sort();
+    let mut trashed = trashed.into_inner().unwrap_or_else(|e| e.into_inner());
+    trashed.sort();
+    let mut removed = removed.into_inner().unwrap_or_else(|e| e.into_inner());
+    removed.sort();
+
+    DeleteResult {
+        deleted: deleted.into_inner(),
+        errors,
+        warnings,
+        needs_confirmation,
+        trashed,
+        removed,
+        trash_limit_exceeded: false,
+    }
+}
+
+/// Trash a single file.
+///
+/// On a volume without a trash, the file is deleted permanently with
+/// `allow_permanent` and deferred otherwise.  When the trash keeps
+/// failing, the file is kept and reported as an error, unless
+/// `permanent_on_trash_failure` is set: then it is deleted permanently and
+/// reported as [`FileOutcome::DeletedWithWarning`].
+fn delete_one(
+    path: &Path,
+    allow_permanent: bool,
+    permanent_on_trash_failure: bool,
+    trash: &dyn Fn(&Path) -> TrashOutcome,
+) -> Result<FileOutcome, (String, String)> {
+    // Try trash first (cross-platform recycle bin).
+    let outcome = match trash_with_retry(path, trash) {
+        TrashOutcome::Trashed => return Ok(FileOutcome::Trashed),
+        TrashOutcome::Unsupported if !allow_permanent => return Ok(FileOutcome::Deferred),
+        TrashOutcome::Unsupported => FileOutcome::Deleted,
+        TrashOutcome::Failed if !permanent_on_trash_failure => {
+            let message = match std::fs::symlink_metadata(path) {
+                Ok(_) if file_lock::is_locked(path) => format!(
+                    "Could not move {} to trash:\n{}",
+                    path.display(),
+                    file_lock::IN_USE_MESSAGE
+                ),
+                Ok(_) => format!(
+                    "Could not move {} to trash; it was not deleted",
+                    path.display()
+                ),
+                Err(e) => format!("Could not delete {}:\n{}", path.display(), e),
+            };
+            return Err((wire_path::encode(path), message));
+        }
+        TrashOutcome::Failed => FileOutcome::DeletedWithWarning(
+            "Could not move to trash; deleted permanently instead".into(),
+        ),
+    };
+    // Permanent deletion, which the caller has agreed to.
+    std::fs::remove_file(path).map(|()| outcome).map_err(|e| {
+        (
+            wire_path::encode(path),
+            format!(
+                "Could not delete {}:\n{}",
+                path.display(),
+                file_lock::describe(&e)
+            ),
+        )
+    })
+}
+
+/// Call `trash` up to [`TRASH_ATTEMPTS`] times while it fails, backing off
+/// between tries.  A missing trash is not retried.
+fn trash_with_retry(path: &Path, trash: &dyn Fn(&Path) -> TrashOutcome) -> TrashOutcome {
+    let mut outcome = trash(path);
+    let mut delay = TRASH_RETRY_DELAY;
+    for _ in 1..TRASH_ATTEMPTS {
+        if outcome != TrashOutcome::Failed {
+            break;
+        }
+        std::thread::sleep(delay);
+        delay *= 2;
+        outcome = trash(path);
+    }
+    outcome
+}
+
+/// Dry-run counterpart of [`delete_one`]: check that `path` could be
+/// deleted without touching it.
+///
+/// A dry run cannot tell whether the trash would fail, so it never warns.
+/// Files another program holds open are reported as errors.
+fn probe_one(path: &Path, allow_permanent: bool) -> Result<FileOutcome, (String, String)> {
+    let check = std::fs::metadata(path).and_then(|meta| {
+        if !meta.is_file() {
+            Err(std::io::Error::other("not a regular file"))
+        } else if file_lock::is_locked(path) {
+            Err(std::io::Error::other(file_lock::IN_USE_MESSAGE))
+        } else {
+            std::fs::File::open(path).map(drop)
+        }
+    });
+    if let Err(e) = check {
+        return Err((
+            wire_path::encode(path),
+            format!("Could not delete {}:\n{}", path.display(), e),
+        ));
+    }
+    if allow_permanent || !scanner::is_network_path(path) {
+        Ok(FileOutcome::Deleted)
+    } else {
+        Ok(FileOutcome::Deferred)
+    }
+}
+
+/// Move trashed files back to their original locations.
+///
+/// When a path was trashed more than once, its most recent copy comes
+/// back.  Paths not found in the trash, or whose original location is
+/// taken again, are reported in `errors`.  `None` where the platform trash
+/// cannot be listed (macOS).
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+pub fn restore_from_trash(original_paths: &[PathBuf]) -> Option<RestoreSummary> {
+    Some(restore_from_trash_with(
+        original_paths,
+        &trash::os_limited::list,
+        &|item| trash::os_limited::restore_all([item]),
+    ))
+}
+
+/// Same as [`restore_from_trash`], listing the trash with `list` and
+/// putting single items back with `restore`.
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+pub fn restore_from_trash_with(
+    original_paths: &[PathBuf],
+    list: &dyn Fn() -> Result<Vec<trash::TrashItem>, trash::Error>,
+    restore: &dyn Fn(trash::TrashItem) -> Result<(), trash::Error>,
+) -> RestoreSummary {
+    let error = |path: &Path, message: String| PathErrorDto {
+        path: wire_path::encode(path),
+        message: format!("Could not restore {}:\n{}", path.display(), message),
+    };
+    let items = match list() {
+        Ok(items) => items,
+        Err(e) => {
+            return RestoreSummary {
+                restored: 0,
+                errors: original_paths
+                    .iter()
+                    .map(|path| error(path, e.to_string()))
+                    .collect(),
+            }
+        }
+    };
+
+    let mut restored = 0;
+    let mut errors = Vec::new();
+    for path in original_paths {
+        let latest = items
+            .iter()
+            .filter(|item| item.original_path() == *path)
+            .max_by_key(|item| item.time_deleted)
+            .cloned();
+        let Some(item) = latest else {
+            errors.push(error(path, "not found in the trash".into()));
+            continue;
+        };
+        match restore(item) {
+            Ok(()) => restored += 1,
+            Err(trash::Error::RestoreCollision { .. }) => {
+                errors.push(error(path, "a file already exists there".into()))
             }
+            Err(e) => errors.push(error(path, e.to_string())),
         }
     }
+    errors.sort_by(|a, b| a.path.cmp(&b.path));
+    RestoreSummary { restored, errors }
+}
 
-    DeleteResult { deleted, errors }
+/// The macOS trash cannot be listed, so nothing can be restored.
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+pub fn restore_from_trash(_original_paths: &[PathBuf]) -> Option<RestoreSummary> {
+    None
+}
+
+/// Remove directories under `root` that contain no files.
+///
+/// Walks bottom-up so a directory whose only children were empty directories
+/// is removed too.  `root` itself is never removed.  Without
+/// `include_subfolders` only the immediate child directories are considered,
+/// and only when they are completely empty.
+pub fn remove_empty_dirs(root: &Path, include_subfolders: bool) -> RemoveEmptyDirsResult {
+    let max_depth = if include_subfolders { usize::MAX } else { 1 };
+    let walker = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(max_depth)
+        .contents_first(true);
+
+    let mut removed: Vec<String> = Vec::new();
+    let mut errors: Vec<PathErrorDto> = Vec::new();
+
+    for result in walker {
+        let dir_entry = match result {
+            Ok(e) => e,
+            Err(err) => {
+                errors.push(PathErrorDto {
+                    path: err.path().map(wire_path::encode).unwrap_or_default(),
+                    message: err.to_string(),
+                });
+                continue;
+            }
+        };
+        if !dir_entry.file_type().is_dir() {
+            continue;
+        }
+
+        let path = dir_entry.path();
+        let is_empty = match std::fs::read_dir(path) {
+            Ok(mut children) => children.next().is_none(),
+            Err(e) => {
+                errors.push(PathErrorDto {
+                    path: wire_path::encode(path),
+                    message: format!("Could not read directory: {}", e),
+                });
+                continue;
+            }
+        };
+        if !is_empty {
+            continue;
+        }
+
+        match std::fs::remove_dir(path) {
+            Ok(()) => removed.push(wire_path::encode(path)),
+            Err(e) => errors.push(PathErrorDto {
+                path: wire_path::encode(path),
+                message: format!("Could not remove directory: {}", e),
+            }),
+        }
+    }
+
+    RemoveEmptyDirsResult {
+        removed_count: removed.len(),
+        removed,
+        errors,
+    }
 }
 
 #[cfg(test)]
@@ -61,6 +510,82 @@ mod tests {
         assert!(result.errors.is_empty());
     }
 
+    #[cfg(any(
+        target_os = "windows",
+        all(
+            unix,
+            not(target_os = "macos"),
+            not(target_os = "ios"),
+            not(target_os = "android")
+        )
+    ))]
+    #[test]
+    fn test_trashed_file_is_restored() {
+        let dir = tempdir().unwrap();
+        let bin = tempdir().unwrap();
+        let f = dir.path().join("restore_me.txt");
+        fs::write(&f, b"keep me after all").unwrap();
+
+        // A trash that moves files into `bin` and back.
+        let items: std::sync::Mutex<Vec<trash::TrashItem>> = Default::default();
+        let trash = |path: &Path| {
+            let mut items = items.lock().unwrap();
+            let seq = items.len();
+            let id = bin.path().join(seq.to_string());
+            fs::rename(path, &id).unwrap();
+            items.push(trash::TrashItem {
+                id: id.into_os_string(),
+                name: path.file_name().unwrap().to_os_string(),
+                original_parent: path.parent().unwrap().to_path_buf(),
+                time_deleted: seq as i64,
+            });
+            TrashOutcome::Trashed
+        };
+        let list = || Ok(items.lock().unwrap().clone());
+        let restore = |item: trash::TrashItem| {
+            items.lock().unwrap().retain(|i| i.id != item.id);
+            fs::rename(&item.id, item.original_path()).map_err(|e| trash::Error::Unknown {
+                description: e.to_string(),
+            })
+        };
+
+        let result = delete_files_with(
+            std::slice::from_ref(&f),
+            1,
+            false,
+            false,
+            false,
+            None,
+            &trash,
+        );
+        assert_eq!(result.trashed, vec![f.to_string_lossy().to_string()]);
+        assert!(!f.exists());
+
+        let restored = restore_from_trash_with(std::slice::from_ref(&f), &list, &restore);
+        assert_eq!(restored.restored, 1, "{:?}", restored.errors);
+        assert_eq!(fs::read(&f).unwrap(), b"keep me after all");
+
+        // Nothing is left in the trash to restore a second time.
+        let again = restore_from_trash_with(std::slice::from_ref(&f), &list, &restore);
+        assert_eq!(again.restored, 0);
+        assert_eq!(again.errors.len(), 1);
+        assert!(again.errors[0].message.contains("not found in the trash"));
+    }
+
+    #[test]
+    fn test_archive_member_is_not_deleted() {
+        let dir = tempdir().unwrap();
+        let zip = dir.path().join("a.zip");
+        fs::write(&zip, b"PK").unwrap();
+        let member = archive::virtual_path(&zip, "inner.txt");
+
+        let result = delete_files(&[member]);
+        assert_eq!(result.deleted, 0);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].1.contains("inside archives"));
+        assert!(zip.exists());
+    }
+
     #[test]
     fn test_empty_list_does_nothing() {
         let result = delete_files(&[]);
@@ -77,4 +602,322 @@ mod tests {
         assert_eq!(result.deleted, 0);
         assert_eq!(result.errors.len(), 1);
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_path_is_reported_losslessly() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = tempdir().unwrap();
+        let f = dir.path().join(std::ffi::OsStr::from_bytes(b"caf\xe9.txt"));
+        fs::write(&f, b"data").unwrap();
+
+        let result = delete_files_with(
+            std::slice::from_ref(&f),
+            1,
+            false,
+            false,
+            false,
+            None,
+            &|_| TrashOutcome::Trashed,
+        );
+        assert_eq!(result.trashed.len(), 1);
+        assert_eq!(wire_path::decode(&result.trashed[0]), f);
+    }
+
+    #[test]
+    fn test_trash_failure_keeps_file_unless_fallback_opted_in() {
+        let dir = tempdir().unwrap();
+        let f = dir.path().join("file.txt");
+        fs::write(&f, b"data").unwrap();
+
+        // Confirming permanent deletes (no-trash volumes, trash limit) is
+        // not an opt-in to the fallback.
+        for allow_permanent in [false, true] {
+            let result = delete_files_with(
+                std::slice::from_ref(&f),
+                1,
+                allow_permanent,
+                false,
+                false,
+                None,
+                &|_| TrashOutcome::Failed,
+            );
+            assert_eq!(result.deleted, 0);
+            assert_eq!(result.errors.len(), 1);
+            assert!(result.errors[0].1.contains("not deleted"));
+            assert!(f.exists());
+        }
+
+        // Once opted in, the fallback is a warning.
+        let result = delete_files_with(
+            std::slice::from_ref(&f),
+            1,
+            false,
+            true,
+            false,
+            None,
+            &|_| TrashOutcome::Failed,
+        );
+        assert_eq!(result.deleted, 1);
+        assert!(result.errors.is_empty());
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].0, f.to_string_lossy());
+        assert!(!f.exists());
+    }
+
+    #[test]
+    fn test_transient_trash_failures_are_retried() {
+        let dir = tempdir().unwrap();
+        let f = dir.path().join("file.txt");
+        fs::write(&f, b"data").unwrap();
+
+        // Fails twice (a locked Recycle Bin), then trashes the file.
+        let calls = AtomicUsize::new(0);
+        let flaky_trash = |p: &Path| {
+            if calls.fetch_add(1, Ordering::Relaxed) < 2 {
+                TrashOutcome::Failed
+            } else {
+                fs::remove_file(p).unwrap();
+                TrashOutcome::Trashed
+            }
+        };
+        let result = delete_files_with(
+            std::slice::from_ref(&f),
+            1,
+            true,
+            false,
+            false,
+            None,
+            &flaky_trash,
+        );
+
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+        assert_eq!(result.deleted, 1);
+        assert_eq!(result.trashed, vec![f.to_string_lossy().to_string()]);
+        assert!(result.warnings.is_empty(), "deleted permanently");
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn test_unsupported_trash_paths_are_deferred() {
+        let dir = tempdir().unwrap();
+        let local = dir.path().join("local.txt");
+        let share = dir.path().join("share.txt");
+        fs::write(&local, b"data").unwrap();
+        fs::write(&share, b"data").unwrap();
+
+        // Mock backend: "share" lives on a volume without a trash.
+        let mock_trash = |p: &Path| {
+            if p.ends_with("share.txt") {
+                TrashOutcome::Unsupported
+            } else {
+                fs::remove_file(p).unwrap();
+                TrashOutcome::Trashed
+            }
+        };
+        let result = delete_files_with(
+            &[local.clone(), share.clone()],
+            2,
+            false,
+            false,
+            false,
+            None,
+            &mock_trash,
+        );
+        assert_eq!(result.deleted, 1);
+        assert!(result.errors.is_empty());
+        assert_eq!(
+            result.needs_confirmation,
+            vec![share.to_string_lossy().to_string()]
+        );
+        assert!(!local.exists());
+        assert!(share.exists());
+
+        // Once confirmed, the deferred file is deleted permanently.
+        let result = delete_files_with(
+            std::slice::from_ref(&share),
+            1,
+            true,
+            false,
+            false,
+            None,
+            &mock_trash,
+        );
+        assert_eq!(result.deleted, 1);
+        assert!(result.needs_confirmation.is_empty());
+        assert!(!share.exists());
+    }
+
+    #[test]
+    fn test_batch_over_trash_limit_is_deferred() {
+        let dir = tempdir().unwrap();
+        let paths: Vec<PathBuf> = ["a.bin", "b.bin", "c.bin"]
+            .iter()
+            .map(|name| {
+                let f = dir.path().join(name);
+                fs::write(&f, [0u8; 400]).unwrap();
+                f
+            })
+            .collect();
+        let trash_called = |_: &Path| -> TrashOutcome { panic!("over the limit must not trash") };
+
+        for dry_run in [true, false] {
+            let result =
+                delete_files_with(&paths, 2, false, false, dry_run, Some(1000), &trash_called);
+            assert!(result.trash_limit_exceeded);
+            assert_eq!(result.deleted, 0);
+            let expected: Vec<String> = paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect();
+            assert_eq!(result.needs_confirmation, expected);
+        }
+        assert!(paths.iter().all(|p| p.exists()));
+
+        // Under the limit, or once confirmed, the batch goes ahead.
+        let result = delete_files_with(&paths[..2], 2, false, false, false, Some(1000), &|p| {
+            fs::remove_file(p).unwrap();
+            TrashOutcome::Trashed
+        });
+        assert!(!result.trash_limit_exceeded);
+        assert_eq!(result.deleted, 2);
+        let result = delete_files_with(&paths[2..], 1, true, false, false, Some(100), &|p| {
+            fs::remove_file(p).unwrap();
+            TrashOutcome::Trashed
+        });
+        assert_eq!(result.deleted, 1);
+    }
+
+    #[test]
+    fn test_dry_run_leaves_files_in_place() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.txt");
+        let b = dir.path().join("b.txt");
+        let missing = dir.path().join("missing.txt");
+        fs::write(&a, b"data").unwrap();
+        fs::write(&b, b"data").unwrap();
+
+        let trash_called = |_: &Path| -> TrashOutcome { panic!("dry run must not trash") };
+        let result = delete_files_with(
+            &[a.clone(), b.clone(), missing.clone()],
+            2,
+            false,
+            false,
+            true,
+            None,
+            &trash_called,
+        );
+        assert_eq!(result.deleted, 2);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].0, missing.to_string_lossy());
+        assert!(result.needs_confirmation.is_empty());
+        assert!(a.exists());
+        assert!(b.exists());
+    }
+
+    #[test]
+    fn test_concurrent_delete_counts_all_files() {
+        let dir = tempdir().unwrap();
+        let paths: Vec<PathBuf> = (0..64)
+            .map(|i| {
+                let f = dir.path().join(format!("file_{:03}.txt", i));
+                fs::write(&f, b"data").unwrap();
+                f
+            })
+            .collect();
+
+        let calls = AtomicUsize::new(0);
+        let counting_trash = |p: &Path| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            fs::remove_file(p).unwrap();
+            TrashOutcome::Trashed
+        };
+        let result = delete_files_with(&paths, 8, false, false, false, None, &counting_trash);
+        assert_eq!(calls.load(Ordering::Relaxed), 64);
+        assert_eq!(result.deleted, 64);
+        assert_eq!(result.trashed.len(), 64);
+        assert!(result.errors.is_empty());
+        assert!(paths.iter().all(|p| !p.exists()));
+    }
+
+    #[test]
+    fn test_concurrent_delete_errors_sorted_by_path() {
+        let dir = tempdir().unwrap();
+        let paths: Vec<PathBuf> = ["c.txt", "a.txt", "b.txt"]
+            .iter()
+            .map(|n| dir.path().join(n))
+            .collect();
+
+        let result = delete_files_concurrent(&paths, 3);
+        assert_eq!(result.deleted, 0);
+        let errored: Vec<&str> = result.errors.iter().map(|(p, _)| p.as_str()).collect();
+        let mut sorted = errored.clone();
+        sorted.sort();
+        assert_eq!(errored, sorted);
+        assert_eq!(errored.len(), 3);
+    }
+
+    #[test]
+    fn test_zero_concurrency_still_deletes() {
+        let dir = tempdir().unwrap();
+        let f = dir.path().join("file.txt");
+        fs::write(&f, b"data").unwrap();
+        let calls = AtomicUsize::new(0);
+        let counting_trash = |p: &Path| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            fs::remove_file(p).unwrap();
+            TrashOutcome::Trashed
+        };
+        let result = delete_files_with(
+            std::slice::from_ref(&f),
+            0,
+            false,
+            false,
+            false,
+            None,
+            &counting_trash,
+        );
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert_eq!(result.deleted, 1);
+        assert_eq!(result.trashed.len(), 1);
+    }
+
+    #[test]
+    fn test_remove_empty_dirs_removes_nested_empty_tree() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("a/b/c")).unwrap();
+        fs::create_dir_all(dir.path().join("a/d")).unwrap();
+
+        let result = remove_empty_dirs(dir.path(), true);
+        assert_eq!(result.removed_count, 4);
+        assert!(result.errors.is_empty());
+        assert!(!dir.path().join("a").exists());
+        // The root itself is never removed.
+        assert!(dir.path().exists());
+    }
+
+    #[test]
+    fn test_remove_empty_dirs_keeps_dir_with_file() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("keep/empty")).unwrap();
+        fs::write(dir.path().join("keep/file.txt"), b"x").unwrap();
+
+        let result = remove_empty_dirs(dir.path(), true);
+        assert_eq!(result.removed_count, 1);
+        assert!(dir.path().join("keep/file.txt").exists());
+        assert!(!dir.path().join("keep/empty").exists());
+    }
+
+    #[test]
+    fn test_remove_empty_dirs_without_subfolders_only_top_level() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("top_empty")).unwrap();
+        fs::create_dir_all(dir.path().join("nested/empty")).unwrap();
+
+        let result = remove_empty_dirs(dir.path(), false);
+        assert_eq!(result.removed_count, 1);
+        assert!(!dir.path().join("top_empty").exists());
+        assert!(dir.path().join("nested/empty").exists());
+    }
 }
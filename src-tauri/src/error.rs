@@ -0,0 +1,120 @@
+//! Machine-readable errors returned by Tauri commands.
+//!
+//! Serialised as `{"code":"folder_not_found","message":"..."}` so the
+//! frontend can branch on `code` instead of matching message text.
+
+use serde::{Deserialize, Serialize};
+
+/// Error type shared by all Tauri commands.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "code", content = "message", rename_all = "snake_case")]
+pub enum CommandError {
+    /// The requested folder (or file) does not exist.
+    FolderNotFound(String),
+    /// The OS refused access to a path.
+    PermissionDenied(String),
+    /// A rename schema or other user-supplied input is malformed.
+    InvalidSchema(String),
+    /// A background task panicked before producing a result.
+    TaskPanicked(String),
+    /// Any other I/O failure.
+    Io(String),
+    /// The operation is not available on this platform.
+    Unsupported(String),
+    /// A cached scan was asked for after a newer scan replaced it.
+    ScanExpired(String),
+}
+
+impl CommandError {
+    /// Stable snake_case code, identical to the serialised `code` field.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CommandError::FolderNotFound(_) => "folder_not_found",
+            CommandError::PermissionDenied(_) => "permission_denied",
+            CommandError::InvalidSchema(_) => "invalid_schema",
+            CommandError::TaskPanicked(_) => "task_panicked",
+            CommandError::Io(_) => "io",
+            CommandError::Unsupported(_) => "unsupported",
+            CommandError::ScanExpired(_) => "scan_expired",
+        }
+    }
+
+    /// Human-friendly message.
+    pub fn message(&self) -> &str {
+        match self {
+            CommandError::FolderNotFound(m)
+            | CommandError::PermissionDenied(m)
+            | CommandError::InvalidSchema(m)
+            | CommandError::TaskPanicked(m)
+            | CommandError::Io(m)
+            | CommandError::Unsupported(m)
+            | CommandError::ScanExpired(m) => m,
+        }
+    }
+
+    /// Build the error returned when `folder` does not exist.
+    pub fn folder_not_found(folder: &str) -> Self {
+        CommandError::FolderNotFound(format!("Folder does not exist: {}", folder))
+    }
+
+    /// Build the error returned when a `spawn_blocking` task panics.
+    pub fn task_panicked(task: &str, err: impl std::fmt::Display) -> Self {
+        CommandError::TaskPanicked(format!("{} task panicked: {}", task, err))
+    }
+
+    /// Classify an I/O error, prefixing its text with `context`.
+    pub fn from_io(context: &str, err: &std::io::Error) -> Self {
+        let message = format!("{}: {}", context, err);
+        match err.kind() {
+            std::io::ErrorKind::NotFound => CommandError::FolderNotFound(message),
+            std::io::ErrorKind::PermissionDenied => CommandError::PermissionDenied(message),
+            _ => CommandError::Io(message),
+        }
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_code_and_message() {
+        let err = CommandError::folder_not_found("/nope");
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["code"], "folder_not_found");
+        assert_eq!(json["message"], "Folder does not exist: /nope");
+    }
+
+    #[test]
+    fn test_code_matches_serialized_tag() {
+        let errors = [
+            CommandError::FolderNotFound(String::new()),
+            CommandError::PermissionDenied(String::new()),
+            CommandError::InvalidSchema(String::new()),
+            CommandError::TaskPanicked(String::new()),
+            CommandError::Io(String::new()),
+            CommandError::Unsupported(String::new()),
+            CommandError::ScanExpired(String::new()),
+        ];
+        for err in errors {
+            let json = serde_json::to_value(&err).unwrap();
+            assert_eq!(json["code"], err.code());
+        }
+    }
+
+    #[test]
+    fn test_from_io_classifies_permission_denied() {
+        let io = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        let err = CommandError::from_io("Failed to open folder", &io);
+        assert_eq!(err.code(), "permission_denied");
+        assert!(err.message().starts_with("Failed to open folder"));
+    }
+}
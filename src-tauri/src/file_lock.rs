@@ -0,0 +1,112 @@
+//! Files held open by other programs.
+//!
+//! Windows refuses to rename or delete a file another program has open
+//! without sharing it, failing with a sharing (or lock) violation whose
+//! raw OS message does not say why.  Such errors are reported as
+//! [`IN_USE_MESSAGE`] instead.  Other platforms let open files be renamed
+//! and deleted, so nothing is ever reported as in use there.
+
+use std::path::Path;
+
+/// Shown in place of the OS error for a file another program holds open.
+pub const IN_USE_MESSAGE: &str = "file is in use by another program";
+
+/// `ERROR_SHARING_VIOLATION` and `ERROR_LOCK_VIOLATION`.
+#[cfg(windows)]
+const IN_USE_OS_ERRORS: [i32; 2] = [32, 33];
+
+/// True when `err` says another program has the file open.
+#[cfg(windows)]
+pub fn is_in_use_error(err: &std::io::Error) -> bool {
+    err.raw_os_error()
+        .is_some_and(|code| IN_USE_OS_ERRORS.contains(&code))
+}
+
+#[cfg(not(windows))]
+pub fn is_in_use_error(_err: &std::io::Error) -> bool {
+    false
+}
+
+/// `err` as shown to the user: [`IN_USE_MESSAGE`] for a file another
+/// program holds open, otherwise the OS message.
+pub fn describe(err: &std::io::Error) -> String {
+    if is_in_use_error(err) {
+        IN_USE_MESSAGE.to_string()
+    } else {
+        err.to_string()
+    }
+}
+
+/// True when another program has `path` open, so renaming or deleting it
+/// would fail.  Probes by opening the file without sharing it; a file that
+/// cannot be opened for any other reason counts as not locked.
+#[cfg(windows)]
+pub fn is_locked(path: &Path) -> bool {
+    use std::os::windows::fs::OpenOptionsExt;
+    match std::fs::OpenOptions::new()
+        .read(true)
+        .share_mode(0)
+        .open(path)
+    {
+        Ok(_) => false,
+        Err(e) => is_in_use_error(&e),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn is_locked(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(all(test, windows))]
+mod tests {
+    use super::*;
+    use crate::autorenamer;
+    use crate::deleter::{self, TrashOutcome};
+    use crate::progress::NoProgress;
+    use crate::types::RenameSchema;
+    use std::fs;
+    use std::os::windows::fs::OpenOptionsExt;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_file_held_open_is_reported_in_use() {
+        let dir = tempdir().unwrap();
+        let f = dir.path().join("report.txt");
+        fs::write(&f, b"data").unwrap();
+        assert!(!is_locked(&f));
+
+        let held = fs::OpenOptions::new()
+            .read(true)
+            .share_mode(0)
+            .open(&f)
+            .unwrap();
+        assert!(is_locked(&f));
+
+        let schema: RenameSchema = serde_json::from_value(serde_json::json!({
+            "components": [{ "kind": "literal", "value": "renamed" }],
+            "separator": "_",
+        }))
+        .unwrap();
+        let renamed =
+            autorenamer::auto_rename_paths(std::slice::from_ref(&f), &schema, &NoProgress);
+        assert_eq!(renamed.error_count, 1);
+        assert!(renamed.errors[0].message.contains(IN_USE_MESSAGE));
+
+        let deleted = deleter::delete_files_with(
+            std::slice::from_ref(&f),
+            1,
+            true,
+            true,
+            false,
+            None,
+            &|_| TrashOutcome::Failed,
+        );
+        assert_eq!(deleted.deleted, 0);
+        assert!(deleted.errors[0].1.contains(IN_USE_MESSAGE));
+
+        drop(held);
+        assert!(!is_locked(&f));
+        assert!(f.exists());
+    }
+}
@@ -1,15 +1,24 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::Read;
+use std::path::{Path, PathBuf};
 
+use crate::audio_fp::{self, Fingerprint};
+use crate::autorenamer;
 use crate::hasher;
 use crate::media_meta;
-use crate::types::{CriterionValue, DuplicateKey, FileEntry, GroupingConfig};
+use crate::progress::{ProgressSink, MIME_SNIFF_BYTES};
+use crate::types::{
+    self, CriterionValue, DigestEncoding, DuplicateGroup, DuplicateKey, ExtensionStats, FileEntry,
+    GroupChange, GroupingConfig, GroupingStats, KnownKey, NameCompare, SameFolderPolicy, ScanDiff,
+    SymlinkHashPolicy,
+};
 
-/// Normalize a file name for comparison (case-insensitive on Windows).
+/// Normalize a file name for comparison (case-insensitive on Windows,
+/// via [`fold_case`]).
 pub fn normalize_name(name: &str) -> String {
     #[cfg(target_os = "windows")]
     {
-        name.to_lowercase()
+        fold_case(name)
     }
     #[cfg(not(target_os = "windows"))]
     {
@@ -17,31 +26,140 @@ pub fn normalize_name(name: &str) -> String {
     }
 }
 
+/// Unicode default full case folding (`CaseFolding.txt`, status C + F).
+///
+/// Unlike `to_lowercase`, folding expands characters that have no
+/// single-character lower case: `ß` and `ẞ` fold to `ss`, so `STRASSE`,
+/// `strasse` and `straße` all compare equal, and ligatures such as `ﬁ`
+/// fold to `fi`.  The folding is locale-independent: the Turkish
+/// mappings (`I` → `ı`) are not applied, so `I` folds to `i`, dotless `ı`
+/// stays distinct from `i`, and dotted `İ` folds to `i` plus a combining
+/// dot above (the same as a decomposed `i̇`).
+pub fn fold_case(name: &str) -> String {
+    caseless::default_case_fold_str(name)
+}
+
+/// Normalize a file name to its "copy" family.
+///
+/// Strips trailing ` (N)`, `(copy)`, `-copy` and `_copy` markers from the
+/// stem (repeatedly, case-insensitive for "copy") before applying
+/// [`normalize_name`], so `report (1).pdf` and `report-copy.pdf` both map to
+/// the key of `report.pdf`.
+pub fn name_family(name: &str) -> String {
+    let (stem, ext) = match name.rfind('.') {
+        Some(dot) if dot > 0 => name.split_at(dot),
+        _ => (name, ""),
+    };
+    let mut stem = stem;
+    while let Some(stripped) = strip_copy_marker(stem) {
+        stem = stripped;
+    }
+    normalize_name(&format!("{stem}{ext}"))
+}
+
+/// Remove one trailing copy marker from `stem`, if present and non-empty
+/// text remains in front of it.
+fn strip_copy_marker(stem: &str) -> Option<&str> {
+    let lower = stem.to_ascii_lowercase();
+    let rest_len = if lower.ends_with("(copy)") {
+        stem.len() - "(copy)".len()
+    } else if lower.ends_with("-copy") || lower.ends_with("_copy") {
+        stem.len() - "-copy".len()
+    } else if let Some(inner) = stem.strip_suffix(')') {
+        let open = inner.rfind(" (")?;
+        let digits = &inner[open + 2..];
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        open
+    } else {
+        return None;
+    };
+    let rest = stem[..rest_len].trim_end();
+    (!rest.is_empty()).then_some(rest)
+}
+
 /// Group files by selected criteria; return only groups with 2+ members.
 ///
 /// Mirrors the Python `find_duplicate_groups` function from core.py:
 /// - Size bucketing to reduce hashing work.
 /// - Only hashes within buckets of 2+ files.
 /// - Skips files exceeding `hash_max_bytes`.
+/// - Skips files whose size changed since they were gathered (counted as
+///   `in_flux`), rather than hashing a half-written file.
+/// - Skips files without a creation time when `use_created` is set.
+/// - Skips non-audio / undecodable files when `use_audio_fingerprint` is set.
+/// - Skips files without readable image dimensions when `use_dimensions`
+///   is set.
+/// - Skips unreadable files when `use_content_prefix` is set.
+/// - Keys symlinks by their resolved target instead of hashing them under
+///   [`SymlinkHashPolicy::LinkPath`].
+/// - Groups files matching one of `config.type_overrides` with that
+///   override's criteria instead (see [`find_routed_groups`]).
+/// - Merges in groups found with each of `config.alternatives` (see
+///   [`find_alternative_groups`]).
 ///
-/// Returns `(groups, hash_skipped_count)`.
+/// Returns `(groups, stats)`.
 pub fn find_duplicate_groups(
     entries: &[FileEntry],
     config: &GroupingConfig,
-    progress_cb: Option<&dyn Fn(usize, usize)>,
-) -> (HashMap<DuplicateKey, Vec<FileEntry>>, usize) {
+    progress: &dyn ProgressSink,
+) -> (HashMap<DuplicateKey, Vec<FileEntry>>, GroupingStats) {
+    find_duplicate_groups_cached(entries, config, &HashMap::new(), progress)
+}
+
+/// Same as [`find_duplicate_groups`], reusing keys from an earlier scan.
+///
+/// An entry whose path is in `known` with the same size and mtime takes its
+/// key from the cache instead of being hashed / inspected again.  Cached keys
+/// that do not fit `config` (criteria changed since the earlier scan) are
+/// ignored.
+pub fn find_duplicate_groups_cached(
+    entries: &[FileEntry],
+    config: &GroupingConfig,
+    known: &HashMap<PathBuf, KnownKey>,
+    progress: &dyn ProgressSink,
+) -> (HashMap<DuplicateKey, Vec<FileEntry>>, GroupingStats) {
+    if !config.alternatives.is_empty() {
+        return find_alternative_groups(entries, config, known, progress);
+    }
+    if !config.type_overrides.is_empty() {
+        return find_routed_groups(entries, config, known, progress);
+    }
     if !config.use_hash
         && !config.use_size
         && !config.use_name
+        && !config.use_name_family
         && !config.use_mtime
+        && !config.use_created
         && !config.use_mime
         && !config.use_media_meta
+        && !config.use_audio_fingerprint
+        && !config.use_dimensions
+        && !config.use_content_prefix
     {
-        return (HashMap::new(), 0);
+        return (HashMap::new(), GroupingStats::default());
     }
 
     let mut groups: HashMap<DuplicateKey, Vec<FileEntry>> = HashMap::new();
     let mut hash_skipped: usize = 0;
+    let mut created_skipped: usize = 0;
+    let mut audio_skipped: usize = 0;
+    let mut dimensions_skipped: usize = 0;
+    let mut in_flux: usize = 0;
+    let mut audio_pending: Vec<(DuplicateKey, Fingerprint, FileEntry)> = Vec::new();
+    let mut hashed: usize = 0;
+    let mut reused: usize = 0;
+
+    let cached_key = |entry: &FileEntry| -> Option<&DuplicateKey> {
+        known
+            .get(&entry.path)
+            .filter(|k| k.size == entry.size && k.mtime == entry.mtime)
+            .map(|k| &k.key)
+            .filter(|key| key_fits_config(key, config))
+            .filter(|key| names_fit_config(key, &entry.path, config))
+            .filter(|key| digest_fits_size(key, entry.size, config))
+    };
 
     // Bucket by size first to reduce hashing work when hashing is enabled.
     let size_buckets: Vec<Vec<&FileEntry>> = if config.use_hash {
@@ -60,7 +178,7 @@ pub fn find_duplicate_groups(
         size_buckets
             .iter()
             .filter(|b| b.len() > 1)
-            .map(|b| b.len())
+            .map(|b| b.iter().filter(|e| cached_key(e).is_none()).count())
             .sum()
     } else {
         0
@@ -71,21 +189,46 @@ pub fn find_duplicate_groups(
         let do_hash_here = config.use_hash && files.len() > 1;
 
         for entry in files {
+            if let Some(key) = cached_key(entry) {
+                reused += 1;
+                groups
+                    .entry(key.clone())
+                    .or_default()
+                    .push((*entry).clone());
+                continue;
+            }
+
             let mut components: Vec<CriterionValue> = Vec::new();
 
             if do_hash_here {
-                if let Some(max_bytes) = config.hash_max_bytes {
+                let link_target = match config.symlink_hash_policy {
+                    SymlinkHashPolicy::FollowTarget => None,
+                    SymlinkHashPolicy::LinkPath => symlink_target(&entry.path),
+                };
+                if let Some(target) = link_target {
+                    components.push(CriterionValue::LinkTarget(target));
+                    hashed_count += 1;
+                    progress.on_hash(hashed_count, total_to_hash);
+                } else if size_changed(entry) {
+                    // Still being written: a digest now would be stale
+                    // before the scan ends.
+                    in_flux += 1;
+                    hashed_count += 1;
+                    progress.on_hash(hashed_count, total_to_hash);
+                    continue;
+                } else if let Some(max_bytes) = config.hash_max_bytes {
                     if entry.size > max_bytes {
                         if config.fast_hash_oversized {
                             // Use head+tail sampling instead of skipping.
                             match hasher::sha256_fast(&entry.path) {
-                                Ok(digest) => components.push(CriterionValue::FastHash(digest)),
+                                Ok(digest) => {
+                                    hashed += 1;
+                                    components.push(CriterionValue::FastHash(digest));
+                                }
                                 Err(_) => {
                                     hash_skipped += 1;
                                     hashed_count += 1;
-                                    if let Some(cb) = &progress_cb {
-                                        cb(hashed_count, total_to_hash);
-                                    }
+                                    progress.on_hash(hashed_count, total_to_hash);
                                     continue;
                                 }
                             }
@@ -93,43 +236,39 @@ pub fn find_duplicate_groups(
                             hash_skipped += 1;
                         }
                         hashed_count += 1;
-                        if let Some(cb) = &progress_cb {
-                            cb(hashed_count, total_to_hash);
-                        }
+                        progress.on_hash(hashed_count, total_to_hash);
                         if !config.fast_hash_oversized {
                             continue;
                         }
                     } else {
-                        match hasher::sha256_file(&entry.path) {
-                            Ok(digest) => components.push(CriterionValue::Hash(digest)),
+                        match content_hash(entry, config) {
+                            Ok(digest) => {
+                                hashed += 1;
+                                components.push(digest);
+                            }
                             Err(_) => {
                                 hashed_count += 1;
-                                if let Some(cb) = &progress_cb {
-                                    cb(hashed_count, total_to_hash);
-                                }
+                                progress.on_hash(hashed_count, total_to_hash);
                                 continue;
                             }
                         }
                         hashed_count += 1;
-                        if let Some(cb) = &progress_cb {
-                            cb(hashed_count, total_to_hash);
-                        }
+                        progress.on_hash(hashed_count, total_to_hash);
                     }
                 } else {
-                    match hasher::sha256_file(&entry.path) {
-                        Ok(digest) => components.push(CriterionValue::Hash(digest)),
+                    match content_hash(entry, config) {
+                        Ok(digest) => {
+                            hashed += 1;
+                            components.push(digest);
+                        }
                         Err(_) => {
                             hashed_count += 1;
-                            if let Some(cb) = &progress_cb {
-                                cb(hashed_count, total_to_hash);
-                            }
+                            progress.on_hash(hashed_count, total_to_hash);
                             continue;
                         }
                     }
                     hashed_count += 1;
-                    if let Some(cb) = &progress_cb {
-                        cb(hashed_count, total_to_hash);
-                    }
+                    progress.on_hash(hashed_count, total_to_hash);
                 }
             }
 
@@ -138,18 +277,35 @@ pub fn find_duplicate_groups(
             }
 
             if config.use_name {
+                components.push(CriterionValue::Name(name_key(
+                    &entry.path,
+                    config.name_compare_mode,
+                )));
+            }
+
+            if config.use_name_family {
                 let name = entry
                     .path
                     .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("");
-                components.push(CriterionValue::Name(normalize_name(name)));
+                    .map(|n| n.to_string_lossy())
+                    .unwrap_or_default();
+                components.push(CriterionValue::NameFamily(name_family(&name)));
             }
 
             if config.use_mtime {
                 components.push(CriterionValue::Mtime(entry.mtime as i64));
             }
 
+            if config.use_created {
+                match entry.created {
+                    Some(ts) => components.push(CriterionValue::Created(ts as i64)),
+                    None => {
+                        created_skipped += 1;
+                        continue;
+                    }
+                }
+            }
+
             if config.use_mime {
                 let mime = detect_mime_type(&entry.path);
                 components.push(CriterionValue::MimeType(mime));
@@ -161,6 +317,42 @@ pub fn find_duplicate_groups(
                 }
             }
 
+            if config.use_dimensions {
+                match media_meta::image_dimensions(&entry.path) {
+                    Some((width, height)) => {
+                        components.push(CriterionValue::Dimensions(width, height))
+                    }
+                    None => {
+                        dimensions_skipped += 1;
+                        continue;
+                    }
+                }
+            }
+
+            if config.use_content_prefix {
+                match content_prefix(&entry.path, config.content_prefix_bytes) {
+                    Some(hex) => components.push(CriterionValue::ContentPrefix(hex)),
+                    None => continue,
+                }
+            }
+
+            if config.use_audio_fingerprint {
+                let fingerprint = if autorenamer::matches_file_type_preset(
+                    &entry.path,
+                    "audio",
+                    &config.custom_file_type_presets,
+                ) {
+                    audio_fp::fingerprint_file(&entry.path)
+                } else {
+                    None
+                };
+                match fingerprint {
+                    Some(fp) => audio_pending.push((components, fp, (*entry).clone())),
+                    None => audio_skipped += 1,
+                }
+                continue;
+            }
+
             if components.is_empty() {
                 continue;
             }
@@ -170,29 +362,549 @@ pub fn find_duplicate_groups(
         }
     }
 
+    cluster_audio(audio_pending, &mut groups);
+
     // Filter to groups with 2+ members.
-    let filtered: HashMap<DuplicateKey, Vec<FileEntry>> =
-        groups.into_iter().filter(|(_, v)| v.len() > 1).collect();
+    let filtered: HashMap<DuplicateKey, Vec<FileEntry>> = groups
+        .into_iter()
+        .map(|(key, files)| match config.same_folder_policy {
+            SameFolderPolicy::Flag => (key, files),
+            SameFolderPolicy::Ignore => (key, one_per_folder(files)),
+        })
+        .filter(|(_, v)| v.len() > 1)
+        .collect();
 
-    (filtered, hash_skipped)
+    (
+        filtered,
+        GroupingStats {
+            hash_skipped,
+            created_skipped,
+            audio_skipped,
+            dimensions_skipped,
+            in_flux,
+            hashed,
+            reused,
+        },
+    )
 }
 
-/// Detect MIME type by reading the first 8 KB of a file and using magic bytes.
-fn detect_mime_type(path: &std::path::Path) -> String {
-    let mut buf = [0u8; 8192];
-    let n = match std::fs::File::open(path).and_then(|mut f| f.read(&mut buf)) {
-        Ok(n) => n,
-        Err(_) => return "unknown".into(),
+/// Keep the first member of each folder, dropping its same-folder copies.
+fn one_per_folder(files: Vec<FileEntry>) -> Vec<FileEntry> {
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+    files
+        .into_iter()
+        .filter(|f| seen.insert(f.path.parent().map(Path::to_path_buf).unwrap_or_default()))
+        .collect()
+}
+
+/// Route each entry to the first of `config.type_overrides` whose file-type
+/// preset it matches (or to `config`'s own criteria when none does), group
+/// every route on its own, and merge the results.
+///
+/// Groups never span routes unless their keys coincide.  `progress`
+/// restarts its count for each route.
+fn find_routed_groups(
+    entries: &[FileEntry],
+    config: &GroupingConfig,
+    known: &HashMap<PathBuf, KnownKey>,
+    progress: &dyn ProgressSink,
+) -> (HashMap<DuplicateKey, Vec<FileEntry>>, GroupingStats) {
+    let overrides = &config.type_overrides;
+    let mut routes: Vec<Vec<FileEntry>> = vec![Vec::new(); overrides.len() + 1];
+    for entry in entries {
+        let route = overrides
+            .iter()
+            .position(|(preset, _)| {
+                autorenamer::matches_file_type_preset(
+                    &entry.path,
+                    preset,
+                    &config.custom_file_type_presets,
+                )
+            })
+            .unwrap_or(overrides.len());
+        routes[route].push(entry.clone());
+    }
+
+    let fallback = GroupingConfig {
+        type_overrides: Vec::new(),
+        alternatives: Vec::new(),
+        ..config.clone()
+    };
+    let configs = overrides
+        .iter()
+        .map(|(_, route_config)| route_config)
+        .chain(std::iter::once(&fallback));
+
+    let mut groups: HashMap<DuplicateKey, Vec<FileEntry>> = HashMap::new();
+    let mut stats = GroupingStats::default();
+    for (route_entries, route_config) in routes.iter().zip(configs) {
+        if route_entries.is_empty() {
+            continue;
+        }
+        let (found, route_stats) =
+            find_duplicate_groups_cached(route_entries, route_config, known, progress);
+        for (key, files) in found {
+            groups.entry(key).or_default().extend(files);
+        }
+        stats.hash_skipped += route_stats.hash_skipped;
+        stats.created_skipped += route_stats.created_skipped;
+        stats.audio_skipped += route_stats.audio_skipped;
+        stats.dimensions_skipped += route_stats.dimensions_skipped;
+        stats.in_flux += route_stats.in_flux;
+        stats.hashed += route_stats.hashed;
+        stats.reused += route_stats.reused;
+    }
+    (groups, stats)
+}
+
+/// Group `entries` under `config`'s own criteria and under each of
+/// `config.alternatives` separately, then merge groups that share a file,
+/// so two files are duplicates when any one criteria set says so.
+///
+/// Merging is transitive: `a` matching `b` by hash and `b` matching `c` by
+/// name puts all three in one group.  A merged group's key joins the
+/// values of the keys it was built from.  `progress` restarts its count for
+/// each criteria set.
+fn find_alternative_groups(
+    entries: &[FileEntry],
+    config: &GroupingConfig,
+    known: &HashMap<PathBuf, KnownKey>,
+    progress: &dyn ProgressSink,
+) -> (HashMap<DuplicateKey, Vec<FileEntry>>, GroupingStats) {
+    let primary = GroupingConfig {
+        alternatives: Vec::new(),
+        ..config.clone()
+    };
+    let index: HashMap<&Path, usize> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| (entry.path.as_path(), i))
+        .collect();
+
+    // Union-find over entry indices.
+    let mut parent: Vec<usize> = (0..entries.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let mut partial: Vec<(DuplicateKey, usize)> = Vec::new();
+    let mut stats = GroupingStats::default();
+    for criteria in std::iter::once(&primary).chain(&config.alternatives) {
+        let (found, found_stats) = find_duplicate_groups_cached(entries, criteria, known, progress);
+        let mut found: Vec<(DuplicateKey, Vec<usize>)> = found
+            .into_iter()
+            .map(|(key, files)| {
+                let members = files.iter().map(|f| index[f.path.as_path()]).collect();
+                (key, members)
+            })
+            .collect();
+        // Stable key order in the merged groups, whatever the map order.
+        found.sort_by_key(|(_, members)| members.iter().min().copied());
+        for (key, members) in found {
+            for pair in members.windows(2) {
+                let (a, b) = (root(&mut parent, pair[0]), root(&mut parent, pair[1]));
+                parent[a] = b;
+            }
+            partial.push((key, members[0]));
+        }
+        stats.hash_skipped += found_stats.hash_skipped;
+        stats.created_skipped += found_stats.created_skipped;
+        stats.audio_skipped += found_stats.audio_skipped;
+        stats.dimensions_skipped += found_stats.dimensions_skipped;
+        stats.in_flux += found_stats.in_flux;
+        stats.hashed += found_stats.hashed;
+        stats.reused += found_stats.reused;
+    }
+
+    let mut keys: HashMap<usize, DuplicateKey> = HashMap::new();
+    for (key, member) in partial {
+        let merged = keys.entry(root(&mut parent, member)).or_default();
+        for value in key {
+            if !merged.contains(&value) {
+                merged.push(value);
+            }
+        }
+    }
+    let mut groups: HashMap<DuplicateKey, Vec<FileEntry>> = HashMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        if let Some(key) = keys.get(&root(&mut parent, i)) {
+            groups.entry(key.clone()).or_default().push(entry.clone());
+        }
+    }
+    (groups, stats)
+}
+
+/// SHA-256 over a group's sorted member paths, so the same files give the
+/// same signature in every scan whatever the grouping criteria.
+pub fn group_signature(files: &[FileEntry]) -> String {
+    let mut paths: Vec<_> = files.iter().map(|f| f.path.to_string_lossy()).collect();
+    paths.sort();
+    hasher::sha256_reader(paths.join("\n").as_bytes(), DigestEncoding::Hex)
+        .expect("reading from memory cannot fail")
+}
+
+/// Bytes freed by deleting every member of a group except the largest.
+pub fn wasted_bytes(files: &[FileEntry]) -> u64 {
+    let total: u64 = files.iter().map(|f| f.size).sum();
+    let largest = files.iter().map(|f| f.size).max().unwrap_or(0);
+    total - largest
+}
+
+/// Check that the file at `path` still produces every value of `key`,
+/// re-deriving each criterion from the disk (re-hashing for hashes).
+///
+/// `Err` names the first value that no longer matches, or why the file
+/// could not be read.  Audio-match values are numbered per scan and cannot
+/// be re-derived, so they are not checked.
+pub fn recheck_key(path: &Path, key: &DuplicateKey) -> Result<(), String> {
+    let meta = std::fs::metadata(path).map_err(|e| e.to_string())?;
+    let secs = |time: std::io::Result<std::time::SystemTime>| {
+        time.ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs_f64() as i64)
     };
-    match infer::get(&buf[..n]) {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    for criterion in key {
+        let matches = match criterion {
+            CriterionValue::Hash(digest) => {
+                hasher::sha256_file(path, hasher::encoding_of(digest)).map_err(|e| e.to_string())?
+                    == *digest
+            }
+            CriterionValue::Blake3(digest) => {
+                hasher::blake3_file(path, hasher::encoding_of(digest)).map_err(|e| e.to_string())?
+                    == *digest
+            }
+            CriterionValue::FastHash(digest) => {
+                hasher::sha256_fast(path).map_err(|e| e.to_string())? == *digest
+            }
+            CriterionValue::Size(size) => meta.len() == *size,
+            // The key does not record the compare mode; either may apply.
+            CriterionValue::Name(value) => {
+                name_key(path, NameCompare::Full) == *value
+                    || name_key(path, NameCompare::StemOnly) == *value
+            }
+            CriterionValue::NameFamily(value) => name_family(&name) == *value,
+            CriterionValue::Mtime(ts) => secs(meta.modified()) == Some(*ts),
+            CriterionValue::Created(ts) => secs(meta.created()) == Some(*ts),
+            CriterionValue::MimeType(mime) => detect_mime_type(path) == *mime,
+            CriterionValue::MediaMeta(fp) => {
+                media_meta::extract_media_fingerprint(path).as_ref() == Some(fp)
+            }
+            CriterionValue::AudioMatch(_) => true,
+            CriterionValue::LinkTarget(target) => symlink_target(path).as_ref() == Some(target),
+            CriterionValue::Dimensions(width, height) => {
+                media_meta::image_dimensions(path) == Some((*width, *height))
+            }
+            // Two hex digits per byte; a file that grew past a short prefix
+            // still matches as long as those bytes are unchanged.
+            CriterionValue::ContentPrefix(hex) => {
+                content_prefix(path, hex.len() / 2).as_ref() == Some(hex)
+            }
+        };
+        if !matches {
+            return Err(format!(
+                "no longer matches {}",
+                types::describe_key(&vec![criterion.clone()])
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Bucket of [`extension_stats`] for files without an extension.
+pub const NO_EXTENSION: &str = "(none)";
+
+/// Duplicate counts and waste per lowercased extension over `groups`.
+///
+/// A group counts once for every extension among its members.  Waste is
+/// split like [`wasted_bytes`]: the first largest member is the one kept,
+/// every other member adds its size to its own extension.
+pub fn extension_stats<'a>(
+    groups: impl IntoIterator<Item = &'a [FileEntry]>,
+) -> BTreeMap<String, ExtensionStats> {
+    let mut stats: BTreeMap<String, ExtensionStats> = BTreeMap::new();
+    for files in groups {
+        let kept = files
+            .iter()
+            .enumerate()
+            .max_by_key(|&(i, f)| (f.size, std::cmp::Reverse(i)))
+            .map(|(i, _)| i);
+        let mut seen: HashSet<String> = HashSet::new();
+        for (i, file) in files.iter().enumerate() {
+            let extension = file
+                .path
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_else(|| NO_EXTENSION.into());
+            let entry = stats.entry(extension.clone()).or_default();
+            entry.file_count += 1;
+            if Some(i) != kept {
+                entry.wasted_bytes += file.size;
+            }
+            if seen.insert(extension) {
+                entry.group_count += 1;
+            }
+        }
+    }
+    stats
+}
+
+/// Resolved target of `path` when it is a symbolic link.
+fn symlink_target(path: &Path) -> Option<String> {
+    let meta = std::fs::symlink_metadata(path).ok()?;
+    if !meta.file_type().is_symlink() {
+        return None;
+    }
+    std::fs::canonicalize(path)
+        .ok()
+        .map(|target| target.to_string_lossy().into_owned())
+}
+
+/// Build a key cache from the groups of an earlier scan result.
+pub fn known_keys_from_groups(groups: &[DuplicateGroup]) -> HashMap<PathBuf, KnownKey> {
+    groups
+        .iter()
+        .flat_map(|group| {
+            group.files.iter().map(move |f| {
+                (
+                    PathBuf::from(&f.path),
+                    KnownKey {
+                        size: f.size,
+                        mtime: f.mtime,
+                        key: group.key.clone(),
+                    },
+                )
+            })
+        })
+        .collect()
+}
+
+/// Compare the groups of an older and a newer scan; see [`ScanDiff`].
+///
+/// Member counts include overflow members, but `added` / `removed` only
+/// list paths either scan actually listed.  Audio-match keys are numbered
+/// per scan, so such groups rarely line up across scans.
+pub fn diff_groups(old: &[DuplicateGroup], new: &[DuplicateGroup]) -> ScanDiff {
+    let member_count = |group: &DuplicateGroup| group.files.len() + group.overflow_count;
+    let old_by_key: HashMap<&DuplicateKey, &DuplicateGroup> =
+        old.iter().map(|group| (&group.key, group)).collect();
+    let new_keys: HashSet<&DuplicateKey> = new.iter().map(|group| &group.key).collect();
+
+    let mut diff = ScanDiff {
+        new_groups: Vec::new(),
+        gone_groups: old
+            .iter()
+            .filter(|group| !new_keys.contains(&group.key))
+            .cloned()
+            .collect(),
+        grown_groups: Vec::new(),
+        shrunk_groups: Vec::new(),
+        unchanged_count: 0,
+    };
+    for group in new {
+        let Some(before) = old_by_key.get(&group.key) else {
+            diff.new_groups.push(group.clone());
+            continue;
+        };
+        let (old_count, new_count) = (member_count(before), member_count(group));
+        if old_count == new_count {
+            diff.unchanged_count += 1;
+            continue;
+        }
+        let paths = |g: &DuplicateGroup| -> HashSet<String> {
+            g.files.iter().map(|f| f.path.clone()).collect()
+        };
+        let (old_paths, new_paths) = (paths(before), paths(group));
+        let listed = |g: &DuplicateGroup, other: &HashSet<String>| -> Vec<String> {
+            g.files
+                .iter()
+                .filter(|f| !other.contains(&f.path))
+                .map(|f| f.path.clone())
+                .collect()
+        };
+        let change = GroupChange {
+            group: group.clone(),
+            previous_count: old_count,
+            added: listed(group, &old_paths),
+            removed: listed(before, &new_paths),
+        };
+        if new_count > old_count {
+            diff.grown_groups.push(change);
+        } else {
+            diff.shrunk_groups.push(change);
+        }
+    }
+    diff
+}
+
+/// True when `key` has exactly the components `config` would produce.
+///
+/// The media fingerprint is optional even when enabled (non-images have
+/// none), so it is only required to be absent when the criterion is off.
+fn key_fits_config(key: &DuplicateKey, config: &GroupingConfig) -> bool {
+    let count = |pred: fn(&CriterionValue) -> bool| key.iter().filter(|c| pred(c)).count();
+    let expect = |enabled: bool| usize::from(enabled);
+
+    count(|c| {
+        matches!(
+            c,
+            CriterionValue::Hash(_)
+                | CriterionValue::Blake3(_)
+                | CriterionValue::FastHash(_)
+                | CriterionValue::LinkTarget(_)
+        )
+    }) == expect(config.use_hash)
+        && (config.symlink_hash_policy == SymlinkHashPolicy::LinkPath
+            || count(|c| matches!(c, CriterionValue::LinkTarget(_))) == 0)
+        && count(|c| matches!(c, CriterionValue::Size(_))) == expect(config.use_size)
+        && count(|c| matches!(c, CriterionValue::Name(_))) == expect(config.use_name)
+        && count(|c| matches!(c, CriterionValue::NameFamily(_)))
+            == expect(config.use_name_family)
+        && count(|c| matches!(c, CriterionValue::Mtime(_))) == expect(config.use_mtime)
+        && count(|c| matches!(c, CriterionValue::Created(_))) == expect(config.use_created)
+        && count(|c| matches!(c, CriterionValue::MimeType(_))) == expect(config.use_mime)
+        && count(|c| matches!(c, CriterionValue::MediaMeta(_))) <= expect(config.use_media_meta)
+        && count(|c| matches!(c, CriterionValue::Dimensions(..))) == expect(config.use_dimensions)
+        // Audio cluster numbers are not stable across scans, so never reuse
+        // them; the prefix length is not recorded in the key, so neither are
+        // content prefixes.
+        && count(|c| matches!(c, CriterionValue::AudioMatch(_))) == 0
+        && !config.use_audio_fingerprint
+        && count(|c| matches!(c, CriterionValue::ContentPrefix(_))) == 0
+        && !config.use_content_prefix
+}
+
+/// True when every name value in `key` is what `config` derives for
+/// `path`, so a key built under the other [`NameCompare`] mode is not
+/// reused.
+fn names_fit_config(key: &DuplicateKey, path: &Path, config: &GroupingConfig) -> bool {
+    key.iter().all(|c| match c {
+        CriterionValue::Name(value) => name_key(path, config.name_compare_mode) == *value,
+        _ => true,
+    })
+}
+
+/// True when every full-content digest in `key` is of the kind and
+/// encoding `config` picks for a file of `size` bytes, so a SHA-256 key is
+/// not reused for a file that is now hashed with BLAKE3 (or the other way
+/// round), nor a hex digest in a base64 scan.
+fn digest_fits_size(key: &DuplicateKey, size: u64, config: &GroupingConfig) -> bool {
+    let wants_blake3 = uses_blake3(size, config);
+    let encoded = |digest: &str| hasher::encoding_of(digest) == config.digest_encoding;
+    key.iter().all(|c| match c {
+        CriterionValue::Hash(digest) => !wants_blake3 && encoded(digest),
+        CriterionValue::Blake3(digest) => wants_blake3 && encoded(digest),
+        _ => true,
+    })
+}
+
+/// True when a file of `size` bytes is hashed with BLAKE3 under `config`.
+fn uses_blake3(size: u64, config: &GroupingConfig) -> bool {
+    config.blake3_min_bytes.is_some_and(|min| size >= min)
+}
+
+/// True when `entry`'s file no longer has the size it was gathered with.
+///
+/// Paths that cannot be stat'ed (archive members, files deleted since)
+/// count as unchanged; hashing them reports its own error.
+fn size_changed(entry: &FileEntry) -> bool {
+    std::fs::metadata(&entry.path).is_ok_and(|meta| meta.len() != entry.size)
+}
+
+/// Full-content digest of `entry`: SHA-256, or BLAKE3 from
+/// [`GroupingConfig::blake3_min_bytes`] up.
+///
+/// Mixing the two is safe because duplicates share a size and so always
+/// get the same kind of digest.
+fn content_hash(entry: &FileEntry, config: &GroupingConfig) -> std::io::Result<CriterionValue> {
+    let encoding = config.digest_encoding;
+    if uses_blake3(entry.size, config) {
+        hasher::blake3_file(&entry.path, encoding).map(CriterionValue::Blake3)
+    } else {
+        hasher::sha256_file(&entry.path, encoding).map(CriterionValue::Hash)
+    }
+}
+
+/// The `use_name` value of `path`: its normalized name, or only its stem
+/// under [`NameCompare::StemOnly`].
+fn name_key(path: &Path, mode: NameCompare) -> String {
+    let name = match mode {
+        NameCompare::Full => path.file_name(),
+        NameCompare::StemOnly => path.file_stem(),
+    };
+    normalize_name(&name.map(|n| n.to_string_lossy()).unwrap_or_default())
+}
+
+/// The first `len` bytes of the file, hex-encoded; `None` if unreadable.
+fn content_prefix(path: &Path, len: usize) -> Option<String> {
+    let mut bytes = Vec::with_capacity(len);
+    std::fs::File::open(path)
+        .and_then(|file| file.take(len as u64).read_to_end(&mut bytes))
+        .ok()?;
+    Some(hasher::encode_digest(&bytes, DigestEncoding::Hex))
+}
+
+/// Assign each fingerprinted entry to a cluster of matching audio.
+///
+/// Entries that share the remaining criteria are compared with the first
+/// member of every cluster seen so far; the cluster number becomes the
+/// trailing `AudioMatch` key component.
+fn cluster_audio(
+    pending: Vec<(DuplicateKey, Fingerprint, FileEntry)>,
+    groups: &mut HashMap<DuplicateKey, Vec<FileEntry>>,
+) {
+    let mut clusters: HashMap<DuplicateKey, Vec<Fingerprint>> = HashMap::new();
+    for (mut key, fingerprint, entry) in pending {
+        let representatives = clusters.entry(key.clone()).or_default();
+        let cluster = match representatives
+            .iter()
+            .position(|rep| audio_fp::is_match(rep, &fingerprint))
+        {
+            Some(index) => index,
+            None => {
+                representatives.push(fingerprint);
+                representatives.len() - 1
+            }
+        };
+        key.push(CriterionValue::AudioMatch(cluster as u32));
+        groups.entry(key).or_default().push(entry);
+    }
+}
+
+/// Detect MIME type by reading the first 8 KB of a file and using magic bytes.
+pub(crate) fn detect_mime_type(path: &std::path::Path) -> String {
+    match sniff_type(path) {
         Some(kind) => kind.mime_type().to_string(),
         None => "unknown".into(),
     }
 }
 
+/// Canonical extension (without the dot) of the type [`detect_mime_type`]
+/// recognises, e.g. `png` for `image/png`.
+pub(crate) fn detect_extension(path: &std::path::Path) -> Option<&'static str> {
+    sniff_type(path).map(|kind| kind.extension())
+}
+
+fn sniff_type(path: &std::path::Path) -> Option<infer::Type> {
+    let mut buf = [0u8; MIME_SNIFF_BYTES as usize];
+    let n = std::fs::File::open(path)
+        .and_then(|mut f| f.read(&mut buf))
+        .ok()?;
+    infer::get(&buf[..n])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::progress::NoProgress;
+    use crate::types::FileTypePresets;
     use std::fs;
     use tempfile::tempdir;
 
@@ -214,6 +926,7 @@ mod tests {
                     path,
                     size: meta.len(),
                     mtime,
+                    created: None,
                 }
             })
             .collect()
@@ -232,11 +945,25 @@ mod tests {
             use_hash: hash,
             use_size: size,
             use_name: name,
+            use_name_family: false,
             use_mtime: mtime,
+            use_created: false,
             use_mime: mime,
             use_media_meta: false,
+            use_audio_fingerprint: false,
+            use_dimensions: false,
+            use_content_prefix: false,
+            content_prefix_bytes: 0,
             hash_max_bytes: max_bytes,
             fast_hash_oversized: false,
+            blake3_min_bytes: None,
+            digest_encoding: DigestEncoding::Hex,
+            symlink_hash_policy: SymlinkHashPolicy::FollowTarget,
+            same_folder_policy: SameFolderPolicy::Flag,
+            name_compare_mode: NameCompare::Full,
+            custom_file_type_presets: FileTypePresets::new(),
+            type_overrides: Vec::new(),
+            alternatives: Vec::new(),
         }
     }
 
@@ -252,7 +979,7 @@ mod tests {
             ],
         );
         let cfg = config(true, false, false, false, false, None);
-        let (groups, _) = find_duplicate_groups(&entries, &cfg, None);
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
         assert_eq!(groups.len(), 1);
         let group = groups.values().next().unwrap();
         let names: std::collections::HashSet<String> = group
@@ -263,6 +990,114 @@ mod tests {
         assert!(names.contains("b.txt"));
     }
 
+    #[test]
+    fn test_blake3_threshold_mixes_digest_kinds() {
+        let dir = tempdir().unwrap();
+        let big = vec![7u8; 64];
+        let entries = make_entries(
+            dir.path(),
+            &[
+                ("small_a.txt", b"tiny"),
+                ("small_b.txt", b"tiny"),
+                ("big_a.bin", &big),
+                ("big_b.bin", &big),
+            ],
+        );
+        let cfg = GroupingConfig {
+            blake3_min_bytes: Some(32),
+            ..config(true, false, false, false, false, None)
+        };
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+
+        assert_eq!(groups.len(), 2);
+        for (key, members) in &groups {
+            assert_eq!(members.len(), 2);
+            let small = members[0].size < 32;
+            assert!(members.iter().all(|e| (e.size < 32) == small));
+            match &key[0] {
+                CriterionValue::Hash(digest) => {
+                    assert!(small);
+                    assert_eq!(
+                        *digest,
+                        hasher::sha256_file(&members[0].path, DigestEncoding::Hex).unwrap()
+                    );
+                }
+                CriterionValue::Blake3(digest) => {
+                    assert!(!small);
+                    assert_eq!(*digest, blake3::hash(&big).to_hex().to_string());
+                }
+                other => panic!("unexpected key {other:?}"),
+            }
+        }
+
+        // A cached SHA-256 key is not reused once the file crosses the
+        // threshold.
+        let sha_key = vec![CriterionValue::Hash("ab".into())];
+        assert!(digest_fits_size(&sha_key, 4, &cfg));
+        assert!(!digest_fits_size(&sha_key, 64, &cfg));
+    }
+
+    #[test]
+    fn test_base64_digests_group_like_hex() {
+        let dir = tempdir().unwrap();
+        let entries = make_entries(
+            dir.path(),
+            &[("a.txt", b"same"), ("b.txt", b"same"), ("c.txt", b"other")],
+        );
+        let hex_cfg = config(true, false, false, false, false, None);
+        let b64_cfg = GroupingConfig {
+            digest_encoding: DigestEncoding::Base64,
+            ..hex_cfg.clone()
+        };
+        let (hex_groups, _) = find_duplicate_groups(&entries, &hex_cfg, &NoProgress);
+        let (b64_groups, _) = find_duplicate_groups(&entries, &b64_cfg, &NoProgress);
+
+        assert_eq!(hex_groups.len(), 1);
+        assert_eq!(b64_groups.len(), 1);
+        let (key, members) = b64_groups.iter().next().unwrap();
+        assert_eq!(members.len(), 2);
+        match &key[0] {
+            CriterionValue::Hash(digest) => {
+                assert_eq!(hasher::encoding_of(digest), DigestEncoding::Base64)
+            }
+            other => panic!("unexpected key {other:?}"),
+        }
+
+        // A hex key cached by an earlier scan is not reused in a base64 one.
+        let hex_key = hex_groups.keys().next().unwrap();
+        assert!(!digest_fits_size(hex_key, 4, &b64_cfg));
+    }
+
+    #[test]
+    fn test_file_growing_before_hash_is_flagged_in_flux() {
+        let dir = tempdir().unwrap();
+        let mut entries = make_entries(
+            dir.path(),
+            &[
+                ("a.txt", b"same content"),
+                ("b.txt", b"same content"),
+                ("growing.txt", b"same"),
+            ],
+        );
+        // As if the file was 4 bytes shorter when gathered: it now lands in
+        // the 12-byte bucket but has grown by hash time.
+        let growing = entries
+            .iter_mut()
+            .find(|e| e.path.ends_with("growing.txt"))
+            .unwrap();
+        growing.size = 12;
+
+        let cfg = config(true, false, false, false, false, None);
+        let (groups, stats) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+
+        assert_eq!(stats.in_flux, 1);
+        assert_eq!(stats.hashed, 2);
+        assert_eq!(groups.len(), 1);
+        let members = groups.values().next().unwrap();
+        assert_eq!(members.len(), 2);
+        assert!(members.iter().all(|e| !e.path.ends_with("growing.txt")));
+    }
+
     #[test]
     fn test_size_only_duplicates() {
         let dir = tempdir().unwrap();
@@ -275,7 +1110,7 @@ mod tests {
             ],
         );
         let cfg = config(false, true, false, false, false, None);
-        let (groups, _) = find_duplicate_groups(&entries, &cfg, None);
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
         assert_eq!(groups.len(), 1);
         let group = groups.values().next().unwrap();
         let names: std::collections::HashSet<String> = group
@@ -305,16 +1140,480 @@ mod tests {
                 path: sub1.join("report.txt"),
                 size: 8,
                 mtime: now,
+                created: None,
             },
             FileEntry {
                 path: sub2.join("report.txt"),
                 size: 8,
                 mtime: now,
+                created: None,
             },
         ];
         let cfg = config(false, false, true, false, false, None);
-        let (groups, _) = find_duplicate_groups(&entries, &cfg, None);
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert_eq!(groups.len(), 1);
+    }
+
+    #[test]
+    fn test_fold_case_expands_german_sharp_s() {
+        assert_eq!(fold_case("STRASSE.txt"), "strasse.txt");
+        assert_eq!(fold_case("straße.txt"), fold_case("STRASSE.txt"));
+        assert_eq!(fold_case("STRAẞE.txt"), fold_case("strasse.txt"));
+    }
+
+    #[test]
+    fn test_fold_case_keeps_turkish_dotless_i_distinct() {
+        // Locale-independent folding: dotted capital İ matches a decomposed
+        // i + U+0307, plain I folds to i, and dotless ı matches neither.
+        assert_eq!(fold_case("İSTANBUL.txt"), fold_case("i\u{307}stanbul.txt"));
+        assert_eq!(fold_case("IRMAK.txt"), "irmak.txt");
+        assert_ne!(fold_case("ırmak.txt"), fold_case("IRMAK.txt"));
+    }
+
+    #[test]
+    fn test_name_family_strips_copy_markers() {
+        assert_eq!(name_family("report.pdf"), normalize_name("report.pdf"));
+        assert_eq!(name_family("report (1).pdf"), normalize_name("report.pdf"));
+        assert_eq!(name_family("report-copy.pdf"), normalize_name("report.pdf"));
+        assert_eq!(name_family("report_Copy.pdf"), normalize_name("report.pdf"));
+        assert_eq!(
+            name_family("report (copy).pdf"),
+            normalize_name("report.pdf")
+        );
+        assert_eq!(
+            name_family("report-copy (2).pdf"),
+            normalize_name("report.pdf")
+        );
+        // Not a copy marker: no space before the number, or nothing left.
+        assert_eq!(
+            name_family("report(1).pdf"),
+            normalize_name("report(1).pdf")
+        );
+        assert_eq!(name_family("(1).pdf"), normalize_name("(1).pdf"));
+        assert_eq!(name_family("copy.pdf"), normalize_name("copy.pdf"));
+    }
+
+    #[test]
+    fn test_name_family_groups_copies() {
+        let dir = tempdir().unwrap();
+        let entries = make_entries(
+            dir.path(),
+            &[
+                ("report.pdf", b"original"),
+                ("report (1).pdf", b"edited copy"),
+                ("report-copy.pdf", b"another"),
+                ("notes.pdf", b"original"),
+            ],
+        );
+        let mut cfg = config(false, false, false, false, false, None);
+        cfg.use_name_family = true;
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
         assert_eq!(groups.len(), 1);
+        let (key, files) = groups.iter().next().unwrap();
+        assert_eq!(
+            key,
+            &vec![CriterionValue::NameFamily(normalize_name("report.pdf"))]
+        );
+        assert_eq!(files.len(), 3);
+        assert!(files
+            .iter()
+            .all(|e| e.path.file_name().unwrap() != "notes.pdf"));
+    }
+
+    #[test]
+    fn test_name_family_composes_with_size() {
+        let dir = tempdir().unwrap();
+        let entries = make_entries(
+            dir.path(),
+            &[
+                ("photo.jpg", b"same bytes"),
+                ("photo (1).jpg", b"same bytes"),
+                ("photo (2).jpg", b"resized"),
+            ],
+        );
+        let mut cfg = config(false, true, false, false, false, None);
+        cfg.use_name_family = true;
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups.values().next().unwrap().len(), 2);
+    }
+
+    /// `target.txt` plus `link_a` and `link_b` pointing at it, and an
+    /// unrelated `copy.txt` with the same bytes.
+    #[cfg(unix)]
+    fn symlink_entries(dir: &std::path::Path) -> Vec<FileEntry> {
+        let target = dir.join("target.txt");
+        fs::write(&target, b"linked bytes").unwrap();
+        fs::write(dir.join("copy.txt"), b"linked bytes").unwrap();
+        std::os::unix::fs::symlink(&target, dir.join("link_a")).unwrap();
+        std::os::unix::fs::symlink(&target, dir.join("link_b")).unwrap();
+        ["target.txt", "copy.txt", "link_a", "link_b"]
+            .iter()
+            .map(|name| {
+                let path = dir.join(name);
+                let meta = fs::metadata(&path).unwrap();
+                FileEntry {
+                    path,
+                    size: meta.len(),
+                    mtime: 0.0,
+                    created: None,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dimensions_groups_images_by_pixel_size() {
+        let dir = tempdir().unwrap();
+        let save = |name: &str, width: u32, height: u32, shade: u8| {
+            let path = dir.path().join(name);
+            image::RgbImage::from_pixel(width, height, image::Rgb([shade; 3]))
+                .save(&path)
+                .unwrap();
+        };
+        save("a.png", 4, 3, 0);
+        save("b.bmp", 4, 3, 200);
+        save("c.png", 5, 5, 0);
+        fs::write(dir.path().join("notes.txt"), b"not an image").unwrap();
+        let (entries, _) = crate::scanner::gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &crate::types::ScanOptions::default(),
+            &NoProgress,
+        );
+
+        let mut cfg = config(false, false, false, false, false, None);
+        cfg.use_dimensions = true;
+        let (groups, stats) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+
+        assert_eq!(groups.len(), 1);
+        let (key, files) = groups.iter().next().unwrap();
+        assert_eq!(key, &vec![CriterionValue::Dimensions(4, 3)]);
+        assert_eq!(crate::types::describe_key(key), "4x3");
+        let mut names: Vec<_> = files
+            .iter()
+            .map(|f| f.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["a.png", "b.bmp"]);
+        assert_eq!(stats.dimensions_skipped, 1);
+    }
+
+    #[test]
+    fn test_stem_only_name_compare_groups_across_extensions() {
+        let dir = tempdir().unwrap();
+        for name in ["report.txt", "report.pdf", "summary.pdf"] {
+            fs::write(dir.path().join(name), name).unwrap();
+        }
+        let (entries, _) = crate::scanner::gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &crate::types::ScanOptions::default(),
+            &NoProgress,
+        );
+
+        let mut cfg = config(false, false, true, false, false, None);
+        let (full, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert!(full.is_empty());
+
+        cfg.name_compare_mode = NameCompare::StemOnly;
+        let (stems, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert_eq!(stems.len(), 1);
+        let (key, files) = stems.iter().next().unwrap();
+        assert_eq!(key, &vec![CriterionValue::Name(normalize_name("report"))]);
+        let mut names: Vec<_> = files
+            .iter()
+            .map(|f| f.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["report.pdf", "report.txt"]);
+        assert!(recheck_key(&files[0].path, key).is_ok());
+    }
+
+    #[test]
+    fn test_content_prefix_groups_files_sharing_first_bytes() {
+        let dir = tempdir().unwrap();
+        let prefix = "0123456789".repeat(7);
+        let mut odd = prefix.clone();
+        odd.replace_range(10..11, "X");
+        fs::write(
+            dir.path().join("a.txt"),
+            format!("{}{}", &prefix[..64], "tail one"),
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("b.txt"),
+            format!("{}{}", &prefix[..64], "tail two"),
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("c.txt"),
+            format!("{}{}", &odd[..64], "tail one"),
+        )
+        .unwrap();
+        let (entries, _) = crate::scanner::gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &crate::types::ScanOptions::default(),
+            &NoProgress,
+        );
+
+        let mut cfg = config(false, true, false, false, false, None);
+        cfg.use_content_prefix = true;
+        cfg.content_prefix_bytes = 64;
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+
+        assert_eq!(groups.len(), 1);
+        let (key, files) = groups.iter().next().unwrap();
+        assert_eq!(
+            key,
+            &vec![
+                CriterionValue::Size(72),
+                CriterionValue::ContentPrefix(hasher::encode_digest(
+                    &prefix.as_bytes()[..64],
+                    DigestEncoding::Hex
+                ))
+            ]
+        );
+        assert!(crate::types::describe_key(key)
+            .ends_with("starts with \"012345678901234567890123...\""));
+        let mut names: Vec<_> = files
+            .iter()
+            .map(|f| f.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["a.txt", "b.txt"]);
+        assert!(recheck_key(&files[0].path, key).is_ok());
+    }
+
+    #[test]
+    fn test_content_prefix_tells_apart_invalid_utf8() {
+        let dir = tempdir().unwrap();
+        // Both prefixes decode to the same replacement character.
+        fs::write(dir.path().join("a.bin"), b"\xff tail").unwrap();
+        fs::write(dir.path().join("b.bin"), b"\xfe tail").unwrap();
+        let (entries, _) = crate::scanner::gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &crate::types::ScanOptions::default(),
+            &NoProgress,
+        );
+
+        let mut cfg = config(false, true, false, false, false, None);
+        cfg.use_content_prefix = true;
+        cfg.content_prefix_bytes = 4;
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert!(groups.is_empty(), "{:?}", groups.keys().collect::<Vec<_>>());
+
+        let key = vec![CriterionValue::ContentPrefix("ff207461".into())];
+        assert!(recheck_key(&dir.path().join("a.bin"), &key).is_ok());
+        assert!(recheck_key(&dir.path().join("b.bin"), &key).is_err());
+    }
+
+    #[test]
+    fn test_alternatives_merge_hash_or_name_matches() {
+        let dir = tempdir().unwrap();
+        for sub in ["a", "b"] {
+            fs::create_dir(dir.path().join(sub)).unwrap();
+        }
+        // a/x.jpg and b/x.jpg share a name; a/x.jpg and y.jpeg share bytes.
+        fs::write(dir.path().join("a/x.jpg"), b"photo").unwrap();
+        fs::write(dir.path().join("b/x.jpg"), b"other photo").unwrap();
+        fs::write(dir.path().join("y.jpeg"), b"photo").unwrap();
+        fs::write(dir.path().join("z.jpg"), b"unrelated").unwrap();
+        let (entries, _) = crate::scanner::gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &crate::types::ScanOptions::default(),
+            &NoProgress,
+        );
+
+        let mut cfg = config(true, false, false, false, false, None);
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert_eq!(groups.values().next().unwrap().len(), 2);
+
+        cfg.alternatives = vec![config(false, false, true, false, false, None)];
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert_eq!(groups.len(), 1);
+        let (key, files) = groups.into_iter().next().unwrap();
+        let mut names: Vec<String> = files
+            .iter()
+            .map(|f| {
+                let rel = f.path.strip_prefix(dir.path()).unwrap();
+                rel.to_string_lossy().replace('\\', "/")
+            })
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["a/x.jpg", "b/x.jpg", "y.jpeg"]);
+        assert!(matches!(
+            key[..],
+            [CriterionValue::Hash(_), CriterionValue::Name(_)]
+        ));
+    }
+
+    #[test]
+    fn test_type_overrides_group_images_and_text_differently() {
+        let dir = tempdir().unwrap();
+        for (name, shade) in [("a.png", 0), ("b.bmp", 200)] {
+            image::RgbImage::from_pixel(4, 3, image::Rgb([shade; 3]))
+                .save(dir.path().join(name))
+                .unwrap();
+        }
+        fs::write(dir.path().join("x.txt"), b"same text").unwrap();
+        fs::write(dir.path().join("y.txt"), b"same text").unwrap();
+        fs::write(dir.path().join("z.txt"), b"other text").unwrap();
+        let (entries, _) = crate::scanner::gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &crate::types::ScanOptions::default(),
+            &NoProgress,
+        );
+
+        let mut images = config(false, false, false, false, false, None);
+        images.use_dimensions = true;
+        let mut cfg = config(true, false, false, false, false, None);
+        cfg.type_overrides = vec![("images".into(), images)];
+        let (groups, stats) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+
+        let mut found: Vec<(bool, Vec<String>)> = groups
+            .iter()
+            .map(|(key, files)| {
+                let mut names: Vec<String> = files
+                    .iter()
+                    .map(|f| f.path.file_name().unwrap().to_string_lossy().into_owned())
+                    .collect();
+                names.sort();
+                (matches!(key[..], [CriterionValue::Dimensions(4, 3)]), names)
+            })
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                (false, vec!["x.txt".to_string(), "y.txt".to_string()]),
+                (true, vec!["a.png".to_string(), "b.bmp".to_string()]),
+            ]
+        );
+        // Text files never reach the dimensions route.
+        assert_eq!(stats.dimensions_skipped, 0);
+    }
+
+    #[test]
+    fn test_type_overrides_resolve_custom_presets() {
+        let dir = tempdir().unwrap();
+        let entries = make_entries(
+            dir.path(),
+            &[
+                ("draft.md", b"first"),
+                ("final.md", b"second"),
+                ("a.txt", b"first"),
+            ],
+        );
+
+        let mut notes = config(false, false, false, false, false, None);
+        notes.use_mime = true;
+        let mut cfg = config(true, false, false, false, false, None);
+        cfg.type_overrides = vec![("notes".into(), notes)];
+        cfg.custom_file_type_presets = FileTypePresets::from([("notes".into(), vec!["md".into()])]);
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+
+        assert_eq!(groups.len(), 1);
+        let mut names: Vec<String> = groups
+            .values()
+            .flatten()
+            .map(|f| f.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["draft.md", "final.md"]);
+    }
+
+    #[test]
+    fn test_same_folder_policy_ignore_drops_same_folder_pair() {
+        let dir = tempdir().unwrap();
+        let entries = make_entries(
+            dir.path(),
+            &[
+                ("file.txt", b"same content"),
+                ("file (1).txt", b"same content"),
+            ],
+        );
+        let mut cfg = config(true, false, false, false, false, None);
+
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert_eq!(groups.len(), 1);
+
+        cfg.same_folder_policy = SameFolderPolicy::Ignore;
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn test_same_folder_policy_ignore_keeps_one_member_per_folder() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("other")).unwrap();
+        let entries = make_entries(
+            dir.path(),
+            &[
+                ("file.txt", b"same content"),
+                ("file (1).txt", b"same content"),
+                ("other/file.txt", b"same content"),
+            ],
+        );
+        let mut cfg = config(true, false, false, false, false, None);
+        cfg.same_folder_policy = SameFolderPolicy::Ignore;
+
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert_eq!(groups.len(), 1);
+        let files = groups.values().next().unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files.iter().any(|f| f.path.ends_with("other/file.txt")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_follow_target_hashes_content() {
+        let dir = tempdir().unwrap();
+        let entries = symlink_entries(dir.path());
+        let cfg = config(true, false, false, false, false, None);
+        let (groups, stats) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups.values().next().unwrap().len(), 4);
+        assert_eq!(stats.hashed, 4);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_link_path_groups_links_without_reading() {
+        let dir = tempdir().unwrap();
+        let entries = symlink_entries(dir.path());
+        let mut cfg = config(true, false, false, false, false, None);
+        cfg.symlink_hash_policy = SymlinkHashPolicy::LinkPath;
+        let (groups, stats) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(stats.hashed, 2);
+
+        let target = fs::canonicalize(dir.path().join("target.txt")).unwrap();
+        let link_key = vec![CriterionValue::LinkTarget(
+            target.to_string_lossy().into_owned(),
+        )];
+        let mut links: Vec<String> = groups[&link_key]
+            .iter()
+            .map(|e| e.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        links.sort();
+        assert_eq!(links, vec!["link_a", "link_b"]);
     }
 
     #[test]
@@ -322,7 +1621,7 @@ mod tests {
         let dir = tempdir().unwrap();
         let entries = make_entries(dir.path(), &[("a.txt", b"x")]);
         let cfg = config(false, false, false, false, false, None);
-        let (groups, _) = find_duplicate_groups(&entries, &cfg, None);
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
         assert!(groups.is_empty());
     }
 
@@ -337,8 +1636,8 @@ mod tests {
             ],
         );
         let cfg = config(true, false, false, false, false, Some(500));
-        let (_, skipped) = find_duplicate_groups(&entries, &cfg, None);
-        assert_eq!(skipped, 2);
+        let (_, stats) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert_eq!(stats.hash_skipped, 2);
     }
 
     #[test]
@@ -346,7 +1645,7 @@ mod tests {
         let dir = tempdir().unwrap();
         let entries = make_entries(dir.path(), &[("only.txt", b"alone")]);
         let cfg = config(true, true, false, false, false, None);
-        let (groups, _) = find_duplicate_groups(&entries, &cfg, None);
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
         assert!(groups.is_empty());
     }
 
@@ -362,7 +1661,7 @@ mod tests {
             ],
         );
         let cfg = config(true, false, false, false, false, None);
-        let (groups, _) = find_duplicate_groups(&entries, &cfg, None);
+        let (groups, _) = find_duplicate_groups(&entries, &cfg, &NoProgress);
         assert!(groups.is_empty());
     }
 
@@ -379,16 +1678,30 @@ mod tests {
             use_hash: true,
             use_size: false,
             use_name: false,
+            use_name_family: false,
             use_mtime: false,
+            use_created: false,
             use_mime: false,
             use_media_meta: false,
+            use_audio_fingerprint: false,
+            use_dimensions: false,
+            use_content_prefix: false,
+            content_prefix_bytes: 0,
             hash_max_bytes: Some(500), // cap below file size
             fast_hash_oversized: true,
+            blake3_min_bytes: None,
+            digest_encoding: DigestEncoding::Hex,
+            symlink_hash_policy: SymlinkHashPolicy::FollowTarget,
+            same_folder_policy: SameFolderPolicy::Flag,
+            name_compare_mode: NameCompare::Full,
+            custom_file_type_presets: FileTypePresets::new(),
+            type_overrides: Vec::new(),
+            alternatives: Vec::new(),
         };
-        let (groups, skipped) = find_duplicate_groups(&entries, &cfg, None);
+        let (groups, stats) = find_duplicate_groups(&entries, &cfg, &NoProgress);
         // Files should be grouped via fast-hash, NOT skipped.
         assert_eq!(groups.len(), 1);
-        assert_eq!(skipped, 0);
+        assert_eq!(stats.hash_skipped, 0);
         // Verify the key uses FastHash variant.
         let key = groups.keys().next().unwrap();
         assert!(matches!(&key[0], CriterionValue::FastHash(_)));
@@ -406,15 +1719,190 @@ mod tests {
             use_hash: true,
             use_size: false,
             use_name: false,
+            use_name_family: false,
             use_mtime: false,
+            use_created: false,
             use_mime: false,
             use_media_meta: false,
+            use_audio_fingerprint: false,
+            use_dimensions: false,
+            use_content_prefix: false,
+            content_prefix_bytes: 0,
             hash_max_bytes: Some(500),
             fast_hash_oversized: false, // disabled
+            blake3_min_bytes: None,
+            digest_encoding: DigestEncoding::Hex,
+            symlink_hash_policy: SymlinkHashPolicy::FollowTarget,
+            same_folder_policy: SameFolderPolicy::Flag,
+            name_compare_mode: NameCompare::Full,
+            custom_file_type_presets: FileTypePresets::new(),
+            type_overrides: Vec::new(),
+            alternatives: Vec::new(),
         };
-        let (groups, skipped) = find_duplicate_groups(&entries, &cfg, None);
+        let (groups, stats) = find_duplicate_groups(&entries, &cfg, &NoProgress);
         // Files should be skipped, not grouped.
         assert!(groups.is_empty());
-        assert_eq!(skipped, 2);
+        assert_eq!(stats.hash_skipped, 2);
+    }
+
+    #[test]
+    fn test_created_groups_identical_creation_times() {
+        let dir = tempdir().unwrap();
+        let mut entries = make_entries(
+            dir.path(),
+            &[("a.txt", b"one"), ("b.txt", b"two"), ("c.txt", b"three")],
+        );
+        entries[0].created = Some(1_700_000_000.25);
+        entries[1].created = Some(1_700_000_000.75);
+        entries[2].created = Some(1_700_000_500.0);
+
+        let cfg = GroupingConfig {
+            use_created: true,
+            ..config(false, false, false, false, false, None)
+        };
+        let (groups, stats) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(stats.created_skipped, 0);
+        let (key, files) = groups.iter().next().unwrap();
+        assert_eq!(key, &vec![CriterionValue::Created(1_700_000_000)]);
+        assert_eq!(files.len(), 2);
+    }
+
+    #[test]
+    fn test_created_unavailable_is_counted() {
+        let dir = tempdir().unwrap();
+        let entries = make_entries(dir.path(), &[("a.txt", b"one"), ("b.txt", b"two")]);
+        let cfg = GroupingConfig {
+            use_created: true,
+            ..config(false, false, false, false, false, None)
+        };
+        let (groups, stats) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert!(groups.is_empty());
+        assert_eq!(stats.created_skipped, 2);
+    }
+
+    #[test]
+    fn test_cached_rescan_only_hashes_new_file() {
+        let dir = tempdir().unwrap();
+        let mut entries = make_entries(
+            dir.path(),
+            &[("a.txt", b"same content"), ("b.txt", b"same content")],
+        );
+        let cfg = config(true, false, false, false, false, None);
+        let (groups, stats) = find_duplicate_groups(&entries, &cfg, &NoProgress);
+        assert_eq!(stats.hashed, 2);
+
+        let (key, files) = groups.into_iter().next().unwrap();
+        let known: HashMap<PathBuf, KnownKey> = files
+            .iter()
+            .map(|f| {
+                (
+                    f.path.clone(),
+                    KnownKey {
+                        size: f.size,
+                        mtime: f.mtime,
+                        key: key.clone(),
+                    },
+                )
+            })
+            .collect();
+
+        // A third copy appears between scans.
+        entries.extend(make_entries(dir.path(), &[("c.txt", b"same content")]));
+        let (groups, stats) = find_duplicate_groups_cached(&entries, &cfg, &known, &NoProgress);
+        assert_eq!(stats.hashed, 1);
+        assert_eq!(stats.reused, 2);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups.values().next().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_cached_key_ignored_when_criteria_change() {
+        let dir = tempdir().unwrap();
+        let entries = make_entries(dir.path(), &[("a.txt", b"aaaa"), ("b.txt", b"bbbb")]);
+        let known: HashMap<PathBuf, KnownKey> = entries
+            .iter()
+            .map(|e| {
+                (
+                    e.path.clone(),
+                    KnownKey {
+                        size: e.size,
+                        mtime: e.mtime,
+                        key: vec![CriterionValue::Size(e.size)],
+                    },
+                )
+            })
+            .collect();
+
+        // Hash-only config: the size-only cached keys no longer apply.
+        let cfg = config(true, false, false, false, false, None);
+        let (groups, stats) = find_duplicate_groups_cached(&entries, &cfg, &known, &NoProgress);
+        assert!(groups.is_empty());
+        assert_eq!(stats.reused, 0);
+        assert_eq!(stats.hashed, 2);
+    }
+
+    fn listed_group(digest: &str, paths: &[&str]) -> DuplicateGroup {
+        let key = vec![CriterionValue::Hash(digest.into())];
+        DuplicateGroup {
+            key_description: crate::types::describe_key(&key),
+            confidence: 1.0,
+            key,
+            files: paths
+                .iter()
+                .map(|path| crate::types::FileEntryDto {
+                    path: path.to_string(),
+                    name: String::new(),
+                    folder: String::new(),
+                    size: 1,
+                    size_human: String::new(),
+                    mtime: 0.0,
+                    mtime_formatted: String::new(),
+                    created: 0.0,
+                    created_formatted: String::new(),
+                })
+                .collect(),
+            keeper: None,
+            overflow_count: 0,
+            signature: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_diff_groups_sorts_changes_into_buckets() {
+        let old = vec![
+            listed_group("aa", &["/x/a1", "/x/a2"]),
+            listed_group("bb", &["/x/b1", "/x/b2"]),
+            listed_group("cc", &["/x/c1", "/x/c2", "/x/c3"]),
+            listed_group("dd", &["/x/d1", "/x/d2"]),
+        ];
+        let new = vec![
+            listed_group("aa", &["/x/a1", "/x/a2", "/y/a3"]),
+            listed_group("cc", &["/x/c1", "/x/c3"]),
+            listed_group("dd", &["/x/d1", "/x/d2"]),
+            listed_group("ee", &["/x/e1", "/x/e2"]),
+        ];
+
+        let diff = diff_groups(&old, &new);
+        let digests = |groups: Vec<&DuplicateGroup>| -> Vec<DuplicateKey> {
+            groups.into_iter().map(|g| g.key.clone()).collect()
+        };
+        assert_eq!(
+            digests(diff.new_groups.iter().collect()),
+            vec![vec![CriterionValue::Hash("ee".into())]]
+        );
+        assert_eq!(
+            digests(diff.gone_groups.iter().collect()),
+            vec![vec![CriterionValue::Hash("bb".into())]]
+        );
+        assert_eq!(diff.grown_groups.len(), 1);
+        let grown = &diff.grown_groups[0];
+        assert_eq!(grown.group.key, vec![CriterionValue::Hash("aa".into())]);
+        assert_eq!(grown.previous_count, 2);
+        assert_eq!(grown.added, vec!["/y/a3"]);
+        assert!(grown.removed.is_empty());
+        assert_eq!(diff.shrunk_groups.len(), 1);
+        assert_eq!(diff.shrunk_groups[0].removed, vec!["/x/c2"]);
+        assert_eq!(diff.unchanged_count, 1);
     }
 }
@@ -1,26 +1,179 @@
 use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 
-use sha2::{Digest, Sha256};
+use base64::Engine;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::archive;
+use crate::types::{CompareResult, DigestEncoding, HashAlgorithm};
 
 const CHUNK_SIZE: usize = 1024 * 1024; // 1 MB
+/// Files at least this large are hashed through a memory map.
+pub const MMAP_THRESHOLD: u64 = 64 * 1024 * 1024; // 64 MB
+/// Slice of the memory map fed to the hasher between progress callbacks.
+const MMAP_SLICE: usize = 16 * CHUNK_SIZE;
 const FAST_HASH_CHUNK: usize = 64 * 1024; // 64 KB
 
-/// Return the SHA-256 hex digest for a file (streamed to handle large files).
-pub fn sha256_file(path: &Path) -> Result<String, std::io::Error> {
-    let mut file = std::fs::File::open(path)?;
-    let mut hasher = Sha256::new();
+/// Return the SHA-256 digest for a file (streamed to handle large files),
+/// written out in `encoding`.
+///
+/// A virtual `zip://archive!member` path hashes that archive member's
+/// content (see [`crate::archive`]).
+pub fn sha256_file(path: &Path, encoding: DigestEncoding) -> Result<String, std::io::Error> {
+    if let Some((archive, member)) = archive::split_virtual_path(path) {
+        return archive::sha256_member(&archive, &member, encoding);
+    }
+    Ok(encode_digest(&digest_file::<Sha256>(path, None)?, encoding))
+}
+
+/// Return the BLAKE3 digest for a file in `encoding`.
+///
+/// Large files are memory-mapped; a virtual `zip://archive!member` path
+/// hashes that member like [`sha256_file`] does.
+pub fn blake3_file(path: &Path, encoding: DigestEncoding) -> Result<String, std::io::Error> {
+    if let Some((archive, member)) = archive::split_virtual_path(path) {
+        return archive::blake3_member(&archive, &member, encoding);
+    }
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_mmap(path)?;
+    Ok(encode_digest(hasher.finalize().as_bytes(), encoding))
+}
+
+/// Return the BLAKE3 digest of everything `reader` yields, in `encoding`.
+pub fn blake3_reader(
+    reader: impl Read,
+    encoding: DigestEncoding,
+) -> Result<String, std::io::Error> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(reader)?;
+    Ok(encode_digest(hasher.finalize().as_bytes(), encoding))
+}
+
+/// Return the SHA-256 digest of everything `reader` yields, in `encoding`.
+pub fn sha256_reader(
+    reader: impl Read,
+    encoding: DigestEncoding,
+) -> Result<String, std::io::Error> {
+    Ok(encode_digest(
+        &digest_reader::<Sha256>(reader, 0, None)?,
+        encoding,
+    ))
+}
+
+/// Return the digest of a file using `algo`, written out in `encoding`.
+///
+/// `progress` is called after every chunk with `(bytes_read, file_size)`.
+pub fn hash_file(
+    path: &Path,
+    algo: HashAlgorithm,
+    encoding: DigestEncoding,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Result<String, std::io::Error> {
+    let digest = match algo {
+        HashAlgorithm::Sha256 => digest_file::<Sha256>(path, progress)?,
+        HashAlgorithm::Sha512 => digest_file::<Sha512>(path, progress)?,
+    };
+    Ok(encode_digest(&digest, encoding))
+}
+
+/// Write out raw digest bytes in `encoding`.
+///
+/// The encoding only changes the text, so digests compare equal exactly
+/// when the bytes do, as long as both sides use the same encoding.
+pub fn encode_digest(digest: &[u8], encoding: DigestEncoding) -> String {
+    match encoding {
+        DigestEncoding::Hex => digest.iter().map(|b| format!("{:02x}", b)).collect(),
+        DigestEncoding::Base64 => base64::engine::general_purpose::STANDARD.encode(digest),
+    }
+}
+
+/// The encoding `digest` (from [`encode_digest`]) is written in.
+///
+/// Padded base64 of a 32- or 64-byte digest always ends in `=`, so it is
+/// never all hex digits.
+pub fn encoding_of(digest: &str) -> DigestEncoding {
+    if digest.bytes().all(|b| b.is_ascii_hexdigit()) {
+        DigestEncoding::Hex
+    } else {
+        DigestEncoding::Base64
+    }
+}
+
+/// Hash a file through digest `D`, memory-mapping it from
+/// [`MMAP_THRESHOLD`] bytes up.
+fn digest_file<D: Digest>(
+    path: &Path,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Result<Vec<u8>, std::io::Error> {
+    digest_file_with::<D>(path, MMAP_THRESHOLD, progress)
+}
+
+/// Same as [`digest_file`] with an explicit mmap threshold.
+///
+/// Files that cannot be mapped (special files, some network shares) fall
+/// back to chunked reads.
+fn digest_file_with<D: Digest>(
+    path: &Path,
+    mmap_threshold: u64,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Result<Vec<u8>, std::io::Error> {
+    let file = std::fs::File::open(path)?;
+    let total = file.metadata()?.len();
+    if total >= mmap_threshold {
+        if let Some(digest) = digest_mmap::<D>(&file, progress) {
+            return Ok(digest);
+        }
+    }
+    digest_reader::<D>(file, total, progress)
+}
+
+/// Feed a memory map of `file` to digest `D` in `MMAP_SLICE` slices.
+///
+/// Returns `None` when the file cannot be mapped.
+fn digest_mmap<D: Digest>(
+    file: &std::fs::File,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Option<Vec<u8>> {
+    // SAFETY: the map is read-only and dropped before returning.  A file
+    // truncated by another process mid-hash can still fault, the usual
+    // trade-off of mmap-based hashing.
+    let map = unsafe { memmap2::Mmap::map(file) }.ok()?;
+    let total = map.len() as u64;
+    let mut hasher = D::new();
+    let mut done: u64 = 0;
+    for slice in map.chunks(MMAP_SLICE) {
+        hasher.update(slice);
+        done += slice.len() as u64;
+        if let Some(cb) = progress {
+            cb(done, total);
+        }
+    }
+    Some(hasher.finalize().to_vec())
+}
+
+/// Stream `reader` through digest `D` in `CHUNK_SIZE` reads.
+fn digest_reader<D: Digest>(
+    mut reader: impl Read,
+    total: u64,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Result<Vec<u8>, std::io::Error> {
+    let mut hasher = D::new();
     let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut done: u64 = 0;
 
     loop {
-        let n = file.read(&mut buffer)?;
+        let n = reader.read(&mut buffer)?;
         if n == 0 {
             break;
         }
         hasher.update(&buffer[..n]);
+        done += n as u64;
+        if let Some(cb) = progress {
+            cb(done, total);
+        }
     }
 
-    Ok(format!("{:x}", hasher.finalize()))
+    Ok(hasher.finalize().to_vec())
 }
 
 /// Return a fast SHA-256 digest based on file size + head chunk + tail chunk.
@@ -66,6 +219,78 @@ pub fn sha256_fast(path: &Path) -> Result<String, std::io::Error> {
     Ok(format!("{:x}", hasher.finalize()))
 }
 
+/// Compare two files byte-for-byte, stopping at the first difference.
+///
+/// Sizes are compared first; when they differ no content is read and
+/// `first_diff_offset` is `None`.
+pub fn compare_files(a: &Path, b: &Path) -> Result<CompareResult, std::io::Error> {
+    let mut file_a = std::fs::File::open(a)?;
+    let mut file_b = std::fs::File::open(b)?;
+    let size_a = file_a.metadata()?.len();
+    let size_b = file_b.metadata()?.len();
+
+    if size_a != size_b {
+        return Ok(CompareResult {
+            identical: false,
+            first_diff_offset: None,
+            size_a,
+            size_b,
+        });
+    }
+
+    let mut buf_a = vec![0u8; CHUNK_SIZE];
+    let mut buf_b = vec![0u8; CHUNK_SIZE];
+    let mut offset: u64 = 0;
+
+    loop {
+        let n_a = read_full(&mut file_a, &mut buf_a)?;
+        let n_b = read_full(&mut file_b, &mut buf_b)?;
+        let n = n_a.min(n_b);
+
+        if let Some(pos) = buf_a[..n].iter().zip(&buf_b[..n]).position(|(x, y)| x != y) {
+            return Ok(CompareResult {
+                identical: false,
+                first_diff_offset: Some(offset + pos as u64),
+                size_a,
+                size_b,
+            });
+        }
+        if n_a != n_b {
+            // One file shrank or grew while we were reading it.
+            return Ok(CompareResult {
+                identical: false,
+                first_diff_offset: Some(offset + n as u64),
+                size_a,
+                size_b,
+            });
+        }
+        if n == 0 {
+            break;
+        }
+        offset += n as u64;
+    }
+
+    Ok(CompareResult {
+        identical: true,
+        first_diff_offset: None,
+        size_a,
+        size_b,
+    })
+}
+
+/// Read until `buf` is full or EOF; return the number of bytes read.
+fn read_full(file: &mut std::fs::File, buf: &mut [u8]) -> Result<usize, std::io::Error> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let n = file.read(&mut buf[filled..])?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+    }
+    Ok(filled)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,7 +303,7 @@ mod tests {
         let f = dir.path().join("test.txt");
         fs::write(&f, b"hello world").unwrap();
         assert_eq!(
-            sha256_file(&f).unwrap(),
+            sha256_file(&f, DigestEncoding::Hex).unwrap(),
             "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
         );
     }
@@ -89,21 +314,86 @@ mod tests {
         let f = dir.path().join("empty.txt");
         fs::write(&f, b"").unwrap();
         assert_eq!(
-            sha256_file(&f).unwrap(),
+            sha256_file(&f, DigestEncoding::Hex).unwrap(),
             "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
         );
     }
 
+    #[test]
+    fn test_base64_encoding_of_known_digest() {
+        let dir = tempdir().unwrap();
+        let f = dir.path().join("empty.txt");
+        fs::write(&f, b"").unwrap();
+        let digest = sha256_file(&f, DigestEncoding::Base64).unwrap();
+        assert_eq!(digest, "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=");
+        assert_eq!(encoding_of(&digest), DigestEncoding::Base64);
+        assert_eq!(
+            encoding_of(&sha256_file(&f, DigestEncoding::Hex).unwrap()),
+            DigestEncoding::Hex
+        );
+    }
+
     #[test]
     fn test_large_file_exercises_chunking() {
         let dir = tempdir().unwrap();
         let f = dir.path().join("large.bin");
         // Larger than 1 MB chunk to exercise the loop
         fs::write(&f, vec![0u8; CHUNK_SIZE + 1]).unwrap();
-        let result = sha256_file(&f).unwrap();
+        let result = sha256_file(&f, DigestEncoding::Hex).unwrap();
         assert_eq!(result.len(), 64); // valid hex digest
     }
 
+    #[test]
+    fn test_hash_file_dispatches_algorithm() {
+        let dir = tempdir().unwrap();
+        let f = dir.path().join("test.txt");
+        fs::write(&f, b"hello world").unwrap();
+        assert_eq!(
+            hash_file(&f, HashAlgorithm::Sha256, DigestEncoding::Hex, None).unwrap(),
+            sha256_file(&f, DigestEncoding::Hex).unwrap()
+        );
+        assert_eq!(
+            hash_file(&f, HashAlgorithm::Sha512, DigestEncoding::Hex, None).unwrap(),
+            "309ecc489c12d6eb4cc40f50c902f2b4d0ed77ee511a7c7a9bcd3ca86d4cd86f\
+             989dd35bc5ff499670da34255b45b0cfd830e81f605dcf7dc5542e93ae9cd76f"
+        );
+    }
+
+    #[test]
+    fn test_hash_file_reports_byte_progress() {
+        let dir = tempdir().unwrap();
+        let f = dir.path().join("large.bin");
+        fs::write(&f, vec![0u8; CHUNK_SIZE + 1]).unwrap();
+        let calls = std::cell::RefCell::new(Vec::new());
+        let cb = |done: u64, total: u64| calls.borrow_mut().push((done, total));
+        hash_file(&f, HashAlgorithm::Sha256, DigestEncoding::Hex, Some(&cb)).unwrap();
+        let total = CHUNK_SIZE as u64 + 1;
+        assert_eq!(calls.into_inner().last(), Some(&(total, total)));
+    }
+
+    #[test]
+    fn test_mmap_path_matches_chunked_path() {
+        let dir = tempdir().unwrap();
+        let f = dir.path().join("large.bin");
+        // Spans several mmap slices and ends mid-slice.
+        let content: Vec<u8> = (0..MMAP_SLICE * 2 + 12_345)
+            .map(|i| (i * 31 % 251) as u8)
+            .collect();
+        fs::write(&f, &content).unwrap();
+
+        let calls = std::cell::RefCell::new(Vec::new());
+        let cb = |done: u64, total: u64| calls.borrow_mut().push((done, total));
+        let mapped = digest_file_with::<Sha256>(&f, 0, Some(&cb)).unwrap();
+        let chunked = digest_file_with::<Sha256>(&f, u64::MAX, None).unwrap();
+        assert_eq!(mapped, chunked);
+
+        // Three slices, so the mmap path (not the 1 MB reader) ran.
+        let total = content.len() as u64;
+        let calls = calls.into_inner();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls.last(), Some(&(total, total)));
+    }
+
     // -- sha256_fast tests --
 
     #[test]
@@ -133,7 +423,7 @@ mod tests {
         let dir = tempdir().unwrap();
         let f = dir.path().join("small.txt");
         fs::write(&f, b"same content").unwrap();
-        let full = sha256_file(&f).unwrap();
+        let full = sha256_file(&f, DigestEncoding::Hex).unwrap();
         let fast = sha256_fast(&f).unwrap();
         assert_ne!(full, fast);
     }
@@ -157,4 +447,59 @@ mod tests {
         fs::write(&f2, &content).unwrap();
         assert_eq!(sha256_fast(&f1).unwrap(), sha256_fast(&f2).unwrap());
     }
+
+    // -- compare_files tests --
+
+    #[test]
+    fn test_compare_identical_files() {
+        let dir = tempdir().unwrap();
+        let f1 = dir.path().join("a.bin");
+        let f2 = dir.path().join("b.bin");
+        let content = vec![0x5A; CHUNK_SIZE + 10];
+        fs::write(&f1, &content).unwrap();
+        fs::write(&f2, &content).unwrap();
+
+        let result = compare_files(&f1, &f2).unwrap();
+        assert!(result.identical);
+        assert_eq!(result.first_diff_offset, None);
+        assert_eq!(result.size_a, result.size_b);
+    }
+
+    #[test]
+    fn test_compare_same_size_reports_first_diff_offset() {
+        let dir = tempdir().unwrap();
+        let f1 = dir.path().join("a.bin");
+        let f2 = dir.path().join("b.bin");
+        let mut content = vec![0u8; CHUNK_SIZE + 100];
+        fs::write(&f1, &content).unwrap();
+        content[CHUNK_SIZE + 42] = 1;
+        fs::write(&f2, &content).unwrap();
+
+        let result = compare_files(&f1, &f2).unwrap();
+        assert!(!result.identical);
+        assert_eq!(result.first_diff_offset, Some(CHUNK_SIZE as u64 + 42));
+    }
+
+    #[test]
+    fn test_compare_different_sizes() {
+        let dir = tempdir().unwrap();
+        let f1 = dir.path().join("a.txt");
+        let f2 = dir.path().join("b.txt");
+        fs::write(&f1, b"short").unwrap();
+        fs::write(&f2, b"longer content").unwrap();
+
+        let result = compare_files(&f1, &f2).unwrap();
+        assert!(!result.identical);
+        assert_eq!(result.first_diff_offset, None);
+        assert_eq!(result.size_a, 5);
+        assert_eq!(result.size_b, 14);
+    }
+
+    #[test]
+    fn test_compare_missing_file_errors() {
+        let dir = tempdir().unwrap();
+        let f1 = dir.path().join("a.txt");
+        fs::write(&f1, b"data").unwrap();
+        assert!(compare_files(&f1, &dir.path().join("gone.txt")).is_err());
+    }
 }
@@ -0,0 +1,146 @@
+//! Pick which member of a duplicate group survives a delete.
+
+use std::cmp::Ordering;
+
+use crate::types::{FileEntry, KeepStrategy};
+
+/// Return the index of the file in `files` that `strategy` keeps.
+///
+/// Returns `None` only for an empty group.  Ties on the strategy's own
+/// ranking fall back to the newest file; a remaining tie keeps the earlier
+/// entry.
+pub fn select_keeper(files: &[FileEntry], strategy: &KeepStrategy) -> Option<usize> {
+    let rank = |file: &FileEntry| -> usize {
+        match strategy {
+            KeepStrategy::Newest => 0,
+            KeepStrategy::FolderPriority(folders) => folders
+                .iter()
+                .position(|folder| file.path.starts_with(folder))
+                .unwrap_or(usize::MAX),
+        }
+    };
+
+    files
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| {
+            rank(a).cmp(&rank(b)).then_with(|| {
+                // Newer first; NaN mtimes sort last.
+                b.mtime.partial_cmp(&a.mtime).unwrap_or(Ordering::Equal)
+            })
+        })
+        .map(|(index, _)| index)
+}
+
+/// Why `strategy` keeps `files[keep]`, in words for a confirmation sheet.
+pub fn keep_reason(files: &[FileEntry], keep: usize, strategy: &KeepStrategy) -> String {
+    match strategy {
+        KeepStrategy::Newest => "newest copy".to_string(),
+        KeepStrategy::FolderPriority(folders) => {
+            let folder_of = |file: &FileEntry| folders.iter().find(|f| file.path.starts_with(f));
+            match folder_of(&files[keep]) {
+                Some(folder) => {
+                    let shared = files
+                        .iter()
+                        .filter(|file| folder_of(file) == Some(folder))
+                        .count();
+                    if shared > 1 {
+                        format!("newest copy in priority folder {}", folder.display())
+                    } else {
+                        format!("in priority folder {}", folder.display())
+                    }
+                }
+                None => "newest copy; no copy is in a priority folder".to_string(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    fn entry(path: &str, mtime: f64) -> FileEntry {
+        FileEntry {
+            path: PathBuf::from(path),
+            size: 10,
+            mtime,
+            created: None,
+        }
+    }
+
+    #[test]
+    fn test_keep_reason_names_the_deciding_rule() {
+        let files = vec![
+            entry("/home/u/Downloads/beach.jpg", 500.0),
+            entry("/home/u/Pictures/beach.jpg", 100.0),
+        ];
+        let strategy = KeepStrategy::FolderPriority(vec![PathBuf::from("/home/u/Pictures")]);
+        assert_eq!(
+            keep_reason(&files, 1, &strategy),
+            format!(
+                "in priority folder {}",
+                Path::new("/home/u/Pictures").display()
+            )
+        );
+        assert_eq!(keep_reason(&files, 0, &KeepStrategy::Newest), "newest copy");
+    }
+
+    #[test]
+    fn test_newest_strategy_keeps_latest_mtime() {
+        let files = vec![entry("/a/x.jpg", 100.0), entry("/b/x.jpg", 300.0)];
+        assert_eq!(select_keeper(&files, &KeepStrategy::Newest), Some(1));
+    }
+
+    #[test]
+    fn test_folder_priority_beats_newer_copy() {
+        // Downloads is scanned first and holds the newer copy, but Pictures
+        // is the priority folder.
+        let files = vec![
+            entry("/home/u/Downloads/beach.jpg", 500.0),
+            entry("/home/u/Pictures/2023/beach.jpg", 100.0),
+        ];
+        let strategy = KeepStrategy::FolderPriority(vec![PathBuf::from("/home/u/Pictures")]);
+        assert_eq!(select_keeper(&files, &strategy), Some(1));
+    }
+
+    #[test]
+    fn test_folder_priority_earliest_match_wins() {
+        let files = vec![
+            entry("/home/u/Backup/beach.jpg", 500.0),
+            entry("/home/u/Pictures/beach.jpg", 100.0),
+        ];
+        let strategy = KeepStrategy::FolderPriority(vec![
+            PathBuf::from("/home/u/Pictures"),
+            PathBuf::from("/home/u/Backup"),
+        ]);
+        assert_eq!(select_keeper(&files, &strategy), Some(1));
+    }
+
+    #[test]
+    fn test_folder_priority_falls_back_to_newest() {
+        let files = vec![
+            entry("/home/u/Downloads/a.jpg", 100.0),
+            entry("/home/u/Desktop/a.jpg", 200.0),
+        ];
+        let strategy = KeepStrategy::FolderPriority(vec![PathBuf::from("/home/u/Pictures")]);
+        assert_eq!(select_keeper(&files, &strategy), Some(1));
+    }
+
+    #[test]
+    fn test_folder_priority_does_not_match_sibling_prefix() {
+        // "/home/u/Pictures-old" is not under "/home/u/Pictures".
+        let files = vec![
+            entry("/home/u/Pictures-old/a.jpg", 100.0),
+            entry("/home/u/Downloads/a.jpg", 200.0),
+        ];
+        let strategy = KeepStrategy::FolderPriority(vec![PathBuf::from("/home/u/Pictures")]);
+        assert_eq!(select_keeper(&files, &strategy), Some(1));
+    }
+
+    #[test]
+    fn test_empty_group_has_no_keeper() {
+        assert_eq!(select_keeper(&[], &KeepStrategy::Newest), None);
+    }
+}
@@ -0,0 +1,185 @@
+//! The most recent scan result, kept on disk so a restart can show it again.
+//!
+//! The file is a plain JSON [`ScanResult`] in the config directory.  Files
+//! can change while the app is closed, so a loaded result is checked
+//! against the disk and marked [`ScanResult::stale`].
+
+use std::path::{Path, PathBuf};
+
+use crate::archive;
+use crate::settings;
+use crate::types::{FileEntryDto, ScanResult};
+
+/// File name of the saved result inside the config directory.
+const LAST_SCAN_FILE: &str = ".duplicate_cleaner_last_scan.json";
+
+/// Path to the saved scan result.
+pub fn last_scan_path() -> PathBuf {
+    settings::config_dir().join(LAST_SCAN_FILE)
+}
+
+/// Save `result` as the last scan.
+pub fn save_last_scan(result: &ScanResult) -> Result<(), String> {
+    save_last_scan_to(result, &last_scan_path())
+}
+
+/// Load the last scan, if any; see [`load_last_scan_from`].
+pub fn load_last_scan() -> Option<ScanResult> {
+    load_last_scan_from(&last_scan_path())
+}
+
+/// Save `result` to `path` as JSON.
+pub fn save_last_scan_to(result: &ScanResult, path: &Path) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string(result).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
+/// Load the result saved at `path`, revalidated against the disk.
+///
+/// `None` when nothing was saved or the file cannot be parsed.
+pub fn load_last_scan_from(path: &Path) -> Option<ScanResult> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let result: ScanResult = serde_json::from_str(&content).ok()?;
+    Some(revalidate(result))
+}
+
+/// Drop listed files that no longer exist, then groups left with fewer
+/// than two members, and mark the result stale.
+///
+/// Members hidden by `max_files_per_group` are not known by path and are
+/// assumed to still exist.  A group whose fixed keeper is gone is dropped
+/// as well, since the rest only duplicated it.
+pub fn revalidate(mut result: ScanResult) -> ScanResult {
+    let before = result.groups.len();
+    let mut freed_waste = 0u64;
+    result.groups.retain_mut(|group| {
+        let listed_waste = wasted_bytes(&group.files);
+        group
+            .files
+            .retain(|file| file_exists(Path::new(&file.path)));
+        let keeper_gone = group
+            .keeper
+            .as_ref()
+            .is_some_and(|keeper| !group.files.iter().any(|f| &f.path == keeper));
+        let keep = !keeper_gone && group.files.len() + group.overflow_count >= 2;
+        freed_waste += listed_waste - if keep { wasted_bytes(&group.files) } else { 0 };
+        keep
+    });
+
+    result.total_group_count -= before - result.groups.len();
+    result.total_wasted_bytes = result.total_wasted_bytes.saturating_sub(freed_waste);
+    result.stale = true;
+    result
+}
+
+/// Archive members count as present while their archive is.
+fn file_exists(path: &Path) -> bool {
+    match archive::split_virtual_path(path) {
+        Some((archive, _)) => archive.is_file(),
+        None => path.is_file(),
+    }
+}
+
+/// Like [`crate::grouper::wasted_bytes`], over the listed members.
+fn wasted_bytes(files: &[FileEntryDto]) -> u64 {
+    let total: u64 = files.iter().map(|f| f.size).sum();
+    let largest = files.iter().map(|f| f.size).max().unwrap_or(0);
+    total - largest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CriterionValue, DuplicateGroup, DuplicateKey, ScanSkipReasons};
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn dto(path: &Path, size: u64) -> FileEntryDto {
+        FileEntryDto {
+            path: path.to_string_lossy().to_string(),
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            folder: path.parent().unwrap().to_string_lossy().to_string(),
+            size,
+            size_human: String::new(),
+            mtime: 0.0,
+            mtime_formatted: String::new(),
+            created: 0.0,
+            created_formatted: String::new(),
+        }
+    }
+
+    fn group(files: Vec<FileEntryDto>) -> DuplicateGroup {
+        let key: DuplicateKey = vec![CriterionValue::Size(files[0].size)];
+        DuplicateGroup {
+            key_description: String::new(),
+            confidence: 0.5,
+            key,
+            files,
+            keeper: None,
+            overflow_count: 0,
+            signature: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_saved_scan_loads_without_vanished_files() {
+        let dir = tempdir().unwrap();
+        let paths: Vec<PathBuf> = ["a1", "a2", "a3", "b1", "b2"]
+            .iter()
+            .map(|name| {
+                let path = dir.path().join(name);
+                fs::write(&path, b"0123456789").unwrap();
+                path
+            })
+            .collect();
+        let result = ScanResult {
+            groups: vec![
+                group(paths[..3].iter().map(|p| dto(p, 10)).collect()),
+                group(paths[3..].iter().map(|p| dto(p, 10)).collect()),
+            ],
+            truncated: false,
+            timed_out: false,
+            file_limit_reached: false,
+            total_group_count: 2,
+            total_wasted_bytes: 30,
+            size_histogram: Vec::new(),
+            by_extension: Default::default(),
+            total_files_scanned: 5,
+            hash_skipped: 0,
+            created_skipped: 0,
+            audio_skipped: 0,
+            dimensions_skipped: 0,
+            in_flux_skipped: 0,
+            scan_skipped: 0,
+            scan_skip_reasons: ScanSkipReasons::default(),
+            elapsed_seconds: 0.1,
+            stale: false,
+            ignored_count: 0,
+            scan_id: 0,
+        };
+        let saved = dir.path().join("state").join(LAST_SCAN_FILE);
+        save_last_scan_to(&result, &saved).unwrap();
+
+        // One removal shrinks the first group; the second loses a pair
+        // and disappears.
+        fs::remove_file(&paths[0]).unwrap();
+        fs::remove_file(&paths[4]).unwrap();
+
+        let loaded = load_last_scan_from(&saved).unwrap();
+        assert!(loaded.stale);
+        assert_eq!(loaded.groups.len(), 1);
+        assert_eq!(loaded.total_group_count, 1);
+        assert_eq!(loaded.total_wasted_bytes, 10);
+        let remaining: Vec<&str> = loaded.groups[0]
+            .files
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(remaining, ["a2", "a3"]);
+
+        assert!(load_last_scan_from(&dir.path().join("missing.json")).is_none());
+    }
+}
@@ -1,26 +1,72 @@
+pub mod archive;
+pub mod audio_fp;
 pub mod autorenamer;
 mod commands;
+mod delete_history;
 pub mod deleter;
+mod error;
+pub mod file_lock;
 pub mod grouper;
 pub mod hasher;
+pub mod keeper;
+mod last_scan;
+pub mod manifest;
 pub mod media_meta;
+pub mod progress;
 pub mod scanner;
 mod settings;
 pub mod types;
+pub mod wire_path;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
+        .manage(commands::RenameCancel::default())
+        .manage(commands::ManifestCancel::default())
+        .manage(commands::ScanCache::default())
         .invoke_handler(tauri::generate_handler![
             commands::cmd_get_default_folder,
+            commands::cmd_can_peek_archives,
             commands::cmd_get_settings,
             commands::cmd_save_settings,
+            commands::cmd_get_recent_folders,
+            commands::cmd_add_recent_folder,
+            commands::cmd_ignore_group,
+            commands::cmd_clear_ignored,
+            commands::cmd_set_theme,
             commands::cmd_open_folder,
+            commands::cmd_reveal_file,
+            commands::cmd_is_file_locked,
             commands::cmd_scan,
+            commands::cmd_get_scan_page,
+            commands::cmd_get_scan_meta,
+            commands::cmd_scan_summary_only,
+            commands::cmd_rescan,
+            commands::cmd_load_last_scan,
+            commands::cmd_diff_scans,
+            commands::cmd_scan_dedupe_against_master,
+            commands::cmd_scan_images,
             commands::cmd_scan_auto_rename,
             commands::cmd_auto_rename,
+            commands::cmd_organize_by_date,
+            commands::cmd_cancel_rename,
+            commands::cmd_preview_name,
             commands::cmd_delete,
+            commands::cmd_restore_from_trash,
+            commands::cmd_get_delete_history,
+            commands::cmd_auto_dedupe,
+            commands::cmd_dry_run_auto_dedupe,
+            commands::cmd_canonicalize_duplicates,
+            commands::cmd_remove_empty_dirs,
+            commands::cmd_compare_two_files,
+            commands::cmd_hash_file,
+            commands::cmd_export_manifest,
+            commands::cmd_cancel_manifest,
+            commands::cmd_verify_manifest,
+            commands::cmd_validate_folder,
+            commands::cmd_estimate_scan_time,
+            commands::cmd_compute_selection_size,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,323 @@
+//! Checksum manifests in `sha256sum` format.
+//!
+//! One `<hex digest>  <path>` line per file, with paths relative to the
+//! folder the manifest describes and `/` as separator, so `sha256sum -c`
+//! can check it from that folder.  Digests may be written in base64
+//! instead, for tools that expect it; `sha256sum` cannot read those.  A
+//! cancelled export ends with an [`INCOMPLETE_MARKER`] comment line.
+//! [`verify_manifest`] re-hashes the listed files to detect drift.
+
+use std::fs::File;
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::hasher;
+use crate::progress::ProgressSink;
+use crate::types::{
+    DigestEncoding, FileEntry, HashAlgorithm, ManifestExport, PathErrorDto, VerifyEntry,
+    VerifyStatus,
+};
+
+/// Start of the last line of a cancelled export.  `sha256sum -c` only
+/// warns about it as an improperly formatted line.
+pub const INCOMPLETE_MARKER: &str = "# incomplete";
+
+/// Hash `entries` into a manifest at `out`, naming them relative to `base`,
+/// with digests in `encoding`.
+///
+/// Bytes hashed so far go to [`ProgressSink::on_manifest`], also while a
+/// large file is being read.  `cancel` is checked before each file; once
+/// it is raised the manifest is closed with [`INCOMPLETE_MARKER`] and the
+/// result is marked `incomplete`.  Unreadable files are left out and
+/// listed in `errors`.
+pub fn export_manifest(
+    entries: &[FileEntry],
+    base: &Path,
+    out: &Path,
+    encoding: DigestEncoding,
+    progress: &dyn ProgressSink,
+    cancel: &AtomicBool,
+) -> std::io::Result<ManifestExport> {
+    let total_bytes: u64 = entries.iter().map(|e| e.size).sum();
+    let mut writer = BufWriter::new(File::create(out)?);
+    let mut done_bytes: u64 = 0;
+    let mut files_written = 0;
+    let mut errors = Vec::new();
+    let mut incomplete = false;
+
+    for (index, entry) in entries.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            incomplete = true;
+            break;
+        }
+        let on_bytes = |done: u64, _total: u64| {
+            progress.on_manifest(done_bytes + done, total_bytes, index, entries.len());
+        };
+        match hasher::hash_file(
+            &entry.path,
+            HashAlgorithm::Sha256,
+            encoding,
+            Some(&on_bytes),
+        ) {
+            Ok(digest) => {
+                writeln!(writer, "{}  {}", digest, manifest_path(&entry.path, base))?;
+                files_written += 1;
+            }
+            Err(e) => errors.push(PathErrorDto {
+                path: entry.path.to_string_lossy().into_owned(),
+                message: e.to_string(),
+            }),
+        }
+        done_bytes += entry.size;
+        progress.on_manifest(done_bytes, total_bytes, index + 1, entries.len());
+    }
+
+    if incomplete {
+        writeln!(
+            writer,
+            "{}: cancelled after {} of {} files",
+            INCOMPLETE_MARKER,
+            files_written,
+            entries.len()
+        )?;
+    }
+    writer.flush()?;
+    Ok(ManifestExport {
+        files_written,
+        files_total: entries.len(),
+        incomplete,
+        errors,
+    })
+}
+
+/// Re-hash every file listed in the `sha256sum` manifest at `manifest`.
+///
+/// Relative paths are resolved against the manifest's folder; hex and
+/// base64 digests are both understood.  Each entry is `Ok` when the
+/// digest still matches, `Changed` when it does not, `Missing` when the
+/// file is gone and `Unreadable` when it cannot be read.  Comment lines
+/// (such as [`INCOMPLETE_MARKER`]), blank lines and lines that are not
+/// `<digest>  <path>` are skipped.  Progress goes to
+/// [`ProgressSink::on_manifest`] after each file.
+pub fn verify_manifest(
+    manifest: &Path,
+    progress: &dyn ProgressSink,
+) -> std::io::Result<Vec<VerifyEntry>> {
+    let text = std::fs::read_to_string(manifest)?;
+    let base = manifest.parent().unwrap_or(Path::new(""));
+    let listed: Vec<(&str, &str)> = text.lines().filter_map(parse_line).collect();
+    let sizes: Vec<u64> = listed
+        .iter()
+        .map(|(_, name)| std::fs::metadata(base.join(name)).map_or(0, |m| m.len()))
+        .collect();
+    let total_bytes: u64 = sizes.iter().sum();
+
+    let mut done_bytes: u64 = 0;
+    let mut entries = Vec::with_capacity(listed.len());
+    for (index, (expected, name)) in listed.iter().enumerate() {
+        let encoding = hasher::encoding_of(expected);
+        let (status, message) = match hasher::sha256_file(&base.join(name), encoding) {
+            Ok(digest) if digest_matches(&digest, expected, encoding) => (VerifyStatus::Ok, None),
+            Ok(_) => (VerifyStatus::Changed, None),
+            Err(e) if e.kind() == ErrorKind::NotFound => (VerifyStatus::Missing, None),
+            Err(e) => (VerifyStatus::Unreadable, Some(e.to_string())),
+        };
+        entries.push(VerifyEntry {
+            path: name.to_string(),
+            status,
+            message,
+        });
+        done_bytes += sizes[index];
+        progress.on_manifest(done_bytes, total_bytes, index + 1, listed.len());
+    }
+    Ok(entries)
+}
+
+/// True when `digest` equals `expected`; hex ignores case, base64 does not.
+fn digest_matches(digest: &str, expected: &str, encoding: DigestEncoding) -> bool {
+    match encoding {
+        DigestEncoding::Hex => digest.eq_ignore_ascii_case(expected),
+        DigestEncoding::Base64 => digest == expected,
+    }
+}
+
+/// True for a SHA-256 digest in hex (64 digits) or padded base64 (44
+/// characters).
+fn is_sha256_digest(digest: &str) -> bool {
+    let base64_char = |b: u8| b.is_ascii_alphanumeric() || b == b'+' || b == b'/';
+    match digest.len() {
+        64 => digest.bytes().all(|b| b.is_ascii_hexdigit()),
+        44 => digest.ends_with('=') && digest[..43].bytes().all(base64_char),
+        _ => false,
+    }
+}
+
+/// Split a manifest line into `(digest, path)`.
+///
+/// Accepts both the text (`<digest>  <path>`) and binary
+/// (`<digest> *<path>`) forms `sha256sum` writes.
+fn parse_line(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_end_matches('\r');
+    if line.starts_with('#') {
+        return None;
+    }
+    let (digest, rest) = line.split_once(' ')?;
+    let name = rest.strip_prefix([' ', '*'])?;
+    (is_sha256_digest(digest) && !name.is_empty()).then_some((digest, name))
+}
+
+/// `path` relative to `base` with `/` separators; the full path when it
+/// is not under `base`.
+fn manifest_path(path: &Path, base: &Path) -> String {
+    match path.strip_prefix(base) {
+        Ok(relative) => relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        Err(_) => path.to_string_lossy().into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::NoProgress;
+    use crate::scanner;
+    use crate::types::ScanOptions;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn entries_in(dir: &Path) -> Vec<FileEntry> {
+        let (mut entries, _) =
+            scanner::gather_recent_files(dir, 0, None, None, &ScanOptions::default(), &NoProgress);
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        entries
+    }
+
+    #[test]
+    fn test_manifest_lists_relative_paths_in_sha256sum_format() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("sub").join("a.txt"), b"hello").unwrap();
+        let out = tempdir().unwrap();
+        let manifest = out.path().join("SHA256SUMS");
+
+        let result = export_manifest(
+            &entries_in(dir.path()),
+            dir.path(),
+            &manifest,
+            DigestEncoding::Hex,
+            &NoProgress,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert!(!result.incomplete);
+        assert_eq!(result.files_written, 1);
+        assert_eq!(
+            fs::read_to_string(&manifest).unwrap(),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  sub/a.txt\n"
+        );
+    }
+
+    #[test]
+    fn test_cancelled_export_writes_partial_manifest_marked_incomplete() {
+        struct CancelAfter<'a> {
+            files: usize,
+            cancel: &'a AtomicBool,
+        }
+        impl ProgressSink for CancelAfter<'_> {
+            fn on_manifest(&self, _: u64, _: u64, files: usize, _: usize) {
+                if files == self.files {
+                    self.cancel.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+
+        let dir = tempdir().unwrap();
+        for i in 0..5 {
+            fs::write(dir.path().join(format!("{i}.bin")), vec![i as u8; 100]).unwrap();
+        }
+        let out = tempdir().unwrap();
+        let manifest = out.path().join("SHA256SUMS");
+        let cancel = AtomicBool::new(false);
+        let sink = CancelAfter {
+            files: 2,
+            cancel: &cancel,
+        };
+
+        let result = export_manifest(
+            &entries_in(dir.path()),
+            dir.path(),
+            &manifest,
+            DigestEncoding::Hex,
+            &sink,
+            &cancel,
+        )
+        .unwrap();
+
+        assert!(result.incomplete);
+        assert_eq!((result.files_written, result.files_total), (2, 5));
+        let text = fs::read_to_string(&manifest).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("  0.bin"));
+        assert!(lines[1].ends_with("  1.bin"));
+        assert!(lines[2].starts_with(INCOMPLETE_MARKER));
+    }
+
+    #[test]
+    fn test_verify_reports_changed_and_missing_files() {
+        let dir = tempdir().unwrap();
+        for name in ["kept.txt", "rotted.txt", "gone.txt"] {
+            fs::write(dir.path().join(name), name).unwrap();
+        }
+        let manifest = dir.path().join("SHA256SUMS");
+        export_manifest(
+            &entries_in(dir.path()),
+            dir.path(),
+            &manifest,
+            DigestEncoding::Hex,
+            &NoProgress,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        fs::write(dir.path().join("rotted.txt"), "flipped bits").unwrap();
+        fs::remove_file(dir.path().join("gone.txt")).unwrap();
+
+        let entries = verify_manifest(&manifest, &NoProgress).unwrap();
+
+        let statuses: Vec<(&str, VerifyStatus)> = entries
+            .iter()
+            .map(|e| (e.path.as_str(), e.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("gone.txt", VerifyStatus::Missing),
+                ("kept.txt", VerifyStatus::Ok),
+                ("rotted.txt", VerifyStatus::Changed),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_line_skips_comments_and_accepts_binary_marker() {
+        let digest = "a".repeat(64);
+        assert_eq!(
+            parse_line(&format!("{digest}  dir/a b.txt")),
+            Some((digest.as_str(), "dir/a b.txt"))
+        );
+        assert_eq!(
+            parse_line(&format!("{digest} *a.bin\r")),
+            Some((digest.as_str(), "a.bin"))
+        );
+        assert_eq!(
+            parse_line("# incomplete: cancelled after 2 of 5 files"),
+            None
+        );
+        assert_eq!(parse_line("not a manifest line"), None);
+    }
+}
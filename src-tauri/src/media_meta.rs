@@ -26,6 +26,19 @@ pub fn extract_media_fingerprint(path: &Path) -> Option<String> {
     }
 }
 
+/// Read an image's pixel dimensions without decoding the pixels.
+///
+/// The format is sniffed from the file content, so misnamed images still
+/// work.  Returns `None` for non-images and unreadable files.
+pub fn image_dimensions(path: &Path) -> Option<(u32, u32)> {
+    image::ImageReader::open(path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
 /// Read image dimensions from EXIF metadata (JPEG / TIFF).
 fn extract_exif_dimensions(path: &Path) -> Option<String> {
     let file = std::fs::File::open(path).ok()?;
@@ -0,0 +1,156 @@
+//! Progress reporting for the long-running scan / group / rename passes.
+
+use std::path::Path;
+
+/// Receives progress from the core scan, grouping and rename functions.
+///
+/// Every method defaults to a no-op, so a sink only implements the phases
+/// it cares about.  The Tauri side forwards them as frontend events; tests
+/// can record them.
+pub trait ProgressSink {
+    /// Walking a folder: `files` collected so far in `dirs` directories.
+    fn on_scan(&self, _files: usize, _dirs: usize) {}
+    /// Grouping: `current` of `total` files hashed / inspected.
+    fn on_hash(&self, _current: usize, _total: usize) {}
+    /// MIME filtering: `checked` of `total` files sniffed.
+    fn on_mime(&self, _checked: usize, _total: usize) {}
+    /// Renaming: about to process `path`, file `current` of `total`.
+    fn on_rename(&self, _current: usize, _total: usize, _path: &Path) {}
+    /// Manifest export: `bytes` of `total_bytes` hashed, `files` of
+    /// `total_files` done.  Called during large files too.
+    fn on_manifest(&self, _bytes: u64, _total_bytes: u64, _files: usize, _total_files: usize) {}
+    /// MIME filtering or hashing as part of one combined run (see
+    /// [`WeightedProgress`]): `current` of `total` in `phase`, and `percent`
+    /// (0–100) of the work of both phases together.  Defaults to the plain
+    /// per-phase callback.
+    fn on_weighted(&self, phase: WorkPhase, current: usize, total: usize, _percent: f64) {
+        match phase {
+            WorkPhase::Mime => self.on_mime(current, total),
+            WorkPhase::Hash => self.on_hash(current, total),
+        }
+    }
+}
+
+/// The two phases [`WeightedProgress`] combines, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkPhase {
+    Mime,
+    Hash,
+}
+
+/// Bytes read to sniff one file's MIME type; a MIME check's work units.
+pub const MIME_SNIFF_BYTES: u64 = 8192;
+
+/// Splits 0–100% between the MIME phase and the hashing phase in
+/// proportion to their work units (bytes read), so the two phases show as
+/// one continuous bar.  Within a phase, progress is linear in files.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseWeights {
+    mime_share: f64,
+}
+
+impl PhaseWeights {
+    /// Weights for `mime_units` bytes sniffed, then `hash_units` hashed.
+    pub fn new(mime_units: u64, hash_units: u64) -> Self {
+        let total = mime_units + hash_units;
+        let mime_share = if total == 0 {
+            0.5
+        } else {
+            mime_units as f64 / total as f64
+        };
+        Self { mime_share }
+    }
+
+    /// Overall percent after `current` of `total` files of `phase`.
+    pub fn percent(&self, phase: WorkPhase, current: usize, total: usize) -> f64 {
+        let fraction = if total == 0 {
+            1.0
+        } else {
+            (current as f64 / total as f64).min(1.0)
+        };
+        let (start, share) = match phase {
+            WorkPhase::Mime => (0.0, self.mime_share),
+            WorkPhase::Hash => (self.mime_share, 1.0 - self.mime_share),
+        };
+        100.0 * (start + share * fraction)
+    }
+}
+
+/// Wraps a sink so `on_mime` and `on_hash` arrive as `on_weighted` with
+/// the combined percentage; other updates pass through unchanged.
+pub struct WeightedProgress<'a> {
+    inner: &'a dyn ProgressSink,
+    weights: PhaseWeights,
+}
+
+impl<'a> WeightedProgress<'a> {
+    pub fn new(inner: &'a dyn ProgressSink, weights: PhaseWeights) -> Self {
+        Self { inner, weights }
+    }
+
+    fn forward(&self, phase: WorkPhase, current: usize, total: usize) {
+        let percent = self.weights.percent(phase, current, total);
+        self.inner.on_weighted(phase, current, total, percent);
+    }
+}
+
+impl ProgressSink for WeightedProgress<'_> {
+    fn on_scan(&self, files: usize, dirs: usize) {
+        self.inner.on_scan(files, dirs);
+    }
+    fn on_hash(&self, current: usize, total: usize) {
+        self.forward(WorkPhase::Hash, current, total);
+    }
+    fn on_mime(&self, checked: usize, total: usize) {
+        self.forward(WorkPhase::Mime, checked, total);
+    }
+    fn on_rename(&self, current: usize, total: usize, path: &Path) {
+        self.inner.on_rename(current, total, path);
+    }
+    fn on_manifest(&self, bytes: u64, total_bytes: u64, files: usize, total_files: usize) {
+        self.inner
+            .on_manifest(bytes, total_bytes, files, total_files);
+    }
+}
+
+/// A sink that ignores every update.
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_weighted_percent_rises_across_both_phases() {
+        #[derive(Default)]
+        struct Percents(RefCell<Vec<f64>>);
+        impl ProgressSink for Percents {
+            fn on_weighted(&self, _: WorkPhase, _: usize, _: usize, percent: f64) {
+                self.0.borrow_mut().push(percent);
+            }
+        }
+
+        // 4 files sniffed (32 KiB), then 96 KiB hashed: MIME is a quarter.
+        let weights = PhaseWeights::new(4 * MIME_SNIFF_BYTES, 12 * MIME_SNIFF_BYTES);
+        let recorder = Percents::default();
+        let sink = WeightedProgress::new(&recorder, weights);
+        for checked in 0..=4 {
+            sink.on_mime(checked, 4);
+        }
+        // Fewer files are left to hash than were sniffed.
+        for hashed in 0..=3 {
+            sink.on_hash(hashed, 3);
+        }
+
+        let percents = recorder.0.into_inner();
+        assert_eq!(percents.len(), 9);
+        assert!(percents.windows(2).all(|w| w[0] <= w[1]), "{:?}", percents);
+        assert_eq!(percents[0], 0.0);
+        assert_eq!(percents[4], 25.0);
+        assert_eq!(percents[5], 25.0);
+        assert_eq!(percents[8], 100.0);
+    }
+}
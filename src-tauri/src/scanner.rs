@@ -1,29 +1,157 @@
-use std::path::Path;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use chrono::{Datelike, TimeZone, Timelike};
 use walkdir::WalkDir;
 
-use crate::types::{FileEntry, ScanSkipReasons};
+use crate::archive;
+use crate::progress::ProgressSink;
+use crate::types::{
+    FileEntry, FolderPrecheck, MtimeFilter, ScanOptions, ScanSkipReasons, ScanTimeEstimate,
+    SizeBucket,
+};
+
+/// Entries the folder precheck visits before giving up on an exact count.
+pub const PRECHECK_MAX_ENTRIES: usize = 5_000;
+
+/// Wall-clock budget for the folder precheck.
+pub const PRECHECK_TIME_BUDGET: Duration = Duration::from_millis(500);
+
+/// Throughput assumed by [`estimate_scan_time`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScanThroughput {
+    /// Files listed and stat'ed per second.
+    pub files_per_sec: f64,
+    /// Bytes hashed per second.
+    pub hash_bytes_per_sec: f64,
+}
+
+/// Conservative figures for a local SSD.
+pub const DEFAULT_SCAN_THROUGHPUT: ScanThroughput = ScanThroughput {
+    files_per_sec: 5_000.0,
+    hash_bytes_per_sec: 200.0 * 1024.0 * 1024.0,
+};
 
 /// Return the file size in bytes, or 0 on any error.
-#[allow(dead_code)]
 pub fn safe_path_size(path: &Path) -> u64 {
     std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
 }
 
+/// Sum the sizes of the regular files in `paths`.
+///
+/// Missing or unreadable paths (and directories) contribute 0.
+pub fn total_size(paths: &[PathBuf]) -> u64 {
+    paths
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Upper bounds (exclusive) of the size histogram buckets, with labels.
+/// A final open-ended bucket collects everything from 1 GB up.
+const SIZE_BUCKETS: &[(u64, &str)] = &[
+    (1 << 10, "<1 KB"),
+    (10 << 10, "1-10 KB"),
+    (100 << 10, "10-100 KB"),
+    (1 << 20, "100 KB-1 MB"),
+    (10 << 20, "1-10 MB"),
+    (100 << 20, "10-100 MB"),
+    (1 << 30, "100 MB-1 GB"),
+];
+
+/// Count files and bytes per log-scale size bucket.
+///
+/// Every bucket is returned, empty ones included, so the UI can draw a
+/// stable axis.
+pub fn size_histogram(entries: &[FileEntry]) -> Vec<SizeBucket> {
+    let mut buckets: Vec<SizeBucket> = Vec::with_capacity(SIZE_BUCKETS.len() + 1);
+    let mut min_bytes = 0;
+    for &(max, label) in SIZE_BUCKETS {
+        buckets.push(SizeBucket {
+            label: label.into(),
+            min_bytes,
+            max_bytes: Some(max),
+            count: 0,
+            total_bytes: 0,
+        });
+        min_bytes = max;
+    }
+    buckets.push(SizeBucket {
+        label: ">1 GB".into(),
+        min_bytes,
+        max_bytes: None,
+        count: 0,
+        total_bytes: 0,
+    });
+
+    for entry in entries {
+        let idx = SIZE_BUCKETS
+            .iter()
+            .position(|&(max, _)| entry.size < max)
+            .unwrap_or(SIZE_BUCKETS.len());
+        buckets[idx].count += 1;
+        buckets[idx].total_bytes += entry.size;
+    }
+    buckets
+}
+
+/// Map the legacy `include_subfolders` flag onto a walk depth.
+///
+/// `true` means unlimited recursion, `false` means the top level only.
+pub fn depth_for_subfolders(include_subfolders: bool) -> Option<usize> {
+    if include_subfolders {
+        None
+    } else {
+        Some(1)
+    }
+}
+
+/// True when the local-time `mtime` (Unix seconds) passes `filter`.
+///
+/// Timestamps that cannot be mapped to a local time never match.
+pub fn mtime_matches(filter: &MtimeFilter, mtime: f64) -> bool {
+    let Some(local) = chrono::Local.timestamp_opt(mtime as i64, 0).earliest() else {
+        return false;
+    };
+    let weekday = local.weekday().num_days_from_monday() as u8;
+    if !filter.weekdays.is_empty() && !filter.weekdays.contains(&weekday) {
+        return false;
+    }
+    match filter.hours {
+        Some((start, end)) if start < end => (start..end).contains(&(local.hour() as u8)),
+        Some((start, end)) if start > end => {
+            let hour = local.hour() as u8;
+            hour >= start || hour < end
+        }
+        _ => true,
+    }
+}
+
 /// Collect files from `folder`, optionally filtering by recency and name prefix.
 ///
 /// - `days_back == 0` means collect all files regardless of age.
 /// - `name_prefix` filters by case-insensitive file name prefix.
-/// - `include_subfolders` controls recursive traversal.
+/// - `max_depth` limits recursion: `Some(1)` scans only the top level,
+///   `None` walks the whole tree (see [`depth_for_subfolders`]).
+/// - `options` carries the optional filters (see [`ScanOptions`]); with
+///   `max_files`, at most that many entries are returned and
+///   `file_limit_reached` is raised if any were left out.
+/// - `progress` hears `on_scan(files_found, dirs_scanned)` every 100 files
+///   or folders (every 100 ms with `parallel_walk`) and once at the end;
+///   `folder` itself counts as a directory.
 ///
 /// Returns `(entries, skip_reason_buckets)`.
 pub fn gather_recent_files(
     folder: &Path,
     days_back: u32,
     name_prefix: Option<&str>,
-    include_subfolders: bool,
-    progress_cb: Option<&dyn Fn(usize)>,
+    max_depth: Option<usize>,
+    options: &ScanOptions,
+    progress: &dyn ProgressSink,
 ) -> (Vec<FileEntry>, ScanSkipReasons) {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -36,38 +164,72 @@ pub fn gather_recent_files(
         Some(now - f64::from(days_back) * 86400.0)
     };
 
-    let prefix_lower = name_prefix.map(|p| p.to_lowercase());
+    let filter = FileFilter {
+        options,
+        cutoff,
+        live_cutoff: options
+            .skip_recently_modified_secs
+            .filter(|&secs| secs > 0)
+            .map(|secs| now - secs as f64),
+        prefix_lower: name_prefix.map(|p| p.to_lowercase()),
+    };
+    let max_depth = max_depth.unwrap_or(usize::MAX).max(1);
+    let deadline = options.timeout.map(|budget| Instant::now() + budget);
 
-    let max_depth = if include_subfolders { usize::MAX } else { 1 };
-    let walker = WalkDir::new(folder).max_depth(max_depth);
+    let (mut entries, skip_reasons, dirs_scanned) = if options.parallel_walk {
+        walk_parallel(folder, max_depth, deadline, &filter, progress)
+    } else {
+        walk_serial(folder, max_depth, deadline, &filter, progress)
+    };
 
-    let mut entries = Vec::new();
-    let mut skip_reasons = ScanSkipReasons::default();
+    // The walk goes one file past the cap to learn whether anything was
+    // left out; archive members can overshoot it further.
+    if let Some(max) = options.max_files {
+        if entries.len() > max {
+            entries.truncate(max);
+            options.file_limit_reached.store(true, Ordering::Relaxed);
+        }
+    }
 
-    for result in walker {
-        let dir_entry = match result {
-            Ok(e) => e,
-            Err(err) => {
-                count_walkdir_skip_reason(&mut skip_reasons, &err);
-                continue;
-            }
-        };
+    // Emit final count so the UI sees the exact total.
+    progress.on_scan(entries.len(), dirs_scanned);
 
-        if !dir_entry.file_type().is_file() {
-            continue;
-        }
+    (entries, skip_reasons)
+}
 
-        let path = dir_entry.path();
+/// The per-file filters of a walk, shared by both walkers.
+struct FileFilter<'a> {
+    options: &'a ScanOptions,
+    /// Oldest mtime kept (the `days_back` filter).
+    cutoff: Option<f64>,
+    /// Newer mtimes are still being written and skipped.
+    live_cutoff: Option<f64>,
+    prefix_lower: Option<String>,
+}
+
+impl FileFilter<'_> {
+    /// Push the entries the file at `path` yields onto `entries`: none when
+    /// it is filtered out, its archive members too when archives are
+    /// peeked into.
+    fn collect(
+        &self,
+        path: &Path,
+        entries: &mut Vec<FileEntry>,
+        skip_reasons: &mut ScanSkipReasons,
+    ) {
+        let options = self.options;
+        if options.skip_hidden && is_dotfile(path) {
+            return;
+        }
 
         // Name prefix filter (case-insensitive).
-        if let Some(ref pfx) = prefix_lower {
+        if let Some(ref pfx) = self.prefix_lower {
             let file_name = path
                 .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("")
-                .to_lowercase();
+                .map(|n| n.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
             if !file_name.starts_with(pfx.as_str()) {
-                continue;
+                return;
             }
         }
 
@@ -75,11 +237,15 @@ pub fn gather_recent_files(
         let meta = match std::fs::metadata(path) {
             Ok(m) => m,
             Err(err) => {
-                count_io_skip_reason(&mut skip_reasons, &err);
-                continue;
+                count_io_skip_reason(skip_reasons, &err);
+                return;
             }
         };
 
+        if options.skip_hidden && has_hidden_attribute(&meta) {
+            return;
+        }
+
         let mtime = meta
             .modified()
             .ok()
@@ -88,31 +254,458 @@ pub fn gather_recent_files(
             .unwrap_or(0.0);
 
         // Recency filter.
-        if let Some(cutoff_ts) = cutoff {
+        if let Some(cutoff_ts) = self.cutoff {
             if mtime < cutoff_ts {
-                continue;
+                return;
+            }
+        }
+
+        if let Some(filter) = &options.mtime_filter {
+            if !mtime_matches(filter, mtime) {
+                return;
             }
         }
 
+        // Still being written; hashing it now would see a partial file.
+        if self.live_cutoff.is_some_and(|cutoff| mtime > cutoff) {
+            skip_reasons.recently_modified += 1;
+            return;
+        }
+
+        let created = meta
+            .created()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs_f64());
+
         entries.push(FileEntry {
             path: path.to_path_buf(),
             size: meta.len(),
             mtime,
+            created,
         });
 
-        if let Some(cb) = &progress_cb {
-            if entries.len() % 100 == 0 {
-                cb(entries.len());
+        // Members take the archive's timestamps; an unreadable archive is
+        // still scanned as a plain file.
+        if options.peek_archives && archive::is_zip(path) {
+            if let Ok(members) = archive::zip_members(path) {
+                entries.extend(members.into_iter().map(|member| FileEntry {
+                    path: archive::virtual_path(path, &member.name),
+                    size: member.size,
+                    mtime,
+                    created,
+                }));
             }
         }
     }
 
-    // Emit final count so the UI sees the exact total.
-    if let Some(cb) = &progress_cb {
-        cb(entries.len());
+    /// True once the walk should stop: cancelled, out of time, or past
+    /// the file cap with `found` files.
+    fn should_stop(&self, deadline: Option<Instant>, found: usize) -> bool {
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            self.options.cancel.store(true, Ordering::Relaxed);
+        }
+        self.options.cancel.load(Ordering::Relaxed)
+            || self.options.max_files.is_some_and(|max| found > max)
     }
+}
 
-    (entries, skip_reasons)
+/// Walk `folder` on the calling thread, in directory order.
+///
+/// Returns `(entries, skip_reasons, dirs_scanned)`.
+fn walk_serial(
+    folder: &Path,
+    max_depth: usize,
+    deadline: Option<Instant>,
+    filter: &FileFilter,
+    progress: &dyn ProgressSink,
+) -> (Vec<FileEntry>, ScanSkipReasons, usize) {
+    let skip_trash = filter.options.skip_trash_dirs;
+    let walker = WalkDir::new(folder)
+        .max_depth(max_depth)
+        .follow_links(filter.options.follow_symlinks)
+        .into_iter()
+        .filter_entry(move |e| {
+            !(skip_trash && e.depth() > 0 && e.file_type().is_dir() && is_trash_dir(e.path()))
+        });
+
+    let mut entries = Vec::new();
+    let mut skip_reasons = ScanSkipReasons::default();
+    let mut dirs_scanned = 0usize;
+
+    for result in walker {
+        if filter.should_stop(deadline, entries.len()) {
+            break;
+        }
+
+        let dir_entry = match result {
+            Ok(e) => e,
+            Err(err) => {
+                count_walkdir_skip_reason(&mut skip_reasons, &err);
+                continue;
+            }
+        };
+
+        if dir_entry.file_type().is_dir() {
+            dirs_scanned += 1;
+            if dirs_scanned.is_multiple_of(100) {
+                progress.on_scan(entries.len(), dirs_scanned);
+            }
+            continue;
+        }
+        if !dir_entry.file_type().is_file() {
+            continue;
+        }
+
+        filter.collect(dir_entry.path(), &mut entries, &mut skip_reasons);
+
+        if entries.len() % 100 == 0 {
+            progress.on_scan(entries.len(), dirs_scanned);
+        }
+    }
+
+    (entries, skip_reasons, dirs_scanned)
+}
+
+/// Walk `folder` with one thread per CPU, so the `stat` calls of a slow
+/// (network) filesystem overlap.
+///
+/// `progress` is called from this thread only, about every 100 ms while
+/// the workers run.  Entries are sorted by path, so the result does not
+/// depend on thread scheduling (except for which files make a `max_files`
+/// cap).  Returns `(entries, skip_reasons, dirs_scanned)`.
+fn walk_parallel(
+    folder: &Path,
+    max_depth: usize,
+    deadline: Option<Instant>,
+    filter: &FileFilter,
+    progress: &dyn ProgressSink,
+) -> (Vec<FileEntry>, ScanSkipReasons, usize) {
+    let skip_trash = filter.options.skip_trash_dirs;
+    let walker = ignore::WalkBuilder::new(folder)
+        .standard_filters(false)
+        .max_depth(Some(max_depth))
+        .follow_links(filter.options.follow_symlinks)
+        .filter_entry(move |e| {
+            !(skip_trash
+                && e.depth() > 0
+                && e.file_type().is_some_and(|t| t.is_dir())
+                && is_trash_dir(e.path()))
+        })
+        .build_parallel();
+
+    let entries: Mutex<Vec<FileEntry>> = Mutex::new(Vec::new());
+    let skip_reasons: Mutex<ScanSkipReasons> = Mutex::new(ScanSkipReasons::default());
+    let files_found = AtomicUsize::new(0);
+    let dirs_scanned = AtomicUsize::new(0);
+
+    std::thread::scope(|scope| {
+        let (done_tx, done_rx) = mpsc::channel::<()>();
+        scope.spawn(|| {
+            // Dropped when the walk ends, which wakes up the loop below.
+            let _done = done_tx;
+            walker.run(|| {
+                Box::new(|result| {
+                    if filter.should_stop(deadline, files_found.load(Ordering::Relaxed)) {
+                        return ignore::WalkState::Quit;
+                    }
+                    let dir_entry = match result {
+                        Ok(e) => e,
+                        Err(err) => {
+                            let mut reasons =
+                                skip_reasons.lock().unwrap_or_else(|e| e.into_inner());
+                            match err.io_error() {
+                                Some(io_err) => count_io_skip_reason(&mut reasons, io_err),
+                                None => reasons.transient_io += 1,
+                            }
+                            return ignore::WalkState::Continue;
+                        }
+                    };
+                    match dir_entry.file_type() {
+                        Some(t) if t.is_dir() => {
+                            dirs_scanned.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Some(t) if t.is_file() => {
+                            let mut found = Vec::new();
+                            let mut reasons = ScanSkipReasons::default();
+                            filter.collect(dir_entry.path(), &mut found, &mut reasons);
+                            if reasons.total() + reasons.recently_modified > 0 {
+                                let mut all =
+                                    skip_reasons.lock().unwrap_or_else(|e| e.into_inner());
+                                all.permissions += reasons.permissions;
+                                all.missing += reasons.missing;
+                                all.transient_io += reasons.transient_io;
+                                all.recently_modified += reasons.recently_modified;
+                            }
+                            if !found.is_empty() {
+                                let mut all = entries.lock().unwrap_or_else(|e| e.into_inner());
+                                all.append(&mut found);
+                                files_found.store(all.len(), Ordering::Relaxed);
+                            }
+                        }
+                        _ => {}
+                    }
+                    ignore::WalkState::Continue
+                })
+            });
+        });
+        while let Err(mpsc::RecvTimeoutError::Timeout) =
+            done_rx.recv_timeout(Duration::from_millis(100))
+        {
+            progress.on_scan(
+                files_found.load(Ordering::Relaxed),
+                dirs_scanned.load(Ordering::Relaxed),
+            );
+        }
+    });
+
+    let mut entries = entries.into_inner().unwrap_or_else(|e| e.into_inner());
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    (
+        entries,
+        skip_reasons.into_inner().unwrap_or_else(|e| e.into_inner()),
+        dirs_scanned.into_inner(),
+    )
+}
+
+/// Keep only entries whose sniffed MIME type matches one of `patterns`:
+/// an exact type (`image/png`) or a whole family (`image/*`), compared
+/// case-insensitively.  Extensions are ignored, so a misnamed file is
+/// judged by its content.
+///
+/// Reads the start of every file, so callers should only run it on
+/// request.  `progress` hears `on_mime(checked, total)` every 100 files and once
+/// at the end.
+pub fn filter_by_mime(
+    entries: Vec<FileEntry>,
+    patterns: &[String],
+    progress: &dyn ProgressSink,
+) -> Vec<FileEntry> {
+    let total = entries.len();
+    let kept = entries
+        .into_iter()
+        .enumerate()
+        .filter(|(index, entry)| {
+            if index.is_multiple_of(100) {
+                progress.on_mime(*index, total);
+            }
+            let mime = crate::grouper::detect_mime_type(&entry.path);
+            patterns.iter().any(|pattern| mime_matches(&mime, pattern))
+        })
+        .map(|(_, entry)| entry)
+        .collect();
+    progress.on_mime(total, total);
+    kept
+}
+
+/// True when `mime` is `pattern`, or in its family for a `type/*` pattern.
+fn mime_matches(mime: &str, pattern: &str) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    match pattern.strip_suffix("/*") {
+        Some(family) => mime
+            .split_once('/')
+            .is_some_and(|(kind, _)| kind.eq_ignore_ascii_case(family)),
+        None => mime.eq_ignore_ascii_case(&pattern),
+    }
+}
+
+/// Probe `folder` without hashing: does it exist, can it be listed, and how
+/// many files does it hold (recursively)?
+///
+/// Counting stops after `max_entries` walked entries or `budget`, whichever
+/// comes first, and sets `count_truncated`.
+pub fn precheck_folder(folder: &Path, max_entries: usize, budget: Duration) -> FolderPrecheck {
+    let exists = folder.is_dir();
+    let readable = exists && std::fs::read_dir(folder).is_ok();
+    let mut precheck = FolderPrecheck {
+        exists,
+        readable,
+        approx_file_count: 0,
+        count_truncated: false,
+        is_network_drive: exists && is_network_path(folder),
+    };
+    if !readable {
+        return precheck;
+    }
+
+    let start = Instant::now();
+    for (visited, entry) in WalkDir::new(folder).min_depth(1).into_iter().enumerate() {
+        if visited >= max_entries || start.elapsed() >= budget {
+            precheck.count_truncated = true;
+            break;
+        }
+        if entry.is_ok_and(|e| e.file_type().is_file()) {
+            precheck.approx_file_count += 1;
+        }
+    }
+    precheck
+}
+
+/// Guess how long a scan of `folder` would take, from a sample bounded
+/// like [`precheck_folder`].
+///
+/// Every file in the `days_back` window costs a listing step.  With
+/// `use_hash`, its bytes are hashed too, up to `hash_max_bytes` per file
+/// (larger files are skipped by the scan).  Hashing is assumed for every
+/// file, although the scan only hashes files that share a size, so the
+/// figure leans high.
+pub fn estimate_scan_time(
+    folder: &Path,
+    days_back: u32,
+    use_hash: bool,
+    hash_max_bytes: Option<u64>,
+    max_depth: Option<usize>,
+    throughput: &ScanThroughput,
+) -> ScanTimeEstimate {
+    let cutoff = (days_back > 0)
+        .then(|| SystemTime::now() - Duration::from_secs(u64::from(days_back) * 86_400));
+    let walker = WalkDir::new(folder)
+        .min_depth(1)
+        .max_depth(max_depth.unwrap_or(usize::MAX).max(1));
+
+    let start = Instant::now();
+    let mut estimate = ScanTimeEstimate {
+        seconds: 0.0,
+        sampled_files: 0,
+        sample_truncated: false,
+    };
+    let mut hashed_bytes: u64 = 0;
+    for (visited, entry) in walker.into_iter().enumerate() {
+        if visited >= PRECHECK_MAX_ENTRIES || start.elapsed() >= PRECHECK_TIME_BUDGET {
+            estimate.sample_truncated = true;
+            break;
+        }
+        let Ok(entry) = entry else { continue };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(meta) = entry.metadata() else { continue };
+        if cutoff.is_some_and(|c| meta.modified().is_ok_and(|m| m < c)) {
+            continue;
+        }
+        estimate.sampled_files += 1;
+        if use_hash && hash_max_bytes.is_none_or(|max| meta.len() <= max) {
+            hashed_bytes += meta.len();
+        }
+    }
+
+    estimate.seconds = estimate.sampled_files as f64 / throughput.files_per_sec
+        + hashed_bytes as f64 / throughput.hash_bytes_per_sec;
+    estimate
+}
+
+/// UNC paths (`\\server\share`) are network locations.  Mapped drive
+/// letters are not detected.
+#[cfg(windows)]
+pub(crate) fn is_network_path(path: &Path) -> bool {
+    use std::path::{Component, Prefix};
+    matches!(
+        path.components().next(),
+        Some(Component::Prefix(p))
+            if matches!(p.kind(), Prefix::UNC(..) | Prefix::VerbatimUNC(..))
+    )
+}
+
+/// Look up the filesystem type of the longest mount point containing `path`
+/// in `/proc/mounts`.
+#[cfg(target_os = "linux")]
+pub(crate) fn is_network_path(path: &Path) -> bool {
+    const NETWORK_FS: &[&str] = &[
+        "nfs",
+        "nfs4",
+        "cifs",
+        "smb3",
+        "smbfs",
+        "afs",
+        "9p",
+        "fuse.sshfs",
+        "davfs",
+    ];
+    let Ok(path) = std::fs::canonicalize(path) else {
+        return false;
+    };
+    let Ok(mounts) = std::fs::read_to_string("/proc/mounts") else {
+        return false;
+    };
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount_point = fields.next()?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            Some((mount_point, fs_type))
+        })
+        .filter(|(mount_point, _)| path.starts_with(mount_point))
+        .max_by_key(|(mount_point, _)| mount_point.len())
+        .is_some_and(|(_, fs_type)| NETWORK_FS.contains(&fs_type))
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+pub(crate) fn is_network_path(_path: &Path) -> bool {
+    false
+}
+
+/// True when the file name starts with `.` (Unix hidden-file convention).
+fn is_dotfile(path: &Path) -> bool {
+    path.file_name()
+        .map(|n| n.to_string_lossy().starts_with('.'))
+        .unwrap_or(false)
+}
+
+/// True when `dir` is a trash / recycle bin folder of any platform.
+///
+/// All platforms' names are checked everywhere, since a removable drive
+/// carries the trash folders of whichever system used it: `$RECYCLE.BIN`,
+/// `RECYCLER` and `RECYCLED` (Windows), `.Trashes` and `.Trash` (macOS),
+/// `.Trash-<uid>` and the home trash `~/.local/share/Trash` (freedesktop).
+/// `RECYCLER` and `RECYCLED` are common words, so they only count at the
+/// root of a volume, where Windows puts them.
+fn is_trash_dir(dir: &Path) -> bool {
+    let name = match dir.file_name() {
+        Some(name) => name.to_string_lossy().to_lowercase(),
+        None => return false,
+    };
+    let home_trash = name == "trash" && dir.parent().is_some_and(|p| p.ends_with(".local/share"));
+    let per_user_trash = name
+        .strip_prefix(".trash-")
+        .is_some_and(|uid| !uid.is_empty() && uid.bytes().all(|b| b.is_ascii_digit()));
+    let volume_recycler = matches!(name.as_str(), "recycler" | "recycled")
+        && dir.parent().is_some_and(is_volume_root);
+    matches!(name.as_str(), "$recycle.bin" | ".trashes" | ".trash")
+        || volume_recycler
+        || home_trash
+        || per_user_trash
+}
+
+/// True when `dir` is the root of a drive or of a mounted volume.
+fn is_volume_root(dir: &Path) -> bool {
+    match dir.parent() {
+        None => true,
+        #[cfg(unix)]
+        Some(parent) => {
+            use std::os::unix::fs::MetadataExt;
+            match (std::fs::metadata(dir), std::fs::metadata(parent)) {
+                (Ok(own), Ok(above)) => own.dev() != above.dev(),
+                _ => false,
+            }
+        }
+        #[cfg(not(unix))]
+        Some(_) => false,
+    }
+}
+
+/// True when the file carries the Windows hidden attribute.
+#[cfg(windows)]
+fn has_hidden_attribute(meta: &std::fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+    meta.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0
+}
+
+#[cfg(not(windows))]
+fn has_hidden_attribute(_meta: &std::fs::Metadata) -> bool {
+    false
 }
 
 fn count_walkdir_skip_reason(skip_reasons: &mut ScanSkipReasons, err: &walkdir::Error) {
@@ -140,6 +733,7 @@ fn count_io_skip_reason(skip_reasons: &mut ScanSkipReasons, err: &std::io::Error
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::progress::NoProgress;
     use std::fs;
     use tempfile::tempdir;
 
@@ -151,6 +745,78 @@ mod tests {
         assert_eq!(safe_path_size(&f), 5);
     }
 
+    #[test]
+    fn test_total_size_ignores_missing_paths() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.bin");
+        let b = dir.path().join("b.bin");
+        fs::write(&a, vec![0u8; 100]).unwrap();
+        fs::write(&b, vec![0u8; 23]).unwrap();
+        let paths = vec![a, dir.path().join("gone.bin"), b, dir.path().to_path_buf()];
+        assert_eq!(total_size(&paths), 123);
+    }
+
+    #[test]
+    fn test_precheck_missing_folder() {
+        let dir = tempdir().unwrap();
+        let p = precheck_folder(&dir.path().join("nope"), 100, PRECHECK_TIME_BUDGET);
+        assert!(!p.exists);
+        assert!(!p.readable);
+        assert_eq!(p.approx_file_count, 0);
+        assert!(!p.is_network_drive);
+    }
+
+    #[test]
+    fn test_precheck_readable_folder_counts_files_recursively() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), b"a").unwrap();
+        fs::write(dir.path().join("b.txt"), b"b").unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("sub").join("c.txt"), b"c").unwrap();
+
+        let p = precheck_folder(dir.path(), PRECHECK_MAX_ENTRIES, PRECHECK_TIME_BUDGET);
+        assert!(p.exists);
+        assert!(p.readable);
+        assert_eq!(p.approx_file_count, 3);
+        assert!(!p.count_truncated);
+    }
+
+    #[test]
+    fn test_precheck_stops_at_entry_limit() {
+        let dir = tempdir().unwrap();
+        for i in 0..10 {
+            fs::write(dir.path().join(format!("{}.txt", i)), b"x").unwrap();
+        }
+        let p = precheck_folder(dir.path(), 4, PRECHECK_TIME_BUDGET);
+        assert_eq!(p.approx_file_count, 4);
+        assert!(p.count_truncated);
+    }
+
+    #[test]
+    fn test_size_histogram_buckets() {
+        let entry = |size: u64| FileEntry {
+            path: PathBuf::from("f"),
+            size,
+            mtime: 0.0,
+            created: None,
+        };
+        let entries: Vec<FileEntry> = [0, 1023, 1024, 5000, 200 << 10, 3 << 20, 1 << 30, 5 << 30]
+            .into_iter()
+            .map(entry)
+            .collect();
+
+        let histogram = size_histogram(&entries);
+        assert_eq!(histogram.len(), 8);
+        let counts: Vec<usize> = histogram.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![2, 2, 0, 1, 1, 0, 0, 2]);
+        assert_eq!(histogram[0].total_bytes, 1023);
+        assert_eq!(histogram[1].total_bytes, 1024 + 5000);
+        assert_eq!(histogram[7].label, ">1 GB");
+        assert_eq!(histogram[7].min_bytes, 1 << 30);
+        assert_eq!(histogram[7].max_bytes, None);
+        assert_eq!(histogram[7].total_bytes, 6 << 30);
+    }
+
     #[test]
     fn test_safe_path_size_missing_file() {
         let dir = tempdir().unwrap();
@@ -164,7 +830,14 @@ mod tests {
         fs::write(dir.path().join("a.txt"), "a").unwrap();
         fs::write(dir.path().join("b.txt"), "b").unwrap();
 
-        let (entries, skipped) = gather_recent_files(dir.path(), 0, None, true, None);
+        let (entries, skipped) = gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &ScanOptions::default(),
+            &NoProgress,
+        );
         assert_eq!(entries.len(), 2);
         assert_eq!(skipped.total(), 0);
     }
@@ -188,7 +861,14 @@ mod tests {
         let recent = dir.path().join("recent.txt");
         fs::write(&recent, "new").unwrap();
 
-        let (entries, _) = gather_recent_files(dir.path(), 7, None, true, None);
+        let (entries, _) = gather_recent_files(
+            dir.path(),
+            7,
+            None,
+            None,
+            &ScanOptions::default(),
+            &NoProgress,
+        );
         let names: Vec<String> = entries
             .iter()
             .map(|e| e.path.file_name().unwrap().to_string_lossy().to_string())
@@ -203,7 +883,14 @@ mod tests {
         fs::write(dir.path().join("report_jan.txt"), "a").unwrap();
         fs::write(dir.path().join("notes.txt"), "b").unwrap();
 
-        let (entries, _) = gather_recent_files(dir.path(), 0, Some("report"), true, None);
+        let (entries, _) = gather_recent_files(
+            dir.path(),
+            0,
+            Some("report"),
+            None,
+            &ScanOptions::default(),
+            &NoProgress,
+        );
         assert_eq!(entries.len(), 1);
         assert!(entries[0].path.file_name().unwrap().to_str().unwrap() == "report_jan.txt");
     }
@@ -216,7 +903,14 @@ mod tests {
         fs::write(sub.join("deep.txt"), "deep").unwrap();
         fs::write(dir.path().join("top.txt"), "top").unwrap();
 
-        let (entries, _) = gather_recent_files(dir.path(), 0, None, true, None);
+        let (entries, _) = gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &ScanOptions::default(),
+            &NoProgress,
+        );
         assert_eq!(entries.len(), 2);
     }
 
@@ -228,18 +922,151 @@ mod tests {
         fs::write(sub.join("deep.txt"), "deep").unwrap();
         fs::write(dir.path().join("top.txt"), "top").unwrap();
 
-        let (entries, _) = gather_recent_files(dir.path(), 0, None, false, None);
+        let (entries, _) = gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            Some(1),
+            &ScanOptions::default(),
+            &NoProgress,
+        );
         assert_eq!(entries.len(), 1);
         assert!(entries[0].path.file_name().unwrap().to_str().unwrap() == "top.txt");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_follow_symlinks_collects_linked_files() {
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("target.txt");
+        fs::write(&target, "data").unwrap();
+        std::os::unix::fs::symlink(&target, dir.path().join("link.txt")).unwrap();
+
+        let (entries, _) = gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &ScanOptions::default(),
+            &NoProgress,
+        );
+        assert_eq!(entries.len(), 1);
+
+        let options = ScanOptions {
+            follow_symlinks: true,
+            ..Default::default()
+        };
+        let (entries, _) = gather_recent_files(dir.path(), 0, None, None, &options, &NoProgress);
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().any(|e| e.path.ends_with("link.txt")));
+    }
+
+    /// `top.txt`, `a/mid.txt`, `a/b/deep.txt`.
+    fn three_level_fixture() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        let deep = dir.path().join("a").join("b");
+        fs::create_dir_all(&deep).unwrap();
+        fs::write(dir.path().join("top.txt"), "top").unwrap();
+        fs::write(dir.path().join("a").join("mid.txt"), "mid").unwrap();
+        fs::write(deep.join("deep.txt"), "deep").unwrap();
+        dir
+    }
+
+    /// Remembers the last `on_scan` / `on_mime` update.
+    #[derive(Default)]
+    struct LastProgress {
+        scan: std::cell::Cell<(usize, usize)>,
+        mime: std::cell::Cell<(usize, usize)>,
+    }
+
+    impl ProgressSink for LastProgress {
+        fn on_scan(&self, files: usize, dirs: usize) {
+            self.scan.set((files, dirs));
+        }
+        fn on_mime(&self, checked: usize, total: usize) {
+            self.mime.set((checked, total));
+        }
+    }
+
+    #[test]
+    fn test_progress_reports_directories_walked() {
+        let dir = three_level_fixture();
+        fs::create_dir(dir.path().join("empty")).unwrap();
+        let progress = LastProgress::default();
+
+        gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &ScanOptions::default(),
+            &progress,
+        );
+        // Three files; root, `a`, `a/b` and `empty`.
+        assert_eq!(progress.scan.get(), (3, 4));
+    }
+
+    fn names_at_depth(dir: &Path, max_depth: Option<usize>) -> Vec<String> {
+        let (entries, _) = gather_recent_files(
+            dir,
+            0,
+            None,
+            max_depth,
+            &ScanOptions::default(),
+            &NoProgress,
+        );
+        let mut names: Vec<String> = entries
+            .iter()
+            .map(|e| e.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_max_depth_one_scans_top_level_only() {
+        let dir = three_level_fixture();
+        assert_eq!(names_at_depth(dir.path(), Some(1)), vec!["top.txt"]);
+    }
+
+    #[test]
+    fn test_max_depth_two_includes_first_subfolder() {
+        let dir = three_level_fixture();
+        assert_eq!(
+            names_at_depth(dir.path(), Some(2)),
+            vec!["mid.txt", "top.txt"]
+        );
+    }
+
+    #[test]
+    fn test_max_depth_unlimited_walks_whole_tree() {
+        let dir = three_level_fixture();
+        assert_eq!(
+            names_at_depth(dir.path(), None),
+            vec!["deep.txt", "mid.txt", "top.txt"]
+        );
+    }
+
+    #[test]
+    fn test_depth_for_subfolders_shim() {
+        assert_eq!(depth_for_subfolders(true), None);
+        assert_eq!(depth_for_subfolders(false), Some(1));
+    }
+
     #[test]
     fn test_skips_directories() {
         let dir = tempdir().unwrap();
         fs::create_dir(dir.path().join("subdir")).unwrap();
         fs::write(dir.path().join("file.txt"), "x").unwrap();
 
-        let (entries, _) = gather_recent_files(dir.path(), 0, None, true, None);
+        let (entries, _) = gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &ScanOptions::default(),
+            &NoProgress,
+        );
         assert_eq!(entries.len(), 1);
         assert!(entries[0].path.file_name().unwrap().to_str().unwrap() == "file.txt");
     }
@@ -247,7 +1074,14 @@ mod tests {
     #[test]
     fn test_empty_folder() {
         let dir = tempdir().unwrap();
-        let (entries, skipped) = gather_recent_files(dir.path(), 0, None, true, None);
+        let (entries, skipped) = gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &ScanOptions::default(),
+            &NoProgress,
+        );
         assert!(entries.is_empty());
         assert_eq!(skipped.total(), 0);
     }
@@ -281,4 +1115,356 @@ mod tests {
         assert_eq!(reasons.missing, 0);
         assert_eq!(reasons.transient_io, 1);
     }
+
+    #[test]
+    fn test_trash_dirs_are_pruned_unless_disabled() {
+        let dir = tempdir().unwrap();
+        let trash = dir.path().join(".Trash");
+        fs::create_dir_all(trash.join("nested")).unwrap();
+        fs::write(trash.join("deleted.jpg"), "d").unwrap();
+        fs::write(trash.join("nested").join("deleted2.jpg"), "d").unwrap();
+        fs::create_dir(dir.path().join("$RECYCLE.BIN")).unwrap();
+        fs::write(dir.path().join("$RECYCLE.BIN").join("$R1.jpg"), "d").unwrap();
+        fs::write(dir.path().join("kept.jpg"), "k").unwrap();
+        fs::create_dir(dir.path().join("Trash can photos")).unwrap();
+        fs::write(dir.path().join("Trash can photos").join("bin.jpg"), "k").unwrap();
+        // Only a volume's own RECYCLED folder is a recycle bin.
+        fs::create_dir(dir.path().join("Recycled")).unwrap();
+        fs::write(dir.path().join("Recycled").join("can.jpg"), "k").unwrap();
+
+        for parallel_walk in [false, true] {
+            let options = ScanOptions {
+                parallel_walk,
+                ..ScanOptions::default()
+            };
+            let (entries, _) =
+                gather_recent_files(dir.path(), 0, None, None, &options, &NoProgress);
+            let mut names: Vec<String> = entries
+                .iter()
+                .map(|e| e.path.file_name().unwrap().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            assert_eq!(names, ["bin.jpg", "can.jpg", "kept.jpg"]);
+        }
+
+        let options = ScanOptions {
+            skip_trash_dirs: false,
+            ..ScanOptions::default()
+        };
+        let (entries, _) = gather_recent_files(dir.path(), 0, None, None, &options, &NoProgress);
+        assert_eq!(entries.len(), 6);
+
+        // Scanning the trash itself still lists it.
+        let (entries, _) =
+            gather_recent_files(&trash, 0, None, None, &ScanOptions::default(), &NoProgress);
+        assert_eq!(entries.len(), 2);
+
+        assert!(is_trash_dir(Path::new("/RECYCLER")));
+    }
+
+    #[test]
+    fn test_skip_hidden_excludes_dotfiles() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(".hidden.jpg"), "h").unwrap();
+        fs::write(dir.path().join("visible.jpg"), "v").unwrap();
+
+        let (all, _) = gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &ScanOptions::default(),
+            &NoProgress,
+        );
+        assert_eq!(all.len(), 2);
+
+        let options = ScanOptions {
+            skip_hidden: true,
+            ..Default::default()
+        };
+        let (entries, _) = gather_recent_files(dir.path(), 0, None, None, &options, &NoProgress);
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].path.file_name().unwrap().to_str().unwrap() == "visible.jpg");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_skip_hidden_excludes_hidden_attribute() {
+        let dir = tempdir().unwrap();
+        let hidden = dir.path().join("hidden.jpg");
+        fs::write(&hidden, "h").unwrap();
+        fs::write(dir.path().join("visible.jpg"), "v").unwrap();
+        let status = std::process::Command::new("attrib")
+            .arg("+h")
+            .arg(&hidden)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let options = ScanOptions {
+            skip_hidden: true,
+            ..Default::default()
+        };
+        let (entries, _) = gather_recent_files(dir.path(), 0, None, None, &options, &NoProgress);
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].path.file_name().unwrap().to_str().unwrap() == "visible.jpg");
+    }
+
+    /// Write `name` with an mtime of the given local date and hour.
+    fn write_with_local_mtime(dir: &Path, name: &str, ymd: (i32, u32, u32), hour: u32) {
+        let path = dir.join(name);
+        fs::write(&path, name).unwrap();
+        let local = chrono::Local
+            .with_ymd_and_hms(ymd.0, ymd.1, ymd.2, hour, 0, 0)
+            .earliest()
+            .unwrap();
+        filetime::set_file_mtime(
+            &path,
+            filetime::FileTime::from_unix_time(local.timestamp(), 0),
+        )
+        .unwrap();
+    }
+
+    fn gathered_names(folder: &Path, filter: MtimeFilter) -> Vec<String> {
+        let options = ScanOptions {
+            mtime_filter: Some(filter),
+            ..Default::default()
+        };
+        let (entries, _) = gather_recent_files(folder, 0, None, None, &options, &NoProgress);
+        let mut names: Vec<String> = entries
+            .iter()
+            .map(|e| e.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_mtime_filter_keeps_weekdays_only() {
+        let dir = tempdir().unwrap();
+        // 2024-01-01 was a Monday.
+        write_with_local_mtime(dir.path(), "mon.txt", (2024, 1, 1), 12);
+        write_with_local_mtime(dir.path(), "wed.txt", (2024, 1, 3), 12);
+        write_with_local_mtime(dir.path(), "fri.txt", (2024, 1, 5), 12);
+        write_with_local_mtime(dir.path(), "sat.txt", (2024, 1, 6), 12);
+        write_with_local_mtime(dir.path(), "sun.txt", (2024, 1, 7), 12);
+
+        let filter = MtimeFilter {
+            weekdays: vec![0, 1, 2, 3, 4],
+            hours: None,
+        };
+        assert_eq!(
+            gathered_names(dir.path(), filter),
+            vec!["fri.txt", "mon.txt", "wed.txt"]
+        );
+    }
+
+    #[test]
+    fn test_mtime_filter_hour_window_wraps_midnight() {
+        let dir = tempdir().unwrap();
+        write_with_local_mtime(dir.path(), "early.txt", (2024, 1, 2), 3);
+        write_with_local_mtime(dir.path(), "noon.txt", (2024, 1, 2), 12);
+        write_with_local_mtime(dir.path(), "late.txt", (2024, 1, 2), 22);
+
+        let filter = MtimeFilter {
+            weekdays: Vec::new(),
+            hours: Some((20, 6)),
+        };
+        assert_eq!(
+            gathered_names(dir.path(), filter),
+            vec!["early.txt", "late.txt"]
+        );
+
+        let filter = MtimeFilter {
+            weekdays: Vec::new(),
+            hours: Some((9, 17)),
+        };
+        assert_eq!(gathered_names(dir.path(), filter), vec!["noon.txt"]);
+    }
+
+    #[test]
+    fn test_mime_filter_keeps_misnamed_png() {
+        let dir = tempdir().unwrap();
+        image::RgbImage::from_pixel(2, 2, image::Rgb([0; 3]))
+            .save_with_format(dir.path().join("photo.dat"), image::ImageFormat::Png)
+            .unwrap();
+        fs::write(dir.path().join("notes.png"), "not really a png").unwrap();
+        let (entries, _) = gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &ScanOptions::default(),
+            &NoProgress,
+        );
+
+        let progress = LastProgress::default();
+        let kept = filter_by_mime(entries, &["IMAGE/*".to_string()], &progress);
+
+        let names: Vec<_> = kept
+            .iter()
+            .map(|e| e.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["photo.dat"]);
+        assert_eq!(progress.mime.get(), (2, 2));
+    }
+
+    #[test]
+    fn test_mime_matches_exact_and_family() {
+        assert!(mime_matches("image/png", "image/png"));
+        assert!(mime_matches("image/png", "image/*"));
+        assert!(!mime_matches("image/png", "video/*"));
+        assert!(!mime_matches("unknown", "image/*"));
+    }
+
+    #[test]
+    fn test_timeout_stops_walk_early() {
+        let dir = tempdir().unwrap();
+        for i in 0..250 {
+            fs::write(dir.path().join(format!("f{i}.txt")), "x").unwrap();
+        }
+        let options = ScanOptions {
+            timeout: Some(Duration::from_millis(10)),
+            ..Default::default()
+        };
+        // Progress fires every 100 files; stalling there blows the budget.
+        struct Slow;
+        impl ProgressSink for Slow {
+            fn on_scan(&self, _files: usize, _dirs: usize) {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        }
+        let (entries, _) = gather_recent_files(dir.path(), 0, None, None, &options, &Slow);
+        assert!(entries.len() < 250, "walk kept going: {}", entries.len());
+        assert!(options.cancel.load(Ordering::Relaxed));
+
+        let (entries, _) = gather_recent_files(
+            dir.path(),
+            0,
+            None,
+            None,
+            &ScanOptions::default(),
+            &NoProgress,
+        );
+        assert_eq!(entries.len(), 250);
+    }
+
+    #[test]
+    fn test_parallel_walk_finds_the_same_files() {
+        let dir = three_level_fixture();
+        for sub in ["x", "y", "z"] {
+            let folder = dir.path().join("a").join(sub);
+            fs::create_dir(&folder).unwrap();
+            for i in 0..40 {
+                fs::write(folder.join(format!("{i}.bin")), "x").unwrap();
+            }
+        }
+        fs::write(dir.path().join(".hidden"), "h").unwrap();
+
+        let walk = |parallel_walk, max_depth| {
+            let options = ScanOptions {
+                parallel_walk,
+                skip_hidden: true,
+                ..Default::default()
+            };
+            let progress = LastProgress::default();
+            let (entries, reasons) =
+                gather_recent_files(dir.path(), 0, None, max_depth, &options, &progress);
+            assert_eq!(reasons.total(), 0);
+            let mut paths: Vec<PathBuf> = entries.into_iter().map(|e| e.path).collect();
+            paths.sort();
+            (paths, progress.scan.get())
+        };
+
+        let (serial, serial_progress) = walk(false, None);
+        let (parallel, parallel_progress) = walk(true, None);
+        assert_eq!(serial.len(), 123);
+        assert_eq!(parallel, serial);
+        // Root, `a`, `a/b` and the three leaf folders.
+        assert_eq!(parallel_progress, serial_progress);
+        assert_eq!(parallel_progress, (123, 6));
+
+        assert_eq!(walk(true, Some(2)).0, walk(false, Some(2)).0);
+    }
+
+    #[test]
+    fn test_max_files_stops_walk_at_cap() {
+        let dir = tempdir().unwrap();
+        for i in 0..250 {
+            fs::write(dir.path().join(format!("f{i}.txt")), "x").unwrap();
+        }
+        let options = ScanOptions {
+            max_files: Some(120),
+            ..Default::default()
+        };
+        let progress = LastProgress::default();
+        let (entries, _) = gather_recent_files(dir.path(), 0, None, None, &options, &progress);
+        assert_eq!(entries.len(), 120);
+        assert_eq!(progress.scan.get().0, 120);
+        assert!(options.file_limit_reached.load(Ordering::Relaxed));
+        // The cap is not a cancellation.
+        assert!(!options.cancel.load(Ordering::Relaxed));
+
+        // A folder holding exactly the cap is scanned in full.
+        let options = ScanOptions {
+            max_files: Some(250),
+            ..Default::default()
+        };
+        let (entries, _) = gather_recent_files(dir.path(), 0, None, None, &options, &NoProgress);
+        assert_eq!(entries.len(), 250);
+        assert!(!options.file_limit_reached.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_recently_modified_files_are_skipped() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("downloading.part"), "x").unwrap();
+
+        let options = ScanOptions {
+            skip_recently_modified_secs: Some(60),
+            ..Default::default()
+        };
+        let (entries, reasons) =
+            gather_recent_files(dir.path(), 0, None, None, &options, &NoProgress);
+        assert!(entries.is_empty());
+        assert_eq!(reasons.recently_modified, 1);
+        assert_eq!(reasons.total(), 0);
+
+        let options = ScanOptions {
+            skip_recently_modified_secs: Some(0),
+            ..Default::default()
+        };
+        let (entries, reasons) =
+            gather_recent_files(dir.path(), 0, None, None, &options, &NoProgress);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(reasons.recently_modified, 0);
+    }
+
+    #[test]
+    fn test_scan_estimate_grows_with_hashed_bytes() {
+        let throughput = ScanThroughput {
+            files_per_sec: 10.0,
+            hash_bytes_per_sec: 100.0,
+        };
+        let small = tempdir().unwrap();
+        let large = tempdir().unwrap();
+        for i in 0..3 {
+            fs::write(small.path().join(format!("{i}.bin")), vec![0u8; 10]).unwrap();
+            fs::write(large.path().join(format!("{i}.bin")), vec![0u8; 1000]).unwrap();
+        }
+
+        let small_est = estimate_scan_time(small.path(), 0, true, None, None, &throughput);
+        let large_est = estimate_scan_time(large.path(), 0, true, None, None, &throughput);
+        assert_eq!(small_est.sampled_files, 3);
+        assert_eq!(large_est.sampled_files, 3);
+        assert!(!large_est.sample_truncated);
+        assert!(large_est.seconds > small_est.seconds);
+
+        // Without hashing only the listing cost remains.
+        let listing = estimate_scan_time(large.path(), 0, false, None, None, &throughput);
+        assert!((listing.seconds - 0.3).abs() < 1e-9);
+        // Files above the hash limit are not hashed.
+        let capped = estimate_scan_time(large.path(), 0, true, Some(100), None, &throughput);
+        assert!((capped.seconds - 0.3).abs() < 1e-9);
+    }
 }
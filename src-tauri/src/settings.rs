@@ -1,63 +1,169 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use directories::UserDirs;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
-/// Application settings, persisted as JSON.
+use crate::types::{
+    CollisionPolicy, CreatedFallback, CriteriaSet, FileTypePresets, MtimeFilter, NameCompare,
+    SameFolderPolicy, SymlinkHashPolicy, DEFAULT_DATE_FORMAT, DEFAULT_TIME_FORMAT,
+};
+
+/// Version written into every saved settings file.  Bump it together with a
+/// new step in [`migrate`] whenever a field changes meaning.
+pub const SETTINGS_SCHEMA_VERSION: u32 = 1;
+
+/// Application settings, persisted as JSON, TOML or YAML.
 ///
 /// Field names and defaults match the Python version for settings compatibility.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct AppSettings {
+    /// Settings schema version; files without one are version 0.
+    pub schema_version: u32,
     pub folder: String,
     pub days: u32,
     pub use_hash: bool,
     pub use_size: bool,
     pub use_name: bool,
+    pub use_name_family: bool,
     pub use_mtime: bool,
+    pub use_created: bool,
     pub use_mime: bool,
     pub use_media_meta: bool,
+    pub use_audio_fingerprint: bool,
+    pub use_dimensions: bool,
+    pub use_content_prefix: bool,
+    /// Bytes compared by `use_content_prefix`.
+    pub content_prefix_bytes: u32,
     pub hash_limit_enabled: bool,
     pub hash_max_mb: u32,
     pub fast_hash_oversized: bool,
+    /// Hash files of at least this many MB with BLAKE3; `None` always
+    /// uses SHA-256.
+    pub blake3_min_mb: Option<u32>,
     pub skip_same_folder_prompt: bool,
     pub rename_kept_enabled: bool,
     pub show_keep_full_paths: bool,
     pub include_subfolders: bool,
+    /// Explicit walk depth; `None` falls back to `include_subfolders`.
+    pub max_depth: Option<usize>,
+    pub skip_hidden: bool,
+    pub follow_symlinks: bool,
+    pub symlink_hash_policy: SymlinkHashPolicy,
+    pub mtime_filter: Option<MtimeFilter>,
+    pub same_folder_policy: SameFolderPolicy,
+    pub name_compare_mode: NameCompare,
+    /// Further criteria sets; files matching under any of them, or under
+    /// the `use_*` flags above, count as duplicates.
+    pub alternative_criteria: Vec<CriteriaSet>,
+    /// File-type presets paired with the criteria used for their files
+    /// instead of the `use_*` flags; the first matching preset wins.
+    pub type_overrides: Vec<(String, CriteriaSet)>,
+    /// Cap on duplicate groups returned per scan; `None` returns all.
+    pub max_groups: Option<usize>,
+    /// Wall-clock budget for the file walk; `None` never times out.
+    pub scan_timeout_secs: Option<u64>,
+    /// Stop the file walk after this many files; `None` walks them all.
+    pub scan_max_files: Option<usize>,
+    /// Sniffed MIME types to keep (`image/*`, ...); `None` keeps all files.
+    pub mime_filter: Option<Vec<String>>,
+    /// Skip files modified within this many seconds (still being written).
+    pub skip_recently_modified_secs: Option<u64>,
+    /// Also group the files stored inside `.zip` archives.
+    pub peek_archives: bool,
+    /// Walk folders on several threads (faster on network drives).
+    pub parallel_walk: bool,
+    /// Leave trash / recycle bin folders out of scans.
+    pub skip_trash_dirs: bool,
+    /// Ask before trashing more than this many MB at once, since a full
+    /// trash may delete them for good; `None` never asks.
+    pub trash_limit_mb: Option<u64>,
+    /// Members listed per duplicate group; `None` lists them all.
+    pub max_files_per_group: Option<usize>,
     pub name_prefix: String,
     pub recent_folders: Vec<String>,
+    /// Signatures of duplicate groups the user dismissed; scans hide them.
+    pub ignored_groups: Vec<String>,
     pub view_mode: String,
     pub auto_file_type_preset: String,
+    /// User-defined presets offered next to the built-in file types.
+    pub custom_file_type_presets: FileTypePresets,
     pub theme: String,
     /// Rename-component schema stored as opaque JSON (avoids a circular
     /// dependency between settings and types modules).
     pub rename_components: JsonValue,
     pub rename_separator: String,
+    pub rename_created_fallback: CreatedFallback,
+    pub rename_date_format: String,
+    pub rename_time_format: String,
+    /// Rename symbolic links themselves, broken ones included.
+    pub rename_symlinks: bool,
+    /// Skip files whose content already exists in their folder.
+    pub rename_skip_duplicate_content: bool,
+    /// Give extensionless files the extension of their sniffed type.
+    pub rename_infer_missing_extension: bool,
+    /// Sequence-number or replace files already holding a target name.
+    pub rename_collision_policy: CollisionPolicy,
+    /// Separator before sequence numbers; `None` uses `rename_separator`.
+    pub rename_sequence_separator: Option<String>,
+    /// Offer to open the folder of the renamed files once a batch is done.
+    pub rename_reveal_on_complete: bool,
+    /// Reject rename literals holding characters file names disallow.
+    pub rename_strict: bool,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
+            schema_version: SETTINGS_SCHEMA_VERSION,
             folder: default_downloads_folder().to_string_lossy().to_string(),
             days: 7,
             use_hash: true,
             use_size: false,
             use_name: false,
+            use_name_family: false,
             use_mtime: false,
+            use_created: false,
             use_mime: false,
             use_media_meta: false,
+            use_audio_fingerprint: false,
+            use_dimensions: false,
+            use_content_prefix: false,
+            content_prefix_bytes: 256,
             hash_limit_enabled: true,
             hash_max_mb: 500,
             fast_hash_oversized: false,
+            blake3_min_mb: None,
             skip_same_folder_prompt: true,
             rename_kept_enabled: true,
             show_keep_full_paths: false,
             include_subfolders: true,
+            max_depth: None,
+            skip_hidden: false,
+            follow_symlinks: false,
+            symlink_hash_policy: SymlinkHashPolicy::FollowTarget,
+            mtime_filter: None,
+            same_folder_policy: SameFolderPolicy::Flag,
+            name_compare_mode: NameCompare::Full,
+            alternative_criteria: Vec::new(),
+            type_overrides: Vec::new(),
+            max_groups: None,
+            scan_timeout_secs: None,
+            scan_max_files: None,
+            mime_filter: None,
+            skip_recently_modified_secs: None,
+            peek_archives: false,
+            parallel_walk: false,
+            skip_trash_dirs: true,
+            trash_limit_mb: None,
+            max_files_per_group: None,
             name_prefix: String::new(),
             recent_folders: Vec::new(),
+            ignored_groups: Vec::new(),
             view_mode: "simplified".into(),
             auto_file_type_preset: "all".into(),
+            custom_file_type_presets: FileTypePresets::new(),
             theme: "system".into(),
             rename_components: serde_json::json!([
                 { "kind": "folder_name" },
@@ -66,6 +172,16 @@ impl Default for AppSettings {
                 { "kind": "sequence", "pad_width": 3 }
             ]),
             rename_separator: "_".into(),
+            rename_created_fallback: CreatedFallback::Now,
+            rename_date_format: DEFAULT_DATE_FORMAT.into(),
+            rename_time_format: DEFAULT_TIME_FORMAT.into(),
+            rename_symlinks: false,
+            rename_skip_duplicate_content: false,
+            rename_infer_missing_extension: false,
+            rename_collision_policy: CollisionPolicy::Sequence,
+            rename_sequence_separator: None,
+            rename_reveal_on_complete: false,
+            rename_strict: false,
         }
     }
 }
@@ -81,38 +197,219 @@ pub fn default_downloads_folder() -> PathBuf {
     std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
 }
 
-/// Path to the settings JSON file.
-pub fn settings_path() -> PathBuf {
-    if let Some(proj_dirs) =
-        directories::ProjectDirs::from("com", "real-dedupe-renamer", "Real Dedupe Renamer")
-    {
-        let config_dir = proj_dirs.config_dir();
-        return config_dir.join(".duplicate_cleaner_settings.json");
+/// On-disk serialization of the settings file, chosen by file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl SettingsFormat {
+    /// `.toml` → TOML, `.yaml` / `.yml` → YAML, anything else → JSON.
+    pub fn from_path(path: &Path) -> Self {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
+        match ext.as_str() {
+            "toml" => Self::Toml,
+            "yaml" | "yml" => Self::Yaml,
+            _ => Self::Json,
+        }
     }
-    // Fallback: next to the executable.
-    PathBuf::from(".duplicate_cleaner_settings.json")
+}
+
+/// Settings file stem; the extension selects the [`SettingsFormat`].
+const SETTINGS_STEM: &str = ".duplicate_cleaner_settings";
+
+/// Extensions probed by [`settings_path`], most specific first so a
+/// hand-written TOML / YAML file wins over the JSON the app created.
+const SETTINGS_EXTENSIONS: &[&str] = &["toml", "yaml", "yml", "json"];
+
+/// Path to the settings file.
+///
+/// Uses the first existing `.duplicate_cleaner_settings.{toml,yaml,yml,json}`
+/// in the config directory, or the JSON path when none exists yet.
+pub fn settings_path() -> PathBuf {
+    resolve_settings_path(&config_dir())
+}
+
+/// The app's config directory, holding the settings and other state files.
+pub fn config_dir() -> PathBuf {
+    directories::ProjectDirs::from("com", "real-dedupe-renamer", "Real Dedupe Renamer")
+        .map(|proj_dirs| proj_dirs.config_dir().to_path_buf())
+        // Fallback: next to the executable.
+        .unwrap_or_default()
+}
+
+fn resolve_settings_path(dir: &Path) -> PathBuf {
+    SETTINGS_EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(format!("{}.{}", SETTINGS_STEM, ext)))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| dir.join(format!("{}.json", SETTINGS_STEM)))
 }
 
 /// Load settings from disk, falling back to defaults on any error.
 pub fn load_settings() -> AppSettings {
-    let path = settings_path();
-    match std::fs::read_to_string(&path) {
-        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-        Err(_) => AppSettings::default(),
-    }
+    load_settings_from(&settings_path())
 }
 
 /// Save settings to disk.
 pub fn save_settings(settings: &AppSettings) -> Result<(), String> {
-    let path = settings_path();
+    save_settings_to(settings, &settings_path())
+}
+
+/// Most entries kept in [`AppSettings::recent_folders`].
+pub const MAX_RECENT_FOLDERS: usize = 20;
+
+/// Drop recent folders that no longer exist.  Returns `true` when any entry
+/// was removed.
+pub fn prune_recent_folders(recent: &mut Vec<String>) -> bool {
+    let before = recent.len();
+    recent.retain(|folder| Path::new(folder).is_dir());
+    recent.len() != before
+}
+
+/// Put `folder` first in `recent`, dropping earlier (case-insensitive)
+/// copies and trimming the list to [`MAX_RECENT_FOLDERS`].
+pub fn push_recent_folder(recent: &mut Vec<String>, folder: &str) {
+    let lower = folder.to_lowercase();
+    recent.retain(|f| f.to_lowercase() != lower);
+    recent.insert(0, folder.to_string());
+    recent.truncate(MAX_RECENT_FOLDERS);
+}
+
+/// Load the recent folders stored at `path`, pruning stale entries and
+/// writing the pruned list back when anything was removed.
+pub fn load_recent_folders_at(path: &Path) -> Result<Vec<String>, String> {
+    let mut settings = load_settings_from(path);
+    if prune_recent_folders(&mut settings.recent_folders) {
+        save_settings_to(&settings, path)?;
+    }
+    Ok(settings.recent_folders)
+}
+
+/// Record `folder` as most recent in the settings at `path`.
+pub fn add_recent_folder_at(path: &Path, folder: &str) -> Result<Vec<String>, String> {
+    let mut settings = load_settings_from(path);
+    push_recent_folder(&mut settings.recent_folders, folder);
+    save_settings_to(&settings, path)?;
+    Ok(settings.recent_folders)
+}
+
+/// Add a group signature to the ignored groups in the settings at `path`.
+pub fn ignore_group_at(path: &Path, signature: &str) -> Result<(), String> {
+    let mut settings = load_settings_from(path);
+    if !settings.ignored_groups.iter().any(|s| s == signature) {
+        settings.ignored_groups.push(signature.to_string());
+        save_settings_to(&settings, path)?;
+    }
+    Ok(())
+}
+
+/// Forget every ignored group in the settings at `path`.  Returns how many
+/// there were.
+pub fn clear_ignored_at(path: &Path) -> Result<usize, String> {
+    let mut settings = load_settings_from(path);
+    let cleared = std::mem::take(&mut settings.ignored_groups).len();
+    if cleared > 0 {
+        save_settings_to(&settings, path)?;
+    }
+    Ok(cleared)
+}
+
+/// Values `AppSettings::theme` may take.
+pub const THEMES: [&str; 3] = ["system", "light", "dark"];
+
+/// Set the theme in the settings at `path`, keeping every other setting.
+pub fn set_theme_at(path: &Path, theme: &str) -> Result<(), String> {
+    let mut settings = load_settings_from(path);
+    settings.theme = theme.to_string();
+    save_settings_to(&settings, path)
+}
+
+/// Load settings from `path` in the format its extension implies.
+pub fn load_settings_from(path: &Path) -> AppSettings {
+    match std::fs::read_to_string(path) {
+        Ok(content) => parse_raw(&content, SettingsFormat::from_path(path))
+            .map(migrate)
+            .unwrap_or_default(),
+        Err(_) => AppSettings::default(),
+    }
+}
+
+/// Save settings to `path` in the format its extension implies.
+pub fn save_settings_to(settings: &AppSettings, path: &Path) -> Result<(), String> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
-    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+    let content = serialize_settings(settings, SettingsFormat::from_path(path))?;
+    std::fs::write(path, content).map_err(|e| e.to_string())?;
     Ok(())
 }
 
+/// Bring raw settings of any schema version up to [`SETTINGS_SCHEMA_VERSION`].
+///
+/// Files written before versioning (v0) use the same fields as v1, so they
+/// only gain the version number.  Versions newer than this build are
+/// loaded as-is (with a warning): fields this build knows are kept,
+/// everything else falls back to defaults.
+pub fn migrate(raw: JsonValue) -> AppSettings {
+    let version = raw
+        .get("schema_version")
+        .and_then(JsonValue::as_u64)
+        .unwrap_or(0);
+    if version > u64::from(SETTINGS_SCHEMA_VERSION) {
+        eprintln!(
+            "Settings schema version {} is newer than supported version {}; loading known fields only",
+            version, SETTINGS_SCHEMA_VERSION
+        );
+    }
+
+    let mut settings: AppSettings = serde_json::from_value(raw).unwrap_or_default();
+    settings.schema_version = SETTINGS_SCHEMA_VERSION;
+    settings
+}
+
+fn parse_raw(content: &str, format: SettingsFormat) -> Result<JsonValue, String> {
+    match format {
+        SettingsFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+        SettingsFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+        SettingsFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+    }
+}
+
+fn serialize_settings(settings: &AppSettings, format: SettingsFormat) -> Result<String, String> {
+    match format {
+        SettingsFormat::Json => serde_json::to_string_pretty(settings).map_err(|e| e.to_string()),
+        SettingsFormat::Toml => {
+            // TOML has no null; optional component fields are simply omitted.
+            let mut settings = settings.clone();
+            strip_nulls(&mut settings.rename_components);
+            toml::to_string_pretty(&settings).map_err(|e| e.to_string())
+        }
+        SettingsFormat::Yaml => serde_yaml::to_string(settings).map_err(|e| e.to_string()),
+    }
+}
+
+/// Remove null object members and array items, recursively.
+fn strip_nulls(value: &mut JsonValue) {
+    match value {
+        JsonValue::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        JsonValue::Array(items) => {
+            items.retain(|v| !v.is_null());
+            items.iter_mut().for_each(strip_nulls);
+        }
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,9 +441,10 @@ mod tests {
             "hash_limit_enabled": true,
             "hash_max_mb": 500
         }"#;
-        let loaded: AppSettings = serde_json::from_str(old_json).unwrap();
+        let loaded = migrate(serde_json::from_str(old_json).unwrap());
         assert!(!loaded.fast_hash_oversized); // default false
         assert!(!loaded.use_media_meta); // default false
+        assert!(!loaded.skip_hidden); // default false
         assert_eq!(loaded.days, 14);
     }
 
@@ -174,4 +472,196 @@ mod tests {
         assert_eq!(loaded.days, 30);
         assert_eq!(loaded.folder, "/tmp/test");
     }
+
+    #[test]
+    fn test_migrate_v0_config() {
+        let v0 = serde_json::json!({
+            "folder": "/tmp/old",
+            "days": 10,
+            "use_hash": false,
+            "use_size": true,
+            "name_prefix": "IMG_",
+            "recent_folders": ["/tmp/old"],
+            "view_mode": "advanced"
+        });
+        let loaded = migrate(v0);
+        assert_eq!(loaded.schema_version, SETTINGS_SCHEMA_VERSION);
+        assert_eq!(loaded.folder, "/tmp/old");
+        assert_eq!(loaded.days, 10);
+        assert!(!loaded.use_hash);
+        assert!(loaded.use_size);
+        assert_eq!(loaded.name_prefix, "IMG_");
+        assert_eq!(loaded.view_mode, "advanced");
+        // Fields unknown to v0 take their defaults.
+        assert_eq!(loaded.rename_separator, "_");
+        assert!(!loaded.skip_hidden);
+    }
+
+    #[test]
+    fn test_migrate_future_version_loads_known_fields() {
+        let future = serde_json::json!({
+            "schema_version": 99,
+            "days": 3,
+            "brand_new_setting": { "nested": true }
+        });
+        let loaded = migrate(future);
+        assert_eq!(loaded.days, 3);
+        assert_eq!(loaded.schema_version, SETTINGS_SCHEMA_VERSION);
+        assert!(loaded.use_hash);
+    }
+
+    #[test]
+    fn test_load_v0_file_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(&path, r#"{ "days": 5, "name_prefix": "DSC" }"#).unwrap();
+        let loaded = load_settings_from(&path);
+        assert_eq!(loaded.days, 5);
+        assert_eq!(loaded.name_prefix, "DSC");
+        assert_eq!(loaded.schema_version, SETTINGS_SCHEMA_VERSION);
+    }
+
+    fn sample_settings() -> AppSettings {
+        AppSettings {
+            days: 21,
+            folder: "/tmp/photos".into(),
+            use_size: true,
+            recent_folders: vec!["/tmp/a".into(), "/tmp/b".into()],
+            rename_components: serde_json::json!([
+                { "kind": "folder_name" },
+                { "kind": "literal", "value": "trip" },
+                { "kind": "sequence", "pad_width": 4 }
+            ]),
+            rename_separator: "-".into(),
+            type_overrides: vec![(
+                "images".into(),
+                CriteriaSet {
+                    use_dimensions: true,
+                    ..CriteriaSet::default()
+                },
+            )],
+            ..Default::default()
+        }
+    }
+
+    fn assert_round_trip(file_name: &str) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(file_name);
+        let s = sample_settings();
+
+        save_settings_to(&s, &path).unwrap();
+        let loaded = load_settings_from(&path);
+
+        assert_eq!(loaded.days, 21);
+        assert_eq!(loaded.folder, "/tmp/photos");
+        assert!(loaded.use_size);
+        assert_eq!(loaded.recent_folders, s.recent_folders);
+        assert_eq!(loaded.rename_components, s.rename_components);
+        assert_eq!(loaded.rename_separator, "-");
+        assert_eq!(loaded.type_overrides, s.type_overrides);
+    }
+
+    #[test]
+    fn test_settings_format_from_extension() {
+        assert_eq!(
+            SettingsFormat::from_path(Path::new("s.toml")),
+            SettingsFormat::Toml
+        );
+        assert_eq!(
+            SettingsFormat::from_path(Path::new("s.YAML")),
+            SettingsFormat::Yaml
+        );
+        assert_eq!(
+            SettingsFormat::from_path(Path::new("s.yml")),
+            SettingsFormat::Yaml
+        );
+        assert_eq!(
+            SettingsFormat::from_path(Path::new("s.json")),
+            SettingsFormat::Json
+        );
+        assert_eq!(
+            SettingsFormat::from_path(Path::new("settings")),
+            SettingsFormat::Json
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        assert_round_trip("settings.json");
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        assert_round_trip("settings.toml");
+    }
+
+    #[test]
+    fn test_yaml_round_trip() {
+        assert_round_trip("settings.yaml");
+    }
+
+    #[test]
+    fn test_recent_folders_prunes_missing_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let kept = dir.path().to_string_lossy().to_string();
+        let gone = dir.path().join("gone").to_string_lossy().to_string();
+        let s = AppSettings {
+            recent_folders: vec![gone, kept.clone()],
+            ..Default::default()
+        };
+        save_settings_to(&s, &path).unwrap();
+
+        assert_eq!(load_recent_folders_at(&path).unwrap(), vec![kept.clone()]);
+        // The pruned list is written back.
+        assert_eq!(load_settings_from(&path).recent_folders, vec![kept]);
+    }
+
+    #[test]
+    fn test_add_recent_folder_dedupes_and_caps() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        for i in 0..MAX_RECENT_FOLDERS + 5 {
+            add_recent_folder_at(&path, &format!("/tmp/folder{i}")).unwrap();
+        }
+        let recent = add_recent_folder_at(&path, "/TMP/FOLDER24").unwrap();
+        assert_eq!(recent.len(), MAX_RECENT_FOLDERS);
+        assert_eq!(recent[0], "/TMP/FOLDER24");
+        assert_eq!(recent[1], "/tmp/folder23");
+        assert!(!recent.iter().any(|f| f == "/tmp/folder24"));
+        assert_eq!(load_settings_from(&path).recent_folders, recent);
+    }
+
+    #[test]
+    fn test_toml_drops_null_component_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.toml");
+        let s = AppSettings {
+            rename_components: serde_json::json!([{ "kind": "literal", "value": null }]),
+            ..Default::default()
+        };
+
+        save_settings_to(&s, &path).unwrap();
+        let loaded = load_settings_from(&path);
+        assert_eq!(
+            loaded.rename_components,
+            serde_json::json!([{ "kind": "literal" }])
+        );
+    }
+
+    #[test]
+    fn test_resolve_settings_path_prefers_existing_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            resolve_settings_path(dir.path()),
+            dir.path().join(".duplicate_cleaner_settings.json")
+        );
+
+        std::fs::write(dir.path().join(".duplicate_cleaner_settings.json"), "{}").unwrap();
+        std::fs::write(dir.path().join(".duplicate_cleaner_settings.toml"), "").unwrap();
+        assert_eq!(
+            resolve_settings_path(dir.path()),
+            dir.path().join(".duplicate_cleaner_settings.toml")
+        );
+    }
 }
@@ -1,4 +1,8 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
@@ -8,34 +12,291 @@ pub struct FileEntry {
     pub path: PathBuf,
     pub size: u64,
     pub mtime: f64,
+    /// Unix seconds from file creation time (`None` if unavailable).
+    pub created: Option<f64>,
+}
+
+/// How the member of a duplicate group to keep is chosen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "folders", rename_all = "snake_case")]
+pub enum KeepStrategy {
+    /// Keep the most recently modified file.
+    Newest,
+    /// Keep the file under the earliest listed folder; newest on ties or
+    /// when no folder matches.
+    FolderPriority(Vec<PathBuf>),
 }
 
 /// A single criterion value used to build grouping keys.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CriterionValue {
     Hash(String),
+    /// BLAKE3 digest, used instead of [`CriterionValue::Hash`] for files at
+    /// or above [`GroupingConfig::blake3_min_bytes`].
+    Blake3(String),
     FastHash(String),
     Size(u64),
     Name(String),
+    /// Name with copy markers (` (1)`, `-copy`, ...) stripped.
+    NameFamily(String),
     Mtime(i64),
+    Created(i64),
     MimeType(String),
     MediaMeta(String),
+    /// Cluster of matching audio fingerprints; only meaningful within one scan.
+    AudioMatch(u32),
+    /// Resolved target of a symlink, standing in for its content hash.
+    LinkTarget(String),
+    /// Image width and height in pixels.
+    Dimensions(u32, u32),
+    /// The file's first bytes, hex-encoded.
+    ContentPrefix(String),
 }
 
 /// A grouping key: ordered list of criterion values.
 pub type DuplicateKey = Vec<CriterionValue>;
 
+/// User-defined file-type presets: preset name → extensions (without the
+/// dot, any case), e.g. `raw_photos: [cr2, nef, arw]`.
+pub type FileTypePresets = BTreeMap<String, Vec<String>>;
+
 /// Configuration for duplicate grouping criteria.
 #[derive(Debug, Clone)]
 pub struct GroupingConfig {
     pub use_hash: bool,
     pub use_size: bool,
     pub use_name: bool,
+    pub use_name_family: bool,
     pub use_mtime: bool,
+    pub use_created: bool,
     pub use_mime: bool,
     pub use_media_meta: bool,
+    pub use_audio_fingerprint: bool,
+    pub use_dimensions: bool,
+    pub use_content_prefix: bool,
+    /// Bytes read for [`CriterionValue::ContentPrefix`]; shorter files use
+    /// their whole content.
+    pub content_prefix_bytes: usize,
     pub hash_max_bytes: Option<u64>,
     pub fast_hash_oversized: bool,
+    /// Files at least this large are hashed with BLAKE3 instead of SHA-256.
+    ///
+    /// Identical files always have the same size, so both copies of a
+    /// duplicate fall on the same side of the threshold and get the same
+    /// kind of digest; a SHA-256 and a BLAKE3 key never need to match.
+    pub blake3_min_bytes: Option<u64>,
+    /// Text form of full-content digests.  Every file of a scan uses the
+    /// same one, so grouping does not depend on it.
+    pub digest_encoding: DigestEncoding,
+    pub symlink_hash_policy: SymlinkHashPolicy,
+    pub same_folder_policy: SameFolderPolicy,
+    pub name_compare_mode: NameCompare,
+    /// User-defined presets that file-type names below (and the audio
+    /// check) are resolved against, next to the built-in ones.
+    pub custom_file_type_presets: FileTypePresets,
+    /// Criteria used instead of the ones above for files matching a
+    /// file-type preset (`"images"`, `"documents"`, ...), checked in order;
+    /// the first matching preset wins.
+    pub type_overrides: Vec<(String, GroupingConfig)>,
+    /// Criteria sets combined with OR: files matching under this config's
+    /// own criteria or under any alternative end up in one group.  Empty
+    /// means every criterion above must match.
+    pub alternatives: Vec<GroupingConfig>,
+}
+
+impl GroupingConfig {
+    /// This config with its criteria replaced by `criteria`; hashing and
+    /// folder settings are kept, overrides and alternatives dropped.
+    pub fn with_criteria(&self, criteria: &CriteriaSet) -> GroupingConfig {
+        GroupingConfig {
+            use_hash: criteria.use_hash,
+            use_size: criteria.use_size,
+            use_name: criteria.use_name,
+            use_name_family: criteria.use_name_family,
+            use_mtime: criteria.use_mtime,
+            use_created: criteria.use_created,
+            use_mime: criteria.use_mime,
+            use_media_meta: criteria.use_media_meta,
+            use_audio_fingerprint: criteria.use_audio_fingerprint,
+            use_dimensions: criteria.use_dimensions,
+            use_content_prefix: criteria.use_content_prefix,
+            type_overrides: Vec::new(),
+            alternatives: Vec::new(),
+            ..self.clone()
+        }
+    }
+}
+
+/// The criteria flags of a [`GroupingConfig`], as sent by the frontend for
+/// alternative criteria sets and per-type overrides.  Unset flags are off.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CriteriaSet {
+    pub use_hash: bool,
+    pub use_size: bool,
+    pub use_name: bool,
+    pub use_name_family: bool,
+    pub use_mtime: bool,
+    pub use_created: bool,
+    pub use_mime: bool,
+    pub use_media_meta: bool,
+    pub use_audio_fingerprint: bool,
+    pub use_dimensions: bool,
+    pub use_content_prefix: bool,
+}
+
+/// Optional scanner behaviour beyond the recency / prefix / subfolder filters.
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Skip dotfiles and (on Windows) files with the hidden attribute.
+    pub skip_hidden: bool,
+    /// Descend into symlinked folders and collect symlinked files.
+    pub follow_symlinks: bool,
+    /// Keep only files modified on certain weekdays / hours (local time).
+    pub mtime_filter: Option<MtimeFilter>,
+    /// Wall-clock budget for the walk; once spent, `cancel` is raised.
+    pub timeout: Option<Duration>,
+    /// Stop the walk once this many files have been collected.
+    pub max_files: Option<usize>,
+    /// Raised when the walk stopped at `max_files` with more files left
+    /// to collect; a folder holding exactly that many leaves it down.
+    pub file_limit_reached: Arc<AtomicBool>,
+    /// Walk and stat on several threads (helps on network filesystems);
+    /// entries then come back sorted by path.
+    pub parallel_walk: bool,
+    /// Checked before every entry: once set, the walk stops and returns
+    /// what it has gathered so far.
+    pub cancel: Arc<AtomicBool>,
+    /// Leave out files modified less than this many seconds ago (likely
+    /// still being written, e.g. partial downloads).  `Some(0)` skips
+    /// nothing.
+    pub skip_recently_modified_secs: Option<u64>,
+    /// Also collect the files stored inside `.zip` archives, as virtual
+    /// `zip://archive!member` entries (needs the `archive-peek` feature).
+    pub peek_archives: bool,
+    /// Do not descend into trash / recycle bin folders (`$RECYCLE.BIN`,
+    /// `.Trash`, ...), whose files are already deleted.  On by default.
+    pub skip_trash_dirs: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            skip_hidden: false,
+            follow_symlinks: false,
+            mtime_filter: None,
+            timeout: None,
+            max_files: None,
+            file_limit_reached: Arc::default(),
+            parallel_walk: false,
+            cancel: Arc::default(),
+            skip_recently_modified_secs: None,
+            peek_archives: false,
+            skip_trash_dirs: true,
+        }
+    }
+}
+
+/// Day-of-week / hour-of-day predicate on a file's modification time,
+/// evaluated in local time.  Composes with the `days` cutoff.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MtimeFilter {
+    /// Days to keep, `0` = Monday … `6` = Sunday.  Empty keeps every day.
+    #[serde(default)]
+    pub weekdays: Vec<u8>,
+    /// Hour window `[start, end)` with hours `0..=24`.  Wraps past
+    /// midnight when `start > end`; `None` (or `start == end`) keeps every
+    /// hour.
+    #[serde(default)]
+    pub hours: Option<(u8, u8)>,
+}
+
+/// Digest used by single-file hash spot checks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+}
+
+/// Text form of a digest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestEncoding {
+    /// Lowercase hex, as `sha256sum` prints it.
+    #[default]
+    Hex,
+    /// Standard base64 with padding.
+    Base64,
+}
+
+/// How the grouper treats a file reached through a symbolic link when
+/// hashing is enabled.
+///
+/// Hard links are ordinary directory entries and are always hashed by
+/// content, so a hard link and its twin group like any other copy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymlinkHashPolicy {
+    /// Hash the bytes of the link target, like a regular file.
+    #[default]
+    FollowTarget,
+    /// Key the link by its resolved target path without reading it, so
+    /// links to the same file group together but never with the file.
+    LinkPath,
+}
+
+/// How the `use_name` criterion compares file names.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameCompare {
+    /// Compare the whole name, extension included.
+    #[default]
+    Full,
+    /// Compare only the stem, so `report.txt` and `report.pdf` match.
+    StemOnly,
+}
+
+/// What the grouper does with duplicates that live in the same folder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SameFolderPolicy {
+    /// Report same-folder copies like any other duplicate.
+    #[default]
+    Flag,
+    /// Treat same-folder copies (`file`, `file (1)`) as intentional: each
+    /// folder contributes one member to a group, and groups left with fewer
+    /// than two members are dropped.
+    Ignore,
+}
+
+/// Counters describing files the grouper could not key.
+#[derive(Debug, Clone, Default)]
+pub struct GroupingStats {
+    /// Files over the hash size cap (or unreadable for fast-hash).
+    pub hash_skipped: usize,
+    /// Files left out because their creation time is unavailable.
+    pub created_skipped: usize,
+    /// Files left out because no audio fingerprint could be computed.
+    pub audio_skipped: usize,
+    /// Files left out because they are not images with readable dimensions.
+    pub dimensions_skipped: usize,
+    /// Files left out because their size changed since they were gathered,
+    /// so they are probably still being written.
+    pub in_flux: usize,
+    /// Files whose content was actually hashed (full or fast).
+    pub hashed: usize,
+    /// Files whose key was reused from an earlier scan.
+    pub reused: usize,
+}
+
+/// Grouping key computed by an earlier scan, valid while size and mtime match.
+#[derive(Debug, Clone)]
+pub struct KnownKey {
+    pub size: u64,
+    pub mtime: f64,
+    pub key: DuplicateKey,
 }
 
 /// File entry DTO sent to the frontend via Tauri commands.
@@ -48,13 +309,33 @@ pub struct FileEntryDto {
     pub size_human: String,
     pub mtime: f64,
     pub mtime_formatted: String,
+    pub created: f64, // Unix seconds from file creation time (0 if unavailable)
+    pub created_formatted: String,
 }
 
 /// A group of duplicate files sent to the frontend.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DuplicateGroup {
     pub key_description: String,
+    /// How likely the group holds true duplicates, in `0.0..=1.0`; see
+    /// [`key_confidence`].
+    pub confidence: f64,
+    /// Raw grouping key, kept so a later re-scan can reuse it.
+    pub key: DuplicateKey,
     pub files: Vec<FileEntryDto>,
+    /// Path of the member that must be kept, e.g. the master copy in a
+    /// master-folder scan.  `None` leaves the choice to the user.
+    #[serde(default)]
+    pub keeper: Option<String>,
+    /// Members left out of `files` by the scan's `max_files_per_group`
+    /// cap.  Totals such as wasted bytes still count them.
+    #[serde(default)]
+    pub overflow_count: usize,
+    /// Stable identity of the group's members; see
+    /// [`crate::grouper::group_signature`].  Pass it to `cmd_ignore_group`
+    /// to hide the group from later scans.
+    #[serde(default)]
+    pub signature: String,
 }
 
 /// Buckets describing why files were skipped during scan traversal.
@@ -63,6 +344,10 @@ pub struct ScanSkipReasons {
     pub permissions: usize,
     pub missing: usize,
     pub transient_io: usize,
+    /// Files left out for being modified too recently.  A deliberate
+    /// filter rather than an error, so not part of [`Self::total`].
+    #[serde(default)]
+    pub recently_modified: usize,
 }
 
 impl ScanSkipReasons {
@@ -75,11 +360,74 @@ impl ScanSkipReasons {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanResult {
     pub groups: Vec<DuplicateGroup>,
+    /// True when `groups` was cut down to the scan's `max_groups` cap.
+    pub truncated: bool,
+    /// True when the walk hit the scan's `timeout_secs` and `groups` only
+    /// covers the files gathered before that.
+    pub timed_out: bool,
+    /// True when the walk stopped at the scan's `max_files` cap and
+    /// `groups` only covers the files gathered up to it.
+    #[serde(default)]
+    pub file_limit_reached: bool,
+    /// Number of groups found, before any cap.
+    pub total_group_count: usize,
+    /// Bytes freed by keeping one file per group, over all groups found.
+    pub total_wasted_bytes: u64,
+    /// Log-scale size histogram over every scanned file.
+    pub size_histogram: Vec<SizeBucket>,
+    /// Duplicates per lowercased extension (`(none)` for files without
+    /// one), over the groups in `groups`.
+    #[serde(default)]
+    pub by_extension: BTreeMap<String, ExtensionStats>,
     pub total_files_scanned: usize,
     pub hash_skipped: usize,
+    pub created_skipped: usize,
+    pub audio_skipped: usize,
+    pub dimensions_skipped: usize,
+    /// Files not hashed because their size changed during the scan (still
+    /// being written); they are in no group.
+    #[serde(default)]
+    pub in_flux_skipped: usize,
     pub scan_skipped: usize,
     pub scan_skip_reasons: ScanSkipReasons,
     pub elapsed_seconds: f64,
+    /// True for a result reloaded from an earlier session; the files may
+    /// have changed since, so the UI should offer a fresh scan.
+    #[serde(default)]
+    pub stale: bool,
+    /// Groups left out because their signature is in the ignored groups.
+    /// Not part of `total_group_count`.
+    #[serde(default)]
+    pub ignored_count: usize,
+    /// Id under which the full result is cached for `cmd_get_scan_page`;
+    /// 0 when it is not cached.
+    #[serde(default)]
+    pub scan_id: u64,
+}
+
+/// Duplicates sharing one extension; see `ScanResult::by_extension`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtensionStats {
+    /// Groups with at least one member of this extension.
+    pub group_count: usize,
+    /// Group members of this extension.
+    pub file_count: usize,
+    /// Bytes freed by deleting the members of this extension that are not
+    /// kept.
+    pub wasted_bytes: u64,
+}
+
+/// One bucket of the scanned-file size histogram.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SizeBucket {
+    /// Display label, e.g. "10-100 KB".
+    pub label: String,
+    /// Inclusive lower bound in bytes.
+    pub min_bytes: u64,
+    /// Exclusive upper bound in bytes; `None` for the last bucket.
+    pub max_bytes: Option<u64>,
+    pub count: usize,
+    pub total_bytes: u64,
 }
 
 /// Candidate file sent to the frontend for auto-renamer mode.
@@ -93,6 +441,8 @@ pub struct AutoRenameCandidateDto {
     pub mtime: f64,
     pub mtime_formatted: String,
     pub created: f64, // Unix seconds from file creation time (0 if unavailable)
+    /// Base-pass rename target (full path); empty when the scan had no schema.
+    pub preview_target: String,
 }
 
 /// Scan result for auto-renamer mode.
@@ -127,6 +477,261 @@ pub struct AutoRenameResult {
     pub error_count: usize,
     pub items: Vec<AutoRenameItemDto>,
     pub errors: Vec<AutoRenameErrorDto>,
+    /// The batch was cancelled; `items` lists the renames done before.
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+/// Result of sorting files into dated folders.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrganizeReport {
+    /// Files moved, with their new paths.
+    pub moved: Vec<AutoRenameItemDto>,
+    /// Files already in their dated folder.
+    pub unchanged: usize,
+    pub errors: Vec<PathErrorDto>,
+}
+
+/// A path paired with the error that prevented an operation on it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathErrorDto {
+    pub path: String,
+    pub message: String,
+}
+
+/// What changed between the duplicate groups of two scans.
+///
+/// Groups are matched by their grouping [`DuplicateKey`], so a group keeps
+/// its identity while members come and go.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanDiff {
+    /// Groups only the newer scan has.
+    pub new_groups: Vec<DuplicateGroup>,
+    /// Groups only the older scan had.
+    pub gone_groups: Vec<DuplicateGroup>,
+    /// Groups with more members than before.
+    pub grown_groups: Vec<GroupChange>,
+    /// Groups with fewer members than before.
+    pub shrunk_groups: Vec<GroupChange>,
+    /// Groups in both scans with the same member count.
+    pub unchanged_count: usize,
+}
+
+/// A group found by both scans whose member count changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupChange {
+    /// The group as the newer scan reported it.
+    pub group: DuplicateGroup,
+    /// Member count in the older scan (listed plus overflow).
+    pub previous_count: usize,
+    /// Listed paths the newer scan added / the older one had and lost.
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Byte-for-byte comparison of two files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompareResult {
+    pub identical: bool,
+    /// Offset of the first differing byte (`None` when identical or when
+    /// the sizes already differ).
+    pub first_diff_offset: Option<u64>,
+    pub size_a: u64,
+    pub size_b: u64,
+}
+
+/// Quick, hash-free probe of a folder before scanning it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderPrecheck {
+    pub exists: bool,
+    pub readable: bool,
+    /// Files seen before the probe stopped (a lower bound when
+    /// `count_truncated` is set).
+    pub approx_file_count: usize,
+    /// The probe hit its entry or time limit before finishing.
+    pub count_truncated: bool,
+    pub is_network_drive: bool,
+}
+
+/// Totals of a scan, without the groups themselves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanSummary {
+    pub group_count: usize,
+    /// Files that would go if one per group were kept.
+    pub duplicate_file_count: usize,
+    pub total_wasted_bytes: u64,
+    pub total_files_scanned: usize,
+    pub elapsed_seconds: f64,
+}
+
+/// Totals of a cached scan; see `cmd_get_scan_meta`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanMeta {
+    pub scan_id: u64,
+    /// Groups available through `cmd_get_scan_page`.
+    pub group_count: usize,
+    pub summary: ScanSummary,
+}
+
+/// Rough duration of a scan, from a bounded sample of the folder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanTimeEstimate {
+    /// Estimated scan time in seconds.  Only an estimate: real throughput
+    /// depends on the disk, caching and how many files need hashing.
+    pub seconds: f64,
+    /// Files in the `days` window seen while sampling.
+    pub sampled_files: usize,
+    /// The sample hit its entry or time limit, so `seconds` only covers
+    /// part of the folder (a lower bound).
+    pub sample_truncated: bool,
+}
+
+/// Result of a delete command.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteSummary {
+    pub deleted: usize,
+    /// Paths on volumes without a trash, left in place until the user
+    /// confirms permanent deletion.
+    pub needs_confirmation: Vec<String>,
+    /// Paths that could not be deleted (or, in a dry run, would not be).
+    #[serde(default)]
+    pub errors: Vec<PathErrorDto>,
+    /// Paths that were deleted with a caveat, e.g. permanently because the
+    /// trash failed.
+    #[serde(default)]
+    pub warnings: Vec<PathErrorDto>,
+    /// Paths moved to the trash, which `cmd_restore_from_trash` can bring
+    /// back.
+    #[serde(default)]
+    pub trashed: Vec<String>,
+    /// Paths left in place because a verified delete found they no longer
+    /// match their duplicate group.
+    #[serde(default)]
+    pub spared: Vec<PathErrorDto>,
+    /// Nothing was deleted: the files add up to more than the trash limit
+    /// and are all in `needs_confirmation`.
+    #[serde(default)]
+    pub trash_limit_exceeded: bool,
+}
+
+/// What a delete recorded in the history was part of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteMode {
+    /// Files the user picked (`cmd_delete`).
+    Manual,
+    /// Copies removed by `cmd_auto_dedupe`.
+    AutoDedupe,
+    /// Copies removed by `cmd_canonicalize_duplicates`.
+    Canonicalize,
+}
+
+/// One deleted file in the delete history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteHistoryEntry {
+    /// Unix seconds of the delete.
+    pub timestamp: f64,
+    pub path: String,
+    pub mode: DeleteMode,
+    /// Size of the file when it was deleted.
+    pub freed_bytes: u64,
+    /// Moved to the trash rather than deleted permanently.
+    pub trashed: bool,
+}
+
+/// Result of restoring files from the trash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreSummary {
+    pub restored: usize,
+    /// Paths that could not be restored.
+    pub errors: Vec<PathErrorDto>,
+}
+
+/// What auto-dedupe did with one duplicate group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoDedupeGroupReport {
+    /// The member the keep strategy picked; never touched.
+    pub kept: String,
+    /// Why the strategy picked `kept`.
+    #[serde(default)]
+    pub keep_reason: String,
+    /// Members actually removed (trashed or, if allowed, deleted).
+    pub deleted: Vec<String>,
+    /// Sum of the sizes of `deleted`.
+    pub freed_bytes: u64,
+    /// Members left in place because their volume has no trash, or all of
+    /// them when `trash_limit_exceeded`.
+    pub needs_confirmation: Vec<String>,
+    pub errors: Vec<PathErrorDto>,
+    /// Nothing was deleted: the members add up to more than the trash
+    /// limit and are all in `needs_confirmation`.
+    #[serde(default)]
+    pub trash_limit_exceeded: bool,
+}
+
+/// Per-group outcome of an auto-dedupe run, for auditing and undo.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AutoDedupeReport {
+    pub groups: Vec<AutoDedupeGroupReport>,
+    /// Sum of the groups' `freed_bytes`.
+    #[serde(default)]
+    pub freed_bytes: u64,
+    /// True for a dry run: nothing was deleted, `deleted` lists what a run
+    /// would remove and `freed_bytes` what it would reclaim.
+    #[serde(default)]
+    pub planned: bool,
+}
+
+/// Outcome of collapsing identical files into one canonically named file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanonicalizeReport {
+    /// Which member survived (under its old name) and which were removed.
+    pub group: AutoDedupeGroupReport,
+    /// New path of the survivor; `None` if it already had its canonical
+    /// name or could not be renamed.
+    pub renamed_to: Option<String>,
+    pub rename_errors: Vec<AutoRenameErrorDto>,
+}
+
+/// Result of removing empty directories under a folder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoveEmptyDirsResult {
+    pub removed_count: usize,
+    pub removed: Vec<String>,
+    pub errors: Vec<PathErrorDto>,
+}
+
+/// Result of writing a checksum manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestExport {
+    pub files_written: usize,
+    pub files_total: usize,
+    /// The export was cancelled; the manifest lists only the files hashed
+    /// before that and ends with an incomplete marker.
+    pub incomplete: bool,
+    /// Files that could not be read and are not in the manifest.
+    pub errors: Vec<PathErrorDto>,
+}
+
+/// Outcome of re-checking one manifest entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifyStatus {
+    /// Content still matches the recorded digest.
+    Ok,
+    Changed,
+    Missing,
+    /// The file exists but could not be read; see `message`.
+    Unreadable,
+}
+
+/// One line of a manifest, re-checked against the disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyEntry {
+    /// Path as written in the manifest.
+    pub path: String,
+    pub status: VerifyStatus,
+    pub message: Option<String>,
 }
 
 /// Progress event emitted during scanning / hashing phases.
@@ -136,6 +741,13 @@ pub struct ScanProgress {
     pub current: usize,
     pub total: usize,
     pub message: String,
+    /// Folders walked so far; only set during the scanning phase.
+    #[serde(default)]
+    pub dirs_scanned: Option<usize>,
+    /// Percent (0–100) of the combined MIME-check and hashing work, set
+    /// when a scan runs both so they show as one bar.
+    #[serde(default)]
+    pub percent: Option<f64>,
 }
 
 /// Schema defining how files should be renamed.
@@ -143,6 +755,146 @@ pub struct ScanProgress {
 pub struct RenameSchema {
     pub components: Vec<RenameComponentDef>,
     pub separator: String,
+    /// Separator that attaches sequence and counter components; `None`
+    /// uses `separator`.
+    #[serde(default)]
+    pub sequence_separator: Option<String>,
+    /// What date/time components use when the creation time is unavailable.
+    #[serde(default)]
+    pub created_fallback: CreatedFallback,
+    /// strftime pattern for the date components.
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    /// strftime pattern for the time components.
+    #[serde(default = "default_time_format")]
+    pub time_format: String,
+    #[serde(default)]
+    pub sanitize: SanitizeOptions,
+    /// Rename symbolic links themselves, including broken ones, instead of
+    /// skipping links whose target is missing or not a regular file.
+    #[serde(default)]
+    pub rename_symlinks: bool,
+    /// Leave a file alone when its folder already holds a file with the
+    /// same content, under any name (including one renamed earlier in the
+    /// same batch).  Counted as skipped.
+    #[serde(default)]
+    pub skip_duplicate_content: bool,
+    /// Give files without an extension the canonical one of their sniffed
+    /// type (`.png`, `.pdf`, ...).  Files of unrecognised type stay bare.
+    #[serde(default)]
+    pub infer_missing_extension: bool,
+    /// What happens when a target name is taken by a file outside the batch.
+    #[serde(default)]
+    pub collision_policy: CollisionPolicy,
+    /// Reject literal text holding characters not allowed in file names
+    /// instead of replacing them, so typos surface before any rename.
+    #[serde(default)]
+    pub strict: bool,
+    /// Process files in the order given instead of by path, for callers
+    /// that sort the batch themselves.  Sequence numbers then follow that
+    /// order.
+    #[serde(default)]
+    pub keep_input_order: bool,
+}
+
+/// No components, `_` as separator, and every other field as it
+/// deserializes when omitted.
+impl Default for RenameSchema {
+    fn default() -> Self {
+        Self {
+            components: Vec::new(),
+            separator: "_".into(),
+            sequence_separator: None,
+            created_fallback: CreatedFallback::default(),
+            date_format: default_date_format(),
+            time_format: default_time_format(),
+            sanitize: SanitizeOptions::default(),
+            rename_symlinks: false,
+            skip_duplicate_content: false,
+            infer_missing_extension: false,
+            collision_policy: CollisionPolicy::default(),
+            strict: false,
+            keep_input_order: false,
+        }
+    }
+}
+
+/// How the auto-renamer resolves a target name already taken on disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionPolicy {
+    /// Append the next free sequence number.
+    #[default]
+    Sequence,
+    /// Replace the existing file.  The replacement is atomic: the target
+    /// always holds either its old content or the complete new file.
+    Overwrite,
+}
+
+/// Date pattern used when a schema does not set `date_format`.
+pub const DEFAULT_DATE_FORMAT: &str = "%Y%m%d";
+
+/// Time pattern used when a schema does not set `time_format`.
+pub const DEFAULT_TIME_FORMAT: &str = "%H%M%S";
+
+fn default_date_format() -> String {
+    DEFAULT_DATE_FORMAT.into()
+}
+
+fn default_time_format() -> String {
+    DEFAULT_TIME_FORMAT.into()
+}
+
+/// Stand-in for the creation time when the filesystem does not report one
+/// (common on Linux).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CreatedFallback {
+    /// Use the current time.
+    #[default]
+    Now,
+    /// Use the modification time.
+    Modified,
+    /// Leave the created date/time components out of the name.
+    SkipComponent,
+}
+
+/// Which timestamp dates a file for date-based organizing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecencyBasis {
+    /// The modification time.
+    #[default]
+    Modified,
+    /// The creation time; the modification time where the filesystem
+    /// does not report one (as [`CreatedFallback::Modified`]).
+    Created,
+}
+
+/// How invalid characters in generated name parts are cleaned up.
+///
+/// Windows-reserved characters are always replaced; these options only
+/// control what they are replaced with and the post-processing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SanitizeOptions {
+    pub replacement: char,
+    pub strip_trailing_dots: bool,
+    pub collapse_repeats: bool,
+    /// Suffix reserved Windows device names (`CON`, `LPT1`, ...) with `_`.
+    /// Applied on every platform so names stay portable.
+    pub guard_reserved_names: bool,
+}
+
+impl Default for SanitizeOptions {
+    fn default() -> Self {
+        Self {
+            replacement: '_',
+            strip_trailing_dots: true,
+            collapse_repeats: false,
+            guard_reserved_names: true,
+        }
+    }
 }
 
 /// A single component in a rename schema.
@@ -157,9 +909,49 @@ pub enum RenameComponentDef {
     DateModified,
     TimeCreated,
     TimeModified,
-    Sequence { pad_width: usize },
+    Sequence {
+        pad_width: usize,
+    },
+    /// Like `Sequence`, but counts in letters: `a`..`z`, `aa`, `ab`, ...
+    /// (`A`, `B`, ... when `uppercase` is set).
+    AlphaSequence {
+        uppercase: bool,
+    },
     OriginalStem,
-    Literal { value: String },
+    /// Fixed text; `{YYYY}`, `{MM}`, `{DD}` (creation date, or the
+    /// modification date when `created_fallback` skips), `{ext}` and
+    /// `{stem}` are filled in per file.
+    Literal {
+        value: String,
+    },
+    /// `keep` words of the original stem (split on space, `_`, `-`), taken
+    /// from the end when `from_end` is set, otherwise from the start.
+    StemWords {
+        keep: usize,
+        from_end: bool,
+    },
+    /// First `chars` hex digits of the file's `algo` digest, so identical
+    /// content always gets the same name part.
+    HashFragment {
+        algo: HashAlgorithm,
+        chars: usize,
+    },
+    /// `start`, `start + step`, ... numbering the renamed files of each
+    /// folder separately, in path order, so every folder restarts at
+    /// `start`.  Unlike `Sequence`, which only appears to break a name
+    /// collision, it is part of every name.
+    FolderCounter {
+        start: u32,
+        step: u32,
+        pad_width: usize,
+    },
+    /// Field of the JSON sidecar next to the file (same stem, `extension`
+    /// instead of the file's own), as a dotted path such as `meta.title`.
+    /// Omitted when the sidecar or the field is missing.
+    SidecarField {
+        extension: String,
+        field: String,
+    },
 }
 
 /// Return a human-friendly size string (e.g. "1.00 KB").
@@ -184,6 +976,10 @@ pub fn describe_key(key: &DuplicateKey) -> String {
                 let short: String = digest.chars().take(8).collect();
                 format!("sha256 {}...", short)
             }
+            CriterionValue::Blake3(digest) => {
+                let short: String = digest.chars().take(8).collect();
+                format!("blake3 {}...", short)
+            }
             CriterionValue::FastHash(digest) => {
                 let short: String = digest.chars().take(8).collect();
                 format!("fast-hash {}...", short)
@@ -194,23 +990,83 @@ pub fn describe_key(key: &DuplicateKey) -> String {
             CriterionValue::Name(name) => {
                 format!("name {}", name)
             }
+            CriterionValue::NameFamily(name) => {
+                format!("name family {}", name)
+            }
             CriterionValue::Mtime(ts) => {
                 let dt = chrono::DateTime::from_timestamp(*ts, 0)
                     .unwrap_or_default()
                     .with_timezone(&chrono::Local);
                 format!("mtime {}", dt.format("%Y-%m-%d %H:%M:%S"))
             }
+            CriterionValue::Created(ts) => {
+                let dt = chrono::DateTime::from_timestamp(*ts, 0)
+                    .unwrap_or_default()
+                    .with_timezone(&chrono::Local);
+                format!("created {}", dt.format("%Y-%m-%d %H:%M:%S"))
+            }
             CriterionValue::MimeType(mime) => {
                 format!("mime {}", mime)
             }
             CriterionValue::MediaMeta(meta) => {
                 format!("media {}", meta)
             }
+            CriterionValue::AudioMatch(cluster) => {
+                format!("audio match #{}", cluster + 1)
+            }
+            CriterionValue::LinkTarget(target) => {
+                format!("link to {}", target)
+            }
+            CriterionValue::Dimensions(width, height) => format!("{}x{}", width, height),
+            CriterionValue::ContentPrefix(hex) => {
+                // Shown as lossy UTF-8; only the key itself is compared.
+                let bytes: Vec<u8> = (0..hex.len())
+                    .step_by(2)
+                    .filter_map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+                    .collect();
+                let text = String::from_utf8_lossy(&bytes);
+                let mut short: String = text.chars().take(24).collect();
+                if short.len() < text.len() {
+                    short.push_str("...");
+                }
+                format!("starts with {:?}", short)
+            }
         })
         .collect();
     parts.join(" | ")
 }
 
+/// Evidence weight of a single criterion, in `0.0..1.0`.
+///
+/// Content checks dominate; metadata-only matches (name, timestamps) are
+/// weak on their own.
+fn criterion_weight(value: &CriterionValue) -> f64 {
+    match value {
+        CriterionValue::Hash(_) | CriterionValue::Blake3(_) | CriterionValue::LinkTarget(_) => 0.99,
+        CriterionValue::FastHash(_) => 0.9,
+        CriterionValue::AudioMatch(_) => 0.75,
+        CriterionValue::ContentPrefix(_) => 0.5,
+        CriterionValue::MimeType(_) => 0.4,
+        CriterionValue::MediaMeta(_) | CriterionValue::Dimensions(..) => 0.35,
+        CriterionValue::Size(_) => 0.3,
+        CriterionValue::Name(_) => 0.2,
+        CriterionValue::NameFamily(_) => 0.15,
+        CriterionValue::Mtime(_) | CriterionValue::Created(_) => 0.1,
+    }
+}
+
+/// Confidence that a group with this key holds true duplicates.
+///
+/// Combines the weights of the criteria present in the key as independent
+/// evidence (`1 - Π(1 - w)`), so every extra criterion raises the score and
+/// an empty key scores 0.
+pub fn key_confidence(key: &DuplicateKey) -> f64 {
+    1.0 - key
+        .iter()
+        .map(|c| 1.0 - criterion_weight(c))
+        .product::<f64>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,6 +1111,38 @@ mod tests {
 
     // -- describe_key tests --
 
+    #[test]
+    fn test_key_confidence_hash_beats_name_only() {
+        let hash = vec![CriterionValue::Hash("abc12345".into())];
+        let name = vec![CriterionValue::Name("report.txt".into())];
+        assert!(key_confidence(&hash) > key_confidence(&name));
+        assert!(key_confidence(&hash) <= 1.0);
+        assert_eq!(key_confidence(&vec![]), 0.0);
+    }
+
+    #[test]
+    fn test_key_confidence_grows_with_criteria() {
+        let size = vec![CriterionValue::Size(10)];
+        let size_name = vec![
+            CriterionValue::Size(10),
+            CriterionValue::Name("a.txt".into()),
+        ];
+        assert!(key_confidence(&size_name) > key_confidence(&size));
+        // Ranking follows hash > mime > size > name > mtime.
+        let ranked = [
+            CriterionValue::Hash("abc".into()),
+            CriterionValue::MimeType("text/plain".into()),
+            CriterionValue::Size(10),
+            CriterionValue::Name("a.txt".into()),
+            CriterionValue::Mtime(0),
+        ];
+        for pair in ranked.windows(2) {
+            assert!(
+                key_confidence(&vec![pair[0].clone()]) > key_confidence(&vec![pair[1].clone()])
+            );
+        }
+    }
+
     #[test]
     fn test_describe_key_hash() {
         let key = vec![CriterionValue::Hash("abcdef1234567890".into())];
@@ -312,4 +1200,35 @@ mod tests {
         assert!(result.contains("mtime"));
         assert!(result.contains("2023"));
     }
+
+    #[test]
+    fn test_describe_key_created() {
+        let key = vec![CriterionValue::Created(1700000000)];
+        let result = describe_key(&key);
+        assert!(result.starts_with("created "));
+        assert!(result.contains("2023"));
+    }
+
+    #[test]
+    fn test_group_serializes_description_and_typed_key() {
+        let key = vec![
+            CriterionValue::Hash("abcdef1234".into()),
+            CriterionValue::Size(42),
+        ];
+        let group = DuplicateGroup {
+            key_description: describe_key(&key),
+            confidence: key_confidence(&key),
+            key,
+            files: Vec::new(),
+            keeper: None,
+            overflow_count: 0,
+            signature: String::new(),
+        };
+        let json = serde_json::to_value(&group).unwrap();
+        assert_eq!(json["key_description"], "sha256 abcdef12... | size 42.00 B");
+        assert_eq!(
+            json["key"],
+            serde_json::json!([{ "Hash": "abcdef1234" }, { "Size": 42 }])
+        );
+    }
 }
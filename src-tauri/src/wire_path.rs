@@ -0,0 +1,103 @@
+//! Lossless path strings for the frontend.
+//!
+//! Paths reach the frontend as strings and come back as the targets of
+//! renames, deletes and reveals, so the string must name the very same
+//! file.  UTF-8 paths are sent as they are.  On Unix a file name may be
+//! any bytes; such a path is sent as [`RAW_MARKER`] followed by its bytes,
+//! with valid UTF-8 copied, `\` doubled and every other byte written as
+//! `\xHH`.  No real path contains the NUL marker, so the two forms cannot
+//! be confused.  Display fields (`name`, `folder`) stay lossy.
+
+use std::path::{Path, PathBuf};
+
+/// First character of an escaped (non-UTF-8) path.
+pub const RAW_MARKER: char = '\0';
+
+/// String for `path` that [`decode`] turns back into the same path.
+pub fn encode(path: &Path) -> String {
+    if let Some(s) = path.to_str() {
+        return s.to_string();
+    }
+    encode_raw(path)
+}
+
+/// Path named by a string from [`encode`]; any other string is taken as a
+/// plain path.
+pub fn decode(s: &str) -> PathBuf {
+    match s.strip_prefix(RAW_MARKER) {
+        Some(escaped) => decode_raw(escaped).unwrap_or_else(|| PathBuf::from(s)),
+        None => PathBuf::from(s),
+    }
+}
+
+#[cfg(unix)]
+fn encode_raw(path: &Path) -> String {
+    use std::fmt::Write;
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut out = String::from(RAW_MARKER);
+    for chunk in path.as_os_str().as_bytes().utf8_chunks() {
+        out.push_str(&chunk.valid().replace('\\', "\\\\"));
+        for byte in chunk.invalid() {
+            let _ = write!(out, "\\x{:02X}", byte);
+        }
+    }
+    out
+}
+
+#[cfg(not(unix))]
+fn encode_raw(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+#[cfg(unix)]
+fn decode_raw(escaped: &str) -> Option<PathBuf> {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+
+    let mut bytes = Vec::with_capacity(escaped.len());
+    let mut rest = escaped;
+    while let Some(pos) = rest.find('\\') {
+        bytes.extend_from_slice(&rest.as_bytes()[..pos]);
+        let tail = &rest[pos + 1..];
+        if let Some(after) = tail.strip_prefix('\\') {
+            bytes.push(b'\\');
+            rest = after;
+        } else {
+            let hex = tail.strip_prefix('x')?.get(..2)?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[3..];
+        }
+    }
+    bytes.extend_from_slice(rest.as_bytes());
+    Some(PathBuf::from(OsString::from_vec(bytes)))
+}
+
+#[cfg(not(unix))]
+fn decode_raw(_escaped: &str) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf8_paths_pass_through() {
+        let path = Path::new("/photos/caf\u{e9} \\ 1.jpg");
+        assert_eq!(encode(path), "/photos/caf\u{e9} \\ 1.jpg");
+        assert_eq!(decode(&encode(path)), path);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_path_round_trips() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(b"/photos/caf\xe9\\x41.jpg"));
+        let encoded = encode(path);
+        assert_eq!(encoded, "\0/photos/caf\\xE9\\\\x41.jpg");
+        assert_eq!(decode(&encoded), path);
+    }
+}
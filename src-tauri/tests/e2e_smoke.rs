@@ -14,6 +14,7 @@ use tempfile::tempdir;
 use real_dedupe_renamer_lib::autorenamer;
 use real_dedupe_renamer_lib::deleter;
 use real_dedupe_renamer_lib::grouper;
+use real_dedupe_renamer_lib::progress::NoProgress;
 use real_dedupe_renamer_lib::scanner;
 use real_dedupe_renamer_lib::types::*;
 
@@ -37,11 +38,25 @@ fn hash_config() -> GroupingConfig {
         use_hash: true,
         use_size: false,
         use_name: false,
+        use_name_family: false,
         use_mtime: false,
+        use_created: false,
         use_mime: false,
         use_media_meta: false,
+        use_audio_fingerprint: false,
+        use_dimensions: false,
+        use_content_prefix: false,
+        content_prefix_bytes: 0,
         hash_max_bytes: None,
         fast_hash_oversized: false,
+        blake3_min_bytes: None,
+        digest_encoding: DigestEncoding::Hex,
+        symlink_hash_policy: SymlinkHashPolicy::FollowTarget,
+        same_folder_policy: SameFolderPolicy::Flag,
+        name_compare_mode: NameCompare::Full,
+        custom_file_type_presets: FileTypePresets::new(),
+        type_overrides: Vec::new(),
+        alternatives: Vec::new(),
     }
 }
 
@@ -69,13 +84,20 @@ fn scan_group_delete_happy_path() {
     write_file(dir.path(), "c.txt", b"unique content");
 
     // Scan
-    let (entries, skip_reasons) = scanner::gather_recent_files(dir.path(), 0, None, true, None);
+    let (entries, skip_reasons) = scanner::gather_recent_files(
+        dir.path(),
+        0,
+        None,
+        None,
+        &ScanOptions::default(),
+        &NoProgress,
+    );
     assert_eq!(entries.len(), 3);
     assert_eq!(skip_reasons.total(), 0);
 
     // Group by hash
-    let (groups, hash_skipped) = grouper::find_duplicate_groups(&entries, &hash_config(), None);
-    assert_eq!(hash_skipped, 0);
+    let (groups, stats) = grouper::find_duplicate_groups(&entries, &hash_config(), &NoProgress);
+    assert_eq!(stats.hash_skipped, 0);
     assert_eq!(groups.len(), 1, "expected exactly one duplicate group");
 
     let group = groups.values().next().unwrap();
@@ -106,8 +128,15 @@ fn delete_preserves_kept_files_in_large_group() {
         write_file(dir.path(), &format!("dup_{}.txt", i), b"same content");
     }
 
-    let (entries, _) = scanner::gather_recent_files(dir.path(), 0, None, true, None);
-    let (groups, _) = grouper::find_duplicate_groups(&entries, &hash_config(), None);
+    let (entries, _) = scanner::gather_recent_files(
+        dir.path(),
+        0,
+        None,
+        None,
+        &ScanOptions::default(),
+        &NoProgress,
+    );
+    let (groups, _) = grouper::find_duplicate_groups(&entries, &hash_config(), &NoProgress);
     assert_eq!(groups.len(), 1);
 
     let group = groups.values().next().unwrap();
@@ -127,9 +156,16 @@ fn delete_preserves_kept_files_in_large_group() {
     }
 
     // Re-scan: single remaining file cannot form a duplicate group.
-    let (entries2, _) = scanner::gather_recent_files(dir.path(), 0, None, true, None);
+    let (entries2, _) = scanner::gather_recent_files(
+        dir.path(),
+        0,
+        None,
+        None,
+        &ScanOptions::default(),
+        &NoProgress,
+    );
     assert_eq!(entries2.len(), 1);
-    let (groups2, _) = grouper::find_duplicate_groups(&entries2, &hash_config(), None);
+    let (groups2, _) = grouper::find_duplicate_groups(&entries2, &hash_config(), &NoProgress);
     assert!(groups2.is_empty(), "single file cannot be a duplicate");
 }
 
@@ -153,9 +189,10 @@ fn scan_group_rename_happy_path() {
             RenameComponentDef::OriginalStem,
         ],
         separator: "_".to_string(),
+        ..RenameSchema::default()
     };
 
-    let result = autorenamer::auto_rename_paths(&[f1.clone(), f2.clone()], &schema);
+    let result = autorenamer::auto_rename_paths(&[f1.clone(), f2.clone()], &schema, &NoProgress);
     assert_eq!(result.renamed_count, 2);
     assert_eq!(result.error_count, 0);
     assert_eq!(result.skipped_count, 0);
@@ -190,9 +227,10 @@ fn rename_with_sequence_resolves_collisions() {
             RenameComponentDef::Sequence { pad_width: 3 },
         ],
         separator: "_".to_string(),
+        ..RenameSchema::default()
     };
 
-    let result = autorenamer::auto_rename_paths(&[f1, f2, f3], &schema);
+    let result = autorenamer::auto_rename_paths(&[f1, f2, f3], &schema, &NoProgress);
     assert_eq!(result.renamed_count, 3);
     assert_eq!(result.error_count, 0);
 
@@ -224,10 +262,17 @@ fn full_pipeline_scan_group_rename_duplicate() {
     write_file(dir.path(), "doc.txt", b"text data"); // unique
 
     // Scan + group
-    let (entries, _) = scanner::gather_recent_files(dir.path(), 0, None, true, None);
+    let (entries, _) = scanner::gather_recent_files(
+        dir.path(),
+        0,
+        None,
+        None,
+        &ScanOptions::default(),
+        &NoProgress,
+    );
     assert_eq!(entries.len(), 3);
 
-    let (groups, _) = grouper::find_duplicate_groups(&entries, &hash_config(), None);
+    let (groups, _) = grouper::find_duplicate_groups(&entries, &hash_config(), &NoProgress);
     assert_eq!(groups.len(), 1);
 
     // Rename the copy, not the original.
@@ -253,9 +298,11 @@ fn full_pipeline_scan_group_rename_duplicate() {
             RenameComponentDef::OriginalStem,
         ],
         separator: "_".to_string(),
+        ..RenameSchema::default()
     };
 
-    let result = autorenamer::auto_rename_paths(std::slice::from_ref(&dup_path), &schema);
+    let result =
+        autorenamer::auto_rename_paths(std::slice::from_ref(&dup_path), &schema, &NoProgress);
     assert_eq!(result.renamed_count, 1);
     assert_eq!(result.error_count, 0);
 
@@ -284,7 +331,14 @@ fn multi_criteria_hash_plus_size() {
     // d.txt: completely different.
     write_file(dir.path(), "d.txt", b"short");
 
-    let (entries, _) = scanner::gather_recent_files(dir.path(), 0, None, true, None);
+    let (entries, _) = scanner::gather_recent_files(
+        dir.path(),
+        0,
+        None,
+        None,
+        &ScanOptions::default(),
+        &NoProgress,
+    );
     assert_eq!(entries.len(), 4);
 
     let config = GroupingConfig {
@@ -293,7 +347,7 @@ fn multi_criteria_hash_plus_size() {
         ..hash_config()
     };
 
-    let (groups, _) = grouper::find_duplicate_groups(&entries, &config, None);
+    let (groups, _) = grouper::find_duplicate_groups(&entries, &config, &NoProgress);
     assert_eq!(groups.len(), 1, "only a.txt+b.txt should group");
 
     let names: HashSet<String> = groups
@@ -315,10 +369,17 @@ fn no_duplicates_returns_no_groups() {
     write_file(dir.path(), "unique2.txt", b"second");
     write_file(dir.path(), "unique3.txt", b"third");
 
-    let (entries, _) = scanner::gather_recent_files(dir.path(), 0, None, true, None);
+    let (entries, _) = scanner::gather_recent_files(
+        dir.path(),
+        0,
+        None,
+        None,
+        &ScanOptions::default(),
+        &NoProgress,
+    );
     assert_eq!(entries.len(), 3);
 
-    let (groups, _) = grouper::find_duplicate_groups(&entries, &hash_config(), None);
+    let (groups, _) = grouper::find_duplicate_groups(&entries, &hash_config(), &NoProgress);
     assert!(
         groups.is_empty(),
         "all unique files should produce no groups"
@@ -334,7 +395,14 @@ fn fast_hash_groups_oversized_files() {
     write_file(dir.path(), "big2.bin", &big_content); // duplicate
     write_file(dir.path(), "small.txt", b"tiny");
 
-    let (entries, _) = scanner::gather_recent_files(dir.path(), 0, None, true, None);
+    let (entries, _) = scanner::gather_recent_files(
+        dir.path(),
+        0,
+        None,
+        None,
+        &ScanOptions::default(),
+        &NoProgress,
+    );
 
     // hash_max_bytes = 1024 so the 2 KB files are "oversized".
     // fast_hash_oversized = true → sample head+tail instead of skipping.
@@ -342,12 +410,14 @@ fn fast_hash_groups_oversized_files() {
         use_hash: true,
         hash_max_bytes: Some(1024),
         fast_hash_oversized: true,
+        blake3_min_bytes: None,
+        digest_encoding: DigestEncoding::Hex,
         ..hash_config()
     };
 
-    let (groups, hash_skipped) = grouper::find_duplicate_groups(&entries, &config, None);
+    let (groups, stats) = grouper::find_duplicate_groups(&entries, &config, &NoProgress);
     assert_eq!(
-        hash_skipped, 0,
+        stats.hash_skipped, 0,
         "fast hash should handle oversized files, not skip them"
     );
     assert_eq!(
@@ -370,7 +440,14 @@ fn scan_respects_prefix_filter() {
     write_file(dir.path(), "IMG_002.jpg", b"photo 2");
     write_file(dir.path(), "DOC_report.pdf", b"document");
 
-    let (entries, _) = scanner::gather_recent_files(dir.path(), 0, Some("IMG"), true, None);
+    let (entries, _) = scanner::gather_recent_files(
+        dir.path(),
+        0,
+        Some("IMG"),
+        None,
+        &ScanOptions::default(),
+        &NoProgress,
+    );
     assert_eq!(entries.len(), 2, "only IMG_ files should be returned");
 }
 
@@ -384,10 +461,24 @@ fn scan_respects_subfolder_toggle() {
     write_file(&sub, "nested.txt", b"nested file");
 
     // Without subfolders.
-    let (entries_flat, _) = scanner::gather_recent_files(dir.path(), 0, None, false, None);
+    let (entries_flat, _) = scanner::gather_recent_files(
+        dir.path(),
+        0,
+        None,
+        Some(1),
+        &ScanOptions::default(),
+        &NoProgress,
+    );
     assert_eq!(entries_flat.len(), 1, "should only find root file");
 
     // With subfolders.
-    let (entries_deep, _) = scanner::gather_recent_files(dir.path(), 0, None, true, None);
+    let (entries_deep, _) = scanner::gather_recent_files(
+        dir.path(),
+        0,
+        None,
+        None,
+        &ScanOptions::default(),
+        &NoProgress,
+    );
     assert_eq!(entries_deep.len(), 2, "should find root + nested files");
 }
@@ -7,7 +7,11 @@ import * as api from "./api";
 import type {
   AppSettings,
   AutoRenameScanResult,
+  CriteriaSet,
+  DeleteSummary,
   DuplicateGroup,
+  MtimeFilter,
+  PathError,
   RenameComponent,
   ScanProgress,
   ScanResult,
@@ -23,6 +27,7 @@ import KeepChoiceDialog from "./components/KeepChoiceDialog";
 import ResultsTable from "./components/ResultsTable";
 import ScanView from "./components/ScanView";
 import SettingsPanel from "./components/SettingsPanel";
+import SizeHistogram from "./components/SizeHistogram";
 import StatusBar from "./components/StatusBar";
 
 const SIMPLIFIED_DEFAULTS: Partial<AppSettings> = {
@@ -30,13 +35,38 @@ const SIMPLIFIED_DEFAULTS: Partial<AppSettings> = {
   use_hash: true,
   use_size: false,
   use_name: false,
+  use_name_family: false,
   use_mtime: false,
+  use_created: false,
   use_mime: false,
   use_media_meta: false,
+  use_audio_fingerprint: false,
+  use_dimensions: false,
+  use_content_prefix: false,
+  content_prefix_bytes: 256,
   hash_limit_enabled: true,
   hash_max_mb: 500,
   fast_hash_oversized: false,
+  blake3_min_mb: null,
   include_subfolders: true,
+  max_depth: null,
+  skip_hidden: false,
+  follow_symlinks: false,
+  symlink_hash_policy: "follow_target",
+  mtime_filter: null,
+  same_folder_policy: "flag",
+  name_compare_mode: "full",
+  alternative_criteria: [],
+  type_overrides: [],
+  max_groups: null,
+  scan_timeout_secs: null,
+  scan_max_files: null,
+  mime_filter: null,
+  skip_recently_modified_secs: null,
+  peek_archives: false,
+  parallel_walk: false,
+  skip_trash_dirs: true,
+  max_files_per_group: null,
   name_prefix: "",
   skip_same_folder_prompt: true,
 };
@@ -47,22 +77,60 @@ const DEFAULT_SETTINGS: AppSettings = {
   use_hash: true,
   use_size: true,
   use_name: false,
+  use_name_family: false,
   use_mtime: false,
+  use_created: false,
   use_mime: false,
   use_media_meta: false,
+  use_audio_fingerprint: false,
+  use_dimensions: false,
+  use_content_prefix: false,
+  content_prefix_bytes: 256,
   hash_limit_enabled: true,
   hash_max_mb: 500,
   fast_hash_oversized: false,
+  blake3_min_mb: null,
   skip_same_folder_prompt: false,
+  trash_limit_mb: null,
   show_keep_full_paths: false,
   include_subfolders: true,
+  max_depth: null,
+  skip_hidden: false,
+  follow_symlinks: false,
+  symlink_hash_policy: "follow_target",
+  mtime_filter: null,
+  same_folder_policy: "flag",
+  name_compare_mode: "full",
+  alternative_criteria: [],
+  type_overrides: [],
+  max_groups: null,
+  scan_timeout_secs: null,
+  scan_max_files: null,
+  mime_filter: null,
+  skip_recently_modified_secs: null,
+  peek_archives: false,
+  parallel_walk: false,
+  skip_trash_dirs: true,
+  max_files_per_group: null,
   name_prefix: "",
   recent_folders: [],
+  ignored_groups: [],
   view_mode: "simplified",
   auto_file_type_preset: "all",
+  custom_file_type_presets: {},
   theme: "system",
   rename_components: DEFAULT_RENAME_COMPONENTS,
   rename_separator: "_",
+  rename_created_fallback: "now",
+  rename_date_format: "%Y%m%d",
+  rename_time_format: "%H%M%S",
+  rename_symlinks: false,
+  rename_skip_duplicate_content: false,
+  rename_infer_missing_extension: false,
+  rename_collision_policy: "sequence",
+  rename_sequence_separator: null,
+  rename_reveal_on_complete: false,
+  rename_strict: false,
 };
 
 type ConfirmState = {
@@ -83,6 +151,7 @@ export default function App() {
   const [scanning, setScanning] = useState(false);
   const [scanResult, setScanResult] = useState<ScanResult | null>(null);
   const [selectedPaths, setSelectedPaths] = useState<Set<string>>(new Set());
+  const [selectedBytes, setSelectedBytes] = useState<number | null>(null);
   const [filterText, setFilterText] = useState("");
   const [confirmState, setConfirmState] = useState<ConfirmState>(null);
   const [keepChoiceOpen, setKeepChoiceOpen] = useState(false);
@@ -99,11 +168,13 @@ export default function App() {
     null,
   );
   const [autoPrefixSearch, setAutoPrefixSearch] = useState("");
+  const [autoOnlyChanging, setAutoOnlyChanging] = useState(false);
   const [autoLastMessage, setAutoLastMessage] = useState("");
   const [autoLastMessageIsError, setAutoLastMessageIsError] = useState(false);
   const [isDragging, setIsDragging] = useState(false);
   const [scanProgress, setScanProgress] = useState<ScanProgress | null>(null);
   const [appVersion, setAppVersion] = useState("");
+  const [canPeekArchives, setCanPeekArchives] = useState(false);
 
   // --- Auto-renamer post-scan filter state (frontend-only) ---
   const [extensionFilter, setExtensionFilter] = useState("");
@@ -119,15 +190,45 @@ export default function App() {
     getVersion().then((v) => setAppVersion(v));
   }, []);
 
+  // Only offer archive peeking when this build can do it
+  useEffect(() => {
+    api
+      .canPeekArchives()
+      .then(setCanPeekArchives)
+      .catch(() => setCanPeekArchives(false));
+  }, []);
+
+  // Recompute the on-disk size of the current selection
+  useEffect(() => {
+    if (selectedPaths.size === 0) {
+      setSelectedBytes(null);
+      return;
+    }
+    let cancelled = false;
+    api
+      .computeSelectionSize([...selectedPaths])
+      .then((bytes) => {
+        if (!cancelled) setSelectedBytes(bytes);
+      })
+      .catch(() => {
+        if (!cancelled) setSelectedBytes(null);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [selectedPaths]);
+
   // Load settings on mount
   useEffect(() => {
     (async () => {
       try {
-        const [loaded, defaultFolder] = await Promise.all([
+        const [loaded, defaultFolder, recentFolders] = await Promise.all([
           api.getSettings(),
           api.getDefaultFolder(),
+          api.getRecentFolders().catch(() => null),
         ]);
         const merged: AppSettings = { ...DEFAULT_SETTINGS, ...loaded };
+        if (recentFolders) merged.recent_folders = recentFolders;
         if (!merged.folder) merged.folder = defaultFolder;
         if (!merged.auto_file_type_preset) merged.auto_file_type_preset = "all";
         // Ensure rename fields have defaults if missing from persisted settings.
@@ -136,6 +237,15 @@ export default function App() {
         }
         if (!merged.rename_separator) merged.rename_separator = "_";
         setSettings(merged);
+
+        // Show the previous session's results until the user scans again.
+        const lastScan = await api.loadLastScan().catch(() => null);
+        if (lastScan) {
+          setScanResult(lastScan);
+          setLastScanDays(merged.days);
+          setLastScanPrefix(merged.name_prefix);
+          setLastScanSubfolders(merged.include_subfolders);
+        }
       } catch {
         try {
           const defaultFolder = await api.getDefaultFolder();
@@ -241,11 +351,33 @@ export default function App() {
     }
   }, [settings.theme]);
 
+  // Keep the theme in sync when another window changes it.
+  useEffect(() => {
+    const unlisten = listen<Theme>("theme-changed", (event) => {
+      setSettings((s) => ({ ...s, theme: event.payload }));
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Folder of the last rename batch, when it asked to reveal it.
+  const renameFolderRef = useRef<string | null>(null);
+  useEffect(() => {
+    const unlisten = listen<string>("rename-complete", (event) => {
+      renameFolderRef.current = event.payload;
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const cycleTheme = () => {
     const order: Theme[] = ["light", "dark", "system"];
     const idx = order.indexOf(settings.theme);
     const next = order[(idx + 1) % order.length];
     updateSetting("theme", next);
+    api.setTheme(next).catch(() => {});
   };
 
   const viewMode = settings.view_mode;
@@ -258,14 +390,39 @@ export default function App() {
         use_hash: settings.use_hash,
         use_size: settings.use_size,
         use_name: settings.use_name,
+        use_name_family: settings.use_name_family,
         use_mtime: settings.use_mtime,
+        use_created: settings.use_created,
         use_mime: settings.use_mime,
         use_media_meta: settings.use_media_meta,
+        use_audio_fingerprint: settings.use_audio_fingerprint,
+        use_dimensions: settings.use_dimensions,
+        use_content_prefix: settings.use_content_prefix,
+        content_prefix_bytes: settings.content_prefix_bytes,
         hash_limit_enabled: settings.hash_limit_enabled,
         hash_max_mb: settings.hash_max_mb,
         fast_hash_oversized: settings.fast_hash_oversized,
+        blake3_min_mb: settings.blake3_min_mb,
         skip_same_folder_prompt: settings.skip_same_folder_prompt,
         include_subfolders: settings.include_subfolders,
+        max_depth: settings.max_depth,
+        skip_hidden: settings.skip_hidden,
+        follow_symlinks: settings.follow_symlinks,
+        symlink_hash_policy: settings.symlink_hash_policy,
+        mtime_filter: settings.mtime_filter,
+        same_folder_policy: settings.same_folder_policy,
+        name_compare_mode: settings.name_compare_mode,
+        alternative_criteria: settings.alternative_criteria,
+        type_overrides: settings.type_overrides,
+        max_groups: settings.max_groups,
+        scan_timeout_secs: settings.scan_timeout_secs,
+        scan_max_files: settings.scan_max_files,
+        mime_filter: settings.mime_filter,
+        skip_recently_modified_secs: settings.skip_recently_modified_secs,
+        peek_archives: settings.peek_archives,
+        parallel_walk: settings.parallel_walk,
+        skip_trash_dirs: settings.skip_trash_dirs,
+        max_files_per_group: settings.max_files_per_group,
         name_prefix: settings.name_prefix,
       });
     } else if (mode === "advanced" && viewMode !== "advanced") {
@@ -278,7 +435,10 @@ export default function App() {
     setSettings((s) => ({ ...s, view_mode: mode }));
   };
 
-  const updateSetting = (field: string, value: boolean | number | string) => {
+  const updateSetting = (
+    field: string,
+    value: boolean | number | string | string[] | MtimeFilter | CriteriaSet[] | null,
+  ) => {
     setSettings((s) => ({ ...s, [field]: value }));
   };
 
@@ -323,9 +483,14 @@ export default function App() {
       !scanSettings.use_hash &&
       !scanSettings.use_size &&
       !scanSettings.use_name &&
+      !scanSettings.use_name_family &&
       !scanSettings.use_mtime &&
+      !scanSettings.use_created &&
       !scanSettings.use_mime &&
-      !scanSettings.use_media_meta
+      !scanSettings.use_media_meta &&
+      !scanSettings.use_audio_fingerprint &&
+      !scanSettings.use_dimensions &&
+      !scanSettings.use_content_prefix
     ) {
       setConfirmState({
         title: "No criteria",
@@ -348,14 +513,40 @@ export default function App() {
         use_hash: scanSettings.use_hash,
         use_size: scanSettings.use_size,
         use_name: scanSettings.use_name,
+        use_name_family: scanSettings.use_name_family,
         use_mtime: scanSettings.use_mtime,
+        use_created: scanSettings.use_created,
         use_mime: scanSettings.use_mime,
         use_media_meta: scanSettings.use_media_meta,
+        use_audio_fingerprint: scanSettings.use_audio_fingerprint,
+        use_dimensions: scanSettings.use_dimensions,
+        use_content_prefix: scanSettings.use_content_prefix,
+        content_prefix_bytes: scanSettings.content_prefix_bytes,
         hash_limit_enabled: scanSettings.hash_limit_enabled,
         hash_max_mb: scanSettings.hash_max_mb,
         fast_hash_oversized: scanSettings.fast_hash_oversized,
+        blake3_min_mb: scanSettings.blake3_min_mb,
         include_subfolders: scanSettings.include_subfolders,
+        max_depth: scanSettings.max_depth,
         name_prefix: scanSettings.name_prefix,
+        skip_hidden: scanSettings.skip_hidden,
+        follow_symlinks: scanSettings.follow_symlinks,
+        symlink_hash_policy: scanSettings.symlink_hash_policy,
+        mtime_filter: scanSettings.mtime_filter,
+        same_folder_policy: scanSettings.same_folder_policy,
+        name_compare_mode: scanSettings.name_compare_mode,
+        timeout_secs: scanSettings.scan_timeout_secs,
+        max_files: scanSettings.scan_max_files,
+        mime_filter: scanSettings.mime_filter,
+        skip_recently_modified_secs: scanSettings.skip_recently_modified_secs,
+        peek_archives: scanSettings.peek_archives,
+        parallel_walk: scanSettings.parallel_walk,
+        skip_trash_dirs: scanSettings.skip_trash_dirs,
+        max_files_per_group: scanSettings.max_files_per_group,
+        max_groups: scanSettings.max_groups,
+        alternatives: scanSettings.alternative_criteria,
+        type_overrides: scanSettings.type_overrides,
+        custom_file_type_presets: scanSettings.custom_file_type_presets,
       });
 
       rememberFolder(folder);
@@ -365,7 +556,7 @@ export default function App() {
       setLastScanPrefix(scanSettings.name_prefix);
       setLastScanSubfolders(scanSettings.include_subfolders);
       setLastScanHadFallback(
-        scanSettings.use_size || scanSettings.use_name || scanSettings.use_mtime || scanSettings.use_mime || scanSettings.use_media_meta,
+        scanSettings.use_size || scanSettings.use_name || scanSettings.use_name_family || scanSettings.use_mtime || scanSettings.use_created || scanSettings.use_mime || scanSettings.use_media_meta || scanSettings.use_audio_fingerprint || scanSettings.use_dimensions || scanSettings.use_content_prefix,
       );
 
       // Simplified mode: auto-prompt delete if duplicates found.
@@ -375,7 +566,7 @@ export default function App() {
     } catch (e) {
       setConfirmState({
         title: "Scan failed",
-        message: String(e),
+        message: api.errorMessage(e),
         buttons: [{ label: "OK", onClick: () => setConfirmState(null) }],
       });
     } finally {
@@ -408,6 +599,22 @@ export default function App() {
         include_subfolders: settings.include_subfolders,
         name_prefix: settings.name_prefix,
         file_type_preset: settings.auto_file_type_preset,
+        custom_file_type_presets: settings.custom_file_type_presets,
+        rename_schema: autoOnlyChanging
+          ? {
+              components: settings.rename_components,
+              separator: settings.rename_separator,
+              created_fallback: settings.rename_created_fallback,
+              date_format: settings.rename_date_format,
+              time_format: settings.rename_time_format,
+              rename_symlinks: settings.rename_symlinks,
+              skip_duplicate_content: settings.rename_skip_duplicate_content,
+              infer_missing_extension: settings.rename_infer_missing_extension,
+              collision_policy: settings.rename_collision_policy,
+              sequence_separator: settings.rename_sequence_separator,
+              strict: settings.rename_strict,
+            }
+          : null,
       });
       rememberFolder(folder);
       setAutoScanResult(result);
@@ -416,7 +623,7 @@ export default function App() {
       setLastScanSubfolders(settings.include_subfolders);
       setAutoLastMessageIsError(false);
     } catch (e) {
-      setAutoLastMessage(String(e));
+      setAutoLastMessage(api.errorMessage(e));
       setAutoLastMessageIsError(true);
     } finally {
       setAutoScanning(false);
@@ -477,15 +684,33 @@ export default function App() {
     if (paths.length === 0) return;
 
     setAutoRenaming(true);
+    setScanProgress(null);
     setAutoLastMessage("");
     setAutoLastMessageIsError(false);
+    renameFolderRef.current = null;
 
     try {
-      const result = await api.autoRename(paths, {
-        components: settings.rename_components,
-        separator: settings.rename_separator,
-      });
+      const result = await api.autoRename(
+        paths,
+        {
+          components: settings.rename_components,
+          separator: settings.rename_separator,
+          created_fallback: settings.rename_created_fallback,
+          date_format: settings.rename_date_format,
+          time_format: settings.rename_time_format,
+          rename_symlinks: settings.rename_symlinks,
+          skip_duplicate_content: settings.rename_skip_duplicate_content,
+          infer_missing_extension: settings.rename_infer_missing_extension,
+          collision_policy: settings.rename_collision_policy,
+          sequence_separator: settings.rename_sequence_separator,
+          strict: settings.rename_strict,
+        },
+        settings.rename_reveal_on_complete,
+      );
       const summaryParts = [`Renamed ${result.renamed_count} file(s).`];
+      if (result.cancelled) {
+        summaryParts.push("Stopped before the rest.");
+      }
       if (result.skipped_count > 0) {
         summaryParts.push(`Skipped ${result.skipped_count} file(s).`);
       }
@@ -500,21 +725,36 @@ export default function App() {
         result.errors.length > 0
           ? `\n\nFirst error:\n${result.errors[0].path}\n${result.errors[0].message}`
           : "";
+      const folder = renameFolderRef.current;
       setConfirmState({
         title: result.error_count > 0 ? "Auto-rename completed with issues" : "Done",
         message: `${summary}${details}`,
-        buttons: [{ label: "OK", onClick: () => setConfirmState(null) }],
+        buttons: [
+          ...(folder
+            ? [
+                {
+                  label: "Open folder",
+                  onClick: () => {
+                    setConfirmState(null);
+                    api.openFolder(folder).catch(() => {});
+                  },
+                },
+              ]
+            : []),
+          { label: "OK", onClick: () => setConfirmState(null) },
+        ],
       });
     } catch (e) {
-      setAutoLastMessage(String(e));
+      setAutoLastMessage(api.errorMessage(e));
       setAutoLastMessageIsError(true);
       setConfirmState({
         title: "Auto-rename failed",
-        message: String(e),
+        message: api.errorMessage(e),
         buttons: [{ label: "OK", onClick: () => setConfirmState(null) }],
       });
     } finally {
       setAutoRenaming(false);
+      setScanProgress(null);
     }
 
     await performAutoScan();
@@ -556,6 +796,94 @@ export default function App() {
     });
   };
 
+  const reportDeleted = (
+    count: number,
+    what: string,
+    errors: PathError[] = [],
+    warnings: PathError[] = [],
+  ) => {
+    const describe = (heading: string, items: PathError[]) => {
+      if (items.length === 0) return "";
+      const lines = items.slice(0, 5).map((i) => `${i.path}: ${i.message}`);
+      const more = items.length > 5 ? `\n...and ${items.length - 5} more` : "";
+      return `\n\n${heading} (${items.length}):\n${lines.join("\n")}${more}`;
+    };
+    setConfirmState({
+      title: errors.length > 0 ? "Done, with errors" : "Done",
+      message:
+        `Deleted ${count} ${what}.` +
+        describe("Could not delete", errors) +
+        describe("Warnings", warnings),
+      buttons: [
+        {
+          label: "OK",
+          onClick: () => {
+            setConfirmState(null);
+            handleScan(); // Re-scan.
+          },
+        },
+      ],
+    });
+  };
+
+  const reportDeleteFailed = (e: unknown) => {
+    setConfirmState({
+      title: "Delete failed",
+      message: api.errorMessage(e),
+      buttons: [{ label: "OK", onClick: () => setConfirmState(null) }],
+    });
+  };
+
+  const trashLimitBytes =
+    settings.trash_limit_mb === null ? null : settings.trash_limit_mb * 1024 * 1024;
+
+  // Files on volumes without a trash, and batches too big for the trash,
+  // are only deleted after explicit consent.
+  const finishDelete = (summary: DeleteSummary, what: string) => {
+    const deferred = summary.needs_confirmation;
+    if (deferred.length === 0) {
+      reportDeleted(summary.deleted, what, summary.errors, summary.warnings);
+      return;
+    }
+    const preview = deferred.slice(0, 5).join("\n");
+    const more = deferred.length > 5 ? `\n...and ${deferred.length - 5} more` : "";
+    setConfirmState({
+      title: summary.trash_limit_exceeded ? "Too big for the trash" : "No trash available",
+      message: summary.trash_limit_exceeded
+        ? `These ${deferred.length} file(s) add up to more than ${settings.trash_limit_mb} MB, which may be too big for the recycle bin, and were not deleted:\n` +
+          `${preview}${more}\n\nDelete them anyway? Files the trash cannot hold are deleted permanently.`
+        : `${deferred.length} file(s) are on a volume without a trash (e.g. a network share) and were not deleted:\n` +
+          `${preview}${more}\n\nDelete them permanently? This cannot be undone.`,
+      buttons: [
+        {
+          label: "Keep them",
+          onClick: () => {
+            setConfirmState(null);
+            reportDeleted(summary.deleted, what, summary.errors, summary.warnings);
+          },
+        },
+        {
+          label: "Delete permanently",
+          onClick: async () => {
+            setConfirmState(null);
+            try {
+              const again = await api.deleteFiles(deferred, true);
+              reportDeleted(
+                summary.deleted + again.deleted,
+                what,
+                [...summary.errors, ...again.errors],
+                [...summary.warnings, ...again.warnings],
+              );
+            } catch (e) {
+              reportDeleteFailed(e);
+            }
+          },
+          variant: "danger",
+        },
+      ],
+    });
+  };
+
   const simplifiedDelete = async (result: ScanResult) => {
     // Auto-keep newest (first file, sorted by mtime desc).
     const toDelete: string[] = [];
@@ -568,26 +896,12 @@ export default function App() {
     if (toDelete.length === 0) return;
 
     try {
-      const count = await api.deleteFiles(toDelete);
-      setConfirmState({
-        title: "Done",
-        message: `Deleted ${count} duplicate file(s).`,
-        buttons: [
-          {
-            label: "OK",
-            onClick: () => {
-              setConfirmState(null);
-              handleScan(); // Re-scan.
-            },
-          },
-        ],
-      });
+      finishDelete(
+        await api.deleteFiles(toDelete, false, false, null, trashLimitBytes),
+        "duplicate file(s)",
+      );
     } catch (e) {
-      setConfirmState({
-        title: "Delete failed",
-        message: String(e),
-        buttons: [{ label: "OK", onClick: () => setConfirmState(null) }],
-      });
+      reportDeleteFailed(e);
     }
   };
 
@@ -612,7 +926,7 @@ export default function App() {
 
       if (manualGroups.length === 0) {
         // All groups are same-folder, proceed directly.
-        confirmAndDelete(autoKeep);
+        void confirmAndDelete(autoKeep);
         return;
       }
 
@@ -653,10 +967,10 @@ export default function App() {
       });
     }
 
-    confirmAndDelete(fullKeep);
+    void confirmAndDelete(fullKeep);
   };
 
-  const confirmAndDelete = (keepPaths: Map<number, string>) => {
+  const confirmAndDelete = async (keepPaths: Map<number, string>) => {
     if (!scanResult) return;
 
     const toDelete: string[] = [];
@@ -677,11 +991,32 @@ export default function App() {
       return sum;
     }, 0);
 
+    // Dry run first so the dialog can warn about files that would fail or
+    // need permanent deletion.  A failed check just skips the warnings.
+    const warnings: string[] = [];
+    try {
+      const preflight = await api.deleteFiles(toDelete, false, true, null, trashLimitBytes);
+      if (preflight.errors.length > 0) {
+        warnings.push(`${preflight.errors.length} file(s) cannot be deleted.`);
+      }
+      if (preflight.trash_limit_exceeded) {
+        warnings.push(`This is more than ${settings.trash_limit_mb} MB and may be too big for the recycle bin.`);
+      } else if (preflight.needs_confirmation.length > 0) {
+        warnings.push(
+          `${preflight.needs_confirmation.length} file(s) are on a volume without a trash.`,
+        );
+      }
+    } catch {
+      // Ignore: the real delete reports its own errors.
+    }
+
     setConfirmState({
       title: "Confirm deletion",
       message:
         `This will delete ${toDelete.length} file(s), freeing ~${humanSize(totalSize)}.\n` +
-        `The selected keep file in each group will be kept.\n\nProceed?`,
+        `The selected keep file in each group will be kept.\n\n` +
+        (warnings.length > 0 ? `${warnings.join("\n")}\n\n` : "") +
+        `Proceed?`,
       buttons: [
         { label: "Cancel", onClick: () => setConfirmState(null) },
         {
@@ -766,34 +1101,52 @@ export default function App() {
 
   const executeDelete = async (paths: string[]) => {
     try {
-      const count = await api.deleteFiles(paths);
-      setConfirmState({
-        title: "Done",
-        message: `Deleted ${count} file(s).`,
-        buttons: [
-          {
-            label: "OK",
-            onClick: () => {
-              setConfirmState(null);
-              handleScan(); // Re-scan.
-            },
-          },
-        ],
-      });
+      finishDelete(await api.deleteFiles(paths, false, false, null, trashLimitBytes), "file(s)");
+    } catch (e) {
+      reportDeleteFailed(e);
+    }
+  };
+
+  const handleRevealFile = async (path: string) => {
+    try {
+      await api.revealFile(path);
+    } catch {
+      /* ignore */
+    }
+  };
+
+  const handleIgnoreGroup = async (group: DuplicateGroup) => {
+    try {
+      await api.ignoreGroup(group.signature);
     } catch (e) {
       setConfirmState({
-        title: "Delete failed",
-        message: String(e),
+        title: "Could not ignore group",
+        message: api.errorMessage(e),
         buttons: [{ label: "OK", onClick: () => setConfirmState(null) }],
       });
+      return;
     }
+    setSettings((s) => ({ ...s, ignored_groups: [...s.ignored_groups, group.signature] }));
+    setScanResult((r) =>
+      r && {
+        ...r,
+        groups: r.groups.filter((g) => g.signature !== group.signature),
+        total_group_count: r.total_group_count - 1,
+        ignored_count: r.ignored_count + 1,
+      },
+    );
   };
 
-  const handleOpenFolder = async (path: string) => {
+  const handleClearIgnored = async () => {
     try {
-      await api.openFolder(path);
-    } catch {
-      /* ignore */
+      await api.clearIgnored();
+      setSettings((s) => ({ ...s, ignored_groups: [] }));
+    } catch (e) {
+      setConfirmState({
+        title: "Could not clear ignored groups",
+        message: api.errorMessage(e),
+        buttons: [{ label: "OK", onClick: () => setConfirmState(null) }],
+      });
     }
   };
 
@@ -862,15 +1215,43 @@ export default function App() {
             useHash={settings.use_hash}
             useSize={settings.use_size}
             useName={settings.use_name}
+            useNameFamily={settings.use_name_family}
             useMtime={settings.use_mtime}
+            useCreated={settings.use_created}
             useMime={settings.use_mime}
             useMediaMeta={settings.use_media_meta}
+            useAudioFingerprint={settings.use_audio_fingerprint}
+            useDimensions={settings.use_dimensions}
+            useContentPrefix={settings.use_content_prefix}
+            contentPrefixBytes={settings.content_prefix_bytes}
             hashLimitEnabled={settings.hash_limit_enabled}
             hashMaxMb={settings.hash_max_mb}
             fastHashOversized={settings.fast_hash_oversized}
+            blake3MinMb={settings.blake3_min_mb}
             includeSubfolders={settings.include_subfolders}
+            maxDepth={settings.max_depth}
+            skipHidden={settings.skip_hidden}
+            followSymlinks={settings.follow_symlinks}
+            symlinkHashPolicy={settings.symlink_hash_policy}
+            mtimeFilter={settings.mtime_filter}
+            sameFolderPolicy={settings.same_folder_policy}
+            nameCompareMode={settings.name_compare_mode}
+            alternativeCriteria={settings.alternative_criteria}
+            maxGroups={settings.max_groups}
+            scanTimeoutSecs={settings.scan_timeout_secs}
+            scanMaxFiles={settings.scan_max_files}
+            mimeFilter={settings.mime_filter}
+            skipRecentlyModifiedSecs={settings.skip_recently_modified_secs}
+            peekArchives={settings.peek_archives}
+            canPeekArchives={canPeekArchives}
+            parallelWalk={settings.parallel_walk}
+            skipTrashDirs={settings.skip_trash_dirs}
+            maxFilesPerGroup={settings.max_files_per_group}
             namePrefix={settings.name_prefix}
             skipSameFolderPrompt={settings.skip_same_folder_prompt}
+            trashLimitMb={settings.trash_limit_mb}
+            ignoredGroupCount={settings.ignored_groups.length}
+            onClearIgnored={handleClearIgnored}
             onChange={updateSetting}
           />
         )}
@@ -879,9 +1260,11 @@ export default function App() {
         {isAutoRenamer && (
           <AutoRenamerPanel
             includeSubfolders={settings.include_subfolders}
+            onlyChanging={autoOnlyChanging}
             prefixScan={settings.name_prefix}
             prefixSearch={autoPrefixSearch}
             fileTypePreset={settings.auto_file_type_preset}
+            customFileTypePresets={settings.custom_file_type_presets}
             extensionFilter={extensionFilter}
             minSizeMb={minSizeMb}
             maxSizeMb={maxSizeMb}
@@ -890,6 +1273,7 @@ export default function App() {
             onIncludeSubfoldersChange={(value) =>
               updateSetting("include_subfolders", value)
             }
+            onOnlyChangingChange={setAutoOnlyChanging}
             onPrefixScanChange={(value) => updateSetting("name_prefix", value)}
             onPrefixSearchChange={setAutoPrefixSearch}
             onFileTypePresetChange={(value) =>
@@ -904,6 +1288,38 @@ export default function App() {
             onRenameSeparatorChange={(sep: string) =>
               setSettings((s) => ({ ...s, rename_separator: sep }))
             }
+            createdFallback={settings.rename_created_fallback}
+            onCreatedFallbackChange={(value) =>
+              updateSetting("rename_created_fallback", value)
+            }
+            dateFormat={settings.rename_date_format}
+            timeFormat={settings.rename_time_format}
+            onDateFormatChange={(value) => updateSetting("rename_date_format", value)}
+            onTimeFormatChange={(value) => updateSetting("rename_time_format", value)}
+            sequenceSeparator={settings.rename_sequence_separator}
+            onSequenceSeparatorChange={(value) =>
+              updateSetting("rename_sequence_separator", value)
+            }
+            renameSymlinks={settings.rename_symlinks}
+            onRenameSymlinksChange={(value) => updateSetting("rename_symlinks", value)}
+            skipDuplicateContent={settings.rename_skip_duplicate_content}
+            onSkipDuplicateContentChange={(value) =>
+              updateSetting("rename_skip_duplicate_content", value)
+            }
+            inferMissingExtension={settings.rename_infer_missing_extension}
+            onInferMissingExtensionChange={(value) =>
+              updateSetting("rename_infer_missing_extension", value)
+            }
+            overwriteExisting={settings.rename_collision_policy === "overwrite"}
+            onOverwriteExistingChange={(value) =>
+              updateSetting("rename_collision_policy", value ? "overwrite" : "sequence")
+            }
+            strict={settings.rename_strict}
+            onStrictChange={(value) => updateSetting("rename_strict", value)}
+            revealOnComplete={settings.rename_reveal_on_complete}
+            onRevealOnCompleteChange={(value) =>
+              updateSetting("rename_reveal_on_complete", value)
+            }
           />
         )}
 
@@ -929,6 +1345,14 @@ export default function App() {
               : `Auto-rename (${filteredAutoCandidates.length})`}
           </button>
         )}
+        {isAutoRenamer && autoRenaming && (
+          <button
+            onClick={() => void api.cancelRename()}
+            className="px-6 py-2 text-sm font-semibold rounded-md bg-gray-100 text-gray-800 hover:bg-gray-200 dark:bg-gray-800 dark:text-gray-300 dark:hover:bg-gray-700"
+          >
+            Stop after current file
+          </button>
+        )}
 
         {/* Status bar */}
         <div>
@@ -937,6 +1361,7 @@ export default function App() {
             <AutoRenameStatus
               scanning={autoScanning}
               renaming={autoRenaming}
+              progress={scanProgress}
               scanResult={autoScanResult}
               days={settings.days}
               prefixScan={settings.name_prefix}
@@ -962,17 +1387,24 @@ export default function App() {
           )}
         </div>
 
+        {/* Size histogram (advanced only) */}
+        {isAdvanced && !scanning && scanResult && (
+          <SizeHistogram buckets={scanResult.size_histogram} />
+        )}
+
         {/* Results table (advanced only) */}
         {isAdvanced && (
           <ResultsTable
             groups={scanResult?.groups ?? []}
             selectedPaths={selectedPaths}
+            selectedSizeLabel={selectedBytes === null ? undefined : `Selected: ${humanSize(selectedBytes)}`}
             onSelectionChange={setSelectedPaths}
             filterText={filterText}
             onFilterChange={setFilterText}
-            onOpenFolder={handleOpenFolder}
+            onRevealFile={handleRevealFile}
             onDeleteSelected={handleDeleteSelected}
             onCopyReport={handleCopyReport}
+            onIgnoreGroup={handleIgnoreGroup}
             hasResults={hasResults}
           />
         )}
@@ -985,6 +1417,10 @@ export default function App() {
             prefixSearch={autoPrefixSearch}
             renameComponents={settings.rename_components}
             renameSeparator={settings.rename_separator}
+            createdFallback={settings.rename_created_fallback}
+            dateFormat={settings.rename_date_format}
+            timeFormat={settings.rename_time_format}
+            sequenceSeparator={settings.rename_sequence_separator}
           />
         )}
       </div>
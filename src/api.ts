@@ -1,17 +1,116 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
   AppSettings,
+  AutoDedupeReport,
   AutoRenameResult,
   AutoRenameScanParams,
   AutoRenameScanResult,
+  CanonicalizeReport,
+  CommandError,
+  CompareResult,
+  DeleteHistoryEntry,
+  DigestEncoding,
+  DeleteSummary,
+  DuplicateGroup,
+  FolderPrecheck,
+  HashAlgorithm,
+  KeepStrategy,
+  ManifestExport,
+  MasterScanParams,
+  OrganizeReport,
+  RecencyBasis,
+  RemoveEmptyDirsResult,
   RenameSchema,
+  RestoreSummary,
+  ScanDiff,
+  ScanMeta,
   ScanParams,
   ScanResult,
+  ScanSummary,
+  ScanTimeEstimate,
+  Theme,
+  VerifyEntry,
 } from "./types";
 
-/** Scan a folder for duplicate files. */
-export async function scan(params: ScanParams): Promise<ScanResult> {
-  return invoke("cmd_scan", { ...params });
+/** True when a rejected command value is a structured `CommandError`. */
+export function isCommandError(e: unknown): e is CommandError {
+  return (
+    typeof e === "object" &&
+    e !== null &&
+    typeof (e as CommandError).code === "string" &&
+    typeof (e as CommandError).message === "string"
+  );
+}
+
+/** Human-readable text for a rejected command value. */
+export function errorMessage(e: unknown): string {
+  return isCommandError(e) ? e.message : String(e);
+}
+
+/**
+ * Scan a folder for duplicate files.  With `pageSize`, only the first
+ * `pageSize` groups are returned; fetch the rest with `getScanPage`.
+ */
+export async function scan(
+  params: ScanParams,
+  pageSize: number | null = null,
+): Promise<ScanResult> {
+  return invoke("cmd_scan", { ...params, page_size: pageSize });
+}
+
+/** Up to `limit` groups of scan `scanId`, from `offset` on. */
+export async function getScanPage(
+  scanId: number,
+  offset: number,
+  limit: number,
+): Promise<DuplicateGroup[]> {
+  return invoke("cmd_get_scan_page", { scan_id: scanId, offset, limit });
+}
+
+/** Totals of scan `scanId`, including how many groups it pages through. */
+export async function getScanMeta(scanId: number): Promise<ScanMeta> {
+  return invoke("cmd_get_scan_meta", { scan_id: scanId });
+}
+
+/** Scan like `scan`, returning only the totals (no files listed). */
+export async function scanSummary(
+  params: Omit<ScanParams, "max_files_per_group" | "max_groups">,
+): Promise<ScanSummary> {
+  return invoke("cmd_scan_summary_only", { ...params });
+}
+
+/** Re-scan a folder, reusing keys of unchanged files from `previous`. */
+export async function rescan(
+  previous: ScanResult,
+  params: ScanParams,
+): Promise<ScanResult> {
+  return invoke("cmd_rescan", { previous, ...params });
+}
+
+/** The last scan saved on disk (vanished files dropped), or null if none. */
+export async function loadLastScan(): Promise<ScanResult | null> {
+  return invoke("cmd_load_last_scan");
+}
+
+/** Groups that appeared, disappeared, grew or shrank between two scans. */
+export async function diffScans(old: ScanResult, next: ScanResult): Promise<ScanDiff> {
+  return invoke("cmd_diff_scans", { old, new: next });
+}
+
+/** Find files in other folders that duplicate a file in the master folder. */
+export async function scanAgainstMaster(
+  params: MasterScanParams,
+): Promise<ScanResult> {
+  return invoke("cmd_scan_dedupe_against_master", { ...params });
+}
+
+/** Find duplicate images (judged by content, grouped by size, dimensions and hash). */
+export async function scanImages(
+  folder: string,
+  days: number,
+  includeSubfolders: boolean,
+): Promise<ScanResult> {
+  return invoke("cmd_scan_images", { folder, days, include_subfolders: includeSubfolders });
 }
 
 /** Scan a folder for auto-renamer candidates. */
@@ -21,17 +120,213 @@ export async function scanAutoRename(
   return invoke("cmd_scan_auto_rename", { ...params });
 }
 
+/** Name `renameSchema` would give the file at `path`; nothing is renamed. */
+export async function previewName(
+  path: string,
+  renameSchema: RenameSchema,
+): Promise<string> {
+  return invoke("cmd_preview_name", { path, rename_schema: renameSchema });
+}
+
 /** Rename files using the provided schema. */
 export async function autoRename(
   paths: string[],
   renameSchema: RenameSchema,
+  revealOnComplete = false,
 ): Promise<AutoRenameResult> {
-  return invoke("cmd_auto_rename", { paths, rename_schema: renameSchema });
+  return invoke("cmd_auto_rename", {
+    paths,
+    rename_schema: renameSchema,
+    reveal_on_complete: revealOnComplete,
+  });
+}
+
+/** Move files into YYYY-MM subfolders of `baseDir`, keeping their names. */
+export async function organizeByDate(
+  paths: string[],
+  baseDir: string,
+  basis: RecencyBasis = "modified",
+): Promise<OrganizeReport> {
+  return invoke("cmd_organize_by_date", { paths, base_dir: baseDir, basis });
+}
+
+/** Stop the running auto-rename after the file it is on. */
+export async function cancelRename(): Promise<void> {
+  return invoke("cmd_cancel_rename");
+}
+
+/**
+ * Delete files (move to trash).  Files on volumes without a trash come back
+ * in `needs_confirmation` unless `allowPermanent` is set.  Files the trash
+ * keeps failing on come back in `errors` unless `permanentOnTrashFailure`
+ * is set, which deletes them permanently.  A `dryRun` only checks the paths and
+ * reports what a real delete would do.  With
+ * `verifyAgainst`, each path is re-checked against its group's key first;
+ * files that changed since the scan are kept and listed in `spared`.
+ */
+export async function deleteFiles(
+  paths: string[],
+  allowPermanent = false,
+  dryRun = false,
+  verifyAgainst: DuplicateGroup[] | null = null,
+  trashLimitBytes: number | null = null,
+  permanentOnTrashFailure = false,
+): Promise<DeleteSummary> {
+  return invoke("cmd_delete", {
+    paths,
+    allow_permanent: allowPermanent,
+    permanent_on_trash_failure: permanentOnTrashFailure,
+    dry_run: dryRun,
+    verify_against: verifyAgainst,
+    trash_limit_bytes: trashLimitBytes,
+  });
+}
+
+/**
+ * Move trashed files (see `DeleteSummary.trashed`) back to their original
+ * paths.  Rejects with an `unsupported` error where the trash cannot be
+ * listed (macOS).
+ */
+export async function restoreFromTrash(originalPaths: string[]): Promise<RestoreSummary> {
+  return invoke("cmd_restore_from_trash", { original_paths: originalPaths });
+}
+
+/** The newest `limit` files the app deleted, across sessions, newest first. */
+export async function getDeleteHistory(limit = 100): Promise<DeleteHistoryEntry[]> {
+  return invoke("cmd_get_delete_history", { limit });
+}
+
+/**
+ * Keep one file per group (picked by `strategy`) and delete the rest,
+ * reporting per group which path was kept and which were deleted.
+ */
+export async function autoDedupe(
+  groups: DuplicateGroup[],
+  strategy: KeepStrategy,
+  allowPermanent = false,
+  trashLimitBytes: number | null = null,
+): Promise<AutoDedupeReport> {
+  return invoke("cmd_auto_dedupe", {
+    groups,
+    strategy,
+    allow_permanent: allowPermanent,
+    trash_limit_bytes: trashLimitBytes,
+  });
 }
 
-/** Delete files (move to trash). */
-export async function deleteFiles(paths: string[]): Promise<number> {
-  return invoke("cmd_delete", { paths });
+/**
+ * What `autoDedupe` would do with `groups`, without deleting anything:
+ * the keeper of each group with its reason, the files that would go and
+ * the bytes that would be reclaimed.
+ */
+export async function dryRunAutoDedupe(
+  groups: DuplicateGroup[],
+  strategy: KeepStrategy,
+): Promise<AutoDedupeReport> {
+  return invoke("cmd_dry_run_auto_dedupe", { groups, strategy });
+}
+
+/**
+ * Collapse byte-identical files into one: keep the file `strategy` picks,
+ * delete the others and give the survivor its `renameSchema` name.
+ */
+export async function canonicalizeDuplicates(
+  groupPaths: string[],
+  renameSchema: RenameSchema,
+  strategy: KeepStrategy,
+  allowPermanent = false,
+  trashLimitBytes: number | null = null,
+): Promise<CanonicalizeReport> {
+  return invoke("cmd_canonicalize_duplicates", {
+    group_paths: groupPaths,
+    rename_schema: renameSchema,
+    strategy,
+    allow_permanent: allowPermanent,
+    trash_limit_bytes: trashLimitBytes,
+  });
+}
+
+/** Remove empty directories under a folder (never the folder itself). */
+export async function removeEmptyDirs(
+  folder: string,
+  includeSubfolders: boolean,
+): Promise<RemoveEmptyDirsResult> {
+  return invoke("cmd_remove_empty_dirs", {
+    folder,
+    include_subfolders: includeSubfolders,
+  });
+}
+
+/** Check whether two files are byte-identical. */
+export async function compareTwoFiles(
+  a: string,
+  b: string,
+): Promise<CompareResult> {
+  return invoke("cmd_compare_two_files", { a, b });
+}
+
+/** Hash one file; large files emit "hash-progress" events while reading. */
+export async function hashFile(
+  path: string,
+  algo: HashAlgorithm,
+  encoding?: DigestEncoding,
+): Promise<string> {
+  return invoke("cmd_hash_file", { path, algo, encoding });
+}
+
+/**
+ * Write a sha256sum manifest of the files in a folder.  Emits "scan-progress"
+ * events in the "manifest" phase; `cancelManifest` stops it early.
+ */
+export async function exportManifest(
+  folder: string,
+  includeSubfolders: boolean,
+  outputPath: string,
+  encoding?: DigestEncoding,
+): Promise<ManifestExport> {
+  return invoke("cmd_export_manifest", {
+    folder,
+    include_subfolders: includeSubfolders,
+    output_path: outputPath,
+    encoding,
+  });
+}
+
+/** Stop the running manifest export after the file it is on. */
+export async function cancelManifest(): Promise<void> {
+  return invoke("cmd_cancel_manifest");
+}
+
+/** Re-hash the files listed in a sha256sum manifest to find changed or missing ones. */
+export async function verifyManifest(manifestPath: string): Promise<VerifyEntry[]> {
+  return invoke("cmd_verify_manifest", { manifest_path: manifestPath });
+}
+
+/** Probe a folder (exists / readable / rough file count) before scanning. */
+export async function validateFolder(folder: string): Promise<FolderPrecheck> {
+  return invoke("cmd_validate_folder", { folder });
+}
+
+/** Estimate how long a scan would take; `hashMaxMb` null means no hash limit. */
+export async function estimateScanTime(
+  folder: string,
+  days: number,
+  useHash: boolean,
+  hashMaxMb: number | null,
+  includeSubfolders: boolean,
+): Promise<ScanTimeEstimate> {
+  return invoke("cmd_estimate_scan_time", {
+    folder,
+    days,
+    use_hash: useHash,
+    hash_max_mb: hashMaxMb,
+    include_subfolders: includeSubfolders,
+  });
+}
+
+/** Sum the on-disk sizes of the given paths (missing files count as 0). */
+export async function computeSelectionSize(paths: string[]): Promise<number> {
+  return invoke("cmd_compute_selection_size", { paths });
 }
 
 /** Load settings from disk. */
@@ -39,17 +334,57 @@ export async function getSettings(): Promise<AppSettings> {
   return invoke("cmd_get_settings");
 }
 
+/** Recent folders that still exist; stale entries are pruned on disk. */
+export async function getRecentFolders(): Promise<string[]> {
+  return invoke("cmd_get_recent_folders");
+}
+
+/** Move `path` to the front of the persisted recent folders. */
+export async function addRecentFolder(path: string): Promise<string[]> {
+  return invoke("cmd_add_recent_folder", { path });
+}
+
+/** Hide the group with `signature` from later scans. */
+export async function ignoreGroup(signature: string): Promise<void> {
+  return invoke("cmd_ignore_group", { signature });
+}
+
+/** Show every ignored group again; returns how many were ignored. */
+export async function clearIgnored(): Promise<number> {
+  return invoke("cmd_clear_ignored");
+}
+
 /** Save settings to disk. */
 export async function saveSettings(settings: AppSettings): Promise<void> {
   return invoke("cmd_save_settings", { settings });
 }
 
+/** Save just the theme; every window is told through `theme-changed`. */
+export async function setTheme(theme: Theme): Promise<void> {
+  return invoke("cmd_set_theme", { theme });
+}
+
 /** Get the default downloads folder path. */
 export async function getDefaultFolder(): Promise<string> {
   return invoke("cmd_get_default_folder");
 }
 
+/** Whether this build can look inside .zip archives while scanning. */
+export async function canPeekArchives(): Promise<boolean> {
+  return invoke("cmd_can_peek_archives");
+}
+
 /** Open a folder in the system file manager. */
 export async function openFolder(path: string): Promise<void> {
   return invoke("cmd_open_folder", { path });
 }
+
+/** True when another program holds the file open (Windows only). */
+export async function isFileLocked(path: string): Promise<boolean> {
+  return invoke("cmd_is_file_locked", { path });
+}
+
+/** Show a file in the system file manager, selected where supported. */
+export async function revealFile(path: string): Promise<void> {
+  return invoke("cmd_reveal_file", { path });
+}
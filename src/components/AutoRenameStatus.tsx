@@ -1,8 +1,14 @@
-import type { AutoFileTypePreset, AutoRenameScanResult } from "../types";
+import type {
+  AutoFileTypePreset,
+  AutoRenameScanResult,
+  BuiltinFileTypePreset,
+  ScanProgress,
+} from "../types";
 
 interface AutoRenameStatusProps {
   scanning: boolean;
   renaming: boolean;
+  progress: ScanProgress | null;
   scanResult: AutoRenameScanResult | null;
   days: number;
   prefixScan: string;
@@ -23,7 +29,7 @@ function formatSkipNotice(scanSkipped: number, reasons: AutoRenameScanResult["sc
   return `Skipped ${scanSkipped} file(s) during scan (permissions: ${reasons.permissions}, missing: ${reasons.missing}, transient I/O: ${reasons.transient_io}).`;
 }
 
-const FILE_TYPE_LABELS: Record<AutoFileTypePreset, string> = {
+const FILE_TYPE_LABELS: Record<BuiltinFileTypePreset, string> = {
   all: "all",
   images: "images",
   videos: "videos",
@@ -35,6 +41,7 @@ const FILE_TYPE_LABELS: Record<AutoFileTypePreset, string> = {
 export default function AutoRenameStatus({
   scanning,
   renaming,
+  progress,
   scanResult,
   days,
   prefixScan,
@@ -55,7 +62,9 @@ export default function AutoRenameStatus({
   if (renaming) {
     return (
       <div className="border border-gray-200 dark:border-gray-700 rounded-md p-3 bg-white dark:bg-gray-800">
-        <p className="text-sm text-gray-600 dark:text-gray-400">Renaming files...</p>
+        <p className="text-sm text-gray-600 dark:text-gray-400">
+          {progress?.phase === "renaming" ? progress.message : "Renaming files..."}
+        </p>
       </div>
     );
   }
@@ -69,7 +78,7 @@ export default function AutoRenameStatus({
   }
 
   const scope = days <= 0 ? "all time" : `last ${days} day(s)`;
-  let summary = `Found ${scanResult.candidates.length} candidate file(s) (${scope}, ${FILE_TYPE_LABELS[fileTypePreset]}).`;
+  let summary = `Found ${scanResult.candidates.length} candidate file(s) (${scope}, ${FILE_TYPE_LABELS[fileTypePreset as BuiltinFileTypePreset] ?? fileTypePreset}).`;
   if (prefixScan) summary += ` Prefix scan: '${prefixScan}'.`;
   if (prefixSearch) summary += ` Prefix search: '${prefixSearch}'.`;
   if (!includeSubfolders) summary += " Subfolders: off.";
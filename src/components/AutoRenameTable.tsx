@@ -1,5 +1,5 @@
 import { useMemo } from "react";
-import type { AutoRenameCandidateDto, RenameComponent } from "../types";
+import type { AutoRenameCandidateDto, CreatedFallback, RenameComponent } from "../types";
 import { buildAllPreviews } from "../utils/renamePreview";
 
 interface AutoRenameTableProps {
@@ -8,6 +8,10 @@ interface AutoRenameTableProps {
   prefixSearch: string;
   renameComponents: RenameComponent[];
   renameSeparator: string;
+  createdFallback: CreatedFallback;
+  dateFormat: string;
+  timeFormat: string;
+  sequenceSeparator: string | null;
 }
 
 export default function AutoRenameTable({
@@ -16,11 +20,29 @@ export default function AutoRenameTable({
   prefixSearch,
   renameComponents,
   renameSeparator,
+  createdFallback,
+  dateFormat,
+  timeFormat,
+  sequenceSeparator,
 }: AutoRenameTableProps) {
   // Two-pass collision-aware preview names.
   const previews = useMemo(
-    () => buildAllPreviews(candidates, renameComponents, renameSeparator),
-    [candidates, renameComponents, renameSeparator],
+    () =>
+      buildAllPreviews(candidates, renameComponents, renameSeparator, {
+        createdFallback,
+        dateFormat,
+        timeFormat,
+        sequenceSeparator,
+      }),
+    [
+      candidates,
+      renameComponents,
+      renameSeparator,
+      createdFallback,
+      dateFormat,
+      timeFormat,
+      sequenceSeparator,
+    ],
   );
 
   function exportCsv() {
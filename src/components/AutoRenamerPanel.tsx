@@ -1,17 +1,29 @@
-import type { AutoFileTypePreset, RenameComponent } from "../types";
+import type {
+  AutoFileTypePreset,
+  CreatedFallback,
+  FileTypePresets,
+  RenameComponent,
+} from "../types";
 import RenameComponentBuilder from "./RenameComponentBuilder";
 
 interface AutoRenamerPanelProps {
   includeSubfolders: boolean;
+  onlyChanging: boolean;
   prefixScan: string;
   prefixSearch: string;
   fileTypePreset: AutoFileTypePreset;
+  /** User-defined presets, listed after the built-ins. */
+  customFileTypePresets: FileTypePresets;
   extensionFilter: string;
   minSizeMb: string;
   maxSizeMb: string;
   renameComponents: RenameComponent[];
   renameSeparator: string;
+  createdFallback: CreatedFallback;
+  dateFormat: string;
+  timeFormat: string;
   onIncludeSubfoldersChange: (value: boolean) => void;
+  onOnlyChangingChange: (value: boolean) => void;
   onPrefixScanChange: (value: string) => void;
   onPrefixSearchChange: (value: string) => void;
   onFileTypePresetChange: (value: AutoFileTypePreset) => void;
@@ -20,6 +32,24 @@ interface AutoRenamerPanelProps {
   onMaxSizeMbChange: (value: string) => void;
   onRenameComponentsChange: (components: RenameComponent[]) => void;
   onRenameSeparatorChange: (separator: string) => void;
+  onCreatedFallbackChange: (value: CreatedFallback) => void;
+  onDateFormatChange: (value: string) => void;
+  onTimeFormatChange: (value: string) => void;
+  /** null attaches sequence numbers with the main separator. */
+  sequenceSeparator: string | null;
+  onSequenceSeparatorChange: (value: string | null) => void;
+  renameSymlinks: boolean;
+  onRenameSymlinksChange: (value: boolean) => void;
+  skipDuplicateContent: boolean;
+  onSkipDuplicateContentChange: (value: boolean) => void;
+  inferMissingExtension: boolean;
+  onInferMissingExtensionChange: (value: boolean) => void;
+  overwriteExisting: boolean;
+  onOverwriteExistingChange: (value: boolean) => void;
+  strict: boolean;
+  onStrictChange: (value: boolean) => void;
+  revealOnComplete: boolean;
+  onRevealOnCompleteChange: (value: boolean) => void;
 }
 
 const FILE_TYPE_OPTIONS: Array<{ value: AutoFileTypePreset; label: string }> = [
@@ -31,17 +61,30 @@ const FILE_TYPE_OPTIONS: Array<{ value: AutoFileTypePreset; label: string }> = [
   { value: "archives", label: "Archives" },
 ];
 
+const CREATED_FALLBACK_OPTIONS: Array<{ value: CreatedFallback; label: string }> = [
+  { value: "now", label: "Current time" },
+  { value: "modified", label: "Modified time" },
+  { value: "skip_component", label: "Leave it out" },
+];
+
 export default function AutoRenamerPanel({
   includeSubfolders,
+  onlyChanging,
   prefixScan,
   prefixSearch,
   fileTypePreset,
+  customFileTypePresets,
   extensionFilter,
   minSizeMb,
   maxSizeMb,
   renameComponents,
   renameSeparator,
+  createdFallback,
+  dateFormat,
+  timeFormat,
+  sequenceSeparator,
   onIncludeSubfoldersChange,
+  onOnlyChangingChange,
   onPrefixScanChange,
   onPrefixSearchChange,
   onFileTypePresetChange,
@@ -50,7 +93,33 @@ export default function AutoRenamerPanel({
   onMaxSizeMbChange,
   onRenameComponentsChange,
   onRenameSeparatorChange,
+  onCreatedFallbackChange,
+  onDateFormatChange,
+  onTimeFormatChange,
+  onSequenceSeparatorChange,
+  renameSymlinks,
+  onRenameSymlinksChange,
+  skipDuplicateContent,
+  onSkipDuplicateContentChange,
+  inferMissingExtension,
+  onInferMissingExtensionChange,
+  overwriteExisting,
+  onOverwriteExistingChange,
+  strict,
+  onStrictChange,
+  revealOnComplete,
+  onRevealOnCompleteChange,
 }: AutoRenamerPanelProps) {
+  // A custom preset replaces the built-in of the same name.
+  const customNames = Object.keys(customFileTypePresets).map((name) => name.toLowerCase());
+  const fileTypeOptions = [
+    ...FILE_TYPE_OPTIONS.filter((option) => !customNames.includes(option.value)),
+    ...Object.entries(customFileTypePresets).map(([name, extensions]) => ({
+      value: name,
+      label: `${name} (${extensions.join(", ")})`,
+    })),
+  ];
+
   return (
     <div className="space-y-4">
       {/* Scan controls */}
@@ -64,6 +133,18 @@ export default function AutoRenamerPanel({
           />
           Include subfolders
         </label>
+        <label
+          className="flex items-center gap-1.5 text-sm"
+          title="Leave out files whose name already matches the rename schema"
+        >
+          <input
+            type="checkbox"
+            checked={onlyChanging}
+            onChange={(e) => onOnlyChangingChange(e.target.checked)}
+            className="rounded"
+          />
+          Only files that would be renamed
+        </label>
         <div className="flex items-center gap-2">
           <span className="text-sm text-gray-700 dark:text-gray-300">Prefix scan:</span>
           <input
@@ -102,7 +183,7 @@ export default function AutoRenamerPanel({
               onChange={(e) => onFileTypePresetChange(e.target.value as AutoFileTypePreset)}
               className="border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm bg-white dark:bg-gray-700 dark:text-gray-100"
             >
-              {FILE_TYPE_OPTIONS.map((option) => (
+              {fileTypeOptions.map((option) => (
                 <option key={option.value} value={option.value}>
                   {option.label}
                 </option>
@@ -158,7 +239,108 @@ export default function AutoRenamerPanel({
           separator={renameSeparator}
           onComponentsChange={onRenameComponentsChange}
           onSeparatorChange={onRenameSeparatorChange}
+          nameOptions={{ createdFallback, dateFormat, timeFormat, sequenceSeparator }}
         />
+        <div className="mt-3 flex flex-wrap items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+          <label className="flex items-center gap-2" title="strftime pattern, e.g. %d%m%Y or %Y-%m-%d">
+            Date format
+            <input
+              type="text"
+              value={dateFormat}
+              onChange={(e) => onDateFormatChange(e.target.value)}
+              placeholder="%Y%m%d"
+              className="w-28 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm font-mono dark:bg-gray-700 dark:text-gray-100"
+            />
+          </label>
+          <label className="flex items-center gap-2" title="strftime pattern, e.g. %H%M or %Hh%M">
+            Time format
+            <input
+              type="text"
+              value={timeFormat}
+              onChange={(e) => onTimeFormatChange(e.target.value)}
+              placeholder="%H%M%S"
+              className="w-28 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm font-mono dark:bg-gray-700 dark:text-gray-100"
+            />
+          </label>
+          <label className="flex items-center gap-2" title="Joins sequence numbers to the name; leave empty to use the separator">
+            Sequence separator
+            <input
+              type="text"
+              value={sequenceSeparator ?? ""}
+              onChange={(e) => onSequenceSeparatorChange(e.target.value || null)}
+              placeholder="same"
+              className="w-16 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm font-mono dark:bg-gray-700 dark:text-gray-100"
+            />
+          </label>
+        </div>
+        <label className="mt-3 flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+          If created time is missing, use
+          <select
+            value={createdFallback}
+            onChange={(e) => onCreatedFallbackChange(e.target.value as CreatedFallback)}
+            className="border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm dark:bg-gray-700 dark:text-gray-100"
+          >
+            {CREATED_FALLBACK_OPTIONS.map((opt) => (
+              <option key={opt.value} value={opt.value}>
+                {opt.label}
+              </option>
+            ))}
+          </select>
+        </label>
+        <label className="mt-2 flex items-center gap-1.5 text-sm text-gray-700 dark:text-gray-300">
+          <input
+            type="checkbox"
+            checked={renameSymlinks}
+            onChange={(e) => onRenameSymlinksChange(e.target.checked)}
+            className="rounded"
+          />
+          Rename symbolic links themselves (even broken ones)
+        </label>
+        <label className="mt-2 flex items-center gap-1.5 text-sm text-gray-700 dark:text-gray-300">
+          <input
+            type="checkbox"
+            checked={skipDuplicateContent}
+            onChange={(e) => onSkipDuplicateContentChange(e.target.checked)}
+            className="rounded"
+          />
+          Skip files whose content already exists in their folder
+        </label>
+        <label className="mt-2 flex items-center gap-1.5 text-sm text-gray-700 dark:text-gray-300">
+          <input
+            type="checkbox"
+            checked={inferMissingExtension}
+            onChange={(e) => onInferMissingExtensionChange(e.target.checked)}
+            className="rounded"
+          />
+          Add an extension to files without one, from their content
+        </label>
+        <label className="mt-2 flex items-center gap-1.5 text-sm text-gray-700 dark:text-gray-300">
+          <input
+            type="checkbox"
+            checked={overwriteExisting}
+            onChange={(e) => onOverwriteExistingChange(e.target.checked)}
+            className="rounded"
+          />
+          Replace existing files with the target name instead of numbering
+        </label>
+        <label className="mt-2 flex items-center gap-1.5 text-sm text-gray-700 dark:text-gray-300">
+          <input
+            type="checkbox"
+            checked={strict}
+            onChange={(e) => onStrictChange(e.target.checked)}
+            className="rounded"
+          />
+          Reject text with characters not allowed in file names instead of replacing them
+        </label>
+        <label className="mt-2 flex items-center gap-1.5 text-sm text-gray-700 dark:text-gray-300">
+          <input
+            type="checkbox"
+            checked={revealOnComplete}
+            onChange={(e) => onRevealOnCompleteChange(e.target.checked)}
+            className="rounded"
+          />
+          Offer to open the folder of the renamed files when done
+        </label>
       </fieldset>
     </div>
   );
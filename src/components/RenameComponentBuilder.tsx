@@ -15,9 +15,9 @@
  */
 
 import { useCallback, useRef, useState } from "react";
-import type { RenameComponent, RenameComponentKind } from "../types";
+import type { HashAlgorithm, RenameComponent, RenameComponentKind } from "../types";
 import { DEFAULT_RENAME_COMPONENTS } from "../types";
-import { buildPreview } from "../utils/renamePreview";
+import { buildPreview, type NameOptions } from "../utils/renamePreview";
 
 // ---------------------------------------------------------------------------
 // Types
@@ -28,6 +28,8 @@ interface RenameComponentBuilderProps {
   separator: string;
   onComponentsChange: (components: RenameComponent[]) => void;
   onSeparatorChange: (separator: string) => void;
+  /** Fallback and date/time patterns applied to the sample preview. */
+  nameOptions?: NameOptions;
 }
 
 // ---------------------------------------------------------------------------
@@ -37,23 +39,33 @@ interface RenameComponentBuilderProps {
 const ADD_OPTIONS: Array<{ kind: RenameComponentKind; label: string }> = [
   { kind: "folder_name", label: "Folder name" },
   { kind: "original_stem", label: "Original name" },
+  { kind: "stem_words", label: "Words from name" },
   { kind: "date_created", label: "Date (created)" },
   { kind: "date_modified", label: "Date (modified)" },
   { kind: "time_created", label: "Time (created)" },
   { kind: "time_modified", label: "Time (modified)" },
   { kind: "sequence", label: "Sequence number" },
+  { kind: "alpha_sequence", label: "Sequence letter" },
+  { kind: "folder_counter", label: "Counter per folder" },
   { kind: "literal", label: "Fixed text\u2026" },
+  { kind: "hash_fragment", label: "Content hash" },
+  { kind: "sidecar_field", label: "Sidecar field\u2026" },
 ];
 
 const CHIP_LABELS: Record<RenameComponentKind, string> = {
   folder_name: "Folder",
   original_stem: "Name",
+  stem_words: "Words",
   date_created: "DateC",
   date_modified: "DateM",
   time_created: "TimeC",
   time_modified: "TimeM",
   sequence: "Seq",
+  alpha_sequence: "SeqA",
   literal: "Text",
+  hash_fragment: "Hash",
+  folder_counter: "Count",
+  sidecar_field: "Sidecar",
 };
 
 // ---------------------------------------------------------------------------
@@ -70,6 +82,7 @@ export default function RenameComponentBuilder({
   separator,
   onComponentsChange,
   onSeparatorChange,
+  nameOptions,
 }: RenameComponentBuilderProps) {
   const dragSrcIdx = useRef<number | null>(null);
   const [showAdd, setShowAdd] = useState(false);
@@ -81,7 +94,25 @@ export default function RenameComponentBuilder({
   const addComponent = (kind: RenameComponentKind) => {
     const comp: RenameComponent = { id: nextId(), kind };
     if (kind === "sequence") comp.pad_width = 3;
+    if (kind === "alpha_sequence") comp.uppercase = false;
     if (kind === "literal") comp.value = "text";
+    if (kind === "stem_words") {
+      comp.keep = 2;
+      comp.from_end = true;
+    }
+    if (kind === "hash_fragment") {
+      comp.algo = "sha256";
+      comp.chars = 8;
+    }
+    if (kind === "sidecar_field") {
+      comp.extension = "json";
+      comp.field = "title";
+    }
+    if (kind === "folder_counter") {
+      comp.start = 1;
+      comp.step = 1;
+      comp.pad_width = 3;
+    }
     onComponentsChange([...components, comp]);
     setShowAdd(false);
   };
@@ -195,7 +226,7 @@ export default function RenameComponentBuilder({
   };
 
   // --- live preview ---
-  const preview = buildPreview(components, separator);
+  const preview = buildPreview(components, separator, nameOptions);
 
   return (
     <div className="space-y-3">
@@ -361,6 +392,130 @@ function Chip({ comp, focused, onRemove, onUpdate, onFocus, onDragStart, onDragO
         />
       )}
 
+      {/* Inline editable: sequence letter case */}
+      {comp.kind === "alpha_sequence" && (
+        <select
+          value={comp.uppercase ? "upper" : "lower"}
+          onChange={(e) => onUpdate({ uppercase: e.target.value === "upper" })}
+          onClick={(e) => e.stopPropagation()}
+          title="Letter case"
+          className="bg-transparent border-b border-blue-400 dark:border-blue-500 outline-none text-xs"
+        >
+          <option value="lower">a</option>
+          <option value="upper">A</option>
+        </select>
+      )}
+
+      {/* Inline editable: folder counter start, step, and pad width */}
+      {comp.kind === "folder_counter" && (
+        <>
+          <input
+            type="number"
+            value={comp.start ?? 1}
+            min={0}
+            onChange={(e) => onUpdate({ start: Math.max(0, Math.floor(Number(e.target.value)) || 0) })}
+            onClick={(e) => e.stopPropagation()}
+            title="First number in each folder"
+            className="w-10 bg-transparent border-b border-blue-400 dark:border-blue-500 outline-none text-xs text-center px-0.5"
+          />
+          <input
+            type="number"
+            value={comp.step ?? 1}
+            min={1}
+            onChange={(e) => onUpdate({ step: Math.max(1, Math.floor(Number(e.target.value)) || 1) })}
+            onClick={(e) => e.stopPropagation()}
+            title="Step between files"
+            className="w-8 bg-transparent border-b border-blue-400 dark:border-blue-500 outline-none text-xs text-center px-0.5"
+          />
+          <input
+            type="number"
+            value={comp.pad_width ?? 3}
+            min={1}
+            max={9}
+            onChange={(e) => onUpdate({ pad_width: Math.max(1, Math.min(9, Number(e.target.value))) })}
+            onClick={(e) => e.stopPropagation()}
+            title="Pad width (digits)"
+            className="w-8 bg-transparent border-b border-blue-400 dark:border-blue-500 outline-none text-xs text-center px-0.5"
+          />
+        </>
+      )}
+
+      {/* Inline editable: stem word count + direction */}
+      {comp.kind === "stem_words" && (
+        <>
+          <select
+            value={comp.from_end ?? true ? "last" : "first"}
+            onChange={(e) => onUpdate({ from_end: e.target.value === "last" })}
+            onClick={(e) => e.stopPropagation()}
+            title="Take words from the start or end of the name"
+            className="bg-transparent border-b border-blue-400 dark:border-blue-500 outline-none text-xs"
+          >
+            <option value="first">first</option>
+            <option value="last">last</option>
+          </select>
+          <input
+            type="number"
+            value={comp.keep ?? 2}
+            min={1}
+            max={20}
+            onChange={(e) => onUpdate({ keep: Math.max(1, Math.min(20, Number(e.target.value))) })}
+            onClick={(e) => e.stopPropagation()}
+            title="Number of words to keep"
+            className="w-8 bg-transparent border-b border-blue-400 dark:border-blue-500 outline-none text-xs text-center px-0.5"
+          />
+        </>
+      )}
+
+      {/* Inline editable: hash algorithm + digit count */}
+      {comp.kind === "hash_fragment" && (
+        <>
+          <select
+            value={comp.algo ?? "sha256"}
+            onChange={(e) => onUpdate({ algo: e.target.value as HashAlgorithm })}
+            onClick={(e) => e.stopPropagation()}
+            title="Digest algorithm"
+            className="bg-transparent border-b border-blue-400 dark:border-blue-500 outline-none text-xs"
+          >
+            <option value="sha256">SHA-256</option>
+            <option value="sha512">SHA-512</option>
+          </select>
+          <input
+            type="number"
+            value={comp.chars ?? 8}
+            min={1}
+            max={64}
+            onChange={(e) => onUpdate({ chars: Math.max(1, Math.min(64, Number(e.target.value))) })}
+            onClick={(e) => e.stopPropagation()}
+            title="Number of hex digits"
+            className="w-8 bg-transparent border-b border-blue-400 dark:border-blue-500 outline-none text-xs text-center px-0.5"
+          />
+        </>
+      )}
+
+      {/* Inline editable: sidecar extension + field path */}
+      {comp.kind === "sidecar_field" && (
+        <>
+          <input
+            type="text"
+            value={comp.extension ?? ""}
+            onChange={(e) => onUpdate({ extension: e.target.value })}
+            onClick={(e) => e.stopPropagation()}
+            placeholder="json"
+            title="Extension of the sidecar file next to each file"
+            className="w-10 bg-transparent border-b border-blue-400 dark:border-blue-500 outline-none text-xs px-0.5"
+          />
+          <input
+            type="text"
+            value={comp.field ?? ""}
+            onChange={(e) => onUpdate({ field: e.target.value })}
+            onClick={(e) => e.stopPropagation()}
+            placeholder="meta.title"
+            title="Field to use, as a dotted path"
+            className="w-20 bg-transparent border-b border-blue-400 dark:border-blue-500 outline-none text-xs px-0.5"
+          />
+        </>
+      )}
+
       <button
         type="button"
         onClick={(e) => {
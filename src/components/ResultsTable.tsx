@@ -7,12 +7,16 @@ type SortDir = "asc" | "desc";
 interface ResultsTableProps {
   groups: DuplicateGroup[];
   selectedPaths: Set<string>;
+  /** Human-readable size of the selection, when known. */
+  selectedSizeLabel?: string;
   onSelectionChange: (paths: Set<string>) => void;
   filterText: string;
   onFilterChange: (text: string) => void;
-  onOpenFolder: (path: string) => void;
+  onRevealFile: (path: string) => void;
   onDeleteSelected: () => void;
   onCopyReport: () => void;
+  /** Hide a group from this and later scans. */
+  onIgnoreGroup?: (group: DuplicateGroup) => void;
   hasResults: boolean;
 }
 
@@ -58,12 +62,14 @@ function sortFiles(
 export default function ResultsTable({
   groups,
   selectedPaths,
+  selectedSizeLabel,
   onSelectionChange,
   filterText,
   onFilterChange,
-  onOpenFolder,
+  onRevealFile,
   onDeleteSelected,
   onCopyReport,
+  onIgnoreGroup,
   hasResults,
 }: ResultsTableProps) {
   const [expandedGroups, setExpandedGroups] = useState<Set<number>>(
@@ -149,6 +155,7 @@ export default function ResultsTable({
           <span className="text-xs text-gray-500 dark:text-gray-400">
             {selectedCount} file(s) selected / {affectedGroups} group(s)
             affected
+            {selectedCount > 0 && selectedSizeLabel ? ` \u00B7 ${selectedSizeLabel}` : ""}
           </span>
         </div>
         <div className="flex items-center gap-2">
@@ -244,14 +251,17 @@ export default function ResultsTable({
                   allChecked={allChecked}
                   someChecked={someChecked}
                   exampleName={exampleName}
-                  fileCount={group.files.length}
+                  fileCount={group.files.length + group.overflow_count}
+                  overflowCount={group.overflow_count}
                   keyDescription={group.key_description}
+                  confidence={group.confidence}
                   files={sortedFiles}
                   selectedPaths={selectedPaths}
                   onToggleGroup={() => toggleGroup(gi)}
                   onToggleGroupFiles={() => toggleGroupFiles(group.files)}
                   onToggleFile={toggleFile}
-                  onOpenFolder={onOpenFolder}
+                  onRevealFile={onRevealFile}
+                  onIgnore={onIgnoreGroup && (() => onIgnoreGroup(group))}
                 />
               );
             })}
@@ -262,6 +272,12 @@ export default function ResultsTable({
   );
 }
 
+function confidenceClass(confidence: number): string {
+  if (confidence >= 0.9) return "bg-green-100 text-green-800 dark:bg-green-900/40 dark:text-green-300";
+  if (confidence >= 0.5) return "bg-amber-100 text-amber-800 dark:bg-amber-900/40 dark:text-amber-300";
+  return "bg-red-100 text-red-800 dark:bg-red-900/40 dark:text-red-300";
+}
+
 /* Extracted to keep the main component cleaner */
 function GroupRows({
   expanded,
@@ -269,13 +285,16 @@ function GroupRows({
   someChecked,
   exampleName,
   fileCount,
+  overflowCount,
   keyDescription,
+  confidence,
   files,
   selectedPaths,
   onToggleGroup,
   onToggleGroupFiles,
   onToggleFile,
-  onOpenFolder,
+  onRevealFile,
+  onIgnore,
 }: {
   groupIndex: number;
   expanded: boolean;
@@ -283,13 +302,16 @@ function GroupRows({
   someChecked: boolean;
   exampleName: string;
   fileCount: number;
+  overflowCount: number;
   keyDescription: string;
+  confidence: number;
   files: FileEntryDto[];
   selectedPaths: Set<string>;
   onToggleGroup: () => void;
   onToggleGroupFiles: () => void;
   onToggleFile: (path: string) => void;
-  onOpenFolder: (path: string) => void;
+  onRevealFile: (path: string) => void;
+  onIgnore?: () => void;
 }) {
   return (
     <>
@@ -321,10 +343,26 @@ function GroupRows({
           </span>
         </td>
         <td className="px-3 py-1.5 text-gray-500 dark:text-gray-400 text-xs">
+          <span
+            className={`mr-2 px-1.5 py-0.5 rounded font-medium ${confidenceClass(confidence)}`}
+            title="Confidence from the criteria that matched"
+          >
+            {Math.round(confidence * 100)}%
+          </span>
           {keyDescription}
         </td>
         <td className="px-3 py-1.5" />
-        <td className="px-3 py-1.5" />
+        <td className="px-3 py-1.5 text-right">
+          {onIgnore && (
+            <button
+              onClick={onIgnore}
+              title="Hide this group from future scans"
+              className="text-xs text-gray-500 dark:text-gray-400 hover:text-gray-800 dark:hover:text-gray-200"
+            >
+              Ignore
+            </button>
+          )}
+        </td>
       </tr>
       {/* File rows */}
       {expanded &&
@@ -334,7 +372,8 @@ function GroupRows({
             className={`border-t border-gray-50 dark:border-gray-700 hover:bg-blue-50 dark:hover:bg-blue-900/30 cursor-default ${
               selectedPaths.has(file.path) ? "bg-blue-50 dark:bg-blue-900/30" : ""
             }`}
-            onDoubleClick={() => onOpenFolder(file.folder)}
+            onDoubleClick={() => onRevealFile(file.path)}
+            title="Double-click to show in file manager"
           >
             <td className="px-2 py-1 text-center">
               <input
@@ -357,6 +396,13 @@ function GroupRows({
             </td>
           </tr>
         ))}
+      {expanded && overflowCount > 0 && (
+        <tr className="border-t border-gray-50 dark:border-gray-700">
+          <td colSpan={6} className="px-3 py-1 pl-12 text-xs italic text-gray-400 dark:text-gray-500">
+            …and {overflowCount} more not listed
+          </td>
+        </tr>
+      )}
     </>
   );
 }
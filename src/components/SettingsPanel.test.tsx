@@ -9,14 +9,40 @@ const defaults = {
   useHash: true,
   useSize: true,
   useName: false,
+  useNameFamily: false,
   useMtime: false,
+  useCreated: false,
   useMime: false,
   useMediaMeta: false,
+  useAudioFingerprint: false,
+  useDimensions: false,
+  useContentPrefix: false,
+  contentPrefixBytes: 256,
   hashLimitEnabled: true,
   hashMaxMb: 500,
   fastHashOversized: false,
+  blake3MinMb: null,
   includeSubfolders: true,
+  maxDepth: null,
+  skipHidden: false,
+  followSymlinks: false,
+  symlinkHashPolicy: "follow_target" as const,
+  mtimeFilter: null,
+  sameFolderPolicy: "flag" as const,
+  nameCompareMode: "full" as const,
+  alternativeCriteria: [],
+  maxGroups: null,
+  scanTimeoutSecs: null,
+  scanMaxFiles: null,
+  mimeFilter: null,
+  skipRecentlyModifiedSecs: null,
+  peekArchives: false,
+  canPeekArchives: true,
+  parallelWalk: false,
+  skipTrashDirs: true,
+  maxFilesPerGroup: null,
   namePrefix: "",
+  trashLimitMb: null,
   skipSameFolderPrompt: false,
 };
 
@@ -58,6 +84,31 @@ describe("SettingsPanel", () => {
     expect(screen.queryByText(/without content hashing/i)).not.toBeInTheDocument();
   });
 
+  it("offers archive peeking only when the build supports it", () => {
+    render(<SettingsPanel {...defaults} onChange={vi.fn()} />);
+    expect(screen.getByLabelText("Look inside .zip archives")).toBeTruthy();
+    cleanup();
+
+    render(<SettingsPanel {...defaults} canPeekArchives={false} onChange={vi.fn()} />);
+    expect(screen.queryByLabelText("Look inside .zip archives")).toBeNull();
+  });
+
+  it("adds and edits alternative criteria sets", async () => {
+    const user = userEvent.setup();
+    const onChange = vi.fn();
+    render(<SettingsPanel {...defaults} onChange={onChange} />);
+    await user.click(screen.getByText("Add criteria set"));
+    expect(onChange).toHaveBeenCalledWith("alternative_criteria", [{}]);
+    cleanup();
+
+    onChange.mockClear();
+    render(
+      <SettingsPanel {...defaults} alternativeCriteria={[{ use_hash: true }]} onChange={onChange} />,
+    );
+    await user.click(screen.getByLabelText("Alternative 1: Name"));
+    expect(onChange).toHaveBeenCalledWith("alternative_criteria", [{ use_hash: true, use_name: true }]);
+  });
+
   it("detects current preset from checkbox state", () => {
     // hash=true, size=true => "default"
     render(<SettingsPanel {...defaults} onChange={vi.fn()} />);
@@ -76,8 +127,13 @@ describe("SettingsPanel", () => {
     expect(onChange).toHaveBeenCalledWith("use_hash", true);
     expect(onChange).toHaveBeenCalledWith("use_size", true);
     expect(onChange).toHaveBeenCalledWith("use_name", true);
+    expect(onChange).toHaveBeenCalledWith("use_name_family", false);
     expect(onChange).toHaveBeenCalledWith("use_mtime", true);
+    expect(onChange).toHaveBeenCalledWith("use_created", true);
     expect(onChange).toHaveBeenCalledWith("use_mime", true);
     expect(onChange).toHaveBeenCalledWith("use_media_meta", true);
+    expect(onChange).toHaveBeenCalledWith("use_audio_fingerprint", false);
+    expect(onChange).toHaveBeenCalledWith("use_dimensions", false);
+    expect(onChange).toHaveBeenCalledWith("use_content_prefix", false);
   });
 });
@@ -1,14 +1,33 @@
+import type { CriteriaSet, MtimeFilter, NameCompare, SameFolderPolicy, SymlinkHashPolicy } from "../types";
+
+const WEEKDAY_LABELS = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/** Criteria offered in each alternative criteria set. */
+const ALTERNATIVE_CRITERIA: [keyof CriteriaSet, string][] = [
+  ["use_hash", "Hash"],
+  ["use_size", "Size"],
+  ["use_name", "Name"],
+  ["use_name_family", "Name family"],
+  ["use_mtime", "Modified"],
+  ["use_created", "Created"],
+  ["use_mime", "MIME"],
+  ["use_media_meta", "Media dimensions"],
+  ["use_audio_fingerprint", "Audio"],
+  ["use_dimensions", "Image dimensions"],
+  ["use_content_prefix", "First bytes"],
+];
+
 type CriteriaPreset = "safe" | "default" | "aggressive" | "custom";
 
-const PRESETS: Record<Exclude<CriteriaPreset, "custom">, { label: string; hash: boolean; size: boolean; name: boolean; mtime: boolean; mime: boolean; mediaMeta: boolean }> = {
-  safe:       { label: "Safe (hash only)",       hash: true,  size: false, name: false, mtime: false, mime: false, mediaMeta: false },
-  default:    { label: "Default (hash + size)",   hash: true,  size: true,  name: false, mtime: false, mime: false, mediaMeta: false },
-  aggressive: { label: "Aggressive (all checks)", hash: true,  size: true,  name: true,  mtime: true,  mime: true,  mediaMeta: true  },
+const PRESETS: Record<Exclude<CriteriaPreset, "custom">, { label: string; hash: boolean; size: boolean; name: boolean; nameFamily: boolean; mtime: boolean; created: boolean; mime: boolean; mediaMeta: boolean; audio: boolean; dimensions: boolean; contentPrefix: boolean }> = {
+  safe:       { label: "Safe (hash only)",       hash: true,  size: false, name: false, nameFamily: false, mtime: false, created: false, mime: false, mediaMeta: false, audio: false, dimensions: false, contentPrefix: false },
+  default:    { label: "Default (hash + size)",   hash: true,  size: true,  name: false, nameFamily: false, mtime: false, created: false, mime: false, mediaMeta: false, audio: false, dimensions: false, contentPrefix: false },
+  aggressive: { label: "Aggressive (all checks)", hash: true,  size: true,  name: true,  nameFamily: false, mtime: true,  created: true,  mime: true,  mediaMeta: true,  audio: false, dimensions: false, contentPrefix: false },
 };
 
-function detectPreset(h: boolean, s: boolean, n: boolean, m: boolean, mi: boolean, mm: boolean): CriteriaPreset {
+function detectPreset(h: boolean, s: boolean, n: boolean, nf: boolean, m: boolean, c: boolean, mi: boolean, mm: boolean, a: boolean, d: boolean, cp: boolean): CriteriaPreset {
   for (const [key, p] of Object.entries(PRESETS) as [Exclude<CriteriaPreset, "custom">, typeof PRESETS[keyof typeof PRESETS]][]) {
-    if (p.hash === h && p.size === s && p.name === n && p.mtime === m && p.mime === mi && p.mediaMeta === mm) return key;
+    if (p.hash === h && p.size === s && p.name === n && p.nameFamily === nf && p.mtime === m && p.created === c && p.mime === mi && p.mediaMeta === mm && p.audio === a && p.dimensions === d && p.contentPrefix === cp) return key;
   }
   return "custom";
 }
@@ -17,34 +36,93 @@ interface SettingsPanelProps {
   useHash: boolean;
   useSize: boolean;
   useName: boolean;
+  useNameFamily: boolean;
   useMtime: boolean;
+  useCreated: boolean;
   useMime: boolean;
   useMediaMeta: boolean;
+  useAudioFingerprint: boolean;
+  useDimensions: boolean;
+  useContentPrefix: boolean;
+  contentPrefixBytes: number;
   hashLimitEnabled: boolean;
   hashMaxMb: number;
   fastHashOversized: boolean;
+  blake3MinMb: number | null;
   includeSubfolders: boolean;
+  maxDepth: number | null;
+  skipHidden: boolean;
+  followSymlinks: boolean;
+  symlinkHashPolicy: SymlinkHashPolicy;
+  mtimeFilter: MtimeFilter | null;
+  sameFolderPolicy: SameFolderPolicy;
+  nameCompareMode: NameCompare;
+  /** Further criteria sets; a match under any of them also counts. */
+  alternativeCriteria: CriteriaSet[];
+  maxGroups: number | null;
+  scanTimeoutSecs: number | null;
+  scanMaxFiles: number | null;
+  mimeFilter: string[] | null;
+  skipRecentlyModifiedSecs: number | null;
+  peekArchives: boolean;
+  /** False when this build cannot read archives; the option is hidden. */
+  canPeekArchives: boolean;
+  parallelWalk: boolean;
+  skipTrashDirs: boolean;
+  maxFilesPerGroup: number | null;
   namePrefix: string;
   skipSameFolderPrompt: boolean;
-  onChange: (field: string, value: boolean | number | string) => void;
+  trashLimitMb: number | null;
+  /** Duplicate groups the user chose to hide from scans. */
+  ignoredGroupCount?: number;
+  onClearIgnored?: () => void;
+  onChange: (field: string, value: boolean | number | string | string[] | MtimeFilter | CriteriaSet[] | null) => void;
 }
 
 export default function SettingsPanel({
   useHash,
   useSize,
   useName,
+  useNameFamily,
   useMtime,
+  useCreated,
   useMime,
   useMediaMeta,
+  useAudioFingerprint,
+  useDimensions,
+  useContentPrefix,
+  contentPrefixBytes,
   hashLimitEnabled,
   hashMaxMb,
   fastHashOversized,
+  blake3MinMb,
   includeSubfolders,
+  maxDepth,
+  skipHidden,
+  followSymlinks,
+  symlinkHashPolicy,
+  mtimeFilter,
+  sameFolderPolicy,
+  nameCompareMode,
+  alternativeCriteria,
+  maxGroups,
+  scanTimeoutSecs,
+  scanMaxFiles,
+  mimeFilter,
+  skipRecentlyModifiedSecs,
+  peekArchives,
+  canPeekArchives,
+  parallelWalk,
+  skipTrashDirs,
+  maxFilesPerGroup,
   namePrefix,
   skipSameFolderPrompt,
+  trashLimitMb,
+  ignoredGroupCount = 0,
+  onClearIgnored,
   onChange,
 }: SettingsPanelProps) {
-  const currentPreset = detectPreset(useHash, useSize, useName, useMtime, useMime, useMediaMeta);
+  const currentPreset = detectPreset(useHash, useSize, useName, useNameFamily, useMtime, useCreated, useMime, useMediaMeta, useAudioFingerprint, useDimensions, useContentPrefix);
 
   function applyPreset(key: string) {
     const p = PRESETS[key as Exclude<CriteriaPreset, "custom">];
@@ -52,9 +130,43 @@ export default function SettingsPanel({
     onChange("use_hash", p.hash);
     onChange("use_size", p.size);
     onChange("use_name", p.name);
+    onChange("use_name_family", p.nameFamily);
     onChange("use_mtime", p.mtime);
+    onChange("use_created", p.created);
     onChange("use_mime", p.mime);
     onChange("use_media_meta", p.mediaMeta);
+    onChange("use_audio_fingerprint", p.audio);
+    onChange("use_dimensions", p.dimensions);
+    onChange("use_content_prefix", p.contentPrefix);
+  }
+
+  function updateAlternative(index: number, field: keyof CriteriaSet, checked: boolean) {
+    onChange(
+      "alternative_criteria",
+      alternativeCriteria.map((set, i) => (i === index ? { ...set, [field]: checked } : set)),
+    );
+  }
+
+  const weekdays = mtimeFilter?.weekdays ?? [];
+  const hours = mtimeFilter?.hours ?? null;
+
+  /** Store the filter, or null once it no longer restricts anything. */
+  function updateMtimeFilter(next: MtimeFilter) {
+    onChange("mtime_filter", next.weekdays.length === 0 && next.hours === null ? null : next);
+  }
+
+  function toggleWeekday(day: number) {
+    const next = weekdays.includes(day)
+      ? weekdays.filter((d) => d !== day)
+      : [...weekdays, day].sort((a, b) => a - b);
+    updateMtimeFilter({ weekdays: next, hours });
+  }
+
+  function updateHour(index: 0 | 1, raw: string) {
+    const current: [number, number] = hours ?? [0, 24];
+    const next: [number, number] = [...current];
+    next[index] = raw === "" ? (index === 0 ? 0 : 24) : Math.min(24, Math.max(0, Math.floor(Number(raw)) || 0));
+    updateMtimeFilter({ weekdays, hours: next[0] === 0 && next[1] === 24 ? null : next });
   }
 
   return (
@@ -107,6 +219,25 @@ export default function SettingsPanel({
             />
             File name
           </label>
+          <label className="flex items-center gap-1.5 text-sm" title="Compare names without their extension, so report.txt and report.pdf match">
+            <input
+              type="checkbox"
+              checked={nameCompareMode === "stem_only"}
+              disabled={!useName}
+              onChange={(e) => onChange("name_compare_mode", e.target.checked ? "stem_only" : "full")}
+              className="rounded"
+            />
+            Ignore extension
+          </label>
+          <label className="flex items-center gap-1.5 text-sm" title="Treat report.pdf, report (1).pdf and report-copy.pdf as one name">
+            <input
+              type="checkbox"
+              checked={useNameFamily}
+              onChange={(e) => onChange("use_name_family", e.target.checked)}
+              className="rounded"
+            />
+            Name family (copies)
+          </label>
           <label className="flex items-center gap-1.5 text-sm">
             <input
               type="checkbox"
@@ -116,6 +247,15 @@ export default function SettingsPanel({
             />
             Modified time
           </label>
+          <label className="flex items-center gap-1.5 text-sm" title="Files without a creation time are left out of groups">
+            <input
+              type="checkbox"
+              checked={useCreated}
+              onChange={(e) => onChange("use_created", e.target.checked)}
+              className="rounded"
+            />
+            Created time
+          </label>
           <label className="flex items-center gap-1.5 text-sm">
             <input
               type="checkbox"
@@ -134,6 +274,45 @@ export default function SettingsPanel({
             />
             Media dimensions
           </label>
+          <label className="flex items-center gap-1.5 text-sm" title="Match audio files that sound the same across formats and bitrates (requires a build with audio fingerprinting)">
+            <input
+              type="checkbox"
+              checked={useAudioFingerprint}
+              onChange={(e) => onChange("use_audio_fingerprint", e.target.checked)}
+              className="rounded"
+            />
+            Audio fingerprint
+          </label>
+          <label className="flex items-center gap-1.5 text-sm" title="Match images by decoded pixel size (PNG, JPEG, GIF, BMP, TIFF, WebP); other files are left out of groups">
+            <input
+              type="checkbox"
+              checked={useDimensions}
+              onChange={(e) => onChange("use_dimensions", e.target.checked)}
+              className="rounded"
+            />
+            Image dimensions
+          </label>
+          <label className="flex items-center gap-1.5 text-sm" title="Match files whose first bytes are identical, e.g. text files sharing a first line">
+            <input
+              type="checkbox"
+              checked={useContentPrefix}
+              onChange={(e) => onChange("use_content_prefix", e.target.checked)}
+              className="rounded"
+            />
+            First
+          </label>
+          <input
+            type="number"
+            min={1}
+            max={65536}
+            value={contentPrefixBytes}
+            aria-label="Content prefix bytes"
+            onChange={(e) =>
+              onChange("content_prefix_bytes", Math.max(1, Math.floor(Number(e.target.value)) || 1))
+            }
+            className="w-20 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm dark:bg-gray-700 dark:text-gray-100"
+          />
+          <span className="text-sm text-gray-500 dark:text-gray-400">bytes</span>
         </div>
         {/* Confidence warning */}
         {!useHash && (
@@ -163,6 +342,29 @@ export default function SettingsPanel({
           />
           <span className="text-sm text-gray-500 dark:text-gray-400">MB</span>
         </div>
+        <div className="flex items-center gap-2 mt-2">
+          <span className="text-sm text-gray-700 dark:text-gray-300">
+            Use BLAKE3 for files from
+          </span>
+          <input
+            type="number"
+            min={1}
+            value={blake3MinMb ?? ""}
+            placeholder="never"
+            aria-label="BLAKE3 threshold"
+            title="Faster on large files. Identical files have the same size, so every copy gets the same kind of hash."
+            onChange={(e) =>
+              onChange(
+                "blake3_min_mb",
+                e.target.value === ""
+                  ? null
+                  : Math.max(1, Math.floor(Number(e.target.value)) || 1),
+              )
+            }
+            className="w-20 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm dark:bg-gray-700 dark:text-gray-100"
+          />
+          <span className="text-sm text-gray-500 dark:text-gray-400">MB</span>
+        </div>
         {hashLimitEnabled && (
           <div className="mt-2 ml-5">
             <label className="flex items-center gap-1.5 text-sm">
@@ -183,6 +385,41 @@ export default function SettingsPanel({
             )}
           </div>
         )}
+        <div className="mt-3 space-y-1" title="Files matching under any of these criteria sets are grouped as well, e.g. same content OR same name">
+          <span className="text-sm text-gray-700 dark:text-gray-300">
+            Or match by:
+          </span>
+          {alternativeCriteria.map((set, index) => (
+            <div key={index} className="flex flex-wrap items-center gap-x-4 gap-y-1 ml-5">
+              {ALTERNATIVE_CRITERIA.map(([field, label]) => (
+                <label key={field} className="flex items-center gap-1 text-sm">
+                  <input
+                    type="checkbox"
+                    checked={set[field] ?? false}
+                    aria-label={`Alternative ${index + 1}: ${label}`}
+                    onChange={(e) => updateAlternative(index, field, e.target.checked)}
+                    className="rounded"
+                  />
+                  {label}
+                </label>
+              ))}
+              <button
+                onClick={() =>
+                  onChange("alternative_criteria", alternativeCriteria.filter((_, i) => i !== index))
+                }
+                className="text-sm text-blue-600 dark:text-blue-400 hover:underline"
+              >
+                Remove
+              </button>
+            </div>
+          ))}
+          <button
+            onClick={() => onChange("alternative_criteria", [...alternativeCriteria, {}])}
+            className="ml-5 text-sm text-blue-600 dark:text-blue-400 hover:underline"
+          >
+            Add criteria set
+          </button>
+        </div>
       </fieldset>
 
       {/* Scan options */}
@@ -196,6 +433,273 @@ export default function SettingsPanel({
           />
           Include subfolders
         </label>
+        {includeSubfolders && (
+          <div className="flex items-center gap-2 ml-5">
+            <span className="text-sm text-gray-700 dark:text-gray-300">
+              Max depth:
+            </span>
+            <input
+              type="number"
+              min={1}
+              value={maxDepth ?? ""}
+              placeholder="unlimited"
+              aria-label="Max depth"
+              onChange={(e) =>
+                onChange(
+                  "max_depth",
+                  e.target.value === ""
+                    ? null
+                    : Math.max(1, Math.floor(Number(e.target.value)) || 1),
+                )
+              }
+              className="w-24 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm dark:bg-gray-700 dark:text-gray-100"
+            />
+          </div>
+        )}
+        <label className="flex items-center gap-1.5 text-sm" title="Skip dotfiles and files marked hidden by the OS">
+          <input
+            type="checkbox"
+            checked={skipHidden}
+            onChange={(e) => onChange("skip_hidden", e.target.checked)}
+            className="rounded"
+          />
+          Skip hidden files
+        </label>
+        <label className="flex items-center gap-1.5 text-sm">
+          <input
+            type="checkbox"
+            checked={followSymlinks}
+            onChange={(e) => onChange("follow_symlinks", e.target.checked)}
+            className="rounded"
+          />
+          Follow symbolic links
+        </label>
+        {canPeekArchives && (
+          <label className="flex items-center gap-1.5 text-sm" title="Compare the files stored inside .zip archives too. Files inside archives are shown but cannot be deleted.">
+            <input
+              type="checkbox"
+              checked={peekArchives}
+              onChange={(e) => onChange("peek_archives", e.target.checked)}
+              className="rounded"
+            />
+            Look inside .zip archives
+          </label>
+        )}
+        <label className="flex items-center gap-1.5 text-sm" title="List folders on several threads at once. Faster on network drives; results are the same.">
+          <input
+            type="checkbox"
+            checked={parallelWalk}
+            onChange={(e) => onChange("parallel_walk", e.target.checked)}
+            className="rounded"
+          />
+          Parallel folder walk
+        </label>
+        <label className="flex items-center gap-1.5 text-sm" title="Leave out recycle bin and trash folders ($RECYCLE.BIN, .Trash, ...), whose files are already deleted">
+          <input
+            type="checkbox"
+            checked={skipTrashDirs}
+            onChange={(e) => onChange("skip_trash_dirs", e.target.checked)}
+            className="rounded"
+          />
+          Skip trash folders
+        </label>
+        {followSymlinks && (
+          <div className="flex items-center gap-2 ml-5">
+            <span className="text-sm text-gray-700 dark:text-gray-300">
+              Hash links by:
+            </span>
+            <select
+              value={symlinkHashPolicy}
+              aria-label="Symlink hash policy"
+              onChange={(e) => onChange("symlink_hash_policy", e.target.value)}
+              className="text-sm border border-gray-300 dark:border-gray-600 rounded px-1.5 py-0.5 dark:bg-gray-700 dark:text-gray-100"
+            >
+              <option value="follow_target">Target content</option>
+              <option value="link_path">Target path (no reading)</option>
+            </select>
+          </div>
+        )}
+        <div className="flex flex-wrap items-center gap-2" title="Filters by the file's modified time, in local time">
+          <span className="text-sm text-gray-700 dark:text-gray-300">
+            Modified on:
+          </span>
+          {WEEKDAY_LABELS.map((label, day) => (
+            <label key={label} className="flex items-center gap-1 text-sm">
+              <input
+                type="checkbox"
+                checked={weekdays.includes(day)}
+                onChange={() => toggleWeekday(day)}
+                className="rounded"
+              />
+              {label}
+            </label>
+          ))}
+          <span className="text-sm text-gray-700 dark:text-gray-300">between</span>
+          <input
+            type="number"
+            min={0}
+            max={24}
+            value={hours ? hours[0] : ""}
+            placeholder="0"
+            aria-label="Modified from hour"
+            onChange={(e) => updateHour(0, e.target.value)}
+            className="w-16 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm dark:bg-gray-700 dark:text-gray-100"
+          />
+          <span className="text-sm text-gray-700 dark:text-gray-300">and</span>
+          <input
+            type="number"
+            min={0}
+            max={24}
+            value={hours ? hours[1] : ""}
+            placeholder="24"
+            aria-label="Modified until hour"
+            onChange={(e) => updateHour(1, e.target.value)}
+            className="w-16 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm dark:bg-gray-700 dark:text-gray-100"
+          />
+          <span className="text-sm text-gray-500 dark:text-gray-400">h</span>
+        </div>
+        <div className="flex items-center gap-2" title="Copies like file and file (1) in one folder are often intentional">
+          <span className="text-sm text-gray-700 dark:text-gray-300">
+            Same-folder copies:
+          </span>
+          <select
+            value={sameFolderPolicy}
+            aria-label="Same-folder policy"
+            onChange={(e) => onChange("same_folder_policy", e.target.value)}
+            className="text-sm border border-gray-300 dark:border-gray-600 rounded px-1.5 py-0.5 dark:bg-gray-700 dark:text-gray-100"
+          >
+            <option value="flag">Flag as duplicates</option>
+            <option value="ignore">Ignore</option>
+          </select>
+        </div>
+        <div className="flex items-center gap-2" title="Judge files by their content, not their extension. Reads the start of every file, so scans take longer">
+          <span className="text-sm text-gray-700 dark:text-gray-300">
+            Only scan content type:
+          </span>
+          <select
+            value={mimeFilter?.join(",") ?? ""}
+            aria-label="Content type filter"
+            onChange={(e) =>
+              onChange("mime_filter", e.target.value === "" ? null : e.target.value.split(","))
+            }
+            className="text-sm border border-gray-300 dark:border-gray-600 rounded px-1.5 py-0.5 dark:bg-gray-700 dark:text-gray-100"
+          >
+            <option value="">Any</option>
+            <option value="image/*">Images</option>
+            <option value="video/*">Videos</option>
+            <option value="audio/*">Audio</option>
+          </select>
+        </div>
+        <div className="flex items-center gap-2" title="Large scans return only the groups that waste the most space">
+          <span className="text-sm text-gray-700 dark:text-gray-300">
+            Show at most
+          </span>
+          <input
+            type="number"
+            min={1}
+            value={maxGroups ?? ""}
+            placeholder="all"
+            aria-label="Max groups"
+            onChange={(e) =>
+              onChange(
+                "max_groups",
+                e.target.value === ""
+                  ? null
+                  : Math.max(1, Math.floor(Number(e.target.value)) || 1),
+              )
+            }
+            className="w-24 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm dark:bg-gray-700 dark:text-gray-100"
+          />
+          <span className="text-sm text-gray-500 dark:text-gray-400">groups</span>
+        </div>
+        <div className="flex items-center gap-2" title="Very large groups list only their first files; the rest are counted but not shown">
+          <span className="text-sm text-gray-700 dark:text-gray-300">
+            List at most
+          </span>
+          <input
+            type="number"
+            min={1}
+            value={maxFilesPerGroup ?? ""}
+            placeholder="all"
+            aria-label="Max files per group"
+            onChange={(e) =>
+              onChange(
+                "max_files_per_group",
+                e.target.value === ""
+                  ? null
+                  : Math.max(1, Math.floor(Number(e.target.value)) || 1),
+              )
+            }
+            className="w-24 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm dark:bg-gray-700 dark:text-gray-100"
+          />
+          <span className="text-sm text-gray-500 dark:text-gray-400">files per group</span>
+        </div>
+        <div className="flex items-center gap-2" title="Stop walking the folder after this long and show what was found so far (guards against hanging network mounts)">
+          <span className="text-sm text-gray-700 dark:text-gray-300">
+            Give up scanning after
+          </span>
+          <input
+            type="number"
+            min={1}
+            value={scanTimeoutSecs ?? ""}
+            placeholder="never"
+            aria-label="Scan timeout"
+            onChange={(e) =>
+              onChange(
+                "scan_timeout_secs",
+                e.target.value === ""
+                  ? null
+                  : Math.max(1, Math.floor(Number(e.target.value)) || 1),
+              )
+            }
+            className="w-24 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm dark:bg-gray-700 dark:text-gray-100"
+          />
+          <span className="text-sm text-gray-500 dark:text-gray-400">seconds</span>
+        </div>
+        <div className="flex items-center gap-2" title="Stop walking the folder after this many files and show what was found so far (keeps huge folders responsive)">
+          <span className="text-sm text-gray-700 dark:text-gray-300">
+            Stop scanning after
+          </span>
+          <input
+            type="number"
+            min={1}
+            value={scanMaxFiles ?? ""}
+            placeholder="no limit"
+            aria-label="Maximum files to scan"
+            onChange={(e) =>
+              onChange(
+                "scan_max_files",
+                e.target.value === ""
+                  ? null
+                  : Math.max(1, Math.floor(Number(e.target.value)) || 1),
+              )
+            }
+            className="w-24 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm dark:bg-gray-700 dark:text-gray-100"
+          />
+          <span className="text-sm text-gray-500 dark:text-gray-400">files</span>
+        </div>
+        <div className="flex items-center gap-2" title="Leave out files changed very recently, such as downloads that are still in progress">
+          <span className="text-sm text-gray-700 dark:text-gray-300">
+            Skip files modified in the last
+          </span>
+          <input
+            type="number"
+            min={0}
+            value={skipRecentlyModifiedSecs ?? ""}
+            placeholder="off"
+            aria-label="Skip recently modified files"
+            onChange={(e) =>
+              onChange(
+                "skip_recently_modified_secs",
+                e.target.value === ""
+                  ? null
+                  : Math.max(0, Math.floor(Number(e.target.value)) || 0),
+              )
+            }
+            className="w-24 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm dark:bg-gray-700 dark:text-gray-100"
+          />
+          <span className="text-sm text-gray-500 dark:text-gray-400">seconds</span>
+        </div>
         <div className="flex items-center gap-2">
           <span className="text-sm text-gray-700 dark:text-gray-300">
             Only scan file names starting with:
@@ -224,6 +728,41 @@ export default function SettingsPanel({
           Skip keep-choice dialog when duplicates are in the same folder (auto
           keep newest)
         </label>
+        <div className="flex items-center gap-2" title="Some recycle bins have a size limit and permanently delete whatever does not fit">
+          <span className="text-sm text-gray-700 dark:text-gray-300">
+            Ask before trashing more than
+          </span>
+          <input
+            type="number"
+            min={1}
+            value={trashLimitMb ?? ""}
+            placeholder="no limit"
+            aria-label="Trash size limit"
+            onChange={(e) =>
+              onChange(
+                "trash_limit_mb",
+                e.target.value === ""
+                  ? null
+                  : Math.max(1, Math.floor(Number(e.target.value)) || 1),
+              )
+            }
+            className="w-24 border border-gray-300 dark:border-gray-600 rounded px-2 py-1 text-sm dark:bg-gray-700 dark:text-gray-100"
+          />
+          <span className="text-sm text-gray-500 dark:text-gray-400">MB</span>
+        </div>
+        {ignoredGroupCount > 0 && onClearIgnored && (
+          <div className="flex items-center gap-2">
+            <span className="text-sm text-gray-700 dark:text-gray-300">
+              {ignoredGroupCount} ignored group(s) hidden from scans
+            </span>
+            <button
+              onClick={onClearIgnored}
+              className="text-sm text-blue-600 dark:text-blue-400 hover:underline"
+            >
+              Show again
+            </button>
+          </div>
+        )}
       </div>
     </div>
   );
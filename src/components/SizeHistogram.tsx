@@ -0,0 +1,37 @@
+import type { SizeBucket } from "../types";
+
+interface SizeHistogramProps {
+  buckets: SizeBucket[];
+}
+
+/** Horizontal bar chart of scanned files per size bucket. */
+export default function SizeHistogram({ buckets }: SizeHistogramProps) {
+  const maxCount = Math.max(1, ...buckets.map((b) => b.count));
+  if (buckets.every((b) => b.count === 0)) return null;
+
+  return (
+    <div className="border border-gray-200 dark:border-gray-700 rounded-md p-3 bg-white dark:bg-gray-800">
+      <p className="text-xs font-medium text-gray-700 dark:text-gray-300 mb-2">
+        Scanned files by size
+      </p>
+      <div className="space-y-1">
+        {buckets.map((bucket) => (
+          <div key={bucket.label} className="flex items-center gap-2 text-xs">
+            <span className="w-24 shrink-0 text-gray-500 dark:text-gray-400">
+              {bucket.label}
+            </span>
+            <div className="flex-1 h-3 bg-gray-100 dark:bg-gray-700 rounded">
+              <div
+                className="h-3 bg-blue-500 dark:bg-blue-400 rounded"
+                style={{ width: `${(bucket.count / maxCount) * 100}%` }}
+              />
+            </div>
+            <span className="w-16 shrink-0 text-right text-gray-600 dark:text-gray-300">
+              {bucket.count}
+            </span>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -38,22 +38,27 @@ export default function StatusBar({
     const hasProgress = scanProgress !== null;
     const isHashing = hasProgress && scanProgress.phase === "hashing";
     const isScanning = hasProgress && scanProgress.phase === "scanning";
-    const pct =
-      isHashing && scanProgress.total > 0
+    const isFiltering = hasProgress && scanProgress.phase === "filtering";
+    // File-type checks followed by hashing share one combined percentage.
+    const isCombined = hasProgress && scanProgress.percent != null;
+    const isTimed = isHashing || isCombined;
+    const pct = isCombined
+      ? Math.round(scanProgress.percent ?? 0)
+      : isHashing && scanProgress.total > 0
         ? Math.round((scanProgress.current / scanProgress.total) * 100)
         : null;
 
     // Track hashing start time for ETA
-    if (isHashing && hashStartRef.current === null) {
+    if (isTimed && hashStartRef.current === null) {
       hashStartRef.current = Date.now();
     }
-    if (!isHashing) {
+    if (!isTimed) {
       hashStartRef.current = null;
     }
 
     // Compute ETA during hashing
     let eta: string | null = null;
-    if (isHashing && pct !== null && pct > 0 && hashStartRef.current !== null) {
+    if (isTimed && pct !== null && pct > 0 && hashStartRef.current !== null) {
       const elapsedMs = Date.now() - hashStartRef.current;
       const remainMs = (elapsedMs / pct) * (100 - pct);
       const remainSec = remainMs / 1000;
@@ -66,7 +71,9 @@ export default function StatusBar({
       ? "Phase 2: Computing hashes"
       : isScanning
         ? "Phase 1: Discovering files"
-        : null;
+        : isFiltering
+          ? "Phase 1: Checking file types"
+          : null;
 
     return (
       <div className="border border-gray-200 dark:border-gray-700 rounded-md p-3 bg-white dark:bg-gray-800">
@@ -148,7 +155,7 @@ export default function StatusBar({
   if (scanResult.groups.length === 0) {
     summary = `No duplicates found (${scope}).`;
   } else {
-    summary = `Found ${scanResult.groups.length} duplicate group(s), ${totalDupes} deletable file(s) (${scope}).`;
+    summary = `Found ${scanResult.total_group_count} duplicate group(s), ${totalDupes} deletable file(s) (${scope}).`;
   }
   if (namePrefix) summary += ` Prefix: '${namePrefix}'.`;
   if (!includeSubfolders) summary += " Subfolders: off.";
@@ -156,6 +163,25 @@ export default function StatusBar({
   summary += ` Time: ${formatTime(scanResult.elapsed_seconds)}`;
 
   const notices: string[] = [];
+  if (scanResult.stale) {
+    notices.push("These results are from an earlier session and may be out of date; scan again to refresh them.");
+  }
+  if (scanResult.timed_out) {
+    notices.push("Scan timed out; results cover only the files found before the time limit.");
+  }
+  if (scanResult.file_limit_reached) {
+    notices.push(
+      `Scan stopped at the file limit; results cover only the first ${scanResult.total_files_scanned} file(s).`,
+    );
+  }
+  if (scanResult.ignored_count > 0) {
+    notices.push(`${scanResult.ignored_count} ignored group(s) hidden.`);
+  }
+  if (scanResult.truncated) {
+    notices.push(
+      `Showing top ${scanResult.groups.length} of ${scanResult.total_group_count} group(s) by wasted space.`,
+    );
+  }
   if (scanResult.hash_skipped > 0) {
     notices.push(
       hashSkippedHasFallback
@@ -163,9 +189,34 @@ export default function StatusBar({
         : "Hashing skipped for some large files; no other checks enabled.",
     );
   }
+  if (scanResult.created_skipped > 0) {
+    notices.push(
+      `${scanResult.created_skipped} file(s) had no creation time and were left out of groups.`,
+    );
+  }
+  if (scanResult.audio_skipped > 0) {
+    notices.push(
+      `${scanResult.audio_skipped} file(s) were not decodable audio and were left out of groups.`,
+    );
+  }
+  if (scanResult.dimensions_skipped > 0) {
+    notices.push(
+      `${scanResult.dimensions_skipped} file(s) were not readable images and were left out of groups.`,
+    );
+  }
+  if (scanResult.in_flux_skipped > 0) {
+    notices.push(
+      `${scanResult.in_flux_skipped} file(s) changed size during the scan (still being written?) and were left out of groups.`,
+    );
+  }
   if (scanResult.scan_skipped > 0) {
     notices.push(formatSkipNotice(scanResult.scan_skipped, scanResult.scan_skip_reasons));
   }
+  if (scanResult.scan_skip_reasons.recently_modified > 0) {
+    notices.push(
+      `${scanResult.scan_skip_reasons.recently_modified} recently modified file(s) were skipped as possibly still being written.`,
+    );
+  }
   if (staleAdvancedNotice) {
     notices.push(
       "Results reflect simplified defaults; rescan to apply advanced settings.",
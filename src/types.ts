@@ -7,12 +7,54 @@ export interface FileEntryDto {
   size_human: string;
   mtime: number;
   mtime_formatted: string;
+  created: number; // Unix seconds; 0 if unavailable
+  created_formatted: string;
 }
 
+/** A single grouping criterion value (serde externally-tagged enum). */
+export type CriterionValue =
+  | { Hash: string }
+  | { FastHash: string }
+  | { Size: number }
+  | { Name: string }
+  | { NameFamily: string }
+  | { Mtime: number }
+  | { Created: number }
+  | { MimeType: string }
+  | { MediaMeta: string }
+  | { AudioMatch: number }
+  | { LinkTarget: string }
+  | { Dimensions: [number, number] }
+  | { ContentPrefix: string };
+
+/** Digest used by single-file hash spot checks. */
+export type HashAlgorithm = "sha256" | "sha512";
+
+/** Text form of a digest; hex unless a command is asked for base64. */
+export type DigestEncoding = "hex" | "base64";
+
+/** How symlinked files are keyed when hashing is on. */
+export type SymlinkHashPolicy = "follow_target" | "link_path";
+
+/** How the member of a duplicate group to keep is chosen. */
+export type KeepStrategy =
+  | { kind: "newest" }
+  | { kind: "folder_priority"; folders: string[] };
+
 /** A group of duplicate files. */
 export interface DuplicateGroup {
   key_description: string;
+  /** Likelihood of true duplicates (0-1), from the criteria in `key`. */
+  confidence: number;
+  /** Raw grouping key; sent back to the backend for incremental re-scans. */
+  key: CriterionValue[];
   files: FileEntryDto[];
+  /** Member that must be kept (the master copy in master-folder scans). */
+  keeper: string | null;
+  /** Members left out of `files` by the `max_files_per_group` cap. */
+  overflow_count: number;
+  /** Stable identity of the members; pass to `ignoreGroup` to hide the group. */
+  signature: string;
 }
 
 /** Buckets describing why files were skipped during scan traversal. */
@@ -20,16 +62,148 @@ export interface ScanSkipReasons {
   permissions: number;
   missing: number;
   transient_io: number;
+  /** Files left out as still being written; not counted in `scan_skipped`. */
+  recently_modified: number;
 }
 
 /** Full scan result from Rust backend. */
 export interface ScanResult {
   groups: DuplicateGroup[];
+  /** True when `groups` was cut down to the scan's `max_groups` cap. */
+  truncated: boolean;
+  /** True when the walk hit `timeout_secs`; groups cover only the files seen. */
+  timed_out: boolean;
+  /** True when the walk stopped at `max_files`; groups cover only the files seen. */
+  file_limit_reached: boolean;
+  /** Number of groups found, before any cap. */
+  total_group_count: number;
+  /** Bytes reclaimable over all groups found, before any cap. */
+  total_wasted_bytes: number;
+  /** Log-scale size histogram over every scanned file. */
+  size_histogram: SizeBucket[];
+  /** Duplicates per lowercased extension ("(none)" without one), over `groups`. */
+  by_extension: Record<string, ExtensionStats>;
   total_files_scanned: number;
   hash_skipped: number;
+  created_skipped: number;
+  audio_skipped: number;
+  dimensions_skipped: number;
+  /** Files not hashed because their size changed mid-scan (still being written). */
+  in_flux_skipped: number;
   scan_skipped: number;
   scan_skip_reasons: ScanSkipReasons;
   elapsed_seconds: number;
+  /** Reloaded from an earlier session; files may have changed since. */
+  stale: boolean;
+  /** Groups hidden because the user ignored them; not in `total_group_count`. */
+  ignored_count: number;
+  /** Id of the cached full result for `getScanPage`; 0 when not cached. */
+  scan_id: number;
+}
+
+/** Totals of a scan, without the groups themselves. */
+export interface ScanSummary {
+  group_count: number;
+  /** Files that would go if one per group were kept. */
+  duplicate_file_count: number;
+  total_wasted_bytes: number;
+  total_files_scanned: number;
+  elapsed_seconds: number;
+}
+
+/** Totals of a cached scan, for paging through its groups. */
+export interface ScanMeta {
+  scan_id: number;
+  /** Groups available through `getScanPage`. */
+  group_count: number;
+  summary: ScanSummary;
+}
+
+/** Duplicates sharing one extension. */
+export interface ExtensionStats {
+  group_count: number;
+  file_count: number;
+  wasted_bytes: number;
+}
+
+/** One bucket of the scanned-file size histogram. */
+export interface SizeBucket {
+  label: string;
+  min_bytes: number;
+  /** Exclusive upper bound; null for the last bucket. */
+  max_bytes: number | null;
+  count: number;
+  total_bytes: number;
+}
+
+/** Result of a delete command. */
+export interface DeleteSummary {
+  deleted: number;
+  /** Paths on volumes without a trash, left in place pending confirmation. */
+  needs_confirmation: string[];
+  /** Paths that could not be deleted (or, in a dry run, would not be). */
+  errors: PathError[];
+  /** Paths deleted with a caveat, e.g. permanently because the trash failed. */
+  warnings: PathError[];
+  /** Paths moved to the trash, which `restoreFromTrash` can bring back. */
+  trashed: string[];
+  /** Paths kept because a verified delete found they changed since the scan. */
+  spared: PathError[];
+  /** Nothing was deleted: the batch is over the trash limit and all paths are in needs_confirmation. */
+  trash_limit_exceeded: boolean;
+}
+
+/** What a delete recorded in the history was part of. */
+export type DeleteMode = "manual" | "auto_dedupe" | "canonicalize";
+
+/** One deleted file in the delete history. */
+export interface DeleteHistoryEntry {
+  /** Unix seconds of the delete. */
+  timestamp: number;
+  path: string;
+  mode: DeleteMode;
+  /** Size of the file when it was deleted. */
+  freed_bytes: number;
+  /** Moved to the trash rather than deleted permanently. */
+  trashed: boolean;
+}
+
+/** Result of restoring files from the trash. */
+export interface RestoreSummary {
+  restored: number;
+  /** Paths that could not be restored. */
+  errors: PathError[];
+}
+
+/** What auto-dedupe did with one duplicate group. */
+export interface AutoDedupeGroupReport {
+  kept: string;
+  /** Why the keep strategy picked `kept`. */
+  keep_reason: string;
+  deleted: string[];
+  freed_bytes: number;
+  /** Paths on volumes without a trash, left in place pending confirmation. */
+  needs_confirmation: string[];
+  errors: PathError[];
+  /** Nothing was deleted: the copies add up to more than the trash limit. */
+  trash_limit_exceeded: boolean;
+}
+
+/** Per-group outcome of an auto-dedupe run. */
+export interface AutoDedupeReport {
+  groups: AutoDedupeGroupReport[];
+  /** Sum of the groups' freed_bytes. */
+  freed_bytes: number;
+  /** True for a dry run: nothing was deleted; `deleted` is what a run would remove. */
+  planned: boolean;
+}
+
+/** Outcome of collapsing identical files into one canonically named file. */
+export interface CanonicalizeReport {
+  group: AutoDedupeGroupReport;
+  /** New path of the survivor; null if it kept its name. */
+  renamed_to: string | null;
+  rename_errors: AutoRenameError[];
 }
 
 /** Candidate file DTO for auto-renamer mode. */
@@ -42,6 +216,8 @@ export interface AutoRenameCandidateDto {
   mtime: number;
   mtime_formatted: string;
   created: number; // Unix seconds; 0 if unavailable
+  /** Base-pass rename target (full path); empty when scanned without a schema. */
+  preview_target: string;
 }
 
 /** Scan result for auto-renamer mode. */
@@ -72,25 +248,138 @@ export interface AutoRenameResult {
   error_count: number;
   items: AutoRenameItem[];
   errors: AutoRenameError[];
+  /** The batch was stopped; `items` lists the renames done before. */
+  cancelled: boolean;
+}
+
+/** Differences between the duplicate groups of two scans, matched by key. */
+export interface ScanDiff {
+  new_groups: DuplicateGroup[];
+  gone_groups: DuplicateGroup[];
+  grown_groups: GroupChange[];
+  shrunk_groups: GroupChange[];
+  unchanged_count: number;
+}
+
+/** A group found by both scans whose member count changed. */
+export interface GroupChange {
+  /** The group as the newer scan reported it. */
+  group: DuplicateGroup;
+  previous_count: number;
+  added: string[];
+  removed: string[];
 }
 
-/** Progress event emitted during scanning / hashing. */
+/** Byte-for-byte comparison of two files. */
+export interface CompareResult {
+  identical: boolean;
+  /** Offset of the first differing byte; null when identical or sizes differ. */
+  first_diff_offset: number | null;
+  size_a: number;
+  size_b: number;
+}
+
+/** Quick, hash-free probe of a folder before scanning it. */
+export interface FolderPrecheck {
+  exists: boolean;
+  readable: boolean;
+  /** Files seen before the probe stopped (a lower bound when truncated). */
+  approx_file_count: number;
+  count_truncated: boolean;
+  is_network_drive: boolean;
+}
+
+/** Rough scan duration from a quick, hash-free sample of the folder. */
+export interface ScanTimeEstimate {
+  /** Estimated seconds; only a guess. */
+  seconds: number;
+  sampled_files: number;
+  /** The sample stopped early, so `seconds` is a lower bound. */
+  sample_truncated: boolean;
+}
+
+/** A path paired with the error that prevented an operation on it. */
+export interface PathError {
+  path: string;
+  message: string;
+}
+
+/** Which timestamp dates a file; "created" falls back to modified. */
+export type RecencyBasis = "modified" | "created";
+
+/** Result of sorting files into YYYY-MM folders. */
+export interface OrganizeReport {
+  moved: AutoRenameItem[];
+  /** Files already in their dated folder. */
+  unchanged: number;
+  errors: PathError[];
+}
+
+/** Result of removing empty directories under a folder. */
+export interface RemoveEmptyDirsResult {
+  removed_count: number;
+  removed: string[];
+  errors: PathError[];
+}
+
+/** Result of writing a sha256sum checksum manifest. */
+export interface ManifestExport {
+  files_written: number;
+  files_total: number;
+  /** Cancelled: only the files hashed so far are listed, followed by a "# incomplete" line. */
+  incomplete: boolean;
+  errors: PathError[];
+}
+
+/** One manifest line re-checked against the disk. */
+export interface VerifyEntry {
+  /** Path as written in the manifest. */
+  path: string;
+  status: "ok" | "changed" | "missing" | "unreadable";
+  message: string | null;
+}
+
+/** Machine-readable error codes returned by backend commands. */
+export type CommandErrorCode =
+  | "folder_not_found"
+  | "permission_denied"
+  | "invalid_schema"
+  | "task_panicked"
+  | "io"
+  | "unsupported"
+  | "scan_expired";
+
+/** Error payload rejected by backend commands. */
+export interface CommandError {
+  code: CommandErrorCode;
+  message: string;
+}
+
+/** Progress event emitted during scanning / hashing / renaming. */
 export interface ScanProgress {
-  phase: "scanning" | "hashing";
+  phase: "scanning" | "filtering" | "hashing" | "renaming" | "manifest";
   current: number;
   total: number;
   message: string;
+  /** Folders walked so far; only set during the scanning phase. */
+  dirs_scanned: number | null;
+  /** Combined file-type check + hashing percent (0-100), when both run; bytes hashed during a manifest export. */
+  percent: number | null;
 }
 
 export type Theme = "light" | "dark" | "system";
 export type ViewMode = "simplified" | "advanced" | "auto_renamer";
-export type AutoFileTypePreset =
+export type BuiltinFileTypePreset =
   | "all"
   | "images"
   | "videos"
   | "audio"
   | "documents"
   | "archives";
+/** A built-in preset or the name of a user-defined one. */
+export type AutoFileTypePreset = BuiltinFileTypePreset | (string & {});
+/** User-defined presets: name -> extensions (without the dot). */
+export type FileTypePresets = Record<string, string[]>;
 
 // ---------------------------------------------------------------------------
 // Rename component schema
@@ -103,8 +392,13 @@ export type RenameComponentKind =
   | "time_created"
   | "time_modified"
   | "sequence"
+  | "alpha_sequence"
   | "original_stem"
-  | "literal";
+  | "literal"
+  | "stem_words"
+  | "hash_fragment"
+  | "folder_counter"
+  | "sidecar_field";
 
 /** A single component in the rename schema (with a client-side `id` for React keys). */
 export interface RenameComponent {
@@ -113,16 +407,72 @@ export interface RenameComponent {
   kind: RenameComponentKind;
   /** Used when kind === "literal". */
   value?: string;
-  /** Used when kind === "sequence". */
+  /** Used when kind === "sequence" or "folder_counter". */
   pad_width?: number;
+  /** Used when kind === "folder_counter": first number in each folder. */
+  start?: number;
+  /** Used when kind === "folder_counter": increment between files. */
+  step?: number;
+  /** Used when kind === "stem_words": number of words to keep. */
+  keep?: number;
+  /** Used when kind === "stem_words": take words from the end of the stem. */
+  from_end?: boolean;
+  /** Used when kind === "hash_fragment": digest algorithm. */
+  algo?: HashAlgorithm;
+  /** Used when kind === "hash_fragment": number of hex digits to keep. */
+  chars?: number;
+  /** Used when kind === "alpha_sequence": capital letters instead of lowercase. */
+  uppercase?: boolean;
+  /** Used when kind === "sidecar_field": extension of the JSON sidecar, e.g. "json". */
+  extension?: string;
+  /** Used when kind === "sidecar_field": dotted path of the field, e.g. "meta.title". */
+  field?: string;
+}
+
+/** How invalid characters in generated name parts are cleaned up. */
+export interface SanitizeOptions {
+  /** Single character used in place of reserved characters (default "_"). */
+  replacement: string;
+  strip_trailing_dots: boolean;
+  collapse_repeats: boolean;
+  /** Suffix reserved Windows device names (CON, LPT1, ...) with "_". */
+  guard_reserved_names: boolean;
 }
 
 /** The rename schema sent to the Rust backend. */
 export interface RenameSchema {
   components: RenameComponent[];
   separator: string;
+  /** Attaches sequence and counter components; null uses `separator`. */
+  sequence_separator?: string | null;
+  /** What to use when a file has no creation time. Defaults to "now". */
+  created_fallback?: CreatedFallback;
+  /** strftime pattern for date components; defaults to "%Y%m%d". */
+  date_format?: string;
+  /** strftime pattern for time components; defaults to "%H%M%S". */
+  time_format?: string;
+  /** Omit to use the backend defaults. */
+  sanitize?: SanitizeOptions;
+  /** Rename symlinks themselves, even broken ones. Defaults to false. */
+  rename_symlinks?: boolean;
+  /** Skip files whose content already exists in their folder. Defaults to false. */
+  skip_duplicate_content?: boolean;
+  /** Add the sniffed type's extension to extensionless files. Defaults to false. */
+  infer_missing_extension?: boolean;
+  /** What to do when a target name is taken on disk. Defaults to "sequence". */
+  collision_policy?: CollisionPolicy;
+  /** Reject literals with characters not allowed in file names instead of replacing them. */
+  strict?: boolean;
+  /** Number files in the order given instead of by path. Defaults to false. */
+  keep_input_order?: boolean;
 }
 
+/** Number or atomically replace a taken target (mirrors Rust CollisionPolicy). */
+export type CollisionPolicy = "sequence" | "overwrite";
+
+/** Substitute for a missing creation time (mirrors Rust CreatedFallback). */
+export type CreatedFallback = "now" | "modified" | "skip_component";
+
 export const DEFAULT_RENAME_COMPONENTS: RenameComponent[] = [
   { id: "1", kind: "folder_name" },
   { id: "2", kind: "date_created" },
@@ -130,30 +480,115 @@ export const DEFAULT_RENAME_COMPONENTS: RenameComponent[] = [
   { id: "4", kind: "sequence", pad_width: 3 },
 ];
 
+/** Whether copies sharing a folder count as duplicates (mirrors Rust SameFolderPolicy). */
+export type SameFolderPolicy = "flag" | "ignore";
+
+/** Whether the file-name criterion compares whole names or only stems (mirrors Rust NameCompare). */
+export type NameCompare = "full" | "stem_only";
+
+/** Weekday / hour predicate on local modification time (mirrors Rust MtimeFilter). */
+export interface MtimeFilter {
+  /** 0 = Monday … 6 = Sunday; empty keeps every day. */
+  weekdays: number[];
+  /** Hour window [start, end); wraps past midnight when start > end. */
+  hours: [number, number] | null;
+}
+
+/** Criteria flags of an alternative criteria set or type override (mirrors Rust CriteriaSet). */
+export interface CriteriaSet {
+  use_hash?: boolean;
+  use_size?: boolean;
+  use_name?: boolean;
+  use_name_family?: boolean;
+  use_mtime?: boolean;
+  use_created?: boolean;
+  use_mime?: boolean;
+  use_media_meta?: boolean;
+  use_audio_fingerprint?: boolean;
+  use_dimensions?: boolean;
+  use_content_prefix?: boolean;
+}
+
 /** Application settings (mirrors Rust AppSettings). */
 export interface AppSettings {
+  /** Settings schema version; stamped by the backend on load/save. */
+  schema_version?: number;
   folder: string;
   days: number;
   use_hash: boolean;
   use_size: boolean;
   use_name: boolean;
+  use_name_family: boolean;
   use_mtime: boolean;
+  use_created: boolean;
   use_mime: boolean;
   use_media_meta: boolean;
+  use_audio_fingerprint: boolean;
+  use_dimensions: boolean;
+  use_content_prefix: boolean;
+  /** Bytes compared by use_content_prefix. */
+  content_prefix_bytes: number;
   hash_limit_enabled: boolean;
   hash_max_mb: number;
   fast_hash_oversized: boolean;
+  /** Hash files of at least this many MB with BLAKE3; null always uses SHA-256. */
+  blake3_min_mb: number | null;
   skip_same_folder_prompt: boolean;
+  /** Ask before trashing more than this many MB at once; null never asks. */
+  trash_limit_mb: number | null;
   show_keep_full_paths: boolean;
   include_subfolders: boolean;
+  /** Explicit walk depth (1 = top level only); null defers to include_subfolders. */
+  max_depth: number | null;
+  skip_hidden: boolean;
+  follow_symlinks: boolean;
+  symlink_hash_policy: SymlinkHashPolicy;
+  mtime_filter: MtimeFilter | null;
+  same_folder_policy: SameFolderPolicy;
+  name_compare_mode: NameCompare;
+  /** Further criteria sets; files matching under any of them also count as duplicates. */
+  alternative_criteria: CriteriaSet[];
+  /** File-type presets paired with the criteria used for their files instead; first match wins. */
+  type_overrides: [string, CriteriaSet][];
+  /** Keep only this many highest-waste groups; null returns all. */
+  max_groups: number | null;
+  /** Stop the file walk after this many seconds; null never times out. */
+  scan_timeout_secs: number | null;
+  /** Stop the file walk after this many files; null walks them all. */
+  scan_max_files: number | null;
+  /** Sniffed MIME types to keep (e.g. "image/*"); null keeps all files. */
+  mime_filter: string[] | null;
+  /** Skip files modified within this many seconds; null keeps them all. */
+  skip_recently_modified_secs: number | null;
+  /** Also group files stored inside .zip archives (listed as zip://archive!member). */
+  peek_archives: boolean;
+  /** Walk folders on several threads (faster on network drives). */
+  parallel_walk: boolean;
+  /** Leave trash / recycle bin folders out of scans. */
+  skip_trash_dirs: boolean;
+  /** List at most this many files per group; null lists them all. */
+  max_files_per_group: number | null;
   name_prefix: string;
   recent_folders: string[];
+  /** Signatures of dismissed duplicate groups; scans hide them. */
+  ignored_groups: string[];
   view_mode: ViewMode;
   auto_file_type_preset: AutoFileTypePreset;
+  custom_file_type_presets: FileTypePresets;
   theme: Theme;
   /** Stored as an opaque JSON blob in settings; parsed on load. */
   rename_components: RenameComponent[];
   rename_separator: string;
+  rename_created_fallback: CreatedFallback;
+  rename_date_format: string;
+  rename_time_format: string;
+  rename_symlinks: boolean;
+  rename_skip_duplicate_content: boolean;
+  rename_infer_missing_extension: boolean;
+  rename_collision_policy: CollisionPolicy;
+  rename_sequence_separator: string | null;
+  rename_reveal_on_complete: boolean;
+  rename_strict: boolean;
 }
 
 /** Parameters for the scan command. */
@@ -163,16 +598,73 @@ export interface ScanParams {
   use_hash: boolean;
   use_size: boolean;
   use_name: boolean;
+  use_name_family: boolean;
   use_mtime: boolean;
+  use_created: boolean;
   use_mime: boolean;
   use_media_meta: boolean;
+  use_audio_fingerprint: boolean;
+  use_dimensions: boolean;
+  use_content_prefix: boolean;
+  content_prefix_bytes: number;
   hash_limit_enabled: boolean;
   hash_max_mb: number;
   fast_hash_oversized: boolean;
+  blake3_min_mb: number | null;
   include_subfolders: boolean;
+  max_depth: number | null;
   name_prefix: string;
+  skip_hidden: boolean;
+  follow_symlinks: boolean;
+  symlink_hash_policy: SymlinkHashPolicy;
+  mtime_filter: MtimeFilter | null;
+  same_folder_policy: SameFolderPolicy;
+  name_compare_mode: NameCompare;
+  timeout_secs: number | null;
+  max_files: number | null;
+  mime_filter: string[] | null;
+  skip_recently_modified_secs: number | null;
+  peek_archives: boolean;
+  parallel_walk: boolean;
+  skip_trash_dirs: boolean;
+  max_files_per_group: number | null;
+  max_groups: number | null;
+  alternatives: CriteriaSet[];
+  type_overrides: [string, CriteriaSet][];
+  /** User-defined presets that file-type names in the criteria may refer to. */
+  custom_file_type_presets: FileTypePresets;
 }
 
+/** Parameters for the master-folder scan command. */
+export type MasterScanParams = Pick<
+  ScanParams,
+  | "use_hash"
+  | "use_size"
+  | "use_name"
+  | "use_name_family"
+  | "use_mtime"
+  | "use_created"
+  | "use_mime"
+  | "use_media_meta"
+  | "use_audio_fingerprint"
+  | "use_dimensions"
+  | "use_content_prefix"
+  | "content_prefix_bytes"
+  | "hash_limit_enabled"
+  | "hash_max_mb"
+  | "fast_hash_oversized"
+  | "blake3_min_mb"
+  | "include_subfolders"
+  | "skip_hidden"
+  | "follow_symlinks"
+  | "symlink_hash_policy"
+  | "name_compare_mode"
+> & {
+  /** Reference archive; its copies are always the keepers. */
+  master: string;
+  others: string[];
+};
+
 /** Parameters for the auto-renamer scan command. */
 export interface AutoRenameScanParams {
   folder: string;
@@ -180,4 +672,7 @@ export interface AutoRenameScanParams {
   include_subfolders: boolean;
   name_prefix: string;
   file_type_preset: AutoFileTypePreset;
+  custom_file_type_presets: FileTypePresets;
+  /** When set, files already named per this schema are left out. */
+  rename_schema?: RenameSchema | null;
 }
@@ -17,6 +17,7 @@ function makeCandidate(
     mtime: 1700000000,
     mtime_formatted: "2023-11-14 22:13:20",
     created: 1700000000,
+    preview_target: "",
   };
 }
 
@@ -41,6 +42,53 @@ describe("renamePreview parity", () => {
     expect(result).toBe("Photos.jpg");
   });
 
+  it("applies the created fallback when created date is missing", () => {
+    const components: RenameComponent[] = [
+      { id: "1", kind: "folder_name" },
+      { id: "2", kind: "date_created" },
+    ];
+    const modified = new Date(2024, 5, 20, 14, 45, 0);
+
+    expect(
+      buildName(components, "_", "Photos", "img", ".jpg", null, modified, null, {
+        createdFallback: "modified",
+      }),
+    ).toBe("Photos_20240620.jpg");
+    expect(
+      buildName(components, "_", "Photos", "img", ".jpg", null, modified, null, {
+        createdFallback: "skip_component",
+      }),
+    ).toBe("Photos.jpg");
+  });
+
+  it("formats date and time parts with the schema patterns", () => {
+    const components: RenameComponent[] = [
+      { id: "1", kind: "date_created" },
+      { id: "2", kind: "time_created" },
+    ];
+    const created = new Date(2024, 3, 15, 9, 30, 5);
+
+    expect(buildName(components, "_", "f", "img", ".jpg", created, null, null)).toBe(
+      "20240415_093005.jpg",
+    );
+    expect(
+      buildName(components, "_", "f", "img", ".jpg", created, null, null, {
+        dateFormat: "%d%m%Y",
+        timeFormat: "%Hh%M",
+      }),
+    ).toBe("15042024_09h30.jpg");
+  });
+
+  it("suffixes reserved Windows device names", () => {
+    const components: RenameComponent[] = [
+      { id: "1", kind: "literal", value: "CON" },
+    ];
+
+    expect(buildName(components, "_", "f", "stem", ".txt", null, null, null)).toBe(
+      "CON_.txt",
+    );
+  });
+
   it("pads Sequence when seq is provided", () => {
     const components: RenameComponent[] = [
       { id: "1", kind: "folder_name" },
@@ -61,6 +109,29 @@ describe("renamePreview parity", () => {
     expect(result).toBe("Photos_007.jpg");
   });
 
+  it("attaches the sequence with its own separator", () => {
+    const components: RenameComponent[] = [
+      { id: "1", kind: "literal", value: "a" },
+      { id: "2", kind: "literal", value: "b" },
+      { id: "3", kind: "sequence", pad_width: 3 },
+    ];
+
+    expect(
+      buildName(components, "-", "f", "x", ".jpg", null, null, 1, { sequenceSeparator: "_" }),
+    ).toBe("a-b_001.jpg");
+  });
+
+  it("expands placeholders inside literals", () => {
+    const components: RenameComponent[] = [
+      { id: "1", kind: "literal", value: "backup_{YYYY}-{MM}_{stem}.{ext}_{foo}" },
+    ];
+    const created = new Date(2021, 2, 9, 8, 0, 0);
+
+    expect(buildName(components, "_", "f", "beach", ".jpg", created, null, null)).toBe(
+      "backup_2021-03_beach.jpg_{foo}.jpg",
+    );
+  });
+
   it("builds literal + original stem with custom separator", () => {
     const components: RenameComponent[] = [
       { id: "1", kind: "literal", value: "backup" },
@@ -81,6 +152,36 @@ describe("renamePreview parity", () => {
     expect(result).toBe("backup-report.pdf");
   });
 
+  it("keeps trailing stem words", () => {
+    const components: RenameComponent[] = [
+      { id: "1", kind: "stem_words", keep: 2, from_end: true },
+    ];
+
+    expect(
+      buildName(components, "-", "f", "IMG_2023_summer_beach", ".jpg", null, null, null),
+    ).toBe("summer-beach.jpg");
+  });
+
+  it("shows a sample digest for hash fragments", () => {
+    const components: RenameComponent[] = [
+      { id: "1", kind: "hash_fragment", algo: "sha256", chars: 8 },
+    ];
+
+    expect(
+      buildName(components, "_", "f", "report", ".pdf", null, null, null),
+    ).toBe("b94d27b9.pdf");
+  });
+
+  it("shows the field path for sidecar fields", () => {
+    const components: RenameComponent[] = [
+      { id: "1", kind: "sidecar_field", extension: "json", field: "meta.title" },
+    ];
+
+    expect(
+      buildName(components, "_", "f", "scan", ".pdf", null, null, null),
+    ).toBe("{meta.title}.pdf");
+  });
+
   it("derives folder name and extension in per-file preview", () => {
     const components: RenameComponent[] = [
       { id: "1", kind: "folder_name" },
@@ -122,6 +223,57 @@ describe("renamePreview parity", () => {
     expect(previews.get(candidates[1].path)).toBe("photos_002.jpg");
   });
 
+  it("numbers colliding names in path order unless asked to keep list order", () => {
+    const components: RenameComponent[] = [
+      { id: "1", kind: "folder_name" },
+      { id: "2", kind: "sequence", pad_width: 3 },
+    ];
+    const candidates = [
+      makeCandidate("/photos/b.jpg", "b.jpg", "/photos", ".jpg"),
+      makeCandidate("/photos/a.jpg", "a.jpg", "/photos", ".jpg"),
+    ];
+
+    const sorted = buildAllPreviews(candidates, components, "_");
+    expect(sorted.get("/photos/a.jpg")).toBe("photos_001.jpg");
+    expect(sorted.get("/photos/b.jpg")).toBe("photos_002.jpg");
+
+    const asListed = buildAllPreviews(candidates, components, "_", { keepInputOrder: true });
+    expect(asListed.get("/photos/b.jpg")).toBe("photos_001.jpg");
+    expect(asListed.get("/photos/a.jpg")).toBe("photos_002.jpg");
+  });
+
+  it("letters colliding names with an alpha sequence", () => {
+    const components: RenameComponent[] = [
+      { id: "1", kind: "folder_name" },
+      { id: "2", kind: "alpha_sequence", uppercase: true },
+    ];
+    const candidates = [
+      makeCandidate("/photos/a.jpg", "a.jpg", "/photos", ".jpg"),
+      makeCandidate("/photos/b.jpg", "b.jpg", "/photos", ".jpg"),
+    ];
+
+    const previews = buildAllPreviews(candidates, components, "_");
+    expect(previews.get("/photos/a.jpg")).toBe("photos_A.jpg");
+    expect(previews.get("/photos/b.jpg")).toBe("photos_B.jpg");
+  });
+
+  it("restarts the folder counter in each folder", () => {
+    const components: RenameComponent[] = [
+      { id: "1", kind: "folder_name" },
+      { id: "2", kind: "folder_counter", start: 10, step: 5, pad_width: 3 },
+    ];
+    const candidates = [
+      makeCandidate("/trip/b.jpg", "b.jpg", "/trip", ".jpg"),
+      makeCandidate("/home/x.jpg", "x.jpg", "/home", ".jpg"),
+      makeCandidate("/trip/a.jpg", "a.jpg", "/trip", ".jpg"),
+    ];
+
+    const previews = buildAllPreviews(candidates, components, "_");
+    expect(previews.get("/trip/a.jpg")).toBe("trip_010.jpg");
+    expect(previews.get("/trip/b.jpg")).toBe("trip_015.jpg");
+    expect(previews.get("/home/x.jpg")).toBe("home_010.jpg");
+  });
+
   it("keeps base name on collision when schema has no Sequence", () => {
     const components: RenameComponent[] = [{ id: "1", kind: "folder_name" }];
     const candidates = [
@@ -5,7 +5,7 @@
  * "New name" previews without a round-trip to the backend.
  */
 
-import type { AutoRenameCandidateDto, RenameComponent } from "../types";
+import type { AutoRenameCandidateDto, CreatedFallback, RenameComponent } from "../types";
 
 // ---------------------------------------------------------------------------
 // Helpers
@@ -15,22 +15,110 @@ function sanitize(input: string): string {
   return input.replace(/[<>:"/\\|?*\x00-\x1f]/g, "_").trim().replace(/^\.+|\.+$/g, "");
 }
 
+const RESERVED_DEVICE_NAME = /^(con|prn|aux|nul|com[1-9]|lpt[1-9])\s*$/i;
+
+/** Mirror of the Rust `guard_reserved_device_name`: `CON` → `CON_`. */
+function guardReservedDeviceName(stem: string): string {
+  const dot = stem.indexOf(".");
+  const baseLen = dot === -1 ? stem.length : dot;
+  const base = stem.slice(0, baseLen);
+  return RESERVED_DEVICE_NAME.test(base)
+    ? `${base}_${stem.slice(baseLen)}`
+    : stem;
+}
+
+/**
+ * Stand-in digest for hash fragments: previews have no file content to hash,
+ * so every file shows the same sample digits.
+ */
+const SAMPLE_DIGEST =
+  "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
 function padStart(n: number, width: number): string {
   return String(n).padStart(width, "0");
 }
 
-function fmtDate(d: Date): string {
-  const y = d.getFullYear();
-  const mo = padStart(d.getMonth() + 1, 2);
-  const day = padStart(d.getDate(), 2);
-  return `${y}${mo}${day}`;
+/** Spells the 1-based `n` as a, b, ..., z, aa, ab, ... (bijective base 26). */
+function alphaSequence(n: number, uppercase: boolean): string {
+  let s = "";
+  while (n > 0) {
+    n -= 1;
+    s = String.fromCharCode(97 + (n % 26)) + s;
+    n = Math.floor(n / 26);
+  }
+  return uppercase ? s.toUpperCase() : s;
+}
+
+/** Fills `{YYYY}`, `{MM}`, `{DD}`, `{ext}` and `{stem}`; unknown tokens are kept. */
+function expandPlaceholders(value: string, d: Date, stem: string, extension: string): string {
+  return value.replace(/\{([^{}]*)\}/g, (token, name: string) => {
+    switch (name) {
+      case "YYYY":
+        return String(d.getFullYear());
+      case "MM":
+        return padStart(d.getMonth() + 1, 2);
+      case "DD":
+        return padStart(d.getDate(), 2);
+      case "ext":
+        return extension.replace(/^\./, "");
+      case "stem":
+        return stem;
+      default:
+        return token;
+    }
+  });
 }
 
-function fmtTime(d: Date): string {
-  const h = padStart(d.getHours(), 2);
-  const mi = padStart(d.getMinutes(), 2);
-  const s = padStart(d.getSeconds(), 2);
-  return `${h}${mi}${s}`;
+export const DEFAULT_DATE_FORMAT = "%Y%m%d";
+export const DEFAULT_TIME_FORMAT = "%H%M%S";
+
+/**
+ * Format `d` with a strftime pattern.  Covers the common subset
+ * (`%Y %y %m %d %H %I %M %S %p %%`); other tokens are kept verbatim.
+ */
+function strftime(d: Date, pattern: string): string {
+  return pattern.replace(/%([a-zA-Z%])/g, (token, spec: string) => {
+    switch (spec) {
+      case "Y":
+        return String(d.getFullYear());
+      case "y":
+        return padStart(d.getFullYear() % 100, 2);
+      case "m":
+        return padStart(d.getMonth() + 1, 2);
+      case "d":
+        return padStart(d.getDate(), 2);
+      case "H":
+        return padStart(d.getHours(), 2);
+      case "I":
+        return padStart(((d.getHours() + 11) % 12) + 1, 2);
+      case "M":
+        return padStart(d.getMinutes(), 2);
+      case "S":
+        return padStart(d.getSeconds(), 2);
+      case "p":
+        return d.getHours() < 12 ? "AM" : "PM";
+      case "%":
+        return "%";
+      default:
+        return token;
+    }
+  });
+}
+
+/** Schema-level settings that shape the date/time parts of a name. */
+export interface NameOptions {
+  /** What stands in for a missing creation date (default "now"). */
+  createdFallback?: CreatedFallback;
+  /** strftime pattern for date parts (default `%Y%m%d`). */
+  dateFormat?: string;
+  /** strftime pattern for time parts (default `%H%M%S`). */
+  timeFormat?: string;
+  /** Position of the file within its folder; folder counters are omitted without it. */
+  folderIndex?: number;
+  /** Separator that attaches sequence and counter parts (default: `separator`). */
+  sequenceSeparator?: string | null;
+  /** Number colliding files in list order instead of path order (`buildAllPreviews`). */
+  keepInputOrder?: boolean;
 }
 
 // ---------------------------------------------------------------------------
@@ -48,6 +136,7 @@ function fmtTime(d: Date): string {
  * @param createdDate Date object for file creation (or null).
  * @param modifiedDate Date object for file modification (or null).
  * @param seq         Sequence number (null → Sequence component is omitted).
+ * @param options     Created-date fallback and date/time patterns.
  */
 export function buildName(
   components: RenameComponent[],
@@ -58,17 +147,29 @@ export function buildName(
   createdDate: Date | null,
   modifiedDate: Date | null,
   seq: number | null,
+  options: NameOptions = {},
 ): string {
+  const {
+    createdFallback = "now",
+    dateFormat = DEFAULT_DATE_FORMAT,
+    timeFormat = DEFAULT_TIME_FORMAT,
+    folderIndex,
+    sequenceSeparator,
+  } = options;
   const now = new Date();
-  const c = createdDate ?? now;
   const m = modifiedDate ?? now;
+  let c: Date | null = createdDate;
+  if (c === null && createdFallback !== "skip_component") {
+    c = createdFallback === "modified" ? m : now;
+  }
 
-  const cDate = fmtDate(c);
-  const cTime = fmtTime(c);
-  const mDate = fmtDate(m);
-  const mTime = fmtTime(m);
+  const cDate = c ? strftime(c, dateFormat) : null;
+  const cTime = c ? strftime(c, timeFormat) : null;
+  const mDate = strftime(m, dateFormat);
+  const mTime = strftime(m, timeFormat);
 
   const parts: string[] = [];
+  const counters = new Set<number>(); // indices of sequence/counter parts
 
   for (const comp of components) {
     switch (comp.kind) {
@@ -83,10 +184,10 @@ export function buildName(
         break;
       }
       case "date_created":
-        parts.push(cDate);
+        if (cDate) parts.push(cDate);
         break;
       case "time_created":
-        parts.push(cTime);
+        if (cTime) parts.push(cTime);
         break;
       case "date_modified":
         parts.push(mDate);
@@ -95,21 +196,64 @@ export function buildName(
         parts.push(mTime);
         break;
       case "literal": {
-        const s = sanitize(comp.value ?? "");
+        const s = sanitize(expandPlaceholders(comp.value ?? "", c ?? m, originalStem, extension));
+        if (s) parts.push(s);
+        break;
+      }
+      case "stem_words": {
+        const words = originalStem.split(/[ _-]/).filter(Boolean);
+        const keep = comp.keep ?? 2;
+        const kept = comp.from_end ?? true
+          ? words.slice(Math.max(0, words.length - keep))
+          : words.slice(0, keep);
+        if (keep > 0 && kept.length > 0) parts.push(kept.map(sanitize).join(separator));
+        break;
+      }
+      case "hash_fragment": {
+        const fragment = SAMPLE_DIGEST.slice(0, comp.chars ?? 8);
+        if (fragment) parts.push(fragment);
+        break;
+      }
+      case "sidecar_field": {
+        // Previews cannot read sidecars; the field path stands in for its value.
+        const s = sanitize(comp.field ? `{${comp.field}}` : "");
         if (s) parts.push(s);
         break;
       }
       case "sequence":
         if (seq !== null) {
+          counters.add(parts.length);
           parts.push(padStart(seq, comp.pad_width ?? 3));
         }
         // When seq is null, omit the sequence part (base-name pass).
         break;
+      case "alpha_sequence":
+        if (seq !== null) {
+          counters.add(parts.length);
+          parts.push(alphaSequence(seq, comp.uppercase ?? false));
+        }
+        break;
+      case "folder_counter":
+        if (folderIndex !== undefined) {
+          const n = (comp.start ?? 1) + folderIndex * (comp.step ?? 1);
+          counters.add(parts.length);
+          parts.push(padStart(n, comp.pad_width ?? 3));
+        }
+        break;
     }
   }
 
-  const stem = parts.length > 0 ? parts.join(separator) : sanitize(originalStem) || "file";
-  return `${stem}${extension}`;
+  // A joint next to a sequence or counter uses the sequence separator.
+  const counterSeparator = sequenceSeparator ?? separator;
+  const joined = parts.reduce(
+    (acc, part, i) =>
+      i === 0
+        ? part
+        : acc + (counters.has(i) || counters.has(i - 1) ? counterSeparator : separator) + part,
+    "",
+  );
+  const stem = parts.length > 0 ? joined : sanitize(originalStem) || "file";
+  return `${guardReservedDeviceName(stem)}${extension}`;
 }
 
 // ---------------------------------------------------------------------------
@@ -120,7 +264,11 @@ export function buildName(
  * Returns a sample filename built from placeholder metadata so the user can
  * see what the schema looks like without needing real files.
  */
-export function buildPreview(components: RenameComponent[], separator: string): string {
+export function buildPreview(
+  components: RenameComponent[],
+  separator: string,
+  options: NameOptions = {},
+): string {
   const sampleCreated = new Date(2024, 3, 15, 9, 30, 0); // 2024-04-15 09:30:00
   const sampleModified = new Date(2024, 5, 20, 14, 45, 0); // 2024-06-20 14:45:00
   return buildName(
@@ -132,6 +280,7 @@ export function buildPreview(components: RenameComponent[], separator: string):
     sampleCreated,
     sampleModified,
     null, // no sequence in sample (unless Sequence component is present — then use 1)
+    { folderIndex: 0, ...options },
   );
 }
 
@@ -149,6 +298,7 @@ export function buildFilePreview(
   separator: string,
   candidate: AutoRenameCandidateDto,
   seq: number | null,
+  options: NameOptions = {},
 ): string {
   // Derive folder name from the folder path.
   const folderName = candidate.folder.replace(/\\/g, "/").split("/").filter(Boolean).pop() ?? "folder";
@@ -176,6 +326,7 @@ export function buildFilePreview(
     createdDate,
     modifiedDate,
     seq,
+    options,
   );
 }
 
@@ -189,7 +340,8 @@ export function buildFilePreview(
  * Pass 1 — compute base names (seq = null, Sequence component omitted).
  * Pass 2 — for each base name that appears more than once, assign
  *           sequential numbers (1, 2, …) to each member of the collision
- *           group using the Sequence component.  If the schema has no
+ *           group using the Sequence component, in path order like the
+ *           backend (list order with `options.keepInputOrder`).  If the schema has no
  *           Sequence component, all colliding files keep the same base name
  *           (the rename button will still work, but the backend will resolve
  *           collisions at rename time).
@@ -200,11 +352,26 @@ export function buildAllPreviews(
   candidates: AutoRenameCandidateDto[],
   components: RenameComponent[],
   separator: string,
+  options: NameOptions = {},
 ): Map<string, string> {
+  // Folder counters number files per folder in path order, like the backend.
+  const byPath = [...candidates].sort((a, b) => (a.path < b.path ? -1 : a.path > b.path ? 1 : 0));
+  const folderIndices = new Map<string, number>(); // path → index in folder
+  const nextIndex = new Map<string, number>(); // folder → next index
+  for (const c of byPath) {
+    const index = nextIndex.get(c.folder) ?? 0;
+    nextIndex.set(c.folder, index + 1);
+    folderIndices.set(c.path, index);
+  }
+  const optionsFor = (c: AutoRenameCandidateDto): NameOptions => ({
+    ...options,
+    folderIndex: folderIndices.get(c.path),
+  });
+
   // Pass 1: base names.
   const baseNames = new Map<string, string>(); // path → base name
   for (const c of candidates) {
-    baseNames.set(c.path, buildFilePreview(components, separator, c, null));
+    baseNames.set(c.path, buildFilePreview(components, separator, c, null, optionsFor(c)));
   }
 
   // Count occurrences of each base name.
@@ -217,12 +384,12 @@ export function buildAllPreviews(
   const seqCounters = new Map<string, number>(); // baseName → next seq
   const result = new Map<string, string>(); // path → final name
 
-  for (const c of candidates) {
+  for (const c of options.keepInputOrder ? candidates : byPath) {
     const base = baseNames.get(c.path)!;
     if ((counts.get(base) ?? 1) > 1) {
       const seq = (seqCounters.get(base) ?? 1);
       seqCounters.set(base, seq + 1);
-      result.set(c.path, buildFilePreview(components, separator, c, seq));
+      result.set(c.path, buildFilePreview(components, separator, c, seq, optionsFor(c)));
     } else {
       result.set(c.path, base);
     }